@@ -1,15 +1,137 @@
-use crate::commands::file::JSON_FILE_EXTENSIONS;
+use crate::commands::file::is_json_file;
+use crate::commands::window::target_window;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use tauri::{Emitter, Manager};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
 
-static PENDING_FILES: Mutex<Vec<String>> = Mutex::new(Vec::new());
-static FRONTEND_READY: Mutex<bool> = Mutex::new(false);
+/// How long an event waits for its page, so a webview that never loads
+/// doesn't hold payloads forever.
+const PENDING_EVENT_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Events sent before a window's page listens for them, delivered in order
+/// once it calls `frontend_ready`.
+#[derive(Default)]
+pub struct PendingEvents(Mutex<EventQueue>);
+
+#[derive(Default)]
+struct EventQueue {
+    /// Windows whose page called `frontend_ready`.
+    ready: HashSet<String>,
+    pending: Vec<PendingEvent>,
+}
+
+struct PendingEvent {
+    /// The window it is for; `None` when no window was open yet.
+    window: Option<String>,
+    event: &'static str,
+    payload: Value,
+    queued_at: Instant,
+}
+
+impl EventQueue {
+    /// Drop events that waited `PENDING_EVENT_TIMEOUT` or longer.
+    fn expire(&mut self, now: Instant) {
+        self.pending.retain(|pending| {
+            let waited = now.duration_since(pending.queued_at);
+            if waited >= PENDING_EVENT_TIMEOUT {
+                log::warn!(
+                    "Dropped {} after waiting {}s for the editor",
+                    pending.event,
+                    waited.as_secs()
+                );
+            }
+            waited < PENDING_EVENT_TIMEOUT
+        });
+    }
+
+    /// Mark `label` ready and take the events held for it, oldest first.
+    fn take_ready(&mut self, label: &str, now: Instant) -> Vec<PendingEvent> {
+        self.expire(now);
+        self.ready.insert(label.to_string());
+        let (ready, waiting) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|pending| {
+                pending
+                    .window
+                    .as_deref()
+                    .is_none_or(|window| window == label)
+            });
+        self.pending = waiting;
+        ready
+    }
+}
+
+/// Send `event` to `window` if its page listens already, else hold it until
+/// the page is ready. Without a window it goes to the first page that is.
+pub(crate) fn emit_when_ready<S: Serialize + Clone>(
+    app: &AppHandle,
+    window: Option<&WebviewWindow>,
+    event: &'static str,
+    payload: S,
+) -> Result<(), String> {
+    let label = window.map(|window| window.label().to_string());
+    let events = app.state::<PendingEvents>();
+    let mut queue = events.0.lock().unwrap();
+    if let Some(label) = label
+        .as_deref()
+        .filter(|label| queue.ready.contains(*label))
+    {
+        return app
+            .emit_to(label, event, payload)
+            .map_err(|e| e.to_string());
+    }
+    let payload = serde_json::to_value(payload).map_err(|e| e.to_string())?;
+    let now = Instant::now();
+    queue.expire(now);
+    queue.pending.push(PendingEvent {
+        window: label,
+        event,
+        payload,
+        queued_at: now,
+    });
+    drop(queue);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(PENDING_EVENT_TIMEOUT).await;
+        app.state::<PendingEvents>()
+            .0
+            .lock()
+            .unwrap()
+            .expire(Instant::now());
+    });
+    Ok(())
+}
+
+/// A closed window's next page starts out not listening.
+pub(crate) fn forget_window(app: &AppHandle, label: &str) {
+    app.state::<PendingEvents>()
+        .0
+        .lock()
+        .unwrap()
+        .ready
+        .remove(label);
+}
+
+/// Called by the page once its listeners are registered; events held for
+/// this window are sent now, oldest first
 #[tauri::command]
-pub fn get_pending_files() -> Vec<String> {
-    *FRONTEND_READY.lock().unwrap() = true;
-    PENDING_FILES.lock().unwrap().drain(..).collect()
+pub fn frontend_ready(
+    app: AppHandle,
+    window: WebviewWindow,
+    events: tauri::State<'_, PendingEvents>,
+) {
+    // Held while sending, so nothing sent meanwhile overtakes the backlog.
+    let mut queue = events.0.lock().unwrap();
+    for pending in queue.take_ready(window.label(), Instant::now()) {
+        if let Err(error) = app.emit_to(window.label(), pending.event, pending.payload) {
+            log::warn!("Failed to deliver {}: {error}", pending.event);
+        }
+    }
 }
 
 pub fn collect_json_file_args(args: &[String], cwd: &str) -> Vec<String> {
@@ -31,20 +153,17 @@ fn resolve_json_file_arg(arg: &str, cwd: &Path) -> Option<String> {
         cwd.join(raw_path)
     };
 
-    let extension = path.extension().and_then(|ext| ext.to_str())?;
-    if !JSON_FILE_EXTENSIONS
-        .iter()
-        .any(|supported| extension.eq_ignore_ascii_case(supported))
-        || !path.is_file()
-    {
-        return None;
-    }
+    let path = path.to_string_lossy().into_owned();
+    is_openable_json_file(&path).then_some(path)
+}
 
-    Some(path.to_string_lossy().into_owned())
+/// Paths from argv and "Open With" must name an existing JSON file.
+pub fn is_openable_json_file(path: &str) -> bool {
+    Path::new(path).is_file() && is_json_file(path.to_string(), None)
 }
 
 pub fn focus_main_window(app: &tauri::AppHandle) {
-    if let Some(window) = app.get_webview_window("main") {
+    if let Some(window) = target_window(app) {
         let _ = window.unminimize();
         let _ = window.show();
         let _ = window.set_focus();
@@ -58,35 +177,28 @@ pub fn queue_or_emit_open_files(app: &tauri::AppHandle, paths: Vec<String>) {
 
     focus_main_window(app);
 
-    if *FRONTEND_READY.lock().unwrap() {
-        let _ = app.emit("open-file", paths);
-    } else {
-        PENDING_FILES.lock().unwrap().extend(paths);
+    // Opened files go to one window, not to every open editor.
+    if let Err(error) = emit_when_ready(app, target_window(app).as_ref(), "open-file", paths) {
+        log::warn!("Failed to open files: {error}");
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::collect_json_file_args;
+    use super::{collect_json_file_args, EventQueue, PendingEvent, PENDING_EVENT_TIMEOUT};
+    use serde_json::json;
     use std::fs;
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    fn test_dir() -> std::path::PathBuf {
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        let dir = std::env::temp_dir().join(format!("jsonstudio-single-instance-{unique}"));
-        fs::create_dir_all(&dir).unwrap();
-        dir
-    }
+    use std::time::{Duration, Instant};
 
     #[test]
     fn collects_supported_json_file_args_only() {
-        let dir = test_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
         let json = dir.join("data.JSON5");
+        let gzipped = dir.join("export.json.gz");
         let text = dir.join("notes.txt");
         fs::write(&json, "{}").unwrap();
+        fs::write(&gzipped, "").unwrap();
         fs::write(&text, "text").unwrap();
 
         assert_eq!(
@@ -95,17 +207,22 @@ mod tests {
                     "JsonStudio".into(),
                     "--flag".into(),
                     json.to_string_lossy().into_owned(),
+                    gzipped.to_string_lossy().into_owned(),
                     text.to_string_lossy().into_owned()
                 ],
                 dir.to_str().unwrap(),
             ),
-            vec![json.to_string_lossy().into_owned()]
+            vec![
+                json.to_string_lossy().into_owned(),
+                gzipped.to_string_lossy().into_owned()
+            ]
         );
     }
 
     #[test]
     fn resolves_relative_json_args_against_launch_cwd() {
-        let dir = test_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
         let json = dir.join("data.json");
         fs::write(&json, "{}").unwrap();
 
@@ -114,4 +231,58 @@ mod tests {
             vec![json.to_string_lossy().into_owned()]
         );
     }
+
+    fn queued(window: Option<&str>, event: &'static str, queued_at: Instant) -> PendingEvent {
+        PendingEvent {
+            window: window.map(str::to_string),
+            event,
+            payload: json!(event),
+            queued_at,
+        }
+    }
+
+    #[test]
+    fn delivers_held_events_to_their_window_in_order() {
+        let start = Instant::now();
+        let mut queue = EventQueue {
+            pending: vec![
+                queued(None, "open-file", start),
+                queued(Some("editor-2"), "clipboard-content", start),
+                queued(Some("main"), "open-deeplink", start),
+            ],
+            ..Default::default()
+        };
+
+        let events = |taken: Vec<PendingEvent>| taken.iter().map(|e| e.event).collect::<Vec<_>>();
+        assert_eq!(
+            events(queue.take_ready("main", start)),
+            ["open-file", "open-deeplink"]
+        );
+        assert!(queue.ready.contains("main"));
+        assert_eq!(
+            events(queue.take_ready("editor-2", start)),
+            ["clipboard-content"]
+        );
+        assert!(queue.pending.is_empty());
+    }
+
+    #[test]
+    fn drops_events_nobody_picked_up_in_time() {
+        let start = Instant::now();
+        let mut queue = EventQueue {
+            pending: vec![
+                queued(Some("main"), "open-file", start),
+                queued(
+                    Some("main"),
+                    "open-deeplink",
+                    start + Duration::from_secs(5),
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let taken = queue.take_ready("main", start + PENDING_EVENT_TIMEOUT);
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].event, "open-deeplink");
+    }
 }
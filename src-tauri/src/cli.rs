@@ -0,0 +1,385 @@
+// Headless command line
+//
+// `jsonstudio --format|--minify|--validate [options] [files...]` runs the
+// editor's formatter and validator from scripts and exits without creating a
+// window. Files may be glob patterns, for shells that don't expand them; `-`
+// or no file at all reads stdin. Without one of the mode flags the arguments
+// are left to the app, which opens them as files.
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::commands::file::{decode_file, gzip, is_gzip_path};
+use crate::commands::json::{format_json, minify_json, validate_json};
+use crate::error::AppError;
+use crate::text_encoding::{self, DecodedText, LineEnding, LineEndingMode};
+
+const USAGE: &str = "\
+Usage: jsonstudio --format [--indent <n|tab>] [--write | --check] [files...]
+       jsonstudio --minify [--write | --check] [files...]
+       jsonstudio --validate [files...]
+
+Files may be glob patterns; `-` or no file reads stdin.
+--write   rewrite files in place instead of printing them
+--check   list the files that would change and exit 1 if there are any";
+
+/// Every file passed.
+const EXIT_OK: i32 = 0;
+/// A file is invalid or, with `--check`, would change.
+const EXIT_FAILED: i32 = 1;
+/// Bad arguments or a file that couldn't be read or written.
+const EXIT_ERROR: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Format,
+    Minify,
+    Validate,
+}
+
+impl Mode {
+    fn from_flag(flag: &str) -> Option<Self> {
+        match flag {
+            "--format" => Some(Self::Format),
+            "--minify" => Some(Self::Minify),
+            "--validate" => Some(Self::Validate),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    mode: Mode,
+    indent: usize,
+    tabs: bool,
+    write: bool,
+    check: bool,
+    inputs: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    fn name(&self) -> String {
+        match self {
+            Self::Stdin => "<stdin>".to_string(),
+            Self::File(path) => path.display().to_string(),
+        }
+    }
+}
+
+/// Run the command line when `args` (without the program name) ask for it,
+/// returning the exit code; `None` to start the app as usual.
+pub(crate) fn run(args: &[String]) -> Option<i32> {
+    if !args.iter().any(|arg| Mode::from_flag(arg).is_some()) {
+        return None;
+    }
+    attach_console();
+    let code = match parse_args(args) {
+        Ok(options) => execute(&options, &mut io::stdout().lock()),
+        Err(message) => {
+            eprintln!("jsonstudio: {message}\n\n{USAGE}");
+            EXIT_ERROR
+        }
+    };
+    Some(code)
+}
+
+/// Release builds on Windows have no console of their own; write to the one
+/// the command was started from.
+#[cfg(windows)]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut mode = None;
+    let mut options = Options {
+        mode: Mode::Format,
+        indent: 2,
+        tabs: false,
+        write: false,
+        check: false,
+        inputs: Vec::new(),
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if let Some(flag_mode) = Mode::from_flag(arg) {
+            if mode.is_some_and(|mode| mode != flag_mode) {
+                return Err("Choose one of --format, --minify and --validate".to_string());
+            }
+            mode = Some(flag_mode);
+            continue;
+        }
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        match flag {
+            "--indent" => {
+                let value = value
+                    .or_else(|| args.next().cloned())
+                    .ok_or("--indent needs a value")?;
+                if value == "tab" {
+                    options.tabs = true;
+                } else {
+                    options.indent = value
+                        .parse()
+                        .map_err(|_| format!("Invalid indent: {value}"))?;
+                }
+            }
+            "--write" => options.write = true,
+            "--check" => options.check = true,
+            "-" => options.inputs.push(arg.clone()),
+            flag if flag.starts_with('-') => return Err(format!("Unknown option: {flag}")),
+            _ => options.inputs.push(arg.clone()),
+        }
+    }
+    options.mode = mode.ok_or("Choose one of --format, --minify and --validate")?;
+    if options.mode == Mode::Validate && (options.write || options.check) {
+        return Err("--write and --check don't apply to --validate".to_string());
+    }
+    if options.write && options.check {
+        return Err("Use either --write or --check".to_string());
+    }
+    if options.write && options.inputs.iter().all(|input| input == "-") {
+        return Err("--write needs files to rewrite".to_string());
+    }
+    Ok(options)
+}
+
+/// The files named by `inputs`, with glob patterns expanded in order.
+fn expand_inputs(inputs: &[String]) -> Result<Vec<Input>, String> {
+    if inputs.is_empty() {
+        return Ok(vec![Input::Stdin]);
+    }
+    let mut expanded = Vec::new();
+    for input in inputs {
+        if input == "-" {
+            expanded.push(Input::Stdin);
+        } else if input.contains(['*', '?', '[']) {
+            let paths = glob::glob(input).map_err(|e| format!("Invalid pattern {input}: {e}"))?;
+            let before = expanded.len();
+            expanded.extend(
+                paths
+                    .filter_map(Result::ok)
+                    .filter(|path| path.is_file())
+                    .map(Input::File),
+            );
+            if expanded.len() == before {
+                return Err(format!("No files match {input}"));
+            }
+        } else {
+            expanded.push(Input::File(PathBuf::from(input)));
+        }
+    }
+    Ok(expanded)
+}
+
+fn execute(options: &Options, out: &mut impl Write) -> i32 {
+    let inputs = match expand_inputs(&options.inputs) {
+        Ok(inputs) => inputs,
+        Err(message) => {
+            eprintln!("jsonstudio: {message}");
+            return EXIT_ERROR;
+        }
+    };
+    let mut code = EXIT_OK;
+    for input in &inputs {
+        let result = match options.mode {
+            Mode::Validate => validate(input),
+            Mode::Format | Mode::Minify => format(options, input, out),
+        };
+        let input_code = result.unwrap_or_else(|message| {
+            eprintln!("{}: {message}", input.name());
+            EXIT_ERROR
+        });
+        code = code.max(input_code);
+    }
+    code
+}
+
+fn read_input(input: &Input) -> Result<DecodedText, String> {
+    let (path, bytes) = match input {
+        Input::Stdin => {
+            let mut bytes = Vec::new();
+            io::stdin()
+                .read_to_end(&mut bytes)
+                .map_err(|e| e.to_string())?;
+            (Path::new("-"), bytes)
+        }
+        Input::File(path) => (path.as_path(), fs::read(path).map_err(|e| e.to_string())?),
+    };
+    Ok(decode_file(path, &bytes)?)
+}
+
+/// Report an invalid document as `name:line:column: message`.
+fn validate(input: &Input) -> Result<i32, String> {
+    let result = validate_json(&read_input(input)?.content);
+    if result.valid {
+        return Ok(EXIT_OK);
+    }
+    eprintln!(
+        "{}:{}:{}: {}",
+        input.name(),
+        result.error_line.unwrap_or(1),
+        result.error_column.unwrap_or(1),
+        result.error_message.unwrap_or_default()
+    );
+    Ok(EXIT_FAILED)
+}
+
+fn format(options: &Options, input: &Input, out: &mut impl Write) -> Result<i32, String> {
+    let text = read_input(input)?;
+    let formatted = match options.mode {
+        Mode::Minify => minify_json(&text.content, None),
+        _ => format_json(
+            &text.content,
+            Some(options.indent),
+            options.tabs.then(|| "tab".to_string()),
+            None,
+            None,
+            None,
+        ),
+    };
+    let formatted = match formatted {
+        Ok(formatted) => formatted,
+        Err(error) => {
+            report_invalid(input, &error);
+            return Ok(EXIT_FAILED);
+        }
+    };
+    // Keep the file's line endings and final newline, so only the layout of
+    // the JSON itself counts as a change.
+    let line_ending = match text.line_ending {
+        LineEnding::Crlf => LineEndingMode::Crlf,
+        _ => LineEndingMode::Lf,
+    };
+    let output = text_encoding::normalize_lines(&formatted, line_ending, Some(text.final_newline));
+
+    if options.check {
+        if output != text.content {
+            eprintln!("Would reformat {}", input.name());
+            return Ok(EXIT_FAILED);
+        }
+    } else if let (true, Input::File(path)) = (options.write, input) {
+        if output != text.content {
+            let mut bytes = text_encoding::encode(&output, text.encoding, text.has_bom)?;
+            if is_gzip_path(path) {
+                bytes = gzip(&bytes)?;
+            }
+            fs::write(path, bytes).map_err(|e| e.to_string())?;
+        }
+    } else {
+        let newline = if output.ends_with('\n') { "" } else { "\n" };
+        write!(out, "{output}{newline}").map_err(|e| e.to_string())?;
+    }
+    Ok(EXIT_OK)
+}
+
+fn report_invalid(input: &Input, error: &AppError) {
+    match (error.param("line"), error.param("column")) {
+        (Some(line), Some(column)) => {
+            eprintln!("{}:{line}:{column}: {error}", input.name())
+        }
+        _ => eprintln!("{}: {error}", input.name()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{execute, expand_inputs, parse_args, Input, Mode, EXIT_FAILED, EXIT_OK};
+    use std::fs;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn only_handles_arguments_with_a_mode_flag() {
+        assert_eq!(super::run(&args(&["data.json"])), None);
+
+        let options = parse_args(&args(&["--format", "--indent=4", "--check", "a.json"])).unwrap();
+        assert_eq!(
+            (options.mode, options.indent, options.check),
+            (Mode::Format, 4, true)
+        );
+        assert_eq!(options.inputs, ["a.json"]);
+        assert!(parse_args(&args(&["--format", "--minify"])).is_err());
+        assert!(parse_args(&args(&["--validate", "--write", "a.json"])).is_err());
+        assert!(parse_args(&args(&["--minify", "--write", "-"])).is_err());
+        assert!(parse_args(&args(&["--format", "--colour"])).is_err());
+    }
+
+    #[test]
+    fn expands_globs_and_reads_stdin_by_default() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("b.json"), "{}").unwrap();
+        fs::write(dir.join("a.json"), "{}").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        let pattern = dir.join("*.json").to_string_lossy().into_owned();
+
+        assert_eq!(
+            expand_inputs(&[pattern]).unwrap(),
+            [
+                Input::File(dir.join("a.json")),
+                Input::File(dir.join("b.json"))
+            ]
+        );
+        assert_eq!(expand_inputs(&[]).unwrap(), [Input::Stdin]);
+        let missing = dir.join("*.json5").to_string_lossy().into_owned();
+        assert!(expand_inputs(&[missing]).is_err());
+    }
+
+    #[test]
+    fn check_fails_only_for_files_that_would_change() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let formatted = dir.join("formatted.json");
+        let compact = dir.join("compact.json");
+        fs::write(&formatted, "{\r\n  \"a\": 1\r\n}\r\n").unwrap();
+        fs::write(&compact, "{\"a\":1}").unwrap();
+        let check = |path: &std::path::Path| {
+            let options = parse_args(&args(&["--format", "--check", path.to_str().unwrap()]));
+            execute(&options.unwrap(), &mut Vec::new())
+        };
+
+        assert_eq!(check(&formatted), EXIT_OK);
+        assert_eq!(check(&compact), EXIT_FAILED);
+    }
+
+    #[test]
+    fn writes_files_in_place_or_prints_them() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("data.json");
+        fs::write(&path, "{\"a\":[1,2]}\n").unwrap();
+        let path_arg = path.to_str().unwrap();
+
+        let mut out = Vec::new();
+        let options = parse_args(&args(&["--minify", path_arg])).unwrap();
+        assert_eq!(execute(&options, &mut out), EXIT_OK);
+        assert_eq!(String::from_utf8(out).unwrap(), "{\"a\":[1,2]}\n");
+
+        let options = parse_args(&args(&["--format", "--indent", "4", "--write", path_arg]));
+        assert_eq!(execute(&options.unwrap(), &mut Vec::new()), EXIT_OK);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{\n    \"a\": [\n        1,\n        2\n    ]\n}\n"
+        );
+
+        fs::write(&path, "{\"a\":").unwrap();
+        let options = parse_args(&args(&["--validate", path_arg])).unwrap();
+        assert_eq!(execute(&options, &mut Vec::new()), EXIT_FAILED);
+    }
+}
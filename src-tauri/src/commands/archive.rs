@@ -0,0 +1,192 @@
+// ZIP archive commands
+//
+// Lists and reads JSON files inside a ZIP without unpacking it. Entries are
+// only ever read into memory, so names like `../x.json` are looked up as-is
+// and never turned into paths on disk. The size in an entry's header is only
+// a hint: reads stop at `MAX_ENTRY_BYTES` whatever the header claims.
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use super::file::is_json_file;
+use crate::text_encoding::{self, DecodedText};
+
+/// Largest entry extracted into memory.
+const MAX_ENTRY_BYTES: u64 = 256 * 1024 * 1024;
+/// Most memory reserved up front from an entry's declared size.
+const PREALLOCATE_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ZipEntry {
+    pub name: String,
+    pub size: u64,
+    pub compressed_size: u64,
+    pub is_json: bool,
+}
+
+/// List the files in a ZIP archive, in archive order
+#[tauri::command]
+pub async fn zip_list_entries(path: String) -> Result<Vec<ZipEntry>, String> {
+    tokio::task::spawn_blocking(move || list_entries(open_archive(&path)?))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Read one entry of a ZIP archive as text
+#[tauri::command]
+pub async fn zip_read_entry(path: String, entry: String) -> Result<DecodedText, String> {
+    tokio::task::spawn_blocking(move || read_entry(open_archive(&path)?, &entry, MAX_ENTRY_BYTES))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn open_archive(path: &str) -> Result<ZipArchive<BufReader<File>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    ZipArchive::new(BufReader::new(file)).map_err(archive_error)
+}
+
+fn list_entries<R: Read + Seek>(mut archive: ZipArchive<R>) -> Result<Vec<ZipEntry>, String> {
+    let mut entries = Vec::new();
+    for index in 0..archive.len() {
+        // Raw access reads metadata only, which works for encrypted entries too.
+        let file = archive.by_index_raw(index).map_err(archive_error)?;
+        if file.is_dir() {
+            continue;
+        }
+        entries.push(ZipEntry {
+            name: file.name().to_string(),
+            size: file.size(),
+            compressed_size: file.compressed_size(),
+            is_json: is_json_file(file.name().to_string(), None),
+        });
+    }
+    Ok(entries)
+}
+
+fn read_entry<R: Read + Seek>(
+    mut archive: ZipArchive<R>,
+    entry: &str,
+    max_bytes: u64,
+) -> Result<DecodedText, String> {
+    let index = archive
+        .index_for_name(entry)
+        .ok_or_else(|| format!("No entry named {entry} in the archive"))?;
+    if archive
+        .by_index_raw(index)
+        .map_err(archive_error)?
+        .encrypted()
+    {
+        return Err(format!("Archive entry is password-protected: {entry}"));
+    }
+    let file = archive.by_index(index).map_err(archive_error)?;
+    let mut bytes = Vec::with_capacity(file.size().min(PREALLOCATE_MAX_BYTES) as usize);
+    // One byte past the limit tells a too-large entry from one exactly at it.
+    file.take(max_bytes + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to extract {entry}: {}", e))?;
+    if bytes.len() as u64 > max_bytes {
+        return Err(format!(
+            "Archive entry {entry} is larger than {max_bytes} bytes"
+        ));
+    }
+    text_encoding::decode(&bytes)
+}
+
+fn archive_error(error: ZipError) -> String {
+    match error {
+        ZipError::InvalidArchive(_) => format!("Not a valid ZIP archive: {}", error),
+        ZipError::UnsupportedArchive(_) => format!("Unsupported ZIP archive: {}", error),
+        error => format!("Failed to read ZIP archive: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list_entries, read_entry, ZipEntry};
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+    fn archive_bytes(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, content) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn archive(files: &[(&str, &str)]) -> ZipArchive<Cursor<Vec<u8>>> {
+        ZipArchive::new(Cursor::new(archive_bytes(files))).unwrap()
+    }
+
+    #[test]
+    fn lists_and_reads_entries_in_memory() {
+        let files = [
+            ("data/users.json", "{\"a\": 1}"),
+            ("../evil.json", "[]"),
+            ("notes.txt", "hi"),
+        ];
+
+        let entries = list_entries(archive(&files)).unwrap();
+        assert_eq!(
+            entries[0],
+            ZipEntry {
+                name: "data/users.json".to_string(),
+                size: 8,
+                compressed_size: entries[0].compressed_size,
+                is_json: true,
+            }
+        );
+        let flags: Vec<_> = entries.iter().map(|entry| entry.is_json).collect();
+        assert_eq!(flags, [true, true, false]);
+
+        assert_eq!(
+            read_entry(archive(&files), "../evil.json", 2)
+                .unwrap()
+                .content,
+            "[]"
+        );
+        assert_eq!(
+            read_entry(archive(&files), "missing.json", 2).unwrap_err(),
+            "No entry named missing.json in the archive"
+        );
+    }
+
+    #[test]
+    fn refuses_entries_over_the_size_limit() {
+        let files = [("big.json", "[1, 2, 3]")];
+
+        assert_eq!(
+            read_entry(archive(&files), "big.json", 9).unwrap().content,
+            "[1, 2, 3]"
+        );
+        assert_eq!(
+            read_entry(archive(&files), "big.json", 8).unwrap_err(),
+            "Archive entry big.json is larger than 8 bytes"
+        );
+    }
+
+    #[test]
+    fn refuses_password_protected_entries() {
+        let mut bytes = archive_bytes(&[("secret.json", "{}")]);
+        // The writer can't encrypt without the aes-crypto feature, so set the
+        // encrypted flag in the local and central headers by hand.
+        for (signature, flags) in [(b"PK\x03\x04", 6), (b"PK\x01\x02", 8)] {
+            let header = bytes
+                .windows(4)
+                .position(|window| window == signature)
+                .unwrap();
+            bytes[header + flags] |= 1;
+        }
+        let archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        assert_eq!(
+            read_entry(archive, "secret.json", 1024).unwrap_err(),
+            "Archive entry is password-protected: secret.json"
+        );
+    }
+}
@@ -0,0 +1,251 @@
+// Editor breadcrumb command
+//
+// Resolves a cursor position to the chain of nodes containing it. The walk
+// runs over lexer tokens rather than a parsed `Value`, so it keeps working
+// while the document is briefly invalid during editing. Containers nested
+// past serde_json's limit are skipped whole and resolve to their own path.
+use serde::Serialize;
+use std::iter::Peekable;
+
+use crate::json_lexer::{Lexer, TokenKind};
+use crate::json_pointer::{child_pointer, index_pointer};
+use crate::json_simd::SERDE_MAX_DEPTH;
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct PathAtOffset {
+    /// Pointers from the root to the innermost node at the cursor; empty when
+    /// the cursor is outside the document.
+    pub pointers: Vec<String>,
+    /// Whether the cursor is on the key of the innermost node.
+    pub on_key: bool,
+}
+
+/// Find the JSON path at a cursor position
+///
+/// `line` and `column` are 1-based, with columns in UTF-16 code units as
+/// reported by Monaco. A cursor in whitespace between tokens resolves to the
+/// enclosing container.
+#[tauri::command]
+pub fn json_path_at_offset(content: &str, line: usize, column: usize) -> PathAtOffset {
+    let mut walker = PathWalker {
+        tokens: Lexer::new(content).peekable(),
+        cursor: byte_offset(content, line, column),
+        source_len: content.len(),
+        found: None,
+        depth: 0,
+    };
+    walker.value("");
+    match walker.found {
+        Some((pointer, on_key)) => PathAtOffset {
+            pointers: pointer_chain(&pointer),
+            on_key,
+        },
+        None => PathAtOffset {
+            pointers: Vec::new(),
+            on_key: false,
+        },
+    }
+}
+
+struct PathWalker<'a> {
+    tokens: Peekable<Lexer<'a>>,
+    cursor: usize,
+    source_len: usize,
+    /// Innermost node containing the cursor. Nodes finish innermost first,
+    /// so the first match wins.
+    found: Option<(String, bool)>,
+    /// Containers currently open.
+    depth: usize,
+}
+
+impl PathWalker<'_> {
+    fn record(&mut self, pointer: &str, start: usize, end: usize, on_key: bool) {
+        if self.found.is_none() && (start..=end).contains(&self.cursor) {
+            self.found = Some((pointer.to_string(), on_key));
+        }
+    }
+
+    fn value(&mut self, pointer: &str) {
+        let Some(token) = self.tokens.next() else {
+            return;
+        };
+        let end = match token.kind {
+            TokenKind::BeginObject | TokenKind::BeginArray if self.depth == SERDE_MAX_DEPTH => {
+                self.skip_container()
+            }
+            TokenKind::BeginObject | TokenKind::BeginArray => {
+                self.depth += 1;
+                let end = if token.kind == TokenKind::BeginObject {
+                    self.object(pointer)
+                } else {
+                    self.array(pointer)
+                };
+                self.depth -= 1;
+                end
+            }
+            _ => token.end,
+        };
+        self.record(pointer, token.start, end, false);
+    }
+
+    /// Walk members up to the closing brace and return the object's end.
+    fn object(&mut self, pointer: &str) -> usize {
+        loop {
+            let Some(token) = self.tokens.peek() else {
+                return self.source_len;
+            };
+            match &token.kind {
+                TokenKind::EndObject => return self.tokens.next().map_or(0, |token| token.end),
+                // A mismatched closer belongs to an enclosing container.
+                TokenKind::EndArray => return token.start,
+                TokenKind::String(name) | TokenKind::Word(name) => {
+                    let child = child_pointer(pointer, name);
+                    let (start, end) = (token.start, token.end);
+                    self.tokens.next();
+                    self.record(&child, start, end, true);
+                    if self
+                        .tokens
+                        .peek()
+                        .is_some_and(|token| token.kind == TokenKind::Colon)
+                    {
+                        self.tokens.next();
+                        self.value(&child);
+                    }
+                }
+                _ => {
+                    self.tokens.next();
+                }
+            }
+        }
+    }
+
+    /// Walk elements up to the closing bracket and return the array's end.
+    fn array(&mut self, pointer: &str) -> usize {
+        let mut index = 0;
+        loop {
+            let Some(token) = self.tokens.peek() else {
+                return self.source_len;
+            };
+            match token.kind {
+                TokenKind::EndArray => return self.tokens.next().map_or(0, |token| token.end),
+                TokenKind::EndObject => return token.start,
+                TokenKind::Comma | TokenKind::Colon => {
+                    self.tokens.next();
+                }
+                _ => {
+                    self.value(&index_pointer(pointer, index));
+                    index += 1;
+                }
+            }
+        }
+    }
+
+    /// Skip the rest of a container whose opener was just consumed and
+    /// return its end.
+    fn skip_container(&mut self) -> usize {
+        let mut depth = 1usize;
+        for token in self.tokens.by_ref() {
+            match token.kind {
+                TokenKind::BeginObject | TokenKind::BeginArray => depth += 1,
+                TokenKind::EndObject | TokenKind::EndArray => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return token.end;
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.source_len
+    }
+}
+
+/// Byte offset of a 1-based line and UTF-16 column, clamped to the line end.
+pub(crate) fn byte_offset(content: &str, line: usize, column: usize) -> usize {
+    let mut line_start = 0;
+    for _ in 1..line {
+        match content[line_start..].find('\n') {
+            Some(newline) => line_start += newline + 1,
+            None => return content.len(),
+        }
+    }
+    let mut units = 1;
+    for (offset, c) in content[line_start..].char_indices() {
+        if units >= column || c == '\n' {
+            return line_start + offset;
+        }
+        units += c.len_utf16();
+    }
+    content.len()
+}
+
+/// `"/a/0"` becomes `["", "/a", "/a/0"]`. Escaped tokens never contain `/`.
+fn pointer_chain(pointer: &str) -> Vec<String> {
+    let mut chain = vec![String::new()];
+    let mut current = String::new();
+    for token in pointer.split('/').skip(1) {
+        current.push('/');
+        current.push_str(token);
+        chain.push(current.clone());
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_path_at_offset;
+    use crate::json_simd::SERDE_MAX_DEPTH;
+
+    fn path(content: &str, line: usize, column: usize) -> (Vec<String>, bool) {
+        let result = json_path_at_offset(content, line, column);
+        (result.pointers, result.on_key)
+    }
+
+    fn chain(pointers: &[&str]) -> Vec<String> {
+        pointers.iter().map(|pointer| pointer.to_string()).collect()
+    }
+
+    #[test]
+    fn resolves_keys_values_and_whitespace() {
+        let content = "{\n  \"user\": {\n    \"tags\": [\"a\", \"b\"],\n    \"a/b\": 1\n  }\n}\n";
+
+        assert_eq!(path(content, 2, 4), (chain(&["", "/user"]), true));
+        assert_eq!(
+            path(content, 3, 19),
+            (chain(&["", "/user", "/user/tags", "/user/tags/1"]), false)
+        );
+        // Between the elements resolves to the array.
+        assert_eq!(
+            path(content, 3, 18),
+            (chain(&["", "/user", "/user/tags"]), false)
+        );
+        assert_eq!(
+            path(content, 4, 12),
+            (chain(&["", "/user", "/user/a~1b"]), false)
+        );
+        assert_eq!(path(content, 5, 1), (chain(&["", "/user"]), false));
+        assert_eq!(path(content, 6, 1), (chain(&[""]), false));
+        assert_eq!(path(content, 7, 1), (Vec::new(), false));
+    }
+
+    #[test]
+    fn counts_columns_in_utf16_and_tolerates_unclosed_containers() {
+        // The emoji takes two UTF-16 code units, so the "b" key starts at column 12.
+        let content = "{\"😀a\": 1, \"b\": [1, ";
+
+        assert_eq!(path(content, 1, 12), (chain(&["", "/b"]), true));
+        assert_eq!(path(content, 1, 18), (chain(&["", "/b", "/b/0"]), false));
+        assert_eq!(path(content, 1, 21), (chain(&["", "/b"]), false));
+    }
+
+    #[test]
+    fn resolves_past_the_depth_limit_to_the_skipped_container() {
+        let content = format!("{}{}, 1]", "[".repeat(100_000), "]".repeat(99_999));
+        let deepest: Vec<String> = (0..=SERDE_MAX_DEPTH)
+            .map(|depth| "/0".repeat(depth))
+            .collect();
+
+        assert_eq!(path(&content, 1, 150_000), (deepest, false));
+        assert_eq!(path(&content, 1, 200_002), (chain(&["", "/1"]), false));
+    }
+}
@@ -0,0 +1,245 @@
+// Canonical JSON commands
+//
+// Canonicalization follows RFC 8785 (JSON Canonicalization Scheme): object
+// keys sorted by UTF-16 code units, no whitespace, minimal string escaping and
+// numbers serialized with the ECMAScript Number-to-String algorithm.
+use md5::Md5;
+use serde_json::Value;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use super::json::parse_to_value;
+
+#[derive(Clone, Copy)]
+enum HashAlgorithm {
+    Sha256,
+    Sha1,
+    Md5,
+}
+
+impl HashAlgorithm {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "sha256" | "sha-256" => Ok(Self::Sha256),
+            "sha1" | "sha-1" => Ok(Self::Sha1),
+            "md5" => Ok(Self::Md5),
+            _ => Err(format!("Unsupported hash algorithm: {name}")),
+        }
+    }
+
+    fn hex_digest(self, data: &[u8]) -> String {
+        match self {
+            Self::Sha256 => format!("{:x}", Sha256::digest(data)),
+            Self::Sha1 => format!("{:x}", Sha1::digest(data)),
+            Self::Md5 => format!("{:x}", Md5::digest(data)),
+        }
+    }
+}
+
+/// Serialize JSON in RFC 8785 canonical form
+#[tauri::command]
+pub fn json_canonicalize(content: &str) -> Result<String, String> {
+    let value = parse_to_value(content)?;
+    canonicalize(&value)
+}
+
+/// Hex digest of the canonical form, so equivalent documents hash equally
+#[tauri::command]
+pub fn json_hash(content: &str, algorithm: String) -> Result<String, String> {
+    let algorithm = HashAlgorithm::parse(&algorithm)?;
+    let canonical = json_canonicalize(content)?;
+    Ok(algorithm.hex_digest(canonical.as_bytes()))
+}
+
+pub(crate) fn canonicalize(value: &Value) -> Result<String, String> {
+    let mut out = String::new();
+    write_value(value, &mut out)?;
+    Ok(out)
+}
+
+fn write_value(value: &Value, out: &mut String) -> Result<(), String> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(flag) => out.push_str(if *flag { "true" } else { "false" }),
+        Value::Number(number) => {
+            let float = number
+                .as_f64()
+                .filter(|float| float.is_finite())
+                .ok_or_else(|| format!("Number {number} cannot be canonicalized"))?;
+            out.push_str(&format_number(float));
+        }
+        Value::String(text) => write_string(text, out),
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_value(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(Vec<u16>, &String, &Value)> = map
+                .iter()
+                .map(|(key, child)| (key.encode_utf16().collect(), key, child))
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            out.push('{');
+            for (index, (_, key, child)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_string(key, out);
+                out.push(':');
+                write_value(child, out)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+fn write_string(text: &str, out: &mut String) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// ECMAScript Number::toString for a finite double.
+fn format_number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    if value < 0.0 {
+        return format!("-{}", format_number(-value));
+    }
+
+    // ryu yields the shortest round-tripping digits; only its layout differs
+    // from ECMAScript, so split it into digits and a decimal point position.
+    let mut buffer = ryu::Buffer::new();
+    let shortest = buffer.format_finite(value);
+    let (mantissa, exponent) = match shortest.split_once('e') {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().unwrap_or(0)),
+        None => (shortest, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let all_digits = format!("{integer}{fraction}");
+    let leading_zeros = all_digits.len() - all_digits.trim_start_matches('0').len();
+    let digits = all_digits.trim_matches('0');
+    let k = digits.len() as i32;
+    // The value is 0.<digits> × 10^n.
+    let n = integer.len() as i32 + exponent - leading_zeros as i32;
+
+    if k <= n && n <= 21 {
+        format!("{digits}{}", "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{digits}", "0".repeat((-n) as usize))
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let rest = if k > 1 {
+            format!(".{}", &digits[1..])
+        } else {
+            String::new()
+        };
+        format!("{}{rest}e{sign}{}", &digits[..1], (n - 1).abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_number, json_canonicalize, json_hash};
+
+    #[test]
+    fn formats_numbers_like_the_rfc_8785_test_vectors() {
+        let vectors: [(u64, &str); 24] = [
+            (0x0000000000000000, "0"),
+            (0x8000000000000000, "0"),
+            (0x0000000000000001, "5e-324"),
+            (0x8000000000000001, "-5e-324"),
+            (0x7fefffffffffffff, "1.7976931348623157e+308"),
+            (0xffefffffffffffff, "-1.7976931348623157e+308"),
+            (0x4340000000000000, "9007199254740992"),
+            (0xc340000000000000, "-9007199254740992"),
+            (0x4430000000000000, "295147905179352830000"),
+            (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+            (0x44b52d02c7e14af6, "1e+23"),
+            (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+            (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+            (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+            (0x444b1ae4d6e2ef50, "1e+21"),
+            (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+            (0x3eb0c6f7a0b5ed8d, "0.000001"),
+            (0x41b3de4355555553, "333333333.3333332"),
+            (0x41b3de4355555554, "333333333.33333325"),
+            (0x41b3de4355555555, "333333333.3333333"),
+            (0x41b3de4355555556, "333333333.3333334"),
+            (0x41b3de4355555557, "333333333.33333343"),
+            (0xbecbf647612f3696, "-0.0000033333333333333333"),
+            (0x43143ff3c1cb0959, "1424953923781206.2"),
+        ];
+
+        for (bits, expected) in vectors {
+            assert_eq!(format_number(f64::from_bits(bits)), expected, "{bits:#x}");
+        }
+        assert_eq!(format_number(1.0), "1");
+        assert_eq!(format_number(0.002), "0.002");
+    }
+
+    #[test]
+    fn canonicalizes_the_rfc_example() {
+        let input = r#"{
+            "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+            "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+            "literals": [null, true, false]
+        }"#;
+
+        assert_eq!(
+            json_canonicalize(input).unwrap(),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+    }
+
+    #[test]
+    fn sorts_keys_by_utf16_code_units() {
+        let input = r#"{"\u20ac":"Euro","\r":"CR","\ufb33":"Hebrew","1":"One","\ud83d\ude00":"Smiley","\u0080":"Control","\u00f6":"Latin"}"#;
+
+        assert_eq!(
+            json_canonicalize(input).unwrap(),
+            "{\"\\r\":\"CR\",\"1\":\"One\",\"\u{80}\":\"Control\",\"ö\":\"Latin\",\"€\":\"Euro\",\"😀\":\"Smiley\",\"\u{fb33}\":\"Hebrew\"}"
+        );
+    }
+
+    #[test]
+    fn hashes_the_canonical_form() {
+        let content = r#"{ "b": "x", "a": [1.0, 2.50] }"#;
+
+        assert_eq!(
+            json_hash(content, "sha256".to_string()).unwrap(),
+            "5cefae54a95b31dc660d6cbbb2198f0811fc70ee37b1cc85b1989870cd293ffb"
+        );
+        assert_eq!(
+            json_hash(content, "SHA1".to_string()).unwrap(),
+            "ae1fe4bb30ac8c9bcbcac09a7457933b6582512a"
+        );
+        assert_eq!(
+            json_hash(content, "md5".to_string()).unwrap(),
+            "a150a5643064c0608dafc28c63501f07"
+        );
+        assert!(json_hash(content, "crc32".to_string()).is_err());
+    }
+}
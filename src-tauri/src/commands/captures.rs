@@ -0,0 +1,175 @@
+// Scratch capture commands
+//
+// Clipboard JSON collected during a session is appended, minified, as one
+// line of `app_data_dir/captures/<date>.jsonl`, one file per local day.
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::json::minify_json;
+use super::shortcuts::{notify, read_clipboard};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Serializes appends, so rapid captures never interleave their lines.
+static CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ScratchCaptures {
+    pub date: String,
+    /// The day's capture file; `None` when nothing was captured that day.
+    pub path: Option<String>,
+    pub count: usize,
+}
+
+/// Append the clipboard JSON, minified, to today's capture file
+///
+/// Returns how many payloads were captured today, this one included.
+#[tauri::command]
+pub async fn capture_clipboard_to_scratch(app: AppHandle) -> Result<usize, String> {
+    let line = minify_json(&read_clipboard(&app)?, None)?;
+    let dir = captures_dir(&app)?;
+    let date = chrono::Local::now().format(DATE_FORMAT).to_string();
+    let count = tokio::task::spawn_blocking(move || append_capture(&dir, &date, &line))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    notify(&app, &format!("Captured payload #{count} today"));
+    Ok(count)
+}
+
+/// The capture file of `date` (`YYYY-MM-DD`, today when omitted), to open it
+/// in a tab
+#[tauri::command]
+pub fn get_scratch_captures(
+    app: AppHandle,
+    date: Option<String>,
+) -> Result<ScratchCaptures, String> {
+    let date = match date {
+        Some(date) => validate_date(&date)?,
+        None => chrono::Local::now().format(DATE_FORMAT).to_string(),
+    };
+    scratch_captures(&captures_dir(&app)?, date)
+}
+
+fn captures_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("captures"))
+        .map_err(|e| format!("Failed to resolve data directory: {}", e))
+}
+
+/// Dates become file names, so only real calendar dates are accepted.
+fn validate_date(date: &str) -> Result<String, String> {
+    chrono::NaiveDate::parse_from_str(date, DATE_FORMAT)
+        .map(|date| date.format(DATE_FORMAT).to_string())
+        .map_err(|_| format!("Invalid date: {date}"))
+}
+
+fn capture_path(dir: &Path, date: &str) -> PathBuf {
+    dir.join(format!("{date}.jsonl"))
+}
+
+fn append_capture(dir: &Path, date: &str, line: &str) -> Result<usize, String> {
+    let _guard = CAPTURE_LOCK.lock().unwrap();
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create captures directory: {}", e))?;
+    let path = capture_path(dir, date);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open capture file: {}", e))?;
+    // One write per line, so even another process appending can't split it.
+    file.write_all(format!("{line}\n").as_bytes())
+        .map_err(|e| format!("Failed to save capture: {}", e))?;
+    count_lines(&path)
+}
+
+fn count_lines(path: &Path) -> Result<usize, String> {
+    let file =
+        std::fs::File::open(path).map_err(|e| format!("Failed to read capture file: {}", e))?;
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    let mut count = 0;
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| format!("Failed to read capture file: {}", e))?;
+        if read == 0 {
+            return Ok(count);
+        }
+        count += 1;
+    }
+}
+
+fn scratch_captures(dir: &Path, date: String) -> Result<ScratchCaptures, String> {
+    let path = capture_path(dir, &date);
+    if !path.is_file() {
+        return Ok(ScratchCaptures {
+            date,
+            path: None,
+            count: 0,
+        });
+    }
+    Ok(ScratchCaptures {
+        count: count_lines(&path)?,
+        path: Some(path.to_string_lossy().into_owned()),
+        date,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_capture, scratch_captures, validate_date};
+    use std::fs;
+
+    #[test]
+    fn appends_one_line_per_capture_and_counts_the_day() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        assert_eq!(append_capture(dir, "2024-05-01", "{\"a\":1}").unwrap(), 1);
+        assert_eq!(append_capture(dir, "2024-05-01", "[2]").unwrap(), 2);
+        assert_eq!(append_capture(dir, "2024-05-02", "3").unwrap(), 1);
+
+        let day = scratch_captures(dir, "2024-05-01".into()).unwrap();
+        assert_eq!(day.count, 2);
+        let content = fs::read_to_string(day.path.unwrap()).unwrap();
+        assert_eq!(content, "{\"a\":1}\n[2]\n");
+
+        let empty = scratch_captures(dir, "2024-05-03".into()).unwrap();
+        assert_eq!((empty.path, empty.count), (None, 0));
+    }
+
+    #[test]
+    fn concurrent_captures_keep_their_lines_whole() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let line = format!("[{}]", "1,".repeat(5000) + "1");
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let (dir, line) = (dir.to_path_buf(), line.clone());
+                std::thread::spawn(move || append_capture(&dir, "2024-05-01", &line).unwrap())
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        let content = fs::read_to_string(dir.join("2024-05-01.jsonl")).unwrap();
+        assert_eq!(content.lines().count(), 8);
+        assert!(content.lines().all(|captured| captured == line));
+    }
+
+    #[test]
+    fn accepts_only_calendar_dates() {
+        assert_eq!(validate_date("2024-02-29").unwrap(), "2024-02-29");
+        assert!(validate_date("2023-02-29").is_err());
+        assert_eq!(
+            validate_date("../secrets").unwrap_err(),
+            "Invalid date: ../secrets"
+        );
+    }
+}
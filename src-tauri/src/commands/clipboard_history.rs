@@ -0,0 +1,257 @@
+// Clipboard history commands
+//
+// JSON that went through the clipboard shortcuts is kept in a bounded list,
+// newest first, under `app_data_dir/clipboard-history/`. Small entries live in
+// the index itself; larger ones keep only a preview there and their content
+// in a file of their own, so listing stays cheap.
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::store::{load_store, save_store, write_atomic};
+
+/// Entries kept; the oldest is dropped beyond this.
+const MAX_ENTRIES: usize = 20;
+/// Entries larger than this keep their content on disk, outside the index.
+const MAX_INLINE_BYTES: usize = 64 * 1024;
+/// Characters shown in the history panel for each entry.
+const PREVIEW_CHARS: usize = 120;
+const INDEX_FILE: &str = "index.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClipboardHistoryEntry {
+    pub id: u64,
+    /// Milliseconds since the Unix epoch.
+    pub created: i64,
+    /// Content size in bytes.
+    pub size: u64,
+    /// Start of the content with whitespace runs collapsed.
+    pub preview: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StoredEntry {
+    #[serde(flatten)]
+    info: ClipboardHistoryEntry,
+    /// `None` when the content is in its own file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+/// History loaded on first use, newest first.
+#[derive(Default)]
+pub struct ClipboardHistory(Mutex<Option<Vec<StoredEntry>>>);
+
+/// Clipboard JSON seen by the shortcuts, newest first
+#[tauri::command]
+pub fn get_clipboard_history(
+    app: AppHandle,
+    history: State<'_, ClipboardHistory>,
+) -> Result<Vec<ClipboardHistoryEntry>, String> {
+    let dir = history_dir(&app)?;
+    let mut entries = history.0.lock().unwrap();
+    Ok(entry_list(loaded(&mut entries, &dir)))
+}
+
+/// Full content of one history entry
+#[tauri::command]
+pub fn get_clipboard_history_item(
+    app: AppHandle,
+    history: State<'_, ClipboardHistory>,
+    id: u64,
+) -> Result<String, String> {
+    let dir = history_dir(&app)?;
+    let mut entries = history.0.lock().unwrap();
+    read_entry(&dir, loaded(&mut entries, &dir), id)
+}
+
+/// Forget every entry and delete the stored content
+#[tauri::command]
+pub fn clear_clipboard_history(
+    app: AppHandle,
+    history: State<'_, ClipboardHistory>,
+) -> Result<(), String> {
+    let dir = history_dir(&app)?;
+    let mut entries = history.0.lock().unwrap();
+    *entries = Some(Vec::new());
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to clear clipboard history: {}", e))?;
+    }
+    let _ = app.emit(
+        "clipboard-history-updated",
+        Vec::<ClipboardHistoryEntry>::new(),
+    );
+    Ok(())
+}
+
+/// Remember JSON taken from the clipboard and tell the UI. Failures are
+/// logged, never surfaced, so they can't fail the clipboard action itself.
+pub(crate) fn record_clipboard(app: &AppHandle, text: &str) {
+    let result = history_dir(app).and_then(|dir| {
+        let history = app.state::<ClipboardHistory>();
+        let mut entries = history.0.lock().unwrap();
+        let entries = loaded(&mut entries, &dir);
+        let now = chrono::Utc::now().timestamp_millis();
+        push_entry(&dir, entries, text, now, MAX_ENTRIES, MAX_INLINE_BYTES)?;
+        Ok(entry_list(entries))
+    });
+    match result {
+        Ok(list) => {
+            let _ = app.emit("clipboard-history-updated", list);
+        }
+        Err(error) => log::warn!("Failed to record clipboard history: {error}"),
+    }
+}
+
+/// `record_clipboard` for text not yet known to be JSON. It is parsed on a
+/// blocking thread, so the caller can show it first, and kept only if it is
+/// JSON.
+pub(crate) fn record_clipboard_if_json(app: &AppHandle, text: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if serde_json::from_str::<serde::de::IgnoredAny>(&text).is_ok() {
+            record_clipboard(&app, &text);
+        }
+    });
+}
+
+fn history_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("clipboard-history"))
+        .map_err(|e| format!("Failed to resolve data directory: {}", e))
+}
+
+fn loaded<'a>(entries: &'a mut Option<Vec<StoredEntry>>, dir: &Path) -> &'a mut Vec<StoredEntry> {
+    entries.get_or_insert_with(|| load_store(&dir.join(INDEX_FILE)))
+}
+
+fn content_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{id}.json"))
+}
+
+fn entry_list(entries: &[StoredEntry]) -> Vec<ClipboardHistoryEntry> {
+    entries.iter().map(|entry| entry.info.clone()).collect()
+}
+
+fn preview(text: &str) -> String {
+    let mut preview = String::new();
+    for word in text.split_whitespace() {
+        if !preview.is_empty() {
+            preview.push(' ');
+        }
+        preview.push_str(word);
+        if preview.chars().count() >= PREVIEW_CHARS {
+            return preview.chars().take(PREVIEW_CHARS).chain(['…']).collect();
+        }
+    }
+    preview
+}
+
+fn push_entry(
+    dir: &Path,
+    entries: &mut Vec<StoredEntry>,
+    text: &str,
+    now: i64,
+    max_entries: usize,
+    max_inline: usize,
+) -> Result<(), String> {
+    // Running the same shortcut twice on one copy isn't a new entry.
+    if let Some(newest) = entries.first() {
+        if newest.info.size == text.len() as u64
+            && read_entry(dir, entries, newest.info.id)? == text
+        {
+            return Ok(());
+        }
+    }
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create history directory: {}", e))?;
+    let id = entries.first().map_or(1, |newest| newest.info.id + 1);
+    let content = if text.len() > max_inline {
+        write_atomic(&content_path(dir, id), text.as_bytes())
+            .map_err(|e| format!("Failed to save clipboard entry: {}", e))?;
+        None
+    } else {
+        Some(text.to_string())
+    };
+    entries.insert(
+        0,
+        StoredEntry {
+            info: ClipboardHistoryEntry {
+                id,
+                created: now,
+                size: text.len() as u64,
+                preview: preview(text),
+            },
+            content,
+        },
+    );
+    for dropped in entries.drain(max_entries.min(entries.len())..) {
+        if dropped.content.is_none() {
+            let _ = std::fs::remove_file(content_path(dir, dropped.info.id));
+        }
+    }
+    save_store(&dir.join(INDEX_FILE), entries)
+}
+
+fn read_entry(dir: &Path, entries: &[StoredEntry], id: u64) -> Result<String, String> {
+    let entry = entries
+        .iter()
+        .find(|entry| entry.info.id == id)
+        .ok_or_else(|| format!("Unknown clipboard history entry: {id}"))?;
+    match &entry.content {
+        Some(content) => Ok(content.clone()),
+        None => std::fs::read_to_string(content_path(dir, id))
+            .map_err(|e| format!("Failed to read clipboard entry: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{entry_list, preview, push_entry, read_entry, StoredEntry};
+
+    fn ids(entries: &[StoredEntry]) -> Vec<u64> {
+        entry_list(entries).iter().map(|entry| entry.id).collect()
+    }
+
+    #[test]
+    fn keeps_a_bounded_list_newest_first() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let mut entries = Vec::new();
+        for (now, text) in ["[1]", "[2]", "[2]", "[3]", "[4]"].iter().enumerate() {
+            push_entry(dir, &mut entries, text, now as i64, 3, 100).unwrap();
+        }
+        assert_eq!(ids(&entries), [4, 3, 2]);
+        assert_eq!(read_entry(dir, &entries, 2).unwrap(), "[2]");
+        assert_eq!(
+            read_entry(dir, &entries, 1).unwrap_err(),
+            "Unknown clipboard history entry: 1"
+        );
+    }
+
+    #[test]
+    fn stores_large_entries_outside_the_index() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let mut entries = Vec::new();
+        let large = format!("[{}]", "1, ".repeat(20) + "1");
+        push_entry(dir, &mut entries, &large, 1, 1, 10).unwrap();
+        assert!(entries[0].content.is_none());
+        assert_eq!(read_entry(dir, &entries, 1).unwrap(), large);
+
+        push_entry(dir, &mut entries, "{}", 2, 1, 10).unwrap();
+        assert!(!dir.join("1.json").exists());
+        assert_eq!(ids(&entries), [2]);
+    }
+
+    #[test]
+    fn previews_collapse_whitespace_and_truncate() {
+        assert_eq!(preview("{\n  \"a\": 1\n}"), "{ \"a\": 1 }");
+        let long = preview(&"x ".repeat(200));
+        assert_eq!(long.chars().count(), 121);
+        assert!(long.ends_with('…'));
+    }
+}
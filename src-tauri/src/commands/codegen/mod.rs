@@ -6,6 +6,7 @@ use serde_json::Value;
 
 use parser::parse_code_to_json_ast;
 use schema::{gen_protobuf, gen_thrift};
+pub(crate) use schema::{infer_schema, InferredSchema, JsonType};
 
 #[tauri::command]
 pub fn json_to_code(content: &str, language: &str, class_name: &str) -> Result<String, String> {
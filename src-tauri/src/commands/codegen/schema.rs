@@ -5,7 +5,7 @@ use std::collections::{BTreeMap, BTreeSet};
 // --- Type inference ---
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-enum JsonType {
+pub(crate) enum JsonType {
     String,
     Number,
     Integer,
@@ -179,6 +179,23 @@ fn merge_struct_fields(
     }
 }
 
+/// Inferred structure of a document: named object shapes plus the root type.
+pub(crate) struct InferredSchema {
+    pub(crate) structs: BTreeMap<String, BTreeMap<String, JsonType>>,
+    pub(crate) root: JsonType,
+}
+
+/// Infer the structure of `value` with the same walker the code generators use.
+/// Array elements are merged into one shape; fields missing from some elements
+/// or sometimes null become `Optional`.
+pub(crate) fn infer_schema(value: &Value) -> InferredSchema {
+    let collected = collect_structs(value, "Root");
+    InferredSchema {
+        structs: collected.structs,
+        root: collected.top_level_type,
+    }
+}
+
 struct CollectResult {
     structs: BTreeMap<String, BTreeMap<String, JsonType>>,
     top_level_type: JsonType,
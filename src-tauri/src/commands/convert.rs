@@ -1,19 +1,63 @@
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
+use tauri::AppHandle;
+
+use super::results::{deliver, Delivered};
+use crate::json_value::PlainNumbers;
 
 const TOML_ROOT_ARRAY_MARKER: &str = "# jsonstudio:root-array";
 const XML_KEY_ATTR: &str = "jsonstudio-key";
 
+// The commands return a stored result when the output is large.
+
+#[tauri::command]
+pub fn json_to_yaml(app: AppHandle, content: &str) -> Result<Delivered, String> {
+    Ok(deliver(&app, to_yaml(content)?))
+}
+
 #[tauri::command]
-pub fn json_to_yaml(content: &str) -> Result<String, String> {
+pub fn json_to_toml(app: AppHandle, content: &str) -> Result<Delivered, String> {
+    Ok(deliver(&app, to_toml(content)?))
+}
+
+#[tauri::command]
+pub fn json_to_xml(app: AppHandle, content: &str) -> Result<Delivered, String> {
+    Ok(deliver(&app, to_xml(content)?))
+}
+
+#[tauri::command]
+pub fn json_to_csv(app: AppHandle, content: &str) -> Result<Delivered, String> {
+    Ok(deliver(&app, to_csv(content)?))
+}
+
+#[tauri::command]
+pub fn yaml_to_json(app: AppHandle, content: &str) -> Result<Delivered, String> {
+    Ok(deliver(&app, from_yaml(content)?))
+}
+
+#[tauri::command]
+pub fn toml_to_json(app: AppHandle, content: &str) -> Result<Delivered, String> {
+    Ok(deliver(&app, from_toml(content)?))
+}
+
+#[tauri::command]
+pub fn xml_to_json(app: AppHandle, content: &str) -> Result<Delivered, String> {
+    Ok(deliver(&app, from_xml(content)?))
+}
+
+#[tauri::command]
+pub fn csv_to_json(app: AppHandle, content: &str) -> Result<Delivered, String> {
+    Ok(deliver(&app, from_csv(content)?))
+}
+
+pub(crate) fn to_yaml(content: &str) -> Result<String, String> {
     let value: Value = serde_json::from_str(content)
         .map_err(|e| format!("Invalid JSON: {}", e))?;
-    serde_yaml::to_string(&value)
+    serde_yaml::to_string(&PlainNumbers(&value))
         .map_err(|e| format!("YAML conversion failed: {}", e))
 }
 
-#[tauri::command]
-pub fn json_to_toml(content: &str) -> Result<String, String> {
+pub(crate) fn to_toml(content: &str) -> Result<String, String> {
     let value: Value = serde_json::from_str(content)
         .map_err(|e| format!("Invalid JSON: {}", e))?;
     let is_root_array = value.is_array();
@@ -31,7 +75,7 @@ pub fn json_to_toml(content: &str) -> Result<String, String> {
         }
     };
 
-    let toml = toml::to_string_pretty(&table_value)
+    let toml = toml::to_string_pretty(&PlainNumbers(&table_value))
         .map_err(|e| format!("TOML conversion failed: {}", e))?;
     if is_root_array {
         Ok(format!("{TOML_ROOT_ARRAY_MARKER}\n{toml}"))
@@ -40,8 +84,7 @@ pub fn json_to_toml(content: &str) -> Result<String, String> {
     }
 }
 
-#[tauri::command]
-pub fn json_to_xml(content: &str) -> Result<String, String> {
+pub(crate) fn to_xml(content: &str) -> Result<String, String> {
     let value: Value = serde_json::from_str(content)
         .map_err(|e| format!("Invalid JSON: {}", e))?;
 
@@ -143,8 +186,7 @@ fn escape_xml(s: &str) -> String {
         .replace('\'', "&apos;")
 }
 
-#[tauri::command]
-pub fn json_to_csv(content: &str) -> Result<String, String> {
+pub(crate) fn to_csv(content: &str) -> Result<String, String> {
     let value: Value = serde_json::from_str(content)
         .map_err(|e| format!("Invalid JSON: {}", e))?;
 
@@ -203,16 +245,14 @@ pub fn json_to_csv(content: &str) -> Result<String, String> {
 // Reverse conversions: YAML/TOML/XML/CSV → JSON
 // ============================================================
 
-#[tauri::command]
-pub fn yaml_to_json(content: &str) -> Result<String, String> {
+pub(crate) fn from_yaml(content: &str) -> Result<String, String> {
     let value: Value = serde_yaml::from_str(content)
         .map_err(|e| format!("Invalid YAML: {}", e))?;
     serde_json::to_string_pretty(&value)
         .map_err(|e| format!("JSON conversion failed: {}", e))
 }
 
-#[tauri::command]
-pub fn toml_to_json(content: &str) -> Result<String, String> {
+pub(crate) fn from_toml(content: &str) -> Result<String, String> {
     let is_root_array = content
         .lines()
         .find(|line| !line.trim().is_empty())
@@ -235,8 +275,7 @@ pub fn toml_to_json(content: &str) -> Result<String, String> {
         .map_err(|e| format!("JSON conversion failed: {}", e))
 }
 
-#[tauri::command]
-pub fn xml_to_json(content: &str) -> Result<String, String> {
+pub(crate) fn from_xml(content: &str) -> Result<String, String> {
     let value = parse_xml_to_value(content)?;
     serde_json::to_string_pretty(&value)
         .map_err(|e| format!("JSON conversion failed: {}", e))
@@ -436,8 +475,7 @@ fn children_to_value(children: Vec<(String, Value)>, is_array: bool, is_object:
     Value::Object(map)
 }
 
-#[tauri::command]
-pub fn csv_to_json(content: &str) -> Result<String, String> {
+pub(crate) fn from_csv(content: &str) -> Result<String, String> {
     let mut rdr = csv::ReaderBuilder::new()
         .has_headers(true)
         .from_reader(content.as_bytes());
@@ -470,7 +508,7 @@ pub fn csv_to_json(content: &str) -> Result<String, String> {
 
 #[cfg(test)]
 mod tests {
-    use super::{json_to_xml, xml_to_json};
+    use super::{from_xml, to_toml, to_xml, to_yaml};
     use serde_json::Value;
 
     #[test]
@@ -485,8 +523,8 @@ mod tests {
         ];
 
         for source in samples {
-            let xml = json_to_xml(source).expect("JSON should convert to XML");
-            let converted = xml_to_json(&xml).expect("generated XML should convert to JSON");
+            let xml = to_xml(source).expect("JSON should convert to XML");
+            let converted = from_xml(&xml).expect("generated XML should convert to JSON");
             assert_eq!(
                 serde_json::from_str::<Value>(&converted).unwrap(),
                 serde_json::from_str::<Value>(source).unwrap(),
@@ -498,8 +536,8 @@ mod tests {
     #[test]
     fn xml_round_trip_preserves_keys_that_need_sanitizing() {
         let source = r#"{"a b":1,"a_b":2,"1id":3,"":4,"中文":5}"#;
-        let xml = json_to_xml(source).expect("JSON should convert to XML");
-        let converted = xml_to_json(&xml).expect("generated XML should convert to JSON");
+        let xml = to_xml(source).expect("JSON should convert to XML");
+        let converted = from_xml(&xml).expect("generated XML should convert to JSON");
 
         assert_eq!(
             serde_json::from_str::<Value>(&converted).unwrap(),
@@ -509,33 +547,45 @@ mod tests {
 
     #[test]
     fn xml_to_json_decodes_cdata_text() {
-        let converted = xml_to_json("<root><![CDATA[<raw>]]></root>").unwrap();
+        let converted = from_xml("<root><![CDATA[<raw>]]></root>").unwrap();
         assert_eq!(converted, r#""<raw>""#);
     }
 
     #[test]
     fn toml_round_trip_preserves_root_arrays_without_unwrapping_regular_items_objects() {
         let array_source = r#"[{"id":1}]"#;
-        let array_toml = super::json_to_toml(array_source).unwrap();
+        let array_toml = super::to_toml(array_source).unwrap();
         assert!(array_toml.starts_with("# jsonstudio:root-array\n"));
-        let array_json = super::toml_to_json(&array_toml).unwrap();
+        let array_json = super::from_toml(&array_toml).unwrap();
         assert_eq!(array_json, "[\n  {\n    \"id\": 1\n  }\n]");
 
         let object_source = r#"{"items":[1,2]}"#;
-        let object_toml = super::json_to_toml(object_source).unwrap();
-        let object_json = super::toml_to_json(&object_toml).unwrap();
+        let object_toml = super::to_toml(object_source).unwrap();
+        let object_json = super::from_toml(&object_toml).unwrap();
         assert_eq!(object_json, "{\n  \"items\": [\n    1,\n    2\n  ]\n}");
 
-        assert!(super::toml_to_json("# jsonstudio:root-array\nitems = [1]\nextra = 2").is_err());
+        assert!(super::from_toml("# jsonstudio:root-array\nitems = [1]\nextra = 2").is_err());
         assert_eq!(
-            super::toml_to_json("items = [1]\n# jsonstudio:root-array").unwrap(),
+            super::from_toml("items = [1]\n# jsonstudio:root-array").unwrap(),
             "{\n  \"items\": [\n    1\n  ]\n}"
         );
     }
 
+    #[test]
+    fn yaml_and_toml_output_plain_numbers() {
+        let input = r#"{"count": 3, "ratio": 0.25, "id": 9007199254740993}"#;
+
+        let yaml = to_yaml(input).unwrap();
+        assert_eq!(yaml, "count: 3\nratio: 0.25\nid: 9007199254740993\n");
+
+        let toml = to_toml(input).unwrap();
+        assert!(toml.contains("count = 3\n"));
+        assert!(toml.contains("ratio = 0.25\n"));
+    }
+
     #[test]
     fn csv_rejects_mixed_arrays_instead_of_dropping_rows() {
-        let result = super::json_to_csv(r#"[{"id":1},2]"#);
+        let result = super::to_csv(r#"[{"id":1},2]"#);
         assert!(result.is_err());
     }
 }
@@ -0,0 +1,247 @@
+// Crash reports
+//
+// A panic hook writes what is needed to look into a crash — the panic
+// message, a backtrace, the app version and platform, and the last lines of
+// the log — to `crashes/<timestamp>.txt` in the app data directory, so the
+// page can offer the report on the next launch. Panics inside background
+// tasks are caught and reported as failed tasks instead; those are only
+// logged, since the app keeps running.
+use serde::Serialize;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+
+use super::logging::recent_log_lines;
+
+const CRASH_DIR: &str = "crashes";
+/// Log lines copied into a report.
+const CRASH_LOG_LINES: usize = 100;
+/// Reports kept; the oldest are deleted when a new one is written.
+const MAX_CRASH_REPORTS: usize = 20;
+const MESSAGE_PREFIX: &str = "Message: ";
+
+/// Set once the app data directory is known; earlier panics aren't recorded.
+static CRASH_DIR_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+thread_local! {
+    /// Whether a panic on this thread is caught by `catch_panic`.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct CrashReportInfo {
+    pub id: String,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+    /// First line of the panic message.
+    pub message: String,
+}
+
+/// Record panics, then hand them to the previous hook as before.
+pub(crate) fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if CATCHING.get() {
+            log::error!("Background task panicked: {}", panic_summary(info));
+        } else if let Some(dir) = CRASH_DIR_PATH.get() {
+            log::error!("Panicked: {}", panic_summary(info));
+            let _ = write_report(dir, &crash_report(info));
+        }
+        previous(info);
+    }));
+}
+
+/// Start recording crash reports in the app data directory.
+pub(crate) fn init(app: &AppHandle) {
+    match crash_dir(app) {
+        Ok(dir) => {
+            let _ = CRASH_DIR_PATH.set(dir);
+        }
+        Err(error) => log::warn!("Crash reports are disabled: {error}"),
+    }
+}
+
+/// Run `work`, turning a panic into an error instead of losing the thread.
+pub(crate) fn catch_panic<T>(work: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    let outer = CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(work));
+    CATCHING.set(outer);
+    result.unwrap_or_else(|payload| Err(format!("Task panicked: {}", payload_text(&*payload))))
+}
+
+fn payload_text(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause")
+}
+
+fn panic_summary(info: &PanicHookInfo) -> String {
+    let message = payload_text(info.payload());
+    match info.location() {
+        Some(location) => format!("{message} at {location}"),
+        None => message.to_string(),
+    }
+}
+
+fn crash_report(info: &PanicHookInfo) -> String {
+    let thread = std::thread::current();
+    let log = recent_log_lines(CRASH_LOG_LINES);
+    format!(
+        "JSON Studio {version} crashed\n\
+         Time: {time}\n\
+         Platform: {os} {arch}\n\
+         Thread: {thread}\n\
+         {MESSAGE_PREFIX}{message}\n\n\
+         Backtrace:\n{backtrace}\n\n\
+         Recent log:\n{log}\n",
+        version = env!("CARGO_PKG_VERSION"),
+        time = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        os = std::env::consts::OS,
+        arch = std::env::consts::ARCH,
+        thread = thread.name().unwrap_or("unnamed"),
+        message = panic_summary(info),
+        backtrace = Backtrace::force_capture(),
+        log = log.join("\n"),
+    )
+}
+
+fn crash_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(CRASH_DIR))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+/// Write `report` as a new file named by the current time, then drop the
+/// oldest reports beyond `MAX_CRASH_REPORTS`.
+fn write_report(dir: &Path, report: &str) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let id = chrono::Local::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+    let path = dir.join(format!("{id}.txt"));
+    fs::write(&path, report)?;
+    for old in list_reports(dir).iter().skip(MAX_CRASH_REPORTS) {
+        let _ = fs::remove_file(dir.join(format!("{}.txt", old.id)));
+    }
+    Ok(path)
+}
+
+/// Reports in `dir`, newest first.
+fn list_reports(dir: &Path) -> Vec<CrashReportInfo> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReportInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "txt" {
+                return None;
+            }
+            let id = path.file_stem()?.to_string_lossy().into_owned();
+            let created_at = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or(0);
+            let message = fs::read_to_string(&path)
+                .ok()?
+                .lines()
+                .find_map(|line| line.strip_prefix(MESSAGE_PREFIX).map(str::to_string))
+                .unwrap_or_default();
+            Some(CrashReportInfo {
+                id,
+                created_at,
+                message,
+            })
+        })
+        .collect();
+    // Ids are timestamps, so they sort by age.
+    reports.sort_by(|a, b| b.id.cmp(&a.id));
+    reports
+}
+
+fn read_report(dir: &Path, id: &str) -> Result<String, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err("Invalid crash report id".to_string());
+    }
+    fs::read_to_string(dir.join(format!("{id}.txt")))
+        .map_err(|e| format!("Failed to read crash report: {}", e))
+}
+
+/// Crash reports from earlier sessions, newest first
+#[tauri::command]
+pub fn list_crash_reports(app: AppHandle) -> Result<Vec<CrashReportInfo>, String> {
+    Ok(list_reports(&crash_dir(&app)?))
+}
+
+/// Full text of the crash report `id`
+#[tauri::command]
+pub fn read_crash_report(app: AppHandle, id: String) -> Result<String, String> {
+    read_report(&crash_dir(&app)?, &id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{catch_panic, list_reports, read_report, write_report, MAX_CRASH_REPORTS};
+    use std::fs;
+
+    #[test]
+    fn lists_reports_newest_first_with_their_message() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("20260101-090000-000.txt"), "Message: older\n").unwrap();
+        fs::write(dir.join("20260102-090000-000.txt"), "Message: newer\n").unwrap();
+        fs::write(dir.join("notes.md"), "").unwrap();
+
+        let reports = list_reports(dir);
+        assert_eq!(
+            reports
+                .iter()
+                .map(|report| (report.id.as_str(), report.message.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("20260102-090000-000", "newer"),
+                ("20260101-090000-000", "older")
+            ]
+        );
+        assert_eq!(
+            read_report(dir, "20260101-090000-000").unwrap(),
+            "Message: older\n"
+        );
+        assert!(read_report(dir, "../settings").is_err());
+        assert!(list_reports(&dir.join("missing")).is_empty());
+    }
+
+    #[test]
+    fn keeps_a_limited_number_of_reports() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        for index in 0..MAX_CRASH_REPORTS {
+            fs::write(dir.join(format!("2025{index:04}-000000-000.txt")), "").unwrap();
+        }
+        let newest = write_report(dir, "Message: boom\n").unwrap();
+
+        let reports = list_reports(dir);
+        assert_eq!(reports.len(), MAX_CRASH_REPORTS);
+        assert_eq!(reports[0].message, "boom");
+        assert!(newest.exists());
+        assert!(!dir.join("20250000-000000-000.txt").exists());
+    }
+
+    #[test]
+    fn turns_panics_into_errors() {
+        let result: Result<(), String> = catch_panic(|| panic!("nested too deep"));
+        assert_eq!(result, Err("Task panicked: nested too deep".to_string()));
+        assert_eq!(catch_panic(|| Ok(1)), Ok(1));
+    }
+}
@@ -0,0 +1,642 @@
+// Structural JSON comparison commands
+use regex::RegexSet;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use tauri::Window;
+
+use super::json::parse_to_value;
+use super::tasks::spawn_task;
+use crate::json_pointer::{child_pointer, index_pointer};
+use crate::json_value::{canonical_number, value_preview, value_type_name};
+
+const DEFAULT_DIFF_LIMIT: usize = 1000;
+const PREVIEW_CHARS: usize = 120;
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DiffOptions {
+    /// Compare arrays as multisets instead of position by position.
+    pub ignore_array_order: bool,
+    /// Treat numbers with the same value (`1` and `1.0`) as equal.
+    pub numeric_equality: bool,
+    /// Regex patterns; object members whose key matches are skipped on both sides.
+    pub ignore_keys: Vec<String>,
+    /// Number of entries to skip, for paging through large diffs.
+    pub offset: usize,
+    /// Maximum number of entries returned (default 1000).
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+    TypeChanged,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DiffEntry {
+    pub pointer: String,
+    pub kind: DiffKind,
+    pub left_preview: Option<String>,
+    pub right_preview: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct DiffSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub type_changed: usize,
+    pub total: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DiffResult {
+    pub entries: Vec<DiffEntry>,
+    pub summary: DiffSummary,
+    /// More entries exist after this page.
+    pub truncated: bool,
+}
+
+/// Structurally compare two documents. Object key order is always ignored.
+/// Entries come out in document order, so paging with `offset` is stable;
+/// the summary always counts every difference.
+#[tauri::command]
+pub fn json_diff(left: &str, right: &str, options: DiffOptions) -> Result<DiffResult, String> {
+    let left = parse_to_value(left).map_err(|e| format!("Left document: {e}"))?;
+    let right = parse_to_value(right).map_err(|e| format!("Right document: {e}"))?;
+    diff_values(&left, &right, &options)
+}
+
+/// `json_diff` as a background task; returns its id (see `spawn_task`)
+#[tauri::command]
+pub fn json_diff_async(
+    window: Window,
+    left: String,
+    right: String,
+    options: DiffOptions,
+) -> String {
+    spawn_task(window, "diff", move |task| {
+        task.step(0, 3)?;
+        let left = parse_to_value(&left).map_err(|e| format!("Left document: {e}"))?;
+        task.step(1, 3)?;
+        let right = parse_to_value(&right).map_err(|e| format!("Right document: {e}"))?;
+        task.step(2, 3)?;
+        diff_values(&left, &right, &options)
+    })
+}
+
+fn diff_values(left: &Value, right: &Value, options: &DiffOptions) -> Result<DiffResult, String> {
+    let mut differ = Differ::new(options)?;
+    differ.compare(left, right, "");
+
+    let summary = differ.summary;
+    let truncated = options.offset + differ.entries.len() < summary.total;
+    Ok(DiffResult {
+        entries: differ.entries,
+        summary,
+        truncated,
+    })
+}
+
+struct Differ {
+    ignored_keys: Option<RegexSet>,
+    ignore_array_order: bool,
+    numeric_equality: bool,
+    offset: usize,
+    limit: usize,
+    entries: Vec<DiffEntry>,
+    summary: DiffSummary,
+}
+
+impl Differ {
+    fn new(options: &DiffOptions) -> Result<Self, String> {
+        let ignored_keys = if options.ignore_keys.is_empty() {
+            None
+        } else {
+            Some(
+                RegexSet::new(&options.ignore_keys)
+                    .map_err(|error| format!("Invalid key pattern: {error}"))?,
+            )
+        };
+        Ok(Self {
+            ignored_keys,
+            ignore_array_order: options.ignore_array_order,
+            numeric_equality: options.numeric_equality,
+            offset: options.offset,
+            limit: options.limit.unwrap_or(DEFAULT_DIFF_LIMIT),
+            entries: Vec::new(),
+            summary: DiffSummary::default(),
+        })
+    }
+
+    fn is_ignored(&self, key: &str) -> bool {
+        self.ignored_keys
+            .as_ref()
+            .is_some_and(|patterns| patterns.is_match(key))
+    }
+
+    fn record(
+        &mut self,
+        pointer: &str,
+        kind: DiffKind,
+        left: Option<&Value>,
+        right: Option<&Value>,
+    ) {
+        let index = self.summary.total;
+        self.summary.total += 1;
+        match kind {
+            DiffKind::Added => self.summary.added += 1,
+            DiffKind::Removed => self.summary.removed += 1,
+            DiffKind::Changed => self.summary.changed += 1,
+            DiffKind::TypeChanged => self.summary.type_changed += 1,
+        }
+        if index >= self.offset && self.entries.len() < self.limit {
+            self.entries.push(DiffEntry {
+                pointer: pointer.to_string(),
+                kind,
+                left_preview: left.map(|value| value_preview(value, PREVIEW_CHARS)),
+                right_preview: right.map(|value| value_preview(value, PREVIEW_CHARS)),
+            });
+        }
+    }
+
+    fn numbers_equal(&self, left: &Number, right: &Number) -> bool {
+        if self.numeric_equality {
+            canonical_number(left) == canonical_number(right)
+        } else {
+            left == right
+        }
+    }
+
+    fn compare(&mut self, left: &Value, right: &Value, pointer: &str) {
+        match (left, right) {
+            (Value::Object(left_map), Value::Object(right_map)) => {
+                self.compare_objects(left_map, right_map, pointer)
+            }
+            (Value::Array(left_items), Value::Array(right_items)) => {
+                if self.ignore_array_order {
+                    self.compare_unordered(left_items, right_items, pointer)
+                } else {
+                    self.compare_ordered(left_items, right_items, pointer)
+                }
+            }
+            (Value::Number(a), Value::Number(b)) => {
+                if !self.numbers_equal(a, b) {
+                    self.record(pointer, DiffKind::Changed, Some(left), Some(right));
+                }
+            }
+            _ if value_type_name(left) != value_type_name(right) => {
+                self.record(pointer, DiffKind::TypeChanged, Some(left), Some(right))
+            }
+            _ => {
+                if left != right {
+                    self.record(pointer, DiffKind::Changed, Some(left), Some(right));
+                }
+            }
+        }
+    }
+
+    fn compare_objects(
+        &mut self,
+        left: &Map<String, Value>,
+        right: &Map<String, Value>,
+        pointer: &str,
+    ) {
+        for (key, left_value) in left {
+            if self.is_ignored(key) {
+                continue;
+            }
+            let member_pointer = child_pointer(pointer, key);
+            match right.get(key) {
+                Some(right_value) => self.compare(left_value, right_value, &member_pointer),
+                None => self.record(&member_pointer, DiffKind::Removed, Some(left_value), None),
+            }
+        }
+        for (key, right_value) in right {
+            if !left.contains_key(key) && !self.is_ignored(key) {
+                self.record(
+                    &child_pointer(pointer, key),
+                    DiffKind::Added,
+                    None,
+                    Some(right_value),
+                );
+            }
+        }
+    }
+
+    fn compare_ordered(&mut self, left: &[Value], right: &[Value], pointer: &str) {
+        for index in 0..left.len().max(right.len()) {
+            let item_pointer = index_pointer(pointer, index);
+            match (left.get(index), right.get(index)) {
+                (Some(l), Some(r)) => self.compare(l, r, &item_pointer),
+                (Some(l), None) => self.record(&item_pointer, DiffKind::Removed, Some(l), None),
+                (None, Some(r)) => self.record(&item_pointer, DiffKind::Added, None, Some(r)),
+                (None, None) => {}
+            }
+        }
+    }
+
+    /// Multiset comparison: equal elements cancel out regardless of position,
+    /// the rest are reported as removed (left index) or added (right index).
+    fn compare_unordered(&mut self, left: &[Value], right: &[Value], pointer: &str) {
+        let mut unmatched: HashMap<String, VecDeque<usize>> = HashMap::new();
+        for (index, item) in right.iter().enumerate() {
+            unmatched
+                .entry(self.comparison_key(item))
+                .or_default()
+                .push_back(index);
+        }
+        let mut removed = Vec::new();
+        for (index, item) in left.iter().enumerate() {
+            let key = self.comparison_key(item);
+            if unmatched
+                .get_mut(&key)
+                .and_then(VecDeque::pop_front)
+                .is_none()
+            {
+                removed.push(index);
+            }
+        }
+        let mut added: Vec<usize> = unmatched.into_values().flatten().collect();
+        added.sort_unstable();
+
+        for index in removed {
+            self.record(
+                &index_pointer(pointer, index),
+                DiffKind::Removed,
+                Some(&left[index]),
+                None,
+            );
+        }
+        for index in added {
+            self.record(
+                &index_pointer(pointer, index),
+                DiffKind::Added,
+                None,
+                Some(&right[index]),
+            );
+        }
+    }
+
+    /// Rendering that is equal for two values exactly when `compare` would
+    /// report no differences between them.
+    fn comparison_key(&self, value: &Value) -> String {
+        match value {
+            Value::Number(n) if self.numeric_equality => canonical_number(n),
+            Value::Array(items) => {
+                let mut keys: Vec<String> =
+                    items.iter().map(|item| self.comparison_key(item)).collect();
+                if self.ignore_array_order {
+                    keys.sort();
+                }
+                format!("[{}]", keys.join(","))
+            }
+            Value::Object(map) => {
+                let mut members: Vec<String> = map
+                    .iter()
+                    .filter(|(key, _)| !self.is_ignored(key))
+                    .map(|(key, item)| {
+                        format!(
+                            "{}:{}",
+                            Value::from(key.as_str()),
+                            self.comparison_key(item)
+                        )
+                    })
+                    .collect();
+                members.sort();
+                format!("{{{}}}", members.join(","))
+            }
+            other => other.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EquivalenceOptions {
+    /// Compare arrays as multisets instead of position by position.
+    pub ignore_array_order: bool,
+    /// Numbers whose difference is within this tolerance are equal.
+    pub numeric_epsilon: Option<f64>,
+    pub case_insensitive_strings: bool,
+    /// Pointers skipped on both sides.
+    pub exclude_pointers: Vec<String>,
+}
+
+#[derive(Serialize, Default)]
+pub struct EquivalenceResult {
+    pub equivalent: bool,
+    pub divergent_pointer: Option<String>,
+    pub left_error: Option<String>,
+    pub right_error: Option<String>,
+}
+
+/// Check whether two documents are semantically the same under the given
+/// tolerances, stopping at the first divergence. Parse failures are reported
+/// per side rather than as a command error.
+#[tauri::command]
+pub fn json_equivalent(left: &str, right: &str, options: EquivalenceOptions) -> EquivalenceResult {
+    let (left, right) = match (parse_to_value(left), parse_to_value(right)) {
+        (Ok(left), Ok(right)) => (left, right),
+        (left, right) => {
+            return EquivalenceResult {
+                left_error: left.err().map(String::from),
+                right_error: right.err().map(String::from),
+                ..Default::default()
+            };
+        }
+    };
+
+    let checker = EquivalenceChecker {
+        excluded: options
+            .exclude_pointers
+            .iter()
+            .map(String::as_str)
+            .collect(),
+        options: &options,
+    };
+    let divergent_pointer = checker.first_divergence(&left, &right, "");
+    EquivalenceResult {
+        equivalent: divergent_pointer.is_none(),
+        divergent_pointer,
+        ..Default::default()
+    }
+}
+
+struct EquivalenceChecker<'a> {
+    options: &'a EquivalenceOptions,
+    excluded: HashSet<&'a str>,
+}
+
+impl EquivalenceChecker<'_> {
+    fn first_divergence(&self, left: &Value, right: &Value, pointer: &str) -> Option<String> {
+        if self.excluded.contains(pointer) {
+            return None;
+        }
+        let equal = match (left, right) {
+            (Value::Object(left_map), Value::Object(right_map)) => {
+                return self.object_divergence(left_map, right_map, pointer);
+            }
+            (Value::Array(left_items), Value::Array(right_items)) => {
+                return if self.options.ignore_array_order {
+                    self.unordered_divergence(left_items, right_items, pointer)
+                } else {
+                    self.ordered_divergence(left_items, right_items, pointer)
+                };
+            }
+            (Value::Number(a), Value::Number(b)) => self.numbers_equivalent(a, b),
+            (Value::String(a), Value::String(b)) if self.options.case_insensitive_strings => {
+                a.to_lowercase() == b.to_lowercase()
+            }
+            _ => left == right,
+        };
+        (!equal).then(|| pointer.to_string())
+    }
+
+    fn numbers_equivalent(&self, left: &Number, right: &Number) -> bool {
+        if canonical_number(left) == canonical_number(right) {
+            return true;
+        }
+        match (self.options.numeric_epsilon, left.as_f64(), right.as_f64()) {
+            (Some(epsilon), Some(a), Some(b)) => (a - b).abs() <= epsilon,
+            _ => false,
+        }
+    }
+
+    fn object_divergence(
+        &self,
+        left: &Map<String, Value>,
+        right: &Map<String, Value>,
+        pointer: &str,
+    ) -> Option<String> {
+        for (key, left_value) in left {
+            let member_pointer = child_pointer(pointer, key);
+            let divergence = match right.get(key) {
+                Some(right_value) => {
+                    self.first_divergence(left_value, right_value, &member_pointer)
+                }
+                None if self.excluded.contains(member_pointer.as_str()) => None,
+                None => Some(member_pointer),
+            };
+            if divergence.is_some() {
+                return divergence;
+            }
+        }
+        right
+            .keys()
+            .filter(|key| !left.contains_key(*key))
+            .map(|key| child_pointer(pointer, key))
+            .find(|member_pointer| !self.excluded.contains(member_pointer.as_str()))
+    }
+
+    fn ordered_divergence(&self, left: &[Value], right: &[Value], pointer: &str) -> Option<String> {
+        for (index, (l, r)) in left.iter().zip(right).enumerate() {
+            let divergence = self.first_divergence(l, r, &index_pointer(pointer, index));
+            if divergence.is_some() {
+                return divergence;
+            }
+        }
+        (left.len() != right.len()).then(|| index_pointer(pointer, left.len().min(right.len())))
+    }
+
+    /// Greedy multiset matching. Tolerances make equivalence non-transitive,
+    /// so elements are matched pairwise rather than by hashing.
+    fn unordered_divergence(
+        &self,
+        left: &[Value],
+        right: &[Value],
+        pointer: &str,
+    ) -> Option<String> {
+        if left.len() != right.len() {
+            return Some(pointer.to_string());
+        }
+        let mut used = vec![false; right.len()];
+        for (index, item) in left.iter().enumerate() {
+            let item_pointer = index_pointer(pointer, index);
+            let matched = (0..right.len()).find(|&candidate| {
+                !used[candidate]
+                    && self
+                        .first_divergence(item, &right[candidate], &item_pointer)
+                        .is_none()
+            });
+            match matched {
+                Some(candidate) => used[candidate] = true,
+                None => return Some(item_pointer),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        json_diff, json_equivalent, DiffKind, DiffOptions, DiffResult, EquivalenceOptions,
+    };
+    use serde_json::{json, Value};
+
+    fn diff(left: Value, right: Value, options: DiffOptions) -> DiffResult {
+        json_diff(&left.to_string(), &right.to_string(), options).unwrap()
+    }
+
+    fn kinds(result: &DiffResult) -> Vec<(&str, DiffKind)> {
+        result
+            .entries
+            .iter()
+            .map(|entry| (entry.pointer.as_str(), entry.kind))
+            .collect()
+    }
+
+    #[test]
+    fn reports_each_kind_of_difference_in_document_order() {
+        let result = diff(
+            json!({"same": 1, "gone": true, "v": "a", "t": 1, "list": [1, 2]}),
+            json!({"t": "1", "v": "b", "same": 1, "list": [1], "new": null}),
+            DiffOptions::default(),
+        );
+
+        assert_eq!(
+            kinds(&result),
+            vec![
+                ("/gone", DiffKind::Removed),
+                ("/v", DiffKind::Changed),
+                ("/t", DiffKind::TypeChanged),
+                ("/list/1", DiffKind::Removed),
+                ("/new", DiffKind::Added),
+            ]
+        );
+        assert_eq!(result.entries[1].left_preview.as_deref(), Some("\"a\""));
+        assert_eq!(result.summary.total, 5);
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn applies_comparison_options() {
+        let left = json!({"n": 1, "items": [{"id": 1}, {"id": 2, "at": "t1"}], "updatedAt": "x"});
+        let right =
+            json!({"n": 1.0, "items": [{"id": 2, "at": "t2"}, {"id": 1}], "updatedAt": "y"});
+
+        let strict = diff(left.clone(), right.clone(), DiffOptions::default());
+        assert_eq!(strict.summary.changed, 4);
+        assert_eq!(strict.summary.total, 6);
+
+        let relaxed = diff(
+            left,
+            right,
+            DiffOptions {
+                ignore_array_order: true,
+                numeric_equality: true,
+                ignore_keys: vec!["(?i)at$".to_string()],
+                ..Default::default()
+            },
+        );
+        assert_eq!(relaxed.summary.total, 0);
+    }
+
+    #[test]
+    fn unordered_arrays_report_unmatched_elements() {
+        let result = diff(
+            json!([1, 2, 2, 3]),
+            json!([3, 2, 4, 1]),
+            DiffOptions {
+                ignore_array_order: true,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            kinds(&result),
+            vec![("/2", DiffKind::Removed), ("/2", DiffKind::Added)]
+        );
+    }
+
+    #[test]
+    fn pages_through_large_diffs() {
+        let left = json!((0..50).collect::<Vec<_>>());
+        let right = json!((100..150).collect::<Vec<_>>());
+
+        let page = diff(
+            left,
+            right,
+            DiffOptions {
+                offset: 10,
+                limit: Some(5),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(page.summary.total, 50);
+        assert_eq!(page.entries.len(), 5);
+        assert_eq!(page.entries[0].pointer, "/10");
+        assert!(page.truncated);
+    }
+
+    fn equivalent(left: Value, right: Value, options: EquivalenceOptions) -> Option<String> {
+        let result = json_equivalent(&left.to_string(), &right.to_string(), options);
+        assert_eq!(result.equivalent, result.divergent_pointer.is_none());
+        result.divergent_pointer
+    }
+
+    #[test]
+    fn finds_the_first_divergent_pointer() {
+        assert_eq!(
+            equivalent(
+                json!({"b": 1, "a": 1.0}),
+                json!({"a": 1, "b": 1}),
+                Default::default()
+            ),
+            None
+        );
+        assert_eq!(
+            equivalent(
+                json!({"a": [1, 2, 3], "b": "x"}),
+                json!({"a": [1, 5, 3], "b": "y"}),
+                Default::default()
+            ),
+            Some("/a/1".to_string())
+        );
+        assert_eq!(
+            equivalent(json!([1]), json!([1, 2]), Default::default()),
+            Some("/1".to_string())
+        );
+    }
+
+    #[test]
+    fn applies_tolerances_and_exclusions() {
+        let left = json!({"tags": ["A", "b"], "score": 0.30000000000000004, "meta": {"at": 1}});
+        let right = json!({"tags": ["B", "a"], "score": 0.3, "meta": {"at": 2}});
+
+        assert!(equivalent(left.clone(), right.clone(), Default::default()).is_some());
+        assert_eq!(
+            equivalent(
+                left,
+                right,
+                EquivalenceOptions {
+                    ignore_array_order: true,
+                    numeric_epsilon: Some(1e-9),
+                    case_insensitive_strings: true,
+                    exclude_pointers: vec!["/meta/at".to_string()],
+                }
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors_for_each_side() {
+        let result = json_equivalent("{oops", "[1,", Default::default());
+
+        assert!(!result.equivalent);
+        assert!(result.left_error.is_some());
+        assert!(result.right_error.is_some());
+
+        let one_side = json_equivalent("{}", "[1,", Default::default());
+        assert!(one_side.left_error.is_none());
+        assert!(one_side.right_error.is_some());
+    }
+}
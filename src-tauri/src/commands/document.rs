@@ -0,0 +1,429 @@
+// Backend document commands
+//
+// Parsed documents kept in managed state so views over large files (the
+// virtualized tree) can fetch pieces without sending the text over IPC again,
+// and the heavy commands can reuse the parsed value instead of parsing the
+// same text on every call. Each document has its own lock, so reads of one
+// document run in parallel and only an update waits for them.
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::State;
+
+use super::file::read_text;
+use super::incremental::EditSession;
+use super::json::parse_to_value;
+use crate::json_pointer::{child_pointer, index_pointer, resolve};
+use crate::json_value::{value_preview, value_type_name};
+
+const PREVIEW_CHARS: usize = 120;
+/// Combined source size of the open documents; past it the least recently
+/// used ones are closed.
+const MAX_DOCUMENT_BYTES: usize = 512 * 1024 * 1024;
+
+/// A document's text together with its parsed value. The text is kept because
+/// formatting and statistics preserve number literals exactly as written.
+pub(crate) struct Document {
+    pub(crate) content: String,
+    pub(crate) value: Value,
+    /// Whether the text is standard JSON rather than JSON5.
+    pub(crate) strict: bool,
+    hash: u64,
+}
+
+impl Document {
+    pub(crate) fn parse(content: String) -> Result<Self, String> {
+        let (value, strict) = match serde_json::from_str::<Value>(&content) {
+            Ok(value) => (value, true),
+            Err(_) => (parse_to_value(&content)?, false),
+        };
+        Ok(Self {
+            hash: content_hash(&content),
+            content,
+            value,
+            strict,
+        })
+    }
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct Entry {
+    document: RwLock<Document>,
+    /// `DocumentStore::clock` at the last access, for eviction.
+    last_used: AtomicU64,
+    bytes: AtomicUsize,
+    /// The editor's text as `json_validate_incremental` applies its edits.
+    session: Mutex<Option<EditSession>>,
+}
+
+pub(crate) struct DocumentStore {
+    documents: Mutex<HashMap<String, Arc<Entry>>>,
+    next_id: AtomicU64,
+    clock: AtomicU64,
+    max_bytes: usize,
+}
+
+impl Default for DocumentStore {
+    fn default() -> Self {
+        Self::with_limit(MAX_DOCUMENT_BYTES)
+    }
+}
+
+impl DocumentStore {
+    fn with_limit(max_bytes: usize) -> Self {
+        Self {
+            documents: Mutex::default(),
+            next_id: AtomicU64::new(0),
+            clock: AtomicU64::new(0),
+            max_bytes,
+        }
+    }
+
+    pub(crate) fn insert(&self, document: Document) -> String {
+        let id = self.reserve_id();
+        self.insert_as(id.clone(), document);
+        id
+    }
+
+    /// Hand out an id before the document exists, for loads that report
+    /// progress (and can be cancelled) under that id.
+    pub(crate) fn reserve_id(&self) -> String {
+        format!("doc-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    pub(crate) fn insert_as(&self, doc_id: String, document: Document) {
+        let entry = Arc::new(Entry {
+            bytes: AtomicUsize::new(document.content.len()),
+            document: RwLock::new(document),
+            last_used: AtomicU64::new(self.tick()),
+            session: Mutex::new(None),
+        });
+        let mut documents = self.documents.lock().unwrap();
+        documents.insert(doc_id.clone(), entry);
+        self.evict(&mut documents, &doc_id);
+    }
+
+    /// Replace a document's text; it is only parsed again when the text
+    /// changed. Returns whether it did.
+    pub(crate) fn update(&self, doc_id: &str, content: String) -> Result<bool, String> {
+        let entry = self.entry(doc_id)?;
+        *entry.session.lock().unwrap() = None;
+        if entry.document.read().unwrap().hash == content_hash(&content) {
+            entry.bytes.store(content.len(), Ordering::Relaxed);
+            return Ok(false);
+        }
+        // Parse outside the lock so readers of the old text aren't blocked.
+        let document = Document::parse(content)?;
+        entry.bytes.store(document.content.len(), Ordering::Relaxed);
+        *entry.document.write().unwrap() = document;
+        self.evict(&mut self.documents.lock().unwrap(), doc_id);
+        Ok(true)
+    }
+
+    /// Run `read` on a document. Readers share its lock, so callers can run
+    /// concurrently; a document closed meanwhile stays alive until they finish.
+    pub(crate) fn read<T>(
+        &self,
+        doc_id: &str,
+        read: impl FnOnce(&Document) -> T,
+    ) -> Result<T, String> {
+        let entry = self.entry(doc_id)?;
+        let document = entry.document.read().unwrap();
+        Ok(read(&document))
+    }
+
+    /// Run `edit` on the editor's text for a document, which starts out as
+    /// the document's text and starts over on `update`. Edits of one
+    /// document run one at a time.
+    pub(crate) fn edit_session<T>(
+        &self,
+        doc_id: &str,
+        edit: impl FnOnce(&mut EditSession) -> T,
+    ) -> Result<T, String> {
+        let entry = self.entry(doc_id)?;
+        let mut session = entry.session.lock().unwrap();
+        let session = session.get_or_insert_with(|| {
+            EditSession::new(entry.document.read().unwrap().content.clone())
+        });
+        let edited = edit(session);
+        let content_len = entry.document.read().unwrap().content.len();
+        entry
+            .bytes
+            .store(content_len + session.text_len(), Ordering::Relaxed);
+        self.evict(&mut self.documents.lock().unwrap(), doc_id);
+        Ok(edited)
+    }
+
+    pub(crate) fn remove(&self, doc_id: &str) -> bool {
+        self.documents.lock().unwrap().remove(doc_id).is_some()
+    }
+
+    fn entry(&self, doc_id: &str) -> Result<Arc<Entry>, String> {
+        let entry = self
+            .documents
+            .lock()
+            .unwrap()
+            .get(doc_id)
+            .cloned()
+            .ok_or_else(|| format!("Unknown document: {doc_id}"))?;
+        entry.last_used.store(self.tick(), Ordering::Relaxed);
+        Ok(entry)
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Close the least recently used documents until the rest fit the limit.
+    /// `keep` is the document just stored, which stays even if it alone is
+    /// over the limit.
+    fn evict(&self, documents: &mut HashMap<String, Arc<Entry>>, keep: &str) {
+        let size = |entry: &Arc<Entry>| entry.bytes.load(Ordering::Relaxed);
+        let mut total: usize = documents.values().map(size).sum();
+        while total > self.max_bytes {
+            let Some(oldest) = documents
+                .iter()
+                .filter(|(id, _)| id.as_str() != keep)
+                .min_by_key(|(_, entry)| entry.last_used.load(Ordering::Relaxed))
+                .map(|(id, _)| id.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = documents.remove(&oldest) {
+                total -= size(&entry);
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct TreeChild {
+    /// Member name for object children.
+    pub key: Option<String>,
+    /// Position for array children.
+    pub index: Option<usize>,
+    pub pointer: String,
+    pub value_type: String,
+    /// Compact rendering of scalars; containers have none.
+    pub preview: Option<String>,
+    /// Members or items directly inside the child; 0 for scalars.
+    pub child_count: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TreeChildren {
+    pub children: Vec<TreeChild>,
+    /// Children of the node in total, for sizing the virtualized list.
+    pub total: usize,
+}
+
+/// Parse a document and keep it in backend state; returns its id
+///
+/// The text comes from `content` or, when that is absent, from the file at
+/// `path`.
+#[tauri::command]
+pub async fn open_document(
+    content: Option<String>,
+    path: Option<String>,
+    store: State<'_, DocumentStore>,
+) -> Result<String, String> {
+    let content = match (content, path) {
+        (Some(content), _) => content,
+        (None, Some(path)) => read_text(Path::new(&path)).await?.content,
+        (None, None) => return Err("Either content or path is required".to_string()),
+    };
+    Ok(store.insert(Document::parse(content)?))
+}
+
+/// Replace the text of an open document; returns whether it changed
+#[tauri::command]
+pub fn update_document(
+    doc_id: String,
+    content: String,
+    store: State<'_, DocumentStore>,
+) -> Result<bool, String> {
+    store.update(&doc_id, content)
+}
+
+/// Drop a document from backend state
+#[tauri::command]
+pub fn close_document(doc_id: String, store: State<'_, DocumentStore>) -> bool {
+    store.remove(&doc_id)
+}
+
+/// Page through the direct children of the node at `pointer`
+///
+/// `pointer` `""` lists the root's children. Up to `limit` children are
+/// returned starting at `offset`.
+#[tauri::command]
+pub fn json_tree_children(
+    doc_id: String,
+    pointer: String,
+    offset: usize,
+    limit: usize,
+    store: State<'_, DocumentStore>,
+) -> Result<TreeChildren, String> {
+    store.read(&doc_id, |document| {
+        tree_children(resolve(&document.value, &pointer)?, &pointer, offset, limit)
+    })?
+}
+
+fn tree_children(
+    node: &Value,
+    pointer: &str,
+    offset: usize,
+    limit: usize,
+) -> Result<TreeChildren, String> {
+    let (children, total) = match node {
+        Value::Array(items) => (
+            items
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(limit)
+                .map(|(index, item)| {
+                    tree_child(None, Some(index), index_pointer(pointer, index), item)
+                })
+                .collect(),
+            items.len(),
+        ),
+        Value::Object(map) => (
+            map.iter()
+                .skip(offset)
+                .take(limit)
+                .map(|(key, child)| {
+                    tree_child(Some(key.clone()), None, child_pointer(pointer, key), child)
+                })
+                .collect(),
+            map.len(),
+        ),
+        scalar => {
+            return Err(format!(
+                "Expected an object or array at \"{pointer}\", found {}",
+                value_type_name(scalar)
+            ))
+        }
+    };
+    Ok(TreeChildren { children, total })
+}
+
+fn tree_child(
+    key: Option<String>,
+    index: Option<usize>,
+    pointer: String,
+    value: &Value,
+) -> TreeChild {
+    let (preview, child_count) = match value {
+        Value::Array(items) => (None, items.len()),
+        Value::Object(map) => (None, map.len()),
+        scalar => (Some(value_preview(scalar, PREVIEW_CHARS)), 0),
+    };
+    TreeChild {
+        key,
+        index,
+        pointer,
+        value_type: value_type_name(value).to_string(),
+        preview,
+        child_count,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tree_children, Document, DocumentStore};
+    use serde_json::json;
+
+    #[test]
+    fn pages_through_children_with_previews_and_counts() {
+        let value = json!({"a/b": [1, {"x": 1}, [], "s"], "n": null});
+
+        let root = tree_children(&value, "", 0, 10).unwrap();
+        assert_eq!(root.total, 2);
+        assert_eq!(root.children[0].key.as_deref(), Some("a/b"));
+        assert_eq!(root.children[0].pointer, "/a~1b");
+        assert_eq!(root.children[0].child_count, 4);
+        assert_eq!(root.children[1].preview.as_deref(), Some("null"));
+
+        let page = tree_children(&value["a/b"], "/a~1b", 1, 2).unwrap();
+        assert_eq!(page.total, 4);
+        let summary: Vec<_> = page
+            .children
+            .iter()
+            .map(|child| (child.index, child.value_type.as_str(), child.child_count))
+            .collect();
+        assert_eq!(summary, [(Some(1), "object", 1), (Some(2), "array", 0)]);
+
+        assert_eq!(
+            tree_children(&value["n"], "/n", 0, 10).unwrap_err(),
+            "Expected an object or array at \"/n\", found null"
+        );
+    }
+
+    fn parsed(content: &str) -> Document {
+        Document::parse(content.to_string()).unwrap()
+    }
+
+    #[test]
+    fn stores_documents_by_id() {
+        let store = DocumentStore::default();
+
+        let first = store.insert(parsed("[1]"));
+        let second = store.insert(parsed("[2]"));
+
+        assert_ne!(first, second);
+        assert_eq!(
+            store.read(&second, |doc| doc.value.clone()).unwrap(),
+            json!([2])
+        );
+        assert!(store.remove(&first));
+        assert_eq!(
+            store.read(&first, |_| ()).unwrap_err(),
+            format!("Unknown document: {first}")
+        );
+    }
+
+    #[test]
+    fn reparses_only_changed_content() {
+        let store = DocumentStore::default();
+        let id = store.insert(parsed("{\"a\": 1}"));
+
+        assert!(!store.update(&id, "{\"a\": 1}".to_string()).unwrap());
+        assert!(store.update(&id, "{a: 2, /* five */}".to_string()).unwrap());
+        let (value, strict) = store
+            .read(&id, |doc| (doc.value.clone(), doc.strict))
+            .unwrap();
+        assert_eq!((value, strict), (json!({"a": 2}), false));
+
+        assert!(store.update(&id, "{".to_string()).is_err());
+        assert_eq!(
+            store.read(&id, |doc| doc.value.clone()).unwrap(),
+            json!({"a": 2})
+        );
+    }
+
+    #[test]
+    fn evicts_least_recently_used_documents_over_the_limit() {
+        let store = DocumentStore::with_limit(12);
+        let first = store.insert(parsed("[1, 2]"));
+        let second = store.insert(parsed("[3, 4]"));
+        store.read(&first, |_| ()).unwrap();
+
+        let third = store.insert(parsed("[5]"));
+        assert!(store.read(&second, |_| ()).is_err());
+        assert!(store.read(&first, |_| ()).is_ok());
+
+        let large = store.insert(parsed("[1, 2, 3, 4, 5]"));
+        assert!(store.read(&large, |_| ()).is_ok());
+        assert!(store.read(&first, |_| ()).is_err() && store.read(&third, |_| ()).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+// Draft autosave commands
+//
+// Unsaved editor content is written under `app_data_dir/drafts/` so tabs can
+// be restored after a crash. Each draft is a content file plus a small
+// metadata file, so listing drafts never reads their content.
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::store::{load_store, save_store, write_atomic};
+
+/// Total size of all drafts; the oldest are evicted beyond this.
+const MAX_DRAFTS_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Serializes writes and evictions in the drafts directory.
+static DRAFTS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DraftInfo {
+    pub tab_id: String,
+    /// File the tab was opened from, if any.
+    pub file_path: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub modified: i64,
+    /// Content size in bytes.
+    pub size: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Draft {
+    #[serde(flatten)]
+    pub info: DraftInfo,
+    pub content: String,
+}
+
+/// Save the unsaved content of a tab
+#[tauri::command]
+pub fn save_draft(
+    app: AppHandle,
+    tab_id: String,
+    content: String,
+    file_path: Option<String>,
+) -> Result<(), String> {
+    let dir = drafts_dir(&app)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    save_draft_in(&dir, &tab_id, &content, file_path, now, MAX_DRAFTS_BYTES)
+}
+
+/// Drafts left from earlier sessions, newest first
+#[tauri::command]
+pub fn list_drafts(app: AppHandle) -> Result<Vec<DraftInfo>, String> {
+    Ok(list_drafts_in(&drafts_dir(&app)?))
+}
+
+/// Load a draft with its content
+#[tauri::command]
+pub fn load_draft(app: AppHandle, tab_id: String) -> Result<Draft, String> {
+    load_draft_in(&drafts_dir(&app)?, &tab_id)
+}
+
+/// Delete a draft once its tab is saved or closed; returns whether it existed
+#[tauri::command]
+pub fn delete_draft(app: AppHandle, tab_id: String) -> Result<bool, String> {
+    let dir = drafts_dir(&app)?;
+    let _guard = DRAFTS_LOCK.lock().unwrap();
+    Ok(remove_draft(&dir, &validate_tab_id(&tab_id)?))
+}
+
+fn drafts_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("drafts"))
+        .map_err(|e| format!("Failed to resolve data directory: {}", e))
+}
+
+/// Tab ids become file names, so only plain identifiers are accepted.
+fn validate_tab_id(tab_id: &str) -> Result<String, String> {
+    if tab_id.is_empty()
+        || !tab_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid draft id: {tab_id}"));
+    }
+    Ok(tab_id.to_string())
+}
+
+fn content_path(dir: &Path, tab_id: &str) -> PathBuf {
+    dir.join(format!("{tab_id}.draft"))
+}
+
+fn meta_path(dir: &Path, tab_id: &str) -> PathBuf {
+    dir.join(format!("{tab_id}.meta.json"))
+}
+
+fn save_draft_in(
+    dir: &Path,
+    tab_id: &str,
+    content: &str,
+    file_path: Option<String>,
+    now: i64,
+    max_bytes: u64,
+) -> Result<(), String> {
+    let tab_id = validate_tab_id(tab_id)?;
+    let _guard = DRAFTS_LOCK.lock().unwrap();
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create drafts directory: {}", e))?;
+    // Content first: metadata without its content is never listed.
+    write_atomic(&content_path(dir, &tab_id), content.as_bytes())
+        .map_err(|e| format!("Failed to save draft: {}", e))?;
+    let info = DraftInfo {
+        tab_id: tab_id.clone(),
+        file_path,
+        modified: now,
+        size: content.len() as u64,
+    };
+    save_store(&meta_path(dir, &tab_id), &info)?;
+    evict_oldest(dir, &tab_id, max_bytes);
+    Ok(())
+}
+
+/// Drop the oldest drafts, never the one just saved, until under `max_bytes`.
+fn evict_oldest(dir: &Path, keep: &str, max_bytes: u64) {
+    let drafts = list_drafts_in(dir);
+    let mut total: u64 = drafts.iter().map(|draft| draft.size).sum();
+    for draft in drafts.iter().rev() {
+        if total <= max_bytes {
+            break;
+        }
+        if draft.tab_id != keep {
+            remove_draft(dir, &draft.tab_id);
+            total -= draft.size;
+        }
+    }
+}
+
+fn list_drafts_in(dir: &Path) -> Vec<DraftInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut drafts: Vec<DraftInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let tab_id = name.strip_suffix(".meta.json")?;
+            let info: DraftInfo = load_store(&entry.path());
+            (info.tab_id == tab_id && content_path(dir, tab_id).is_file()).then_some(info)
+        })
+        .collect();
+    drafts.sort_by_key(|draft| std::cmp::Reverse(draft.modified));
+    drafts
+}
+
+fn load_draft_in(dir: &Path, tab_id: &str) -> Result<Draft, String> {
+    let tab_id = validate_tab_id(tab_id)?;
+    let content = std::fs::read_to_string(content_path(dir, &tab_id))
+        .map_err(|_| format!("Unknown draft: {tab_id}"))?;
+    let info = load_store(&meta_path(dir, &tab_id));
+    Ok(Draft { info, content })
+}
+
+fn remove_draft(dir: &Path, tab_id: &str) -> bool {
+    let removed = std::fs::remove_file(content_path(dir, tab_id)).is_ok();
+    let _ = std::fs::remove_file(meta_path(dir, tab_id));
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list_drafts_in, load_draft_in, remove_draft, save_draft_in};
+
+    #[test]
+    fn saves_lists_and_loads_drafts_newest_first() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        save_draft_in(dir, "tab-1", "{\"a\":", Some("/a.json".into()), 1, 1000).unwrap();
+        save_draft_in(dir, "tab-2", "[1]", None, 2, 1000).unwrap();
+
+        let listed: Vec<_> = list_drafts_in(dir)
+            .into_iter()
+            .map(|draft| (draft.tab_id, draft.size))
+            .collect();
+        assert_eq!(listed, [("tab-2".to_string(), 3), ("tab-1".to_string(), 5)]);
+
+        let draft = load_draft_in(dir, "tab-1").unwrap();
+        assert_eq!(draft.content, "{\"a\":");
+        assert_eq!(draft.info.file_path.as_deref(), Some("/a.json"));
+
+        assert!(remove_draft(dir, "tab-1"));
+        assert_eq!(
+            load_draft_in(dir, "tab-1").unwrap_err(),
+            "Unknown draft: tab-1"
+        );
+        assert!(load_draft_in(dir, "../secrets").is_err());
+    }
+
+    #[test]
+    fn evicts_the_oldest_drafts_beyond_the_size_cap() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        save_draft_in(dir, "old", "aaaa", None, 1, 10).unwrap();
+        save_draft_in(dir, "mid", "bbbb", None, 2, 10).unwrap();
+        save_draft_in(dir, "new", "cccc", None, 3, 10).unwrap();
+
+        let kept: Vec<_> = list_drafts_in(dir)
+            .into_iter()
+            .map(|draft| draft.tab_id)
+            .collect();
+        assert_eq!(kept, ["new", "mid"]);
+    }
+}
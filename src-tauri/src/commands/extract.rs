@@ -0,0 +1,231 @@
+// JSON extraction commands
+//
+// Pulls JSON payloads out of text that isn't JSON as a whole, such as log
+// lines with a timestamp prefix. Candidates are found by bracket matching
+// that skips over strings, then kept only if they parse.
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+/// Smallest region kept by default; `{}` and `[]` alone are rarely payloads.
+const DEFAULT_MIN_SIZE: usize = 3;
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ExtractedJson {
+    /// Byte offset of the opening bracket in the text or file.
+    pub offset: usize,
+    /// Length of the region in bytes.
+    pub length: usize,
+    pub value: Value,
+}
+
+/// Find every balanced `{...}` or `[...]` region in `content` that parses as
+/// JSON, in text order
+///
+/// Regions shorter than `min_size` bytes are skipped. A region nested inside
+/// one that was extracted is not reported separately.
+#[tauri::command]
+pub fn extract_json_from_text(content: &str, min_size: Option<usize>) -> Vec<ExtractedJson> {
+    let mut found = Vec::new();
+    extract_into(content, 0, min_size.unwrap_or(DEFAULT_MIN_SIZE), &mut found);
+    found
+}
+
+/// Extract JSON from a file line by line, so large logs are never held in
+/// memory whole. A payload that spans several lines is not found.
+#[tauri::command]
+pub async fn extract_json_from_file(
+    path: String,
+    min_size: Option<usize>,
+) -> Result<Vec<ExtractedJson>, String> {
+    let min_size = min_size.unwrap_or(DEFAULT_MIN_SIZE);
+    tokio::task::spawn_blocking(move || {
+        let file = File::open(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+        extract_from_reader(BufReader::new(file), min_size)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn extract_from_reader(
+    mut reader: impl BufRead,
+    min_size: usize,
+) -> Result<Vec<ExtractedJson>, String> {
+    let mut found = Vec::new();
+    let mut line = Vec::new();
+    let mut line_start = 0;
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            return Ok(found);
+        }
+        // Offsets are only exact on valid UTF-8 lines; elsewhere the lossy
+        // replacement can shift them.
+        extract_into(
+            &String::from_utf8_lossy(&line),
+            line_start,
+            min_size,
+            &mut found,
+        );
+        line_start += read;
+    }
+}
+
+fn extract_into(text: &str, base: usize, min_size: usize, found: &mut Vec<ExtractedJson>) {
+    let bytes = text.as_bytes();
+    let mut brackets = BracketMatcher {
+        bytes,
+        ends: HashMap::new(),
+    };
+    let mut pos = 0;
+    while let Some(start) = bytes[pos..]
+        .iter()
+        .position(|&b| b == b'{' || b == b'[')
+        .map(|index| pos + index)
+    {
+        let parsed = brackets.end_of(start).and_then(|end| {
+            let slice = &text[start..end];
+            let value = serde_json::from_str::<Value>(slice).ok()?;
+            Some((end, value))
+        });
+        match parsed {
+            Some((end, value)) if end - start >= min_size => {
+                found.push(ExtractedJson {
+                    offset: base + start,
+                    length: end - start,
+                    value,
+                });
+                pos = end;
+            }
+            // Too small, or not JSON: an inner bracket may still start a payload.
+            _ => pos = start + 1,
+        }
+    }
+}
+
+/// Finds where bracketed regions end. Brackets inside strings are ignored.
+///
+/// A scan from one opener also settles every opener it meets outside a
+/// string: from there on both scans agree on what is a string, so the inner
+/// region ends where the outer scan closes it, and fails where it fails. Each
+/// byte is then scanned about once instead of once per enclosing opener.
+struct BracketMatcher<'a> {
+    bytes: &'a [u8],
+    /// End (exclusive) of each region scanned so far; `None` when unbalanced.
+    ends: HashMap<usize, Option<usize>>,
+}
+
+impl BracketMatcher<'_> {
+    fn end_of(&mut self, start: usize) -> Option<usize> {
+        if !self.ends.contains_key(&start) {
+            self.scan(start);
+        }
+        self.ends[&start]
+    }
+
+    fn scan(&mut self, start: usize) {
+        // Openers still open, with the closer each expects.
+        let mut open: Vec<(usize, u8)> = Vec::new();
+        let mut in_string = false;
+        let mut escaped = false;
+        for (index, &byte) in self.bytes.iter().enumerate().skip(start) {
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                b'"' => in_string = true,
+                b'{' => open.push((index, b'}')),
+                b'[' => open.push((index, b']')),
+                b'}' | b']' => {
+                    match open.last() {
+                        Some(&(opener, closer)) if closer == byte => {
+                            open.pop();
+                            self.ends.insert(opener, Some(index + 1));
+                        }
+                        _ => break,
+                    }
+                    if open.is_empty() {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (opener, _) in open {
+            self.ends.insert(opener, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_from_reader, extract_json_from_text};
+    use serde_json::json;
+
+    #[test]
+    fn extracts_payloads_after_log_prefixes() {
+        let log = "2024-01-01 [INFO] request {\"id\": 1, \"path\": \"/a}[\"}\n\
+                   2024-01-01 [WARN] retry [1, 2] {}\n";
+        let found = extract_json_from_text(log, None);
+        let summary: Vec<_> = found
+            .iter()
+            .map(|item| (&log[item.offset..item.offset + item.length], &item.value))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "{\"id\": 1, \"path\": \"/a}[\"}",
+                    &json!({"id": 1, "path": "/a}["})
+                ),
+                ("[1, 2]", &json!([1, 2])),
+            ]
+        );
+
+        assert_eq!(extract_json_from_text(log, Some(2)).len(), 3);
+    }
+
+    #[test]
+    fn finds_payloads_inside_unbalanced_or_invalid_regions() {
+        let found = extract_json_from_text("[tag {\"a\": [1]} {not json}", None);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].offset, &found[0].value), (5, &json!({"a": [1]})));
+    }
+
+    #[test]
+    fn reports_file_offsets_across_lines() {
+        let content = "first {\"n\": 1}\r\nsecond {\"n\": 2}\n";
+        let found = extract_from_reader(content.as_bytes(), 3).unwrap();
+        let offsets: Vec<_> = found.iter().map(|item| item.offset).collect();
+        assert_eq!(offsets, [6, 23]);
+        assert_eq!(&content[23..31], "{\"n\": 2}");
+    }
+
+    #[test]
+    fn scans_deeply_unbalanced_text_once() {
+        let content = format!(
+            "{}{{\"a\": 1}} {}",
+            "[".repeat(200_000),
+            "}".repeat(200_000)
+        );
+
+        let found = extract_json_from_text(&content, None);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            (found[0].offset, &found[0].value),
+            (200_000, &json!({"a": 1}))
+        );
+    }
+}
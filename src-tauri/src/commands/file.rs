@@ -1,9 +1,37 @@
 // File operation commands
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+use serde::de::{self, Deserializer as _, SeqAccess, Visitor};
 use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
-use tauri_plugin_dialog::DialogExt;
+use serde_json::ser::PrettyFormatter;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Window};
+
+use super::convert::{to_csv, to_yaml};
+use super::document::{Document, DocumentStore};
+use super::history::record_snapshot;
+use super::json::{format_json, minify_json, parse_to_value};
+use super::logging::LogFailure;
+use super::mapped::{file_too_large, map_file};
+use super::progress::ProgressGuard;
+use super::results::{deliver_text, DeliveredText};
+use super::settings::{setting, MAX_EDITOR_FILE_BYTES_SETTING};
+use super::store::{last_directory, record_recent_file, remember_directory, write_atomic};
+use crate::error::{AppError, ErrorCode};
+use crate::json_writer::{write_value, RawNumbers};
+use crate::text_encoding::{self, DecodedText, LineEndingMode, TextEncoding};
+use tauri_plugin_dialog::{DialogExt, FileDialogBuilder};
 use tauri_plugin_opener::OpenerExt;
+use walkdir::WalkDir;
 
 pub(crate) const JSON_FILE_EXTENSIONS: &[&str] = &[
     "json",
@@ -19,6 +47,49 @@ pub(crate) const JSON_FILE_EXTENSIONS: &[&str] = &[
     "sarif",
 ];
 
+const GZIP_EXTENSION: &str = "gz";
+const GZIP_MAGIC: &[u8] = &[0x1F, 0x8B];
+
+/// Dialog filter: the JSON extensions plus gzip-compressed JSON. A bare `gz`
+/// would offer every gzip file, tarballs included.
+pub(crate) fn dialog_extensions() -> Vec<&'static str> {
+    let mut extensions = JSON_FILE_EXTENSIONS.to_vec();
+    extensions.push("json.gz");
+    extensions
+}
+
+/// A file dialog that starts where the last one left off, or wherever the
+/// OS chooses when that directory is gone.
+pub(crate) fn file_dialog(app: &AppHandle) -> FileDialogBuilder<tauri::Wry> {
+    let dialog = app.dialog().file();
+    match last_directory(app) {
+        Some(dir) => dialog.set_directory(dir),
+        None => dialog,
+    }
+}
+
+pub(crate) fn remember_parent(app: &AppHandle, path: &str) {
+    if let Some(parent) = Path::new(path).parent() {
+        remember_directory(app, parent);
+    }
+}
+
+/// Save name proposed for a file opened as `name` (a name or a path):
+/// `data.json.gz` and `data.json5` become `data<suffix>.json`.
+fn suggested_file_name(name: &str, suffix: &str) -> String {
+    let name = Path::new(name)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = name.strip_suffix(".gz").unwrap_or(&name);
+    let stem = Path::new(name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or_else(|| "untitled".to_string());
+    format!("{stem}{suffix}.json")
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct FileNode {
     pub name: String,
@@ -27,90 +98,413 @@ pub struct FileNode {
     pub children: Option<Vec<FileNode>>,
 }
 
+/// A file that couldn't be read or parsed in a batch operation.
+#[derive(Serialize, Debug)]
+pub struct FileFailure {
+    pub path: String,
+    /// The `ErrorCode` of `error`, e.g. `file_too_large_for_editing`.
+    pub code: &'static str,
+    pub error: String,
+}
+
+impl FileFailure {
+    fn new(path: String, error: AppError) -> Self {
+        Self {
+            path,
+            code: error.code.as_str(),
+            error: error.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct ReadFilesResult {
+    /// Files read successfully, as `(path, text)` in the order requested.
+    pub files: Vec<(String, DeliveredText)>,
+    pub failures: Vec<FileFailure>,
+}
+
 /// Open a JSON file using file picker dialog
 #[tauri::command]
-pub async fn open_file_dialog(app: AppHandle) -> Result<Option<(String, String)>, String> {
-    let file_path = app.dialog()
-        .file()
-        .add_filter("JSON Files", JSON_FILE_EXTENSIONS)
-        .add_filter("All Files", &["*"])
-        .blocking_pick_file();
-
-    match file_path {
-        Some(path) => {
-            let path_str = path.to_string();
-            let path_buf = PathBuf::from(&path_str);
-            match tokio::fs::read_to_string(&path_buf).await {
-                Ok(content) => Ok(Some((path_str, content))),
-                Err(e) => Err(format!("Failed to read file: {}", e)),
+pub async fn open_file_dialog(
+    app: AppHandle,
+) -> Result<Option<(String, DeliveredText)>, AppError> {
+    async {
+        let file_path = file_dialog(&app)
+            .add_filter("JSON Files", &dialog_extensions())
+            .add_filter("All Files", &["*"])
+            .blocking_pick_file();
+
+        match file_path {
+            Some(path) => {
+                let path_str = path.to_string();
+                remember_parent(&app, &path_str);
+                let path_buf = PathBuf::from(&path_str);
+                check_editor_limit(&app, &path_buf).await?;
+                let text = read_text(&path_buf).await?;
+                record_recent_file(&app, &path_str);
+                Ok(Some((path_str, deliver_text(&app, text))))
             }
+            None => Ok(None), // User cancelled
         }
-        None => Ok(None), // User cancelled
     }
+    .await
+    .logged("open_file_dialog")
 }
 
-/// Save content to a file (existing file path)
+/// Open several JSON files at once using a multi-select file picker
 #[tauri::command]
-pub async fn save_file(path: String, content: String) -> Result<(), String> {
-    tokio::fs::write(&path, content)
-        .await
-        .map_err(|e| format!("Failed to save file: {}", e))
+pub async fn open_files_dialog(app: AppHandle) -> Result<ReadFilesResult, AppError> {
+    async {
+        let file_paths = file_dialog(&app)
+            .add_filter("JSON Files", &dialog_extensions())
+            .add_filter("All Files", &["*"])
+            .blocking_pick_files();
+
+        let paths: Vec<String> = file_paths
+            .unwrap_or_default()
+            .into_iter()
+            .map(|path| path.to_string())
+            .collect();
+        if let Some(first) = paths.first() {
+            remember_parent(&app, first);
+        }
+        Ok(read_files(app, paths).await)
+    }
+    .await
+    .logged("open_files_dialog")
+}
+
+#[derive(Serialize, Debug)]
+pub struct CompareSide {
+    pub path: String,
+    pub text: Option<DecodedText>,
+    /// Why the file couldn't be read, when `text` is missing.
+    pub error: Option<String>,
+    /// The `ErrorCode` of `error`.
+    pub error_code: Option<&'static str>,
 }
 
+#[derive(Serialize, Debug)]
+pub struct CompareFiles {
+    pub left: CompareSide,
+    pub right: CompareSide,
+}
+
+/// Pick two files to compare: both at once, or one after the other when the
+/// first pick holds a single file. Returns `None` if the user cancels.
 #[tauri::command]
-pub async fn rename_file(path: String, new_file_name: String) -> Result<String, String> {
-    let new_file_name = new_file_name.trim();
-    if new_file_name.is_empty()
-        || Path::new(new_file_name)
-            .file_name()
-            .and_then(|name| name.to_str())
-            != Some(new_file_name)
-    {
-        return Err("File name must not contain a path".to_string());
+pub async fn open_compare_dialog(app: AppHandle) -> Result<Option<CompareFiles>, AppError> {
+    async {
+        let mut paths: Vec<String> = file_dialog(&app)
+            .set_title("Select files to compare")
+            .add_filter("JSON Files", &dialog_extensions())
+            .add_filter("All Files", &["*"])
+            .blocking_pick_files()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|path| path.to_string())
+            .collect();
+        if paths.len() == 1 {
+            let second = file_dialog(&app)
+                .set_title("Select the file to compare against")
+                .add_filter("JSON Files", &dialog_extensions())
+                .add_filter("All Files", &["*"])
+                .blocking_pick_file();
+            paths.extend(second.map(|path| path.to_string()));
+        }
+        if let Some(first) = paths.first() {
+            remember_parent(&app, first);
+        }
+
+        match <[String; 2]>::try_from(paths) {
+            Ok([left, right]) => Ok(Some(compare_files(app, left, right).await)),
+            Err(paths) if paths.len() < 2 => Ok(None),
+            Err(_) => Err(ErrorCode::SelectTwoFiles.into()),
+        }
     }
+    .await
+    .logged("open_compare_dialog")
+}
 
-    let source_path = PathBuf::from(&path);
-    let Some(parent) = source_path.parent() else {
-        return Err("File path has no parent directory".to_string());
-    };
-    let target_path = parent.join(new_file_name);
-    if target_path == source_path {
-        return Ok(path);
+/// Read two files for `json_diff`, e.g. when two files are dropped together
+#[tauri::command]
+pub async fn compare_files(app: AppHandle, left_path: String, right_path: String) -> CompareFiles {
+    CompareFiles {
+        left: compare_side(&app, left_path).await,
+        right: compare_side(&app, right_path).await,
     }
-    if target_path.exists() {
-        return Err("A file with that name already exists".to_string());
+}
+
+async fn compare_side(app: &AppHandle, path: String) -> CompareSide {
+    match read_within_limit(app, Path::new(&path)).await {
+        Ok(text) => CompareSide {
+            path,
+            text: Some(text),
+            error: None,
+            error_code: None,
+        },
+        Err(error) => CompareSide {
+            path,
+            text: None,
+            error_code: Some(error.code.as_str()),
+            error: Some(error.into()),
+        },
     }
+}
 
-    tokio::fs::rename(&source_path, &target_path)
+/// How `save_file` and `save_file_dialog` write the text; the `SaveOptions`
+/// of `services/file.ts`.
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SaveOptions {
+    /// Defaults to UTF-8; pass back what `read_file` detected to keep the
+    /// file's original encoding.
+    pub encoding: Option<String>,
+    pub bom: Option<bool>,
+    /// Defaults to whether the path ends in `.gz`.
+    pub compress: Option<bool>,
+    /// `lf`, `crlf` or `preserve` (the default).
+    pub line_ending: Option<String>,
+    /// Adds or strips the trailing newline when set.
+    pub final_newline: Option<bool>,
+}
+
+impl SaveOptions {
+    /// The bytes to write to `path` for `content`.
+    fn encode(&self, content: &str, path: &Path) -> Result<Vec<u8>, AppError> {
+        let encoding = match &self.encoding {
+            Some(name) => TextEncoding::parse(name)?,
+            None => TextEncoding::Utf8,
+        };
+        let mode = match &self.line_ending {
+            Some(name) => LineEndingMode::parse(name)?,
+            None => LineEndingMode::Preserve,
+        };
+        let content = text_encoding::normalize_lines(content, mode, self.final_newline);
+        let bytes = text_encoding::encode(&content, encoding, self.bom.unwrap_or(false))
+            .map_err(|e| ErrorCode::SaveFile.reason(e))?;
+        if self.compress.unwrap_or_else(|| is_gzip_path(path)) {
+            gzip(&bytes)
+        } else {
+            Ok(bytes)
+        }
+    }
+}
+
+/// Save content to a file (existing file path)
+///
+/// `options` defaults to plain UTF-8 with the line endings left as they are.
+/// The content being replaced is kept in the file's history.
+#[tauri::command]
+pub async fn save_file(
+    app: AppHandle,
+    path: String,
+    content: String,
+    options: Option<SaveOptions>,
+) -> Result<(), AppError> {
+    async {
+        let options = options.unwrap_or_default();
+        // Encoding and the snapshot both do blocking work; the snapshot reads
+        // the previous content, so the write comes after it.
+        tokio::task::spawn_blocking(move || {
+            let bytes = options.encode(&content, Path::new(&path))?;
+            record_snapshot(&app, &path, &bytes);
+            std::fs::write(&path, bytes).map_err(|e| ErrorCode::SaveFile.reason(e))
+        })
         .await
-        .map_err(|error| format!("Failed to rename file: {error}"))?;
-    Ok(target_path.to_string_lossy().into_owned())
+        .map_err(|e| ErrorCode::TaskFailed.reason(e))?
+    }
+    .await
+    .logged("save_file")
+}
+
+#[tauri::command]
+pub async fn rename_file(path: String, new_file_name: String) -> Result<String, AppError> {
+    async {
+        let new_file_name = new_file_name.trim();
+        if new_file_name.is_empty()
+            || Path::new(new_file_name)
+                .file_name()
+                .and_then(|name| name.to_str())
+                != Some(new_file_name)
+        {
+            return Err(ErrorCode::FileNameHasPath.into());
+        }
+
+        let source_path = PathBuf::from(&path);
+        let Some(parent) = source_path.parent() else {
+            return Err(ErrorCode::NoParentDirectory.into());
+        };
+        let target_path = parent.join(new_file_name);
+        if target_path == source_path {
+            return Ok(path);
+        }
+        if target_path.exists() {
+            return Err(ErrorCode::FileExists.into());
+        }
+
+        tokio::fs::rename(&source_path, &target_path)
+            .await
+            .map_err(|error| ErrorCode::RenameFile.reason(error))?;
+        Ok(target_path.to_string_lossy().into_owned())
+    }
+    .await
+    .logged("rename_file")
 }
 
-/// Save content to a new file using save dialog
+/// Save content to a new file using save dialog, written as `options` asks
 #[tauri::command]
 pub async fn save_file_dialog(
     app: AppHandle,
     content: String,
     default_file_name: String,
-) -> Result<Option<String>, String> {
-    let file_path = app.dialog()
-        .file()
-        .add_filter("JSON Files", JSON_FILE_EXTENSIONS)
-        .add_filter("All Files", &["*"])
-        .set_file_name(&default_file_name)
-        .blocking_save_file();
+    suggested_name: Option<String>,
+    options: Option<SaveOptions>,
+) -> Result<Option<String>, AppError> {
+    async {
+        let options = options.unwrap_or_default();
+        let file_name = suggested_name
+            .map(|name| suggested_file_name(&name, ""))
+            .unwrap_or(default_file_name);
+        let file_path = file_dialog(&app)
+            .add_filter("JSON Files", &dialog_extensions())
+            .add_filter("All Files", &["*"])
+            .set_file_name(&file_name)
+            .blocking_save_file();
+
+        match file_path {
+            Some(path) => {
+                let path_str = path.to_string();
+                remember_parent(&app, &path_str);
+                let path_buf = PathBuf::from(&path_str);
+                let bytes = options.encode(&content, &path_buf)?;
+                tokio::fs::write(&path_buf, bytes)
+                    .await
+                    .map_err(|e| ErrorCode::SaveFile.reason(e))?;
+                Ok(Some(path_str))
+            }
+            None => Ok(None), // User cancelled
+        }
+    }
+    .await
+    .logged("save_file_dialog")
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExportOptions {
+    pub default_file_name: Option<String>,
+    /// Name or path of the file being exported; proposes `<name>.formatted.json`
+    /// when `default_file_name` isn't given.
+    pub suggested_name: Option<String>,
+    /// Indent for pretty JSON; defaults to 2.
+    pub indent: Option<usize>,
+}
+
+/// Output format of `export_file_dialog`, taken from the chosen file name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ExportFormat {
+    Pretty,
+    Minified,
+    Ndjson,
+    Csv,
+    Yaml,
+}
+
+impl ExportFormat {
+    fn from_path(path: &Path) -> Result<Self, AppError> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if name.ends_with(".min.json") {
+            return Ok(Self::Minified);
+        }
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "json" => Ok(Self::Pretty),
+            "jsonl" | "ndjson" => Ok(Self::Ndjson),
+            "csv" => Ok(Self::Csv),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            _ => Err(ErrorCode::UnsupportedExportFormat.with("format", name)),
+        }
+    }
+}
+
+/// Export the document in the format picked in the save dialog
+///
+/// The file name decides the conversion: `.json` pretty-printed, `.min.json`
+/// minified, `.jsonl`/`.ndjson` one line per root array element, `.csv` and
+/// `.yaml`. The conversion runs before anything is written, so a failure
+/// leaves no file behind.
+#[tauri::command]
+pub async fn export_file_dialog(
+    app: AppHandle,
+    content: String,
+    options: Option<ExportOptions>,
+) -> Result<Option<String>, AppError> {
+    async {
+        let options = options.unwrap_or_default();
+        let file_name = match (&options.default_file_name, &options.suggested_name) {
+            (Some(name), _) => name.clone(),
+            (None, Some(name)) => suggested_file_name(name, ".formatted"),
+            (None, None) => "export.json".to_string(),
+        };
+        let file_path = file_dialog(&app)
+            .add_filter("JSON", &["json"])
+            .add_filter("NDJSON", &["jsonl", "ndjson"])
+            .add_filter("CSV", &["csv"])
+            .add_filter("YAML", &["yaml", "yml"])
+            .set_file_name(file_name)
+            .blocking_save_file();
+
+        match file_path {
+            Some(path) => {
+                let path_str = path.to_string();
+                remember_parent(&app, &path_str);
+                let format = ExportFormat::from_path(Path::new(&path_str))?;
+                let output = export_content(&content, format, options.indent)?;
+                tokio::fs::write(&path_str, output)
+                    .await
+                    .map_err(|e| ErrorCode::SaveFile.reason(e))?;
+                Ok(Some(path_str))
+            }
+            None => Ok(None),
+        }
+    }
+    .await
+    .logged("export_file_dialog")
+}
 
-    match file_path {
-        Some(path) => {
-            let path_str = path.to_string();
-            let path_buf = PathBuf::from(&path_str);
-            tokio::fs::write(&path_buf, content)
-                .await
-                .map_err(|e| format!("Failed to save file: {}", e))?;
-            Ok(Some(path_str))
+fn export_content(
+    content: &str,
+    format: ExportFormat,
+    indent: Option<usize>,
+) -> Result<String, AppError> {
+    match format {
+        ExportFormat::Pretty => {
+            format_json(content, Some(indent.unwrap_or(2)), None, None, None, None)
+        }
+        ExportFormat::Minified => minify_json(content, None),
+        ExportFormat::Ndjson => {
+            let Value::Array(items) = parse_to_value(content)? else {
+                return Err(ErrorCode::NdjsonNeedsArray.into());
+            };
+            let mut lines = String::new();
+            for item in &items {
+                let line =
+                    serde_json::to_string(item).map_err(|e| ErrorCode::Formatting.reason(e))?;
+                lines.push_str(&line);
+                lines.push('\n');
+            }
+            Ok(lines)
         }
-        None => Ok(None), // User cancelled
+        ExportFormat::Csv => Ok(to_csv(content)?),
+        ExportFormat::Yaml => Ok(to_yaml(content)?),
     }
 }
 
@@ -120,43 +514,369 @@ pub async fn save_binary_file_dialog(
     bytes: Vec<u8>,
     default_file_name: String,
     extension: String,
-) -> Result<Option<String>, String> {
-    let file_path = app.dialog()
-        .file()
-        .add_filter("Export Files", &[extension.as_str()])
-        .add_filter("All Files", &["*"])
-        .set_file_name(&default_file_name)
-        .blocking_save_file();
+) -> Result<Option<String>, AppError> {
+    async {
+        let file_path = file_dialog(&app)
+            .add_filter("Export Files", &[extension.as_str()])
+            .add_filter("All Files", &["*"])
+            .set_file_name(&default_file_name)
+            .blocking_save_file();
 
-    match file_path {
-        Some(path) => {
-            let path_str = path.to_string();
-            let path_buf = PathBuf::from(&path_str);
-            tokio::fs::write(&path_buf, bytes)
-                .await
-                .map_err(|e| format!("Failed to save file: {}", e))?;
-            Ok(Some(path_str))
+        match file_path {
+            Some(path) => {
+                let path_str = path.to_string();
+                remember_parent(&app, &path_str);
+                let path_buf = PathBuf::from(&path_str);
+                tokio::fs::write(&path_buf, bytes)
+                    .await
+                    .map_err(|e| ErrorCode::SaveFile.reason(e))?;
+                Ok(Some(path_str))
+            }
+            None => Ok(None),
         }
-        None => Ok(None),
     }
+    .await
+    .logged("save_binary_file_dialog")
 }
 
-/// Read file content by path (for drag & drop)
+/// Read file content by path (for drag & drop); large text is returned as a
+/// stored result
 #[tauri::command]
-pub async fn read_file(path: String) -> Result<String, String> {
-    tokio::fs::read_to_string(&path)
+pub async fn read_file(app: AppHandle, path: String) -> Result<DeliveredText, AppError> {
+    async {
+        check_editor_limit(&app, Path::new(&path)).await?;
+        let text = read_text(Path::new(&path)).await?;
+        record_recent_file(&app, &path);
+        Ok(deliver_text(&app, text))
+    }
+    .await
+    .logged("read_file")
+}
+
+/// Read several files in one call (for dropping multiple files); a file that
+/// fails is reported in `failures` without failing the rest
+#[tauri::command]
+pub async fn read_files(app: AppHandle, paths: Vec<String>) -> ReadFilesResult {
+    let mut result = ReadFilesResult::default();
+    for path in paths {
+        match read_within_limit(&app, Path::new(&path)).await {
+            Ok(text) => {
+                record_recent_file(&app, &path);
+                result.files.push((path, deliver_text(&app, text)));
+            }
+            Err(error) => result.failures.push(FileFailure::new(path, error)),
+        }
+    }
+    result
+}
+
+/// Refuse a file over the size limit the user set for the editor, with the
+/// error `file_too_large` gives. Gzip files are measured compressed.
+async fn check_editor_limit(app: &AppHandle, path: &Path) -> Result<(), AppError> {
+    let size = tokio::fs::metadata(path)
         .await
-        .map_err(|e| format!("Failed to read file: {}", e))
+        .map_err(|e| ErrorCode::ReadFile.reason(e))?
+        .len();
+    let limit = setting::<u64>(app, MAX_EDITOR_FILE_BYTES_SETTING)
+        .unwrap_or(DEFAULT_MAX_EDITOR_FILE_BYTES);
+    if size <= limit {
+        return Ok(());
+    }
+    Err(file_too_large(app, path, size, limit).await)
+}
+
+/// `read_text` after `check_editor_limit`, for batch reads that report each
+/// file's error separately.
+async fn read_within_limit(app: &AppHandle, path: &Path) -> Result<DecodedText, AppError> {
+    check_editor_limit(app, path).await?;
+    read_text(path).await
+}
+
+/// `read_text_blocking` off the command thread.
+pub(crate) async fn read_text(path: &Path) -> Result<DecodedText, AppError> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || read_text_blocking(&path))
+        .await
+        .map_err(|e| ErrorCode::TaskFailed.reason(e))?
+}
+
+/// Read a text file in any encoding `text_encoding` detects, without its BOM
+/// and decompressing gzip. Large files are mapped rather than read, so only
+/// the decoded text is held.
+fn read_text_blocking(path: &Path) -> Result<DecodedText, AppError> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| ErrorCode::ReadFile.reason(e))?
+        .len();
+    if size >= MAP_READ_MIN_BYTES {
+        return decode_file(path, &map_file(path)?);
+    }
+    let bytes = std::fs::read(path).map_err(|e| ErrorCode::ReadFile.reason(e))?;
+    decode_file(path, &bytes)
+}
+
+/// Decode file bytes, decompressing gzip first. Either the magic bytes or a
+/// `.gz` extension selects gzip, so a corrupt archive reports a
+/// decompression error rather than a parse error on binary data. Files made
+/// of several gzip members, e.g. appended logs, decompress as one.
+pub(crate) fn decode_file(path: &Path, bytes: &[u8]) -> Result<DecodedText, AppError> {
+    if bytes.starts_with(GZIP_MAGIC) || is_gzip_path(path) {
+        let mut decompressed = Vec::new();
+        MultiGzDecoder::new(bytes)
+            .read_to_end(&mut decompressed)
+            .map_err(|e| ErrorCode::Decompress.reason(e))?;
+        return Ok(text_encoding::decode(&decompressed)?);
+    }
+    Ok(text_encoding::decode(bytes)?)
+}
+
+pub(crate) fn gzip(bytes: &[u8]) -> Result<Vec<u8>, AppError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(bytes)
+        .and_then(|()| encoder.finish())
+        .map_err(|e| ErrorCode::Compress.reason(e))
+}
+
+pub(crate) fn is_gzip_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(GZIP_EXTENSION))
+}
+
+/// Files this large are mapped when read, instead of copied into a buffer.
+const MAP_READ_MIN_BYTES: u64 = 16 * 1024 * 1024;
+/// Editor size limit when the user hasn't set one.
+const DEFAULT_MAX_EDITOR_FILE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Default read size for `read_file_streamed`.
+const DEFAULT_READ_CHUNK_BYTES: usize = 1024 * 1024;
+/// Leading text returned by `read_file_streamed` for the editor.
+pub(crate) const STREAMED_PREVIEW_BYTES: usize = 64 * 1024;
+
+/// Streamed reads in flight, by document id, so they can be cancelled.
+#[derive(Default)]
+pub(crate) struct FileReads {
+    active: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FileReadProgress {
+    pub doc_id: String,
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct StreamedFile {
+    /// Handle of the parsed document in backend state.
+    pub doc_id: String,
+    /// Leading part of the file, cut at a character boundary.
+    pub preview: String,
+    /// Whether `preview` is shorter than the file.
+    pub truncated: bool,
+    pub total_bytes: u64,
+}
+
+/// Read a large file in chunks without sending it over IPC as one string
+///
+/// Emits `file-read-progress` events carrying the document id, which
+/// `cancel_file_read` accepts. The content is parsed into backend state; only
+/// a preview and the document handle are returned.
+#[tauri::command]
+pub async fn read_file_streamed(
+    app: AppHandle,
+    window: Window,
+    path: String,
+    chunk_size: Option<usize>,
+) -> Result<StreamedFile, AppError> {
+    async {
+        let doc_id = app.state::<DocumentStore>().reserve_id();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        app.state::<FileReads>()
+            .active
+            .lock()
+            .unwrap()
+            .insert(doc_id.clone(), cancelled.clone());
+
+        let task_app = app.clone();
+        let task_id = doc_id.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let progress = ProgressGuard::start(window);
+            let bytes = read_in_chunks(
+                Path::new(&path),
+                chunk_size.unwrap_or(DEFAULT_READ_CHUNK_BYTES).max(1),
+                &cancelled,
+                |bytes_read, total_bytes| {
+                    progress.update(bytes_read, total_bytes);
+                    let _ = task_app.emit(
+                        "file-read-progress",
+                        FileReadProgress {
+                            doc_id: task_id.clone(),
+                            bytes_read,
+                            total_bytes,
+                        },
+                    );
+                },
+            )?;
+            let total_bytes = bytes.len() as u64;
+            let content = decode_file(Path::new(&path), &bytes)?.content;
+            let preview = preview_prefix(&content, STREAMED_PREVIEW_BYTES).to_string();
+            let truncated = preview.len() < content.len();
+            task_app
+                .state::<DocumentStore>()
+                .insert_as(task_id.clone(), Document::parse(content)?);
+            Ok(StreamedFile {
+                doc_id: task_id,
+                preview,
+                truncated,
+                total_bytes,
+            })
+        })
+        .await
+        .map_err(|e| ErrorCode::TaskFailed.reason(e));
+
+        app.state::<FileReads>()
+            .active
+            .lock()
+            .unwrap()
+            .remove(&doc_id);
+        result?
+    }
+    .await
+    .logged("read_file_streamed")
+}
+
+/// Stop a `read_file_streamed` call; returns whether it was still running
+#[tauri::command]
+pub fn cancel_file_read(doc_id: String, reads: tauri::State<'_, FileReads>) -> bool {
+    match reads.active.lock().unwrap().get(&doc_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+fn read_in_chunks(
+    path: &Path,
+    chunk_size: usize,
+    cancelled: &AtomicBool,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<Vec<u8>, AppError> {
+    let mut file = File::open(path).map_err(|e| ErrorCode::ReadFile.reason(e))?;
+    let total_bytes = file
+        .metadata()
+        .map_err(|e| ErrorCode::ReadFile.reason(e))?
+        .len();
+    let mut bytes = Vec::with_capacity(total_bytes as usize);
+    let mut chunk = vec![0; chunk_size];
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(ErrorCode::ReadCancelled.into());
+        }
+        let read = file
+            .read(&mut chunk)
+            .map_err(|e| ErrorCode::ReadFile.reason(e))?;
+        if read == 0 {
+            return Ok(bytes);
+        }
+        bytes.extend_from_slice(&chunk[..read]);
+        on_progress(bytes.len() as u64, total_bytes);
+    }
+}
+
+fn preview_prefix(content: &str, max_bytes: usize) -> &str {
+    if content.len() <= max_bytes {
+        return content;
+    }
+    let mut end = max_bytes;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    &content[..end]
+}
+
+#[derive(Serialize, Debug)]
+pub struct FilePreview {
+    pub content: String,
+    pub total_bytes: u64,
+    /// Whether the file goes on past `content`.
+    pub truncated: bool,
+}
+
+/// Read at most `max_bytes` from the start of a file, to decide how to open it
+///
+/// The rest of the file is never read. A character split by the limit is
+/// dropped, and invalid UTF-8 is replaced rather than failing the preview.
+#[tauri::command]
+pub fn read_file_preview(path: String, max_bytes: usize) -> Result<FilePreview, AppError> {
+    let file = File::open(&path).map_err(|e| ErrorCode::ReadFile.reason(e))?;
+    let total_bytes = file
+        .metadata()
+        .map_err(|e| ErrorCode::ReadFile.reason(e))?
+        .len();
+    let mut bytes = Vec::with_capacity(max_bytes.min(total_bytes as usize));
+    file.take(max_bytes as u64)
+        .read_to_end(&mut bytes)
+        .map_err(|e| ErrorCode::ReadFile.reason(e))?;
+    let truncated = (bytes.len() as u64) < total_bytes;
+    Ok(FilePreview {
+        content: preview_text(&bytes, truncated),
+        total_bytes,
+        truncated,
+    })
+}
+
+pub(crate) fn preview_text(bytes: &[u8], truncated: bool) -> String {
+    let mut bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    // Drop the last character if the limit cut it short.
+    let lead = bytes
+        .iter()
+        .rposition(|byte| byte & 0xC0 != 0x80)
+        .filter(|&lead| bytes.len() - lead < 4);
+    if let (true, Some(lead)) = (truncated, lead) {
+        let width = match bytes[lead] {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+        if lead + width > bytes.len() {
+            bytes = &bytes[..lead];
+        }
+    }
+    String::from_utf8_lossy(bytes).into_owned()
 }
 
 /// Check if file path is valid JSON file
+///
+/// `extensions` replaces the default JSON extension set. Directories never
+/// count, whatever their name.
 #[tauri::command]
-pub fn is_json_file(path: String) -> bool {
+pub fn is_json_file(path: String, extensions: Option<Vec<String>>) -> bool {
     let path = PathBuf::from(path);
+    if path.is_dir() {
+        return false;
+    }
+    match extensions {
+        Some(extensions) => has_json_extension(&path, &extensions),
+        None => has_json_extension(&path, JSON_FILE_EXTENSIONS),
+    }
+}
+
+fn has_json_extension(path: &Path, extensions: &[impl AsRef<str>]) -> bool {
+    let mut path = path.to_path_buf();
+    // `data.json.gz` counts by its inner extension.
+    if is_gzip_path(&path) {
+        path.set_extension("");
+    }
     path.extension()
         .and_then(|ext| ext.to_str())
-        .map(is_supported_json_extension)
-        .unwrap_or(false)
+        .is_some_and(|ext| {
+            extensions
+                .iter()
+                .any(|supported| ext.eq_ignore_ascii_case(supported.as_ref()))
+        })
 }
 
 fn is_supported_json_extension(ext: &str) -> bool {
@@ -165,6 +885,73 @@ fn is_supported_json_extension(ext: &str) -> bool {
         .any(|supported| ext.eq_ignore_ascii_case(supported))
 }
 
+/// Bytes `detect_json_content` reads from the start of a file.
+const SNIFF_BYTES: usize = 8 * 1024;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonConfidence {
+    /// Starts with an object or array whose brackets balance so far.
+    Likely,
+    /// Starts like a JSON scalar, or a container with unbalanced brackets.
+    Possible,
+    Unlikely,
+}
+
+/// Guess from the first few KB whether a file holds JSON, whatever its name
+#[tauri::command]
+pub fn detect_json_content(path: String) -> Result<JsonConfidence, AppError> {
+    let file = File::open(&path).map_err(|e| ErrorCode::ReadFile.reason(e))?;
+    let mut sample = Vec::with_capacity(SNIFF_BYTES);
+    file.take(SNIFF_BYTES as u64 + 1)
+        .read_to_end(&mut sample)
+        .map_err(|e| ErrorCode::ReadFile.reason(e))?;
+    let complete = sample.len() <= SNIFF_BYTES;
+    sample.truncate(SNIFF_BYTES);
+    Ok(sniff_json(&sample, complete))
+}
+
+/// `complete` says whether `sample` is the whole file, so unclosed brackets
+/// at the end are truncation rather than a broken document.
+fn sniff_json(sample: &[u8], complete: bool) -> JsonConfidence {
+    let sample = sample.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(sample);
+    let Some(start) = sample.iter().position(|b| !b.is_ascii_whitespace()) else {
+        return JsonConfidence::Unlikely;
+    };
+    match sample[start] {
+        b'{' | b'[' => {}
+        b'"' | b'-' | b't' | b'f' | b'n' | b'0'..=b'9' => return JsonConfidence::Possible,
+        _ => return JsonConfidence::Unlikely,
+    }
+
+    let mut closers = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for &byte in &sample[start..] {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' => closers.push(b'}'),
+            b'[' => closers.push(b']'),
+            b'}' | b']' if closers.pop() != Some(byte) => return JsonConfidence::Unlikely,
+            _ => {}
+        }
+    }
+    if closers.is_empty() || !complete {
+        JsonConfidence::Likely
+    } else {
+        JsonConfidence::Possible
+    }
+}
+
 /// Get file name from path
 #[tauri::command]
 pub fn get_file_name(path: String) -> Option<String> {
@@ -176,54 +963,146 @@ pub fn get_file_name(path: String) -> Option<String> {
 
 /// Open a folder using directory picker dialog
 #[tauri::command]
-pub async fn open_folder_dialog(app: AppHandle) -> Result<Option<String>, String> {
-    let folder_path = app.dialog()
-        .file()
-        .blocking_pick_folder();
+pub async fn open_folder_dialog(app: AppHandle) -> Result<Option<String>, AppError> {
+    async {
+        let folder_path = file_dialog(&app).blocking_pick_folder();
 
-    match folder_path {
-        Some(path) => {
-            // Use path.to_string() which is generally safe in Tauri v2 for PathBuf conversion
-            let p = PathBuf::from(path.to_string());
-            let canonical = p.canonicalize().unwrap_or(p);
-            Ok(Some(canonical.to_string_lossy().into_owned()))
+        match folder_path {
+            Some(path) => {
+                // Use path.to_string() which is generally safe in Tauri v2 for PathBuf conversion
+                let p = PathBuf::from(path.to_string());
+                let canonical = p.canonicalize().unwrap_or(p);
+                remember_directory(&app, &canonical);
+                Ok(Some(canonical.to_string_lossy().into_owned()))
+            }
+            None => Ok(None),
         }
-        None => Ok(None),
     }
+    .await
+    .logged("open_folder_dialog")
 }
 
 /// Read directory (shallow), filtering for supported JSON files, ignoring subdirectories.
 #[tauri::command]
-pub async fn read_json_dir(path: String) -> Result<Vec<FileNode>, String> {
-    tokio::task::spawn_blocking(move || {
-        let root_path = Path::new(&path).canonicalize().unwrap_or_else(|_| PathBuf::from(&path));
-        let mut files = Vec::new();
-
-        if let Ok(entries) = std::fs::read_dir(&root_path) {
-            for entry in entries.flatten() {
-                let file_type = entry.file_type().map_err(|e| e.to_string())?;
-                if file_type.is_file() {
-                    let path_buf = entry.path();
-                    if let Some(ext) = path_buf.extension().and_then(|s| s.to_str()) {
-                        if is_supported_json_extension(ext) {
-                            files.push(FileNode {
-                                name: entry.file_name().to_string_lossy().into_owned(),
-                                path: path_buf.to_string_lossy().into_owned(),
-                                is_dir: false,
-                                children: None,
-                            });
+pub async fn read_json_dir(path: String) -> Result<Vec<FileNode>, AppError> {
+    async {
+        tokio::task::spawn_blocking(move || {
+            let root_path = Path::new(&path)
+                .canonicalize()
+                .unwrap_or_else(|_| PathBuf::from(&path));
+            let mut files = Vec::new();
+
+            if let Ok(entries) = std::fs::read_dir(&root_path) {
+                for entry in entries.flatten() {
+                    let file_type = entry.file_type().map_err(|e| e.to_string())?;
+                    if file_type.is_file() {
+                        let path_buf = entry.path();
+                        if let Some(ext) = path_buf.extension().and_then(|s| s.to_str()) {
+                            if is_supported_json_extension(ext) {
+                                files.push(FileNode {
+                                    name: entry.file_name().to_string_lossy().into_owned(),
+                                    path: path_buf.to_string_lossy().into_owned(),
+                                    is_dir: false,
+                                    children: None,
+                                });
+                            }
                         }
                     }
                 }
             }
-        }
 
-        // Sort files alphabetically
-        files.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-        Ok(files)
-    })
+            // Sort files alphabetically
+            files.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+            Ok(files)
+        })
+        .await
+        .map_err(|e| ErrorCode::TaskFailed.reason(e))?
+    }
     .await
-    .map_err(|e| format!("Task failed: {}", e))?
+    .logged("read_json_dir")
+}
+
+/// Deepest level `list_json_files` descends to when recursive.
+const MAX_LIST_DEPTH: usize = 16;
+
+#[derive(Serialize, Debug)]
+pub struct JsonFileEntry {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    /// Milliseconds since the Unix epoch.
+    pub modified: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonFileList {
+    pub entries: Vec<JsonFileEntry>,
+    /// Whether listing stopped at `max_entries`.
+    pub truncated: bool,
+}
+
+/// List the JSON files in a directory, optionally including subdirectories
+///
+/// Stops after `max_entries` files. Symlinked directories are followed, but
+/// a link back to an ancestor is skipped instead of looping.
+#[tauri::command]
+pub async fn list_json_files(
+    dir: String,
+    recursive: bool,
+    max_entries: usize,
+) -> Result<JsonFileList, AppError> {
+    tokio::task::spawn_blocking(move || list_json_files_in(Path::new(&dir), recursive, max_entries))
+        .await
+        .map_err(|e| ErrorCode::TaskFailed.reason(e))
+        .and_then(|result| result)
+        .logged("list_json_files")
+}
+
+fn list_json_files_in(
+    dir: &Path,
+    recursive: bool,
+    max_entries: usize,
+) -> Result<JsonFileList, AppError> {
+    if !dir.is_dir() {
+        return Err(ErrorCode::DirectoryMissing.into());
+    }
+    let walker = WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(if recursive { MAX_LIST_DEPTH } else { 1 })
+        .follow_links(true)
+        .sort_by_file_name();
+
+    let mut entries = Vec::new();
+    let mut truncated = false;
+    // Unreadable entries and symlink loops come through as errors; skip them.
+    for entry in walker.into_iter().filter_map(Result::ok) {
+        let path = entry.path();
+        if !entry.file_type().is_file() || !has_json_extension(path, JSON_FILE_EXTENSIONS) {
+            continue;
+        }
+        if entries.len() == max_entries {
+            truncated = true;
+            break;
+        }
+        let metadata = entry.metadata().ok();
+        entries.push(JsonFileEntry {
+            path: path.to_string_lossy().into_owned(),
+            name: entry.file_name().to_string_lossy().into_owned(),
+            size: metadata.as_ref().map_or(0, |metadata| metadata.len()),
+            modified: metadata
+                .and_then(|metadata| metadata.modified().ok())
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_millis() as u64),
+        });
+    }
+
+    entries.sort_by(|a, b| {
+        a.name
+            .to_lowercase()
+            .cmp(&b.name.to_lowercase())
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    Ok(JsonFileList { entries, truncated })
 }
 
 /// Create an untitled JSON file inside the specified directory.
@@ -231,33 +1110,710 @@ pub async fn read_json_dir(path: String) -> Result<Vec<FileNode>, String> {
 /// Writes a default empty object to prevent JSON parsing issues.
 /// Returns the absolute path of the created file.
 #[tauri::command]
-pub async fn create_untitled_json(dir_path: String) -> Result<String, String> {
-    let base_path = PathBuf::from(&dir_path);
-    if !base_path.exists() || !base_path.is_dir() {
-        return Err("Directory does not exist".to_string());
-    }
+pub async fn create_untitled_json(dir_path: String) -> Result<String, AppError> {
+    async {
+        let base_path = PathBuf::from(&dir_path);
+        if !base_path.exists() || !base_path.is_dir() {
+            return Err(ErrorCode::DirectoryMissing.into());
+        }
 
-    let mut index = 0;
-    let mut file_path = base_path.join("untitled.json");
+        let mut index = 0;
+        let mut file_path = base_path.join("untitled.json");
 
-    while file_path.exists() {
-        index += 1;
-        file_path = base_path.join(format!("untitled_{}.json", index));
-    }
+        while file_path.exists() {
+            index += 1;
+            file_path = base_path.join(format!("untitled_{}.json", index));
+        }
 
-    // Default valid JSON content
-    let default_content = "{\n  \n}";
-    tokio::fs::write(&file_path, default_content)
-        .await
-        .map_err(|e| format!("Failed to create file: {}", e))?;
+        // Default valid JSON content
+        let default_content = "{\n  \n}";
+        tokio::fs::write(&file_path, default_content)
+            .await
+            .map_err(|e| ErrorCode::CreateFile.reason(e))?;
 
-    Ok(file_path.to_string_lossy().into_owned())
+        Ok(file_path.to_string_lossy().into_owned())
+    }
+    .await
+    .logged("create_untitled_json")
 }
 
 /// Reveal file in system file explorer (select the file)
 #[tauri::command]
-pub fn show_in_folder(app: AppHandle, path: String) -> Result<(), String> {
+pub fn show_in_folder(app: AppHandle, path: String) -> Result<(), AppError> {
     app.opener()
         .reveal_item_in_dir(&path)
-        .map_err(|e| format!("Failed to reveal in folder: {}", e))
+        .map_err(|e| ErrorCode::RevealInFolder.reason(e))
+        .logged("show_in_folder")
+}
+
+/// Largest combined document `join_json_files` returns inline; bigger results
+/// need an output path.
+const MAX_INLINE_JOIN_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(Serialize, Debug)]
+pub struct JoinResult {
+    /// The combined document, when no output path was given.
+    pub content: Option<String>,
+    pub output_path: Option<String>,
+    /// Input files that made it into the result.
+    pub joined_files: usize,
+    pub element_count: usize,
+    pub failures: Vec<FileFailure>,
+}
+
+/// Join JSON files into one array, in the order given. With `flatten`, the
+/// elements of array inputs are added individually. Unparseable files fail
+/// the whole join unless `skip_invalid` is set, in which case they are
+/// reported in `failures`. The result is written to `output` or, when small
+/// enough, returned as `content`.
+#[tauri::command]
+pub async fn join_json_files(
+    window: Window,
+    paths: Vec<String>,
+    output: Option<String>,
+    flatten: Option<bool>,
+    skip_invalid: Option<bool>,
+) -> Result<JoinResult, AppError> {
+    async {
+        tokio::task::spawn_blocking(move || {
+            let _progress = ProgressGuard::start(window);
+            let (joined, source, failures) = join_files(&paths, flatten.unwrap_or(false));
+            if !failures.is_empty() && !skip_invalid.unwrap_or(false) {
+                let failure = &failures[0];
+                return Err(ErrorCode::JoinFailed
+                    .reason(&failure.error)
+                    .with("path", failure.path.as_str()));
+            }
+            let joined_files = paths.len() - failures.len();
+            let element_count = joined.len();
+            let content = write_joined(joined, &source)?;
+
+            let (content, output_path) = match output {
+                Some(output) => {
+                    write_atomic(Path::new(&output), content.as_bytes())
+                        .map_err(|e| ErrorCode::SaveFile.reason(e))?;
+                    (None, Some(output))
+                }
+                None if content.len() > MAX_INLINE_JOIN_BYTES => {
+                    return Err(ErrorCode::JoinTooLarge
+                        .with("size", content.len())
+                        .with("limit", MAX_INLINE_JOIN_BYTES));
+                }
+                None => (Some(content), None),
+            };
+            Ok(JoinResult {
+                content,
+                output_path,
+                joined_files,
+                element_count,
+                failures,
+            })
+        })
+        .await
+        .map_err(|e| ErrorCode::TaskFailed.reason(e))?
+    }
+    .await
+    .logged("join_json_files")
+}
+
+/// The values of the files that parsed, with their texts one after another
+/// so the joined document can keep each number's spelling.
+fn join_files(paths: &[String], flatten: bool) -> (Vec<Value>, String, Vec<FileFailure>) {
+    let mut joined = Vec::new();
+    let mut source = String::new();
+    let mut failures = Vec::new();
+    for path in paths {
+        let parsed = read_text_blocking(Path::new(path))
+            .and_then(|text| Ok((parse_to_value(&text.content)?, text.content)));
+        match parsed {
+            Ok((value, content)) => {
+                match value {
+                    Value::Array(items) if flatten => joined.extend(items),
+                    value => joined.push(value),
+                }
+                source.push_str(&content);
+                source.push('\n');
+            }
+            Err(error) => failures.push(FileFailure::new(path.clone(), error)),
+        }
+    }
+    (joined, source, failures)
+}
+
+/// The joined array, pretty-printed with numbers spelled as in their files.
+fn write_joined(joined: Vec<Value>, source: &str) -> Result<String, AppError> {
+    write_value(
+        &Value::Array(joined),
+        RawNumbers::new(PrettyFormatter::new(), source),
+    )
+}
+
+/// Elements between `file-split-progress` events.
+const SPLIT_PROGRESS_INTERVAL: usize = 1000;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SplitProgress {
+    pub elements_written: usize,
+    pub files_created: usize,
+}
+
+#[derive(Clone, Copy)]
+enum SplitBy {
+    Elements,
+    Bytes,
+}
+
+impl SplitBy {
+    fn parse(name: &str) -> Result<Self, AppError> {
+        match name {
+            "elements" => Ok(Self::Elements),
+            "bytes" => Ok(Self::Bytes),
+            _ => Err(ErrorCode::UnsupportedSplitMode.with("mode", name)),
+        }
+    }
+}
+
+/// Split a file holding a JSON array into `basename_0001.json`, `basename_0002.json`, ...
+/// in `output_dir`, with at most `chunk_size` elements or bytes per file.
+/// Elements are streamed, so the array is never held in memory as a whole.
+/// Emits `file-split-progress` events and returns the paths written.
+#[tauri::command]
+pub async fn split_json_file(
+    app: AppHandle,
+    window: Window,
+    path: String,
+    output_dir: String,
+    chunk_size: usize,
+    by: String,
+) -> Result<Vec<String>, AppError> {
+    async {
+        let by = SplitBy::parse(&by)?;
+        if chunk_size == 0 {
+            return Err(ErrorCode::ChunkSizeTooSmall.into());
+        }
+        tokio::task::spawn_blocking(move || {
+            let _progress = ProgressGuard::start(window);
+            split_array_file(
+                Path::new(&path),
+                Path::new(&output_dir),
+                chunk_size,
+                by,
+                |progress| {
+                    let _ = app.emit("file-split-progress", progress);
+                },
+            )
+        })
+        .await
+        .map_err(|e| ErrorCode::TaskFailed.reason(e))?
+    }
+    .await
+    .logged("split_json_file")
+}
+
+fn split_array_file(
+    path: &Path,
+    output_dir: &Path,
+    chunk_size: usize,
+    by: SplitBy,
+    on_progress: impl FnMut(SplitProgress),
+) -> Result<Vec<String>, AppError> {
+    let file = File::open(path).map_err(|e| ErrorCode::ReadFile.reason(e))?;
+    let mut reader = BufReader::new(file);
+    // Nothing is created for a document that isn't an array.
+    if first_byte(&mut reader).map_err(|e| ErrorCode::ReadFile.reason(e))? != Some(b'[') {
+        return Err(ErrorCode::InvalidJsonArray.reason("the document is not an array"));
+    }
+    std::fs::create_dir_all(output_dir).map_err(|e| ErrorCode::CreateDirectory.reason(e))?;
+    let mut chunks = ChunkWriter {
+        output_dir,
+        stem: path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("split"),
+        chunk_size,
+        by,
+        on_progress,
+        outputs: Vec::new(),
+        current: None,
+        count: 0,
+        bytes: 0,
+        elements_written: 0,
+        failure: None,
+    };
+
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let result = deserializer
+        .deserialize_seq(ElementVisitor(&mut chunks))
+        .and_then(|()| deserializer.end())
+        .map_err(|e| {
+            chunks
+                .failure
+                .take()
+                .unwrap_or_else(|| ErrorCode::InvalidJsonArray.reason(e))
+        })
+        .and_then(|()| chunks.finish());
+    if result.is_err() {
+        // Don't leave a partial split behind.
+        chunks.current = None;
+        for output in &chunks.outputs {
+            let _ = std::fs::remove_file(output);
+        }
+    }
+    result.map(|()| chunks.outputs)
+}
+
+/// Skip leading whitespace and peek at the first byte after it.
+fn first_byte(reader: &mut impl BufRead) -> std::io::Result<Option<u8>> {
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(None);
+        }
+        match buffer.iter().position(|byte| !byte.is_ascii_whitespace()) {
+            Some(index) => {
+                let byte = buffer[index];
+                reader.consume(index);
+                return Ok(Some(byte));
+            }
+            None => {
+                let len = buffer.len();
+                reader.consume(len);
+            }
+        }
+    }
+}
+
+/// Feeds array elements to the chunk writer as they are parsed.
+struct ElementVisitor<'a, 'p, F>(&'a mut ChunkWriter<'p, F>);
+
+impl<'de, F: FnMut(SplitProgress)> Visitor<'de> for ElementVisitor<'_, '_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(element) = seq.next_element::<Value>()? {
+            if let Err(error) = self.0.push(&element) {
+                let message = error.to_string();
+                self.0.failure = Some(error);
+                return Err(de::Error::custom(message));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes one element per line: `[\n`, elements joined by `,\n`, `\n]\n`.
+struct ChunkWriter<'p, F> {
+    output_dir: &'p Path,
+    stem: &'p str,
+    chunk_size: usize,
+    by: SplitBy,
+    on_progress: F,
+    outputs: Vec<String>,
+    current: Option<BufWriter<File>>,
+    /// Elements and bytes in the current file.
+    count: usize,
+    bytes: usize,
+    elements_written: usize,
+    /// Write error that stopped the parse, reported instead of a JSON error.
+    failure: Option<AppError>,
+}
+
+impl<F: FnMut(SplitProgress)> ChunkWriter<'_, F> {
+    fn push(&mut self, element: &Value) -> Result<(), AppError> {
+        let text = serde_json::to_string(element).map_err(|e| e.to_string())?;
+        let full = match self.by {
+            SplitBy::Elements => self.count >= self.chunk_size,
+            // A single oversized element still gets a file of its own.
+            SplitBy::Bytes => self.count > 0 && self.bytes + 2 + text.len() + 3 > self.chunk_size,
+        };
+        if self.current.is_none() || full {
+            self.close()?;
+            self.open()?;
+        }
+
+        let separator = if self.count > 0 { ",\n" } else { "" };
+        let writer = self.current.as_mut().expect("chunk file is open");
+        write!(writer, "{separator}{text}").map_err(|e| ErrorCode::WriteFile.reason(e))?;
+        self.count += 1;
+        self.bytes += separator.len() + text.len();
+        self.elements_written += 1;
+        if self.elements_written.is_multiple_of(SPLIT_PROGRESS_INTERVAL) {
+            self.report();
+        }
+        Ok(())
+    }
+
+    fn open(&mut self) -> Result<(), AppError> {
+        let path =
+            self.output_dir
+                .join(format!("{}_{:04}.json", self.stem, self.outputs.len() + 1));
+        // Never overwrite: a failed split deletes every file it wrote.
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|e| ErrorCode::CreateFile.reason(e))?;
+        let mut writer = BufWriter::new(file);
+        writer
+            .write_all(b"[\n")
+            .map_err(|e| ErrorCode::WriteFile.reason(e))?;
+        self.outputs.push(path.to_string_lossy().into_owned());
+        self.current = Some(writer);
+        self.count = 0;
+        self.bytes = 2;
+        self.report();
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), AppError> {
+        if let Some(mut writer) = self.current.take() {
+            writer
+                .write_all(b"\n]\n")
+                .and_then(|()| writer.flush())
+                .map_err(|e| ErrorCode::WriteFile.reason(e))?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), AppError> {
+        self.close()?;
+        self.report();
+        Ok(())
+    }
+
+    fn report(&mut self) {
+        (self.on_progress)(SplitProgress {
+            elements_written: self.elements_written,
+            files_created: self.outputs.len(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_file, export_content, gzip, is_json_file, join_files, list_json_files_in,
+        preview_prefix, preview_text, read_in_chunks, sniff_json, split_array_file,
+        suggested_file_name, write_joined, ExportFormat, JsonConfidence, SplitBy, SplitProgress,
+    };
+    use crate::error::ErrorCode;
+    use serde_json::{json, Value};
+    use std::fs;
+    use std::path::Path;
+    use std::sync::atomic::AtomicBool;
+
+    fn read_array(path: &str) -> Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn splits_by_element_count_with_numbered_names() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let input = dir.join("export.json");
+        fs::write(&input, r#"[{"id": 1}, {"id": 2}, {"id": 3}, 4.50, "five"]"#).unwrap();
+        let mut events = Vec::new();
+
+        let outputs =
+            split_array_file(&input, &dir.join("out"), 2, SplitBy::Elements, |progress| {
+                events.push(progress)
+            })
+            .unwrap();
+
+        assert_eq!(outputs.len(), 3);
+        assert!(outputs[0].ends_with("export_0001.json"));
+        assert!(outputs[2].ends_with("export_0003.json"));
+        assert_eq!(
+            fs::read_to_string(&outputs[1]).unwrap(),
+            "[\n{\"id\":3},\n4.50\n]\n"
+        );
+        assert_eq!(fs::read_to_string(&outputs[2]).unwrap(), "[\n\"five\"\n]\n");
+        assert_eq!(
+            events.last(),
+            Some(&SplitProgress {
+                elements_written: 5,
+                files_created: 3
+            })
+        );
+    }
+
+    #[test]
+    fn splits_by_bytes_and_rejects_non_array_roots() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let input = dir.join("numbers.json");
+        fs::write(&input, "[1000, 2000, 3000, 4000000000]").unwrap();
+
+        let outputs =
+            split_array_file(&input, &dir.join("out"), 16, SplitBy::Bytes, |_| {}).unwrap();
+
+        let sizes: Vec<_> = outputs
+            .iter()
+            .map(|output| fs::metadata(output).unwrap().len())
+            .collect();
+        assert_eq!(sizes, [15, 9, 15]);
+        assert_eq!(read_array(&outputs[2]), json!([4000000000u64]));
+
+        let object = dir.join("object.json");
+        fs::write(&object, r#"{"a": [1, 2]}"#).unwrap();
+        let out = dir.join("object-out");
+        let error = split_array_file(&object, &out, 1, SplitBy::Elements, |_| {}).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid JSON array: the document is not an array"
+        );
+        assert!(!out.exists());
+
+        let truncated = dir.join("truncated.json");
+        fs::write(&truncated, "  [1, 2, 3").unwrap();
+        let error = split_array_file(&truncated, &out, 1, SplitBy::Elements, |_| {}).unwrap_err();
+        assert!(error.to_string().starts_with("Invalid JSON array: EOF"));
+        assert_eq!(fs::read_dir(&out).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn split_never_overwrites_existing_chunks() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let input = dir.join("export.json");
+        fs::write(&input, "[1, 2, 3]").unwrap();
+        let out = dir.join("out");
+        fs::create_dir_all(&out).unwrap();
+        fs::write(out.join("export_0002.json"), "keep").unwrap();
+
+        let error = split_array_file(&input, &out, 1, SplitBy::Elements, |_| {}).unwrap_err();
+
+        assert!(error.to_string().starts_with("Failed to create file"));
+        assert!(!out.join("export_0001.json").exists());
+        assert_eq!(
+            fs::read_to_string(out.join("export_0002.json")).unwrap(),
+            "keep"
+        );
+    }
+
+    #[test]
+    fn joins_files_in_order_and_reports_invalid_ones() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let paths: Vec<String> = [("a.json", "[1, 2]"), ("b.json5", "{b: 1}"), ("c.json", "{")]
+            .iter()
+            .map(|(name, content)| {
+                let path = dir.join(name);
+                fs::write(&path, content).unwrap();
+                path.to_string_lossy().into_owned()
+            })
+            .collect();
+
+        let (nested, _, failures) = join_files(&paths, false);
+        assert_eq!(Value::Array(nested), json!([[1, 2], {"b": 1}]));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].path, paths[2]);
+
+        let (flat, _, _) = join_files(&paths[..2], true);
+        assert_eq!(Value::Array(flat), json!([1, 2, {"b": 1}]));
+    }
+
+    #[test]
+    fn joined_documents_keep_number_spellings() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let paths: Vec<String> = [
+            ("a.json", "[1E3, 2.50]"),
+            ("b.json", "[7E1"),
+            ("c.json", r#"{"e": 5E-2}"#),
+        ]
+        .iter()
+        .map(|(name, content)| {
+            let path = dir.join(name);
+            fs::write(&path, content).unwrap();
+            path.to_string_lossy().into_owned()
+        })
+        .collect();
+
+        let (joined, source, failures) = join_files(&paths, true);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(
+            write_joined(joined, &source).unwrap(),
+            "[\n  1E3,\n  2.50,\n  {\n    \"e\": 5E-2\n  }\n]"
+        );
+    }
+
+    #[test]
+    fn joins_files_in_any_encoding_or_compression_the_editor_opens() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain("[1]".encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let (gbk, _, _) = encoding_rs::GBK.encode(r#"{"城市": "北京，上海，广州，深圳"}"#);
+        let inputs: [(&str, &[u8]); 4] = [
+            ("utf16.json", &utf16),
+            ("bom.json", b"\xEF\xBB\xBF\"two\""),
+            ("gbk.json", &gbk),
+            ("gzip.json.gz", &gzip(b"[null]").unwrap()),
+        ];
+        let paths: Vec<String> = inputs
+            .iter()
+            .map(|(name, bytes)| {
+                let path = dir.join(name);
+                fs::write(&path, bytes).unwrap();
+                path.to_string_lossy().into_owned()
+            })
+            .collect();
+
+        let (joined, _, failures) = join_files(&paths, true);
+        assert!(failures.is_empty());
+        assert_eq!(
+            Value::Array(joined),
+            json!([1, "two", {"城市": "北京，上海，广州，深圳"}, null])
+        );
+    }
+
+    #[test]
+    fn reads_in_chunks_with_progress_until_cancelled() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("big.json");
+        fs::write(&path, "[\"é\", 1]").unwrap();
+
+        let mut progress = Vec::new();
+        let bytes = read_in_chunks(&path, 4, &AtomicBool::new(false), |read, total| {
+            progress.push((read, total))
+        })
+        .unwrap();
+        assert_eq!(bytes, fs::read(&path).unwrap());
+        assert_eq!(progress, [(4, 9), (8, 9), (9, 9)]);
+
+        let cancelled = read_in_chunks(&path, 4, &AtomicBool::new(true), |_, _| {});
+        assert_eq!(cancelled.unwrap_err().code, ErrorCode::ReadCancelled);
+
+        // "é" spans bytes 2..4, so a 3-byte preview stops before it.
+        assert_eq!(preview_prefix("[\"é\", 1]", 3), "[\"");
+    }
+
+    #[test]
+    fn decompresses_gzip_by_magic_or_extension() {
+        let compressed = gzip("{\"a\": 1}".as_bytes()).unwrap();
+
+        let by_magic = decode_file(Path::new("export.json"), &compressed).unwrap();
+        assert_eq!(by_magic.content, "{\"a\": 1}");
+        let mut members = gzip(b"{\"a\": 1}\n").unwrap();
+        members.extend(gzip(b"{\"b\": 2}\n").unwrap());
+        assert_eq!(
+            decode_file(Path::new("events.ndjson.gz"), &members)
+                .unwrap()
+                .content,
+            "{\"a\": 1}\n{\"b\": 2}\n"
+        );
+        assert!(decode_file(Path::new("export.json.gz"), b"{}")
+            .unwrap_err()
+            .to_string()
+            .starts_with("Failed to decompress gzip file"));
+        assert!(is_json_file("export.JSON.gz".to_string(), None));
+        assert!(!is_json_file("backup.tar.gz".to_string(), None));
+    }
+
+    #[test]
+    fn lists_json_files_by_name_up_to_the_limit() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for name in ["b.json", "A.geojson", "notes.txt", "nested/c.jsonl"] {
+            fs::write(dir.join(name), "{}").unwrap();
+        }
+
+        let names = |recursive, max_entries| {
+            let list = list_json_files_in(dir, recursive, max_entries).unwrap();
+            let names: Vec<_> = list.entries.into_iter().map(|entry| entry.name).collect();
+            (names, list.truncated)
+        };
+        assert_eq!(names(false, 10).0, ["A.geojson", "b.json"]);
+        assert_eq!(names(true, 10).0, ["A.geojson", "b.json", "c.jsonl"]);
+        assert_eq!(names(true, 1), (vec!["A.geojson".to_string()], true));
+    }
+
+    #[test]
+    fn checks_extensions_and_skips_directories() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("fixtures.json");
+        fs::create_dir_all(&dir).unwrap();
+
+        assert!(!is_json_file(dir.to_string_lossy().into_owned(), None));
+        assert!(is_json_file("data.HAR".to_string(), None));
+        assert!(is_json_file(
+            "data.log".to_string(),
+            Some(vec!["log".to_string()])
+        ));
+        assert!(!is_json_file(
+            "data.json".to_string(),
+            Some(vec!["log".to_string()])
+        ));
+    }
+
+    #[test]
+    fn sniffs_json_by_content() {
+        let sniff = |sample: &str, complete| sniff_json(sample.as_bytes(), complete);
+
+        assert_eq!(
+            sniff("\u{feff} \n{\"a\": [1, \"]}\"]}", true),
+            JsonConfidence::Likely
+        );
+        assert_eq!(sniff("[{\"a\": 1}, {\"b\"", false), JsonConfidence::Likely);
+        assert_eq!(sniff("[{\"a\": 1}, {\"b\"", true), JsonConfidence::Possible);
+        assert_eq!(sniff("-12.5", true), JsonConfidence::Possible);
+        assert_eq!(sniff("{\"a\": 1]", true), JsonConfidence::Unlikely);
+        assert_eq!(sniff("<html>", true), JsonConfidence::Unlikely);
+        assert_eq!(sniff("  ", true), JsonConfidence::Unlikely);
+    }
+
+    #[test]
+    fn previews_drop_split_characters_and_replace_invalid_bytes() {
+        // "é" is 0xC3 0xA9; the limit cut it after its first byte.
+        assert_eq!(preview_text(b"[\"a\xC3", true), "[\"a");
+        assert_eq!(preview_text(b"\xEF\xBB\xBF[\xFF]", false), "[\u{FFFD}]");
+        assert_eq!(preview_text(b"[\"a\xC3", false), "[\"a\u{FFFD}");
+    }
+
+    #[test]
+    fn exports_by_the_chosen_file_name() {
+        let format = |name: &str| ExportFormat::from_path(Path::new(name));
+        assert_eq!(format("out.MIN.json"), Ok(ExportFormat::Minified));
+        assert_eq!(format("out.yml"), Ok(ExportFormat::Yaml));
+        assert_eq!(
+            format("out.txt").unwrap_err().to_string(),
+            "Unsupported export format: out.txt"
+        );
+
+        let content = r#"[{"a": 1}, {"a": 2}]"#;
+        assert_eq!(
+            export_content(content, ExportFormat::Ndjson, None).unwrap(),
+            "{\"a\":1}\n{\"a\":2}\n"
+        );
+        assert_eq!(
+            export_content(content, ExportFormat::Pretty, Some(1)).unwrap(),
+            "[\n {\n  \"a\": 1\n },\n {\n  \"a\": 2\n }\n]"
+        );
+        assert_eq!(
+            export_content("{}", ExportFormat::Ndjson, None)
+                .unwrap_err()
+                .to_string(),
+            "NDJSON export requires a JSON array at the root"
+        );
+        assert!(export_content("1", ExportFormat::Csv, None).is_err());
+    }
+
+    #[test]
+    fn suggests_save_names_from_the_opened_file() {
+        assert_eq!(suggested_file_name("/tmp/data.json", ""), "data.json");
+        assert_eq!(suggested_file_name("config.json5", ""), "config.json");
+        assert_eq!(
+            suggested_file_name("/logs/events.json.gz", ".formatted"),
+            "events.formatted.json"
+        );
+        assert_eq!(suggested_file_name("", ""), "untitled.json");
+    }
 }
@@ -1,12 +1,23 @@
 // File watcher commands
 use notify::RecursiveMode;
-use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
+use notify_debouncer_mini::new_debouncer;
+use serde::Serialize;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
 use tauri::{AppHandle, Emitter};
 
+/// Payload of the `file-changed` event.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FileChangedEvent {
+    pub path: String,
+    /// Modification time in milliseconds since the Unix epoch; `None` once the
+    /// file is gone.
+    pub mtime: Option<u64>,
+    pub exists: bool,
+}
+
 pub struct FileWatcherState {
     watchers: Arc<Mutex<HashMap<String, notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>>>>,
 }
@@ -20,6 +31,11 @@ impl FileWatcherState {
 }
 
 /// Start watching a file for changes
+///
+/// The parent directory is watched rather than the file itself: editors that
+/// save by writing a temp file and renaming it over the original replace the
+/// inode, which a watch on the file would lose. Bursts of events (editors
+/// often write twice) are debounced into one `file-changed` event.
 #[tauri::command]
 pub async fn watch_file(
     app: AppHandle,
@@ -38,6 +54,7 @@ pub async fn watch_file(
     
     let app_clone = app.clone();
     let path_clone = path.clone();
+    let watched = path_buf.clone();
     
     // Create debounced watcher
     let mut debouncer = new_debouncer(
@@ -45,24 +62,29 @@ pub async fn watch_file(
         move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
             match result {
                 Ok(events) => {
-                    for event in events {
-                        // Only emit for modify events
-                        if matches!(event.kind, DebouncedEventKind::Any) {
-                            let _ = app_clone.emit("file-changed", path_clone.clone());
-                        }
+                    // Siblings in the directory change too; one event per burst is enough.
+                    if events
+                        .iter()
+                        .any(|event| is_watched_path(&watched, &event.path))
+                    {
+                        let _ = app_clone.emit("file-changed", file_changed_event(&path_clone));
                     }
                 }
                 Err(e) => {
-                    eprintln!("File watcher error: {:?}", e);
+                    log::warn!("File watcher error: {:?}", e);
                 }
             }
         },
     ).map_err(|e| format!("Failed to create watcher: {}", e))?;
     
-    // Watch the file
+    // Watch the directory holding the file
+    let directory = match path_buf.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
     debouncer
         .watcher()
-        .watch(&path_buf, RecursiveMode::NonRecursive)
+        .watch(directory, RecursiveMode::NonRecursive)
         .map_err(|e| format!("Failed to watch file: {}", e))?;
     
     // Store the watcher
@@ -92,3 +114,41 @@ pub async fn unwatch_all_files(
     watchers.clear();
     Ok(())
 }
+
+/// Events only come from the file's own directory, so the name identifies it.
+fn is_watched_path(watched: &Path, changed: &Path) -> bool {
+    changed
+        .file_name()
+        .is_some_and(|name| Some(name) == watched.file_name())
+}
+
+fn file_changed_event(path: &str) -> FileChangedEvent {
+    let mtime = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_millis() as u64);
+    FileChangedEvent {
+        path: path.to_string(),
+        mtime,
+        exists: Path::new(path).exists(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_changed_event, is_watched_path};
+    use std::path::Path;
+
+    #[test]
+    fn matches_events_for_the_watched_file_only() {
+        let watched = Path::new("/data/config.json");
+
+        assert!(is_watched_path(watched, Path::new("/data/config.json")));
+        assert!(!is_watched_path(watched, Path::new("/data/.config.json.swp")));
+        assert!(!is_watched_path(watched, Path::new("/data")));
+
+        let missing = file_changed_event("/nonexistent/config.json");
+        assert_eq!((missing.mtime, missing.exists), (None, false));
+    }
+}
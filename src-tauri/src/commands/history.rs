@@ -0,0 +1,212 @@
+// Local file history commands
+//
+// Before `save_file` overwrites a file, the bytes on disk are copied to
+// `app_data_dir/history/<hash of the path>/<timestamp>.json`. Each file keeps
+// a bounded number of snapshots, trimmed oldest first on every save.
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::file::decode_file;
+use super::store::write_atomic;
+use crate::text_encoding::DecodedText;
+
+/// Snapshots kept per file.
+const MAX_SNAPSHOTS: usize = 20;
+/// Total snapshot size kept per file.
+const MAX_HISTORY_BYTES: u64 = 50 * 1024 * 1024;
+const SNAPSHOT_EXTENSION: &str = "json";
+
+/// Serializes snapshot writes and evictions.
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct SnapshotInfo {
+    pub id: String,
+    /// Milliseconds since the Unix epoch.
+    pub created: i64,
+    /// Size in bytes as stored on disk.
+    pub size: u64,
+}
+
+/// Snapshots of a file's earlier versions, newest first
+#[tauri::command]
+pub fn list_file_history(app: AppHandle, path: String) -> Result<Vec<SnapshotInfo>, String> {
+    Ok(list_snapshots(&file_history_dir(&app, &path)?))
+}
+
+/// Read one snapshot, decoded like `read_file`
+#[tauri::command]
+pub fn read_file_history(
+    app: AppHandle,
+    path: String,
+    snapshot_id: String,
+) -> Result<DecodedText, String> {
+    let bytes = read_snapshot(&file_history_dir(&app, &path)?, &snapshot_id)?;
+    Ok(decode_file(Path::new(&path), &bytes)?)
+}
+
+/// Keep the current content of `path` before it is overwritten with
+/// `new_bytes`. Failures are logged, never surfaced, so they can't block
+/// the save itself.
+pub(crate) fn record_snapshot(app: &AppHandle, path: &str, new_bytes: &[u8]) {
+    let Ok(previous) = std::fs::read(path) else {
+        return; // Nothing to keep for a new file.
+    };
+    if previous == new_bytes {
+        return;
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let result = file_history_dir(app, path)
+        .and_then(|dir| save_snapshot(&dir, &previous, now, MAX_SNAPSHOTS, MAX_HISTORY_BYTES));
+    if let Err(error) = result {
+        log::warn!("Failed to record file history: {error}");
+    }
+}
+
+fn file_history_dir(app: &AppHandle, path: &str) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("history").join(path_key(path)))
+        .map_err(|e| format!("Failed to resolve data directory: {}", e))
+}
+
+/// Directory name for a file: a hash of its canonical path, so any path
+/// spelling maps to the same history and no path ends up in a file name.
+fn path_key(path: &str) -> String {
+    let canonical = std::fs::canonicalize(path)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.to_string());
+    let digest = Sha256::digest(canonical.as_bytes());
+    digest[..16]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn snapshot_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{id}.{SNAPSHOT_EXTENSION}"))
+}
+
+fn save_snapshot(
+    dir: &Path,
+    bytes: &[u8],
+    now: i64,
+    max_snapshots: usize,
+    max_bytes: u64,
+) -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock().unwrap();
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create history directory: {}", e))?;
+    // Saves within the same millisecond still get distinct ids.
+    let mut created = now;
+    while snapshot_path(dir, &created.to_string()).exists() {
+        created += 1;
+    }
+    write_atomic(&snapshot_path(dir, &created.to_string()), bytes)
+        .map_err(|e| format!("Failed to save history snapshot: {}", e))?;
+
+    let snapshots = list_snapshots(dir);
+    let mut total: u64 = snapshots.iter().map(|snapshot| snapshot.size).sum();
+    for (kept, snapshot) in snapshots.iter().enumerate().rev() {
+        // The newest snapshot is always kept, even when it alone is too big.
+        if kept == 0 || (kept < max_snapshots && total <= max_bytes) {
+            break;
+        }
+        let _ = std::fs::remove_file(snapshot_path(dir, &snapshot.id));
+        total -= snapshot.size;
+    }
+    Ok(())
+}
+
+fn list_snapshots(dir: &Path) -> Vec<SnapshotInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<SnapshotInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != SNAPSHOT_EXTENSION {
+                return None;
+            }
+            let created: i64 = path.file_stem()?.to_str()?.parse().ok()?;
+            Some(SnapshotInfo {
+                id: created.to_string(),
+                created,
+                size: entry.metadata().ok()?.len(),
+            })
+        })
+        .collect();
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created));
+    snapshots
+}
+
+fn read_snapshot(dir: &Path, snapshot_id: &str) -> Result<Vec<u8>, String> {
+    // Ids are timestamps; anything else could point outside the directory.
+    if snapshot_id.is_empty() || !snapshot_id.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("Unknown snapshot: {snapshot_id}"));
+    }
+    std::fs::read(snapshot_path(dir, snapshot_id))
+        .map_err(|_| format!("Unknown snapshot: {snapshot_id}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list_snapshots, path_key, read_snapshot, save_snapshot};
+    use std::fs;
+
+    fn ids(dir: &std::path::Path) -> Vec<String> {
+        list_snapshots(dir)
+            .into_iter()
+            .map(|snapshot| snapshot.id)
+            .collect()
+    }
+
+    #[test]
+    fn keeps_snapshots_newest_first_and_reads_them_back() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        save_snapshot(dir, b"{\"v\": 1}", 100, 10, 1000).unwrap();
+        save_snapshot(dir, b"{\"v\": 2}", 100, 10, 1000).unwrap();
+
+        assert_eq!(ids(dir), ["101", "100"]);
+        assert_eq!(read_snapshot(dir, "100").unwrap(), b"{\"v\": 1}");
+        assert_eq!(
+            read_snapshot(dir, "../100").unwrap_err(),
+            "Unknown snapshot: ../100"
+        );
+        assert!(read_snapshot(dir, "5").is_err());
+    }
+
+    #[test]
+    fn evicts_the_oldest_snapshots_beyond_count_and_size() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        for now in 1..=4 {
+            save_snapshot(dir, b"1234", now, 3, 1000).unwrap();
+        }
+        assert_eq!(ids(dir), ["4", "3", "2"]);
+
+        save_snapshot(dir, b"12345678", 5, 3, 10).unwrap();
+        assert_eq!(ids(dir), ["5"]);
+    }
+
+    #[test]
+    fn keys_paths_by_their_canonical_form() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let file = dir.join("data.json");
+        fs::write(&file, "{}").unwrap();
+        let aliased = dir.join(".").join("data.json");
+
+        assert_eq!(
+            path_key(&file.to_string_lossy()),
+            path_key(&aliased.to_string_lossy())
+        );
+        assert_eq!(path_key("/a.json").len(), 32);
+        assert_ne!(path_key("/a.json"), path_key("/b.json"));
+    }
+}
@@ -0,0 +1,445 @@
+// Incremental validation
+//
+// The editor sends each change to an open document instead of its whole
+// text. When the text before the change was standard JSON without
+// duplicate keys, only the part the change touches is parsed again: the
+// string or scalar token it falls in, or else the innermost array or object
+// around it. A valid value swapped for another valid value leaves the
+// document valid, so that answer is exact. Whenever the local check can't
+// show the result is valid, the whole text is validated instead and the
+// response says why, so an invalid document is never reported valid.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use super::breadcrumb::byte_offset;
+use super::document::DocumentStore;
+use super::json::{duplicate_key_warnings, validate_json_strict, ValidationResult};
+use crate::error::{AppError, ErrorCode};
+use crate::json_simd::SERDE_MAX_DEPTH;
+
+/// An editor range: 1-based lines and UTF-16 columns, end exclusive.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct EditRange {
+    pub start_line_number: usize,
+    pub start_column: usize,
+    pub end_line_number: usize,
+    pub end_column: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckedScope {
+    /// The string or scalar the edit was made in.
+    Token,
+    /// The innermost array or object around the edit.
+    Container,
+    Document,
+}
+
+#[derive(Serialize, Debug)]
+pub struct IncrementalValidation {
+    #[serde(flatten)]
+    pub result: ValidationResult,
+    /// What was parsed again.
+    pub checked: CheckedScope,
+    /// Why the whole document was validated, when it was.
+    pub fallback_reason: Option<&'static str>,
+}
+
+/// The editor's text for a document, as edits arrive.
+pub(crate) struct EditSession {
+    text: String,
+    /// Whether `text` is standard JSON without duplicate keys; `None` until
+    /// it was validated.
+    clean: Option<bool>,
+}
+
+impl EditSession {
+    pub(crate) fn new(text: String) -> Self {
+        Self { text, clean: None }
+    }
+
+    pub(crate) fn text_len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Replace the bytes `start..end` with `new_text` and validate the result.
+    fn apply(&mut self, start: usize, end: usize, new_text: &str) -> IncrementalValidation {
+        let local = match self.clean {
+            None => Err("the document had not been validated before"),
+            Some(false) => {
+                Err("the text before the edit was not standard JSON without duplicate keys")
+            }
+            Some(true) => check_locally(&self.text, start, end, new_text),
+        };
+        self.text.replace_range(start..end, new_text);
+        match local {
+            Ok(checked) => IncrementalValidation {
+                result: ValidationResult {
+                    valid: true,
+                    error_message: None,
+                    error_line: None,
+                    error_column: None,
+                    warnings: Vec::new(),
+                },
+                checked,
+                fallback_reason: None,
+            },
+            Err(reason) => {
+                let (result, strict) = validate_json_strict(&self.text);
+                self.clean = Some(strict && result.valid && result.warnings.is_empty());
+                IncrementalValidation {
+                    result,
+                    checked: CheckedScope::Document,
+                    fallback_reason: Some(reason),
+                }
+            }
+        }
+    }
+}
+
+/// Show that replacing `start..end` of clean `text` with `new_text` leaves
+/// it clean, parsing as little as possible; the error says why it couldn't.
+fn check_locally(
+    text: &str,
+    start: usize,
+    end: usize,
+    new_text: &str,
+) -> Result<CheckedScope, &'static str> {
+    let around = enclosing(text, start, end);
+    let spliced =
+        |from: usize, to: usize| format!("{}{new_text}{}", &text[from..start], &text[end..to]);
+    match around.string {
+        // A key can become a duplicate, so it is left to the container.
+        Some((open, close)) if end <= close && !is_key(text, close) => {
+            if serde_json::from_str::<String>(&spliced(open, close + 1)).is_ok() {
+                return Ok(CheckedScope::Token);
+            }
+        }
+        Some(_) => {}
+        None => {
+            if let Some((from, to)) = scalar_around(text, start, end) {
+                let token = spliced(from, to);
+                if !token.is_empty()
+                    && token.bytes().all(is_scalar_byte)
+                    && serde_json::from_str::<Value>(&token).is_ok()
+                {
+                    return Ok(CheckedScope::Token);
+                }
+            }
+        }
+    }
+    let Some(container) = around.container else {
+        return Err("the edit is not inside an array or object");
+    };
+    let region = spliced(container.open, container.close + 1);
+    match serde_json::from_str::<Value>(&region) {
+        Ok(value)
+            if container.depth - 1 + nesting(&value) <= SERDE_MAX_DEPTH
+                && duplicate_key_warnings(&region).is_empty() =>
+        {
+            Ok(CheckedScope::Container)
+        }
+        _ => Err("the edited array or object is not valid on its own"),
+    }
+}
+
+struct Container {
+    open: usize,
+    close: usize,
+    /// 1 for the root.
+    depth: usize,
+}
+
+#[derive(Default)]
+struct Enclosing {
+    /// Positions of the quotes of the string `start` is in.
+    string: Option<(usize, usize)>,
+    /// The innermost array or object that keeps both its brackets when
+    /// `start..end` is replaced.
+    container: Option<Container>,
+}
+
+/// Where the bytes `start..end` of standard JSON `text` are.
+fn enclosing(text: &str, start: usize, end: usize) -> Enclosing {
+    let bytes = text.as_bytes();
+    let mut found = Enclosing::default();
+    let mut open: Vec<usize> = Vec::new();
+    let mut in_string: Option<usize> = None;
+    // Containers still open of those open at `start`, and the string
+    // `start` is in.
+    let mut at_start: Option<(usize, Option<usize>)> = None;
+    let mut at = 0;
+    while at < bytes.len() {
+        if at >= start && at_start.is_none() {
+            at_start = Some((open.len(), in_string));
+        }
+        match (in_string, bytes[at]) {
+            (Some(_), b'\\') => at += 1,
+            (Some(quote), b'"') => {
+                in_string = None;
+                if at_start.is_some_and(|(_, string)| string == Some(quote)) {
+                    found.string = Some((quote, at));
+                }
+            }
+            (Some(_), _) => {}
+            (None, b'"') => in_string = Some(at),
+            (None, b'[' | b'{') => open.push(at),
+            (None, b']' | b'}') => {
+                let bracket = open.pop();
+                if let (Some((outer, string)), Some(bracket)) = (at_start, bracket) {
+                    if open.len() < outer {
+                        if at >= end {
+                            found.container = Some(Container {
+                                open: bracket,
+                                close: at,
+                                depth: open.len() + 1,
+                            });
+                            break;
+                        }
+                        // The edit removes this bracket.
+                        at_start = Some((open.len(), string));
+                    }
+                }
+            }
+            (None, _) => {}
+        }
+        at += 1;
+    }
+    found
+}
+
+fn is_key(text: &str, close_quote: usize) -> bool {
+    text[close_quote + 1..].trim_start().starts_with(':')
+}
+
+fn is_scalar_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'-' | b'.')
+}
+
+/// The number or literal whose bytes `start..end` are, outside any string.
+/// `None` when the range reaches past it or there is none.
+fn scalar_around(text: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    if !bytes[start..end].iter().all(|&byte| is_scalar_byte(byte)) {
+        return None;
+    }
+    let before = bytes[..start]
+        .iter()
+        .rev()
+        .take_while(|&&byte| is_scalar_byte(byte));
+    let after = bytes[end..]
+        .iter()
+        .take_while(|&&byte| is_scalar_byte(byte));
+    let (from, to) = (start - before.count(), end + after.count());
+    (from < to).then_some((from, to))
+}
+
+/// Nesting of arrays and objects in `value`; 0 for a scalar.
+fn nesting(value: &Value) -> usize {
+    match value {
+        Value::Array(items) => 1 + items.iter().map(nesting).max().unwrap_or(0),
+        Value::Object(map) => 1 + map.values().map(nesting).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Apply an editor change to an open document and validate the result
+///
+/// Edits must arrive in the order they were made; `update_document` starts
+/// over from the text it is given. The result is the one `json_validate`
+/// gives for the edited text.
+#[tauri::command]
+pub async fn json_validate_incremental(
+    app: AppHandle,
+    doc_id: String,
+    edit_range: EditRange,
+    new_text: String,
+) -> Result<IncrementalValidation, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<DocumentStore>()
+            .edit_session(&doc_id, |session| {
+                let text = &session.text;
+                let start =
+                    byte_offset(text, edit_range.start_line_number, edit_range.start_column);
+                let end = byte_offset(text, edit_range.end_line_number, edit_range.end_column);
+                session.apply(start.min(end), start.max(end), &new_text)
+            })
+            .map_err(AppError::from)
+    })
+    .await
+    .map_err(|e| ErrorCode::TaskFailed.reason(e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CheckedScope, EditSession};
+    use crate::commands::document::{Document, DocumentStore};
+    use crate::commands::json::validate_json;
+    use crate::json_simd::SERDE_MAX_DEPTH;
+    use std::time::Instant;
+
+    /// Apply `edits` as `(find, replace_with)` on the first occurrence of
+    /// `find`, checking each result against validating the whole text.
+    fn edit_all(content: &str, edits: &[(&str, &str)]) -> Vec<CheckedScope> {
+        let mut session = EditSession::new(content.to_string());
+        session.apply(0, 0, "");
+        edits
+            .iter()
+            .map(|(find, replace)| {
+                let start = session.text.find(find).unwrap();
+                let outcome = session.apply(start, start + find.len(), replace);
+                let full = validate_json(&session.text);
+                assert_eq!(
+                    serde_json::to_value(&outcome.result).unwrap(),
+                    serde_json::to_value(&full).unwrap(),
+                    "{}",
+                    session.text
+                );
+                assert_eq!(
+                    outcome.fallback_reason.is_some(),
+                    outcome.checked == CheckedScope::Document
+                );
+                outcome.checked
+            })
+            .collect()
+    }
+
+    #[test]
+    fn checks_edits_inside_tokens_and_containers_locally() {
+        use CheckedScope::{Container, Document, Token};
+
+        let content = r#"[{"name": "Zoë", "age": 41, "tags": ["a", "b"]}, {"ok": true}]"#;
+        let scopes = edit_all(
+            content,
+            &[
+                ("Zoë", "Zoë Q. \\\"Z\\\""),
+                ("41", "42.5e3"),
+                ("true", "false"),
+                (r#""a", "b""#, r#""a", {"c": null}"#),
+                ("\"ok\"", "\"okay\""),
+                ("\"name\"", "\"age\""),
+                // The duplicate key makes the next edit validate everything.
+                ("42.5e3", "7"),
+            ],
+        );
+        assert_eq!(
+            scopes,
+            [Token, Token, Token, Container, Container, Document, Document]
+        );
+    }
+
+    #[test]
+    fn never_reports_an_invalid_edit_valid() {
+        let content = r#"{"a": [1, 2, "x"], "b": {"c": "d"}, "e": -0.5}"#;
+        let edits: &[(&str, &str)] = &[
+            ("\"x\"", "\"x"),
+            ("1, 2", "1 2"),
+            ("2", "2 3"),
+            ("2", "02"),
+            ("-0.5", "-"),
+            ("-0.5", ""),
+            ("\"d\"", "\"d\\q\""),
+            ("\"d\"", "\"\\ud800\""),
+            ("\"d\"", "\"\n\""),
+            ("]", ""),
+            ("{\"c\"", "[\"c\""),
+            ("\"c\": \"d\"", "\"c\": \"d\", \"c\": 1"),
+            ("\"e\"", "\"a\""),
+            ("{", ""),
+        ];
+        for edit in edits {
+            // Each edit starts from the clean document.
+            edit_all(content, &[*edit]);
+        }
+
+        // A string split in two stays valid inside its array.
+        let split = edit_all(r#"["ab"]"#, &[("b", "\", \"b")]);
+        assert_eq!(split, [CheckedScope::Container]);
+
+        // Valid on its own but past serde_json's nesting limit in place.
+        let deep = format!(
+            "{}1{}",
+            "[".repeat(SERDE_MAX_DEPTH),
+            "]".repeat(SERDE_MAX_DEPTH)
+        );
+        assert_eq!(edit_all(&deep, &[("1", "[1]")]), [CheckedScope::Document]);
+    }
+
+    #[test]
+    fn validates_everything_after_json5_or_outside_containers() {
+        let json5 = edit_all("{a: 1}", &[("1", "2"), ("a", "\"a\""), ("2", "3")]);
+        assert_eq!(
+            json5,
+            [
+                CheckedScope::Document,
+                CheckedScope::Document,
+                CheckedScope::Token
+            ]
+        );
+        let scalar = edit_all("\"abc\"", &[("b", "x"), ("\"axc\"", "1")]);
+        assert_eq!(scalar, [CheckedScope::Token, CheckedScope::Document]);
+    }
+
+    #[test]
+    fn keeps_edits_per_document_until_it_is_updated() {
+        let store = DocumentStore::default();
+        let id = store.insert(Document::parse("[1]".to_string()).unwrap());
+        let edit = |start: usize, end: usize, text: &str| {
+            store
+                .edit_session(&id, |session| {
+                    let outcome = session.apply(start, end, text);
+                    (outcome.result.valid, session.text.clone())
+                })
+                .unwrap()
+        };
+
+        assert_eq!(edit(1, 2, "12"), (true, "[12]".to_string()));
+        assert_eq!(edit(3, 4, ""), (false, "[12".to_string()));
+        store.update(&id, "[3]".to_string()).unwrap();
+        assert_eq!(edit(3, 3, " "), (true, "[3] ".to_string()));
+        assert!(store.edit_session("doc-99", |_| ()).is_err());
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture incremental_edits`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn incremental_edits_on_a_40mb_document() {
+        let mut content = String::from("[");
+        let mut index = 0;
+        while content.len() < 40 << 20 {
+            if index > 0 {
+                content.push(',');
+            }
+            content.push_str(&format!(
+                r#"{{"id":{index},"name":"user {index}","tags":["a","b"],"score":1.5E3}}"#
+            ));
+            index += 1;
+        }
+        content.push(']');
+        let mut session = EditSession::new(content);
+        let started = Instant::now();
+        session.apply(0, 0, "");
+        let full_time = started.elapsed();
+
+        let middle = session.text.find(&format!("user {}", index / 2)).unwrap();
+        let started = Instant::now();
+        let typed = session.apply(middle + 4, middle + 4, "s");
+        let token_time = started.elapsed();
+        let id = session.text.find(&format!(":{},", index / 2)).unwrap() + 1;
+        let started = Instant::now();
+        let replaced = session.apply(id, id, "[");
+        let broken_time = started.elapsed();
+
+        println!(
+            "{} MB: full validation {full_time:?}, typing in a string {token_time:?}, \
+             an edit breaking the document {broken_time:?}",
+            session.text.len() >> 20
+        );
+        assert_eq!(typed.checked, CheckedScope::Token);
+        assert!(typed.result.valid && !replaced.result.valid);
+        assert_eq!(replaced.checked, CheckedScope::Document);
+    }
+}
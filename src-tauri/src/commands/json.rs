@@ -6,30 +6,384 @@
 //   3. json5 crate with sanitized input – handles Infinity / NaN which cannot be
 //      represented in serde_json::Value (they are replaced with null before parsing)
 //
-// This chain is used consistently across format, minify, and validate.
+// This chain is used consistently across format, minify, and validate. Large
+// strict JSON documents take shortcuts with the same outcome: simd-json
+// validates them, and format and minify write them straight from the parser.
 
+use serde::{Deserialize, Serialize};
+use serde_json::ser::{CompactFormatter, PrettyFormatter};
 use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, State, Window};
+
+use super::crash::catch_panic;
+use super::document::DocumentStore;
+use super::parse_cache::ParseCache;
+use super::results::{deliver, Delivered};
+use super::tasks::spawn_task;
+use crate::error::{AppError, ErrorCode};
+use crate::json_lexer::{Lexer, TokenKind};
+use crate::json_pointer::{child_pointer, index_pointer};
+use crate::json_simd;
+use crate::json_writer::{self, transcode, write_value, RawNumbers, WidthWriter};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValidationResult {
+    pub valid: bool,
+    pub error_message: Option<String>,
+    pub error_line: Option<usize>,
+    pub error_column: Option<usize>,
+    /// Problems that don't make the document invalid.
+    pub warnings: Vec<ValidationWarning>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    DuplicateKey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValidationWarning {
+    pub kind: WarningKind,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    /// Pointer of the object containing the problem.
+    pub pointer: String,
+}
+
+/// Documents this large are formatted straight from the parser when they are
+/// strict JSON, which never holds the whole document as a `Value`.
+const STREAM_FORMAT_MIN_BYTES: usize = 4 * 1024 * 1024;
+
+/// Documents this large are checked with simd-json first.
+const SIMD_VALIDATE_MIN_BYTES: usize = 1024 * 1024;
+
+/// Inputs this large are handled on the blocking thread pool, so they don't
+/// hold up other commands; smaller ones aren't worth the hop.
+const BLOCKING_MIN_BYTES: usize = 256 * 1024;
+
+/// Validate JSON string (supports JSON5)
+///
+/// Duplicate keys are reported as warnings: the parsers silently keep the
+/// last value, so they never make a document invalid.
+#[tauri::command]
+pub async fn json_validate(app: AppHandle, content: String) -> Result<ValidationResult, AppError> {
+    off_command_thread(content.len(), move || {
+        Ok(app.state::<ParseCache>().validate(&content))
+    })
+    .await
+}
+
+/// `json_validate` on the calling thread
+pub(crate) fn validate_json(content: &str) -> ValidationResult {
+    validate(content, SIMD_VALIDATE_MIN_BYTES)
+}
+
+/// `validate_json`, also telling whether serde_json accepts `content` as
+/// standard JSON.
+pub(crate) fn validate_json_strict(content: &str) -> (ValidationResult, bool) {
+    validate_dialect(content, SIMD_VALIDATE_MIN_BYTES)
+}
+
+/// Run `work` for an input of `len` bytes, on the blocking thread pool when
+/// it is large. A panic fails the command like an error.
+pub(crate) async fn off_command_thread<T, F>(len: usize, work: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, AppError> + Send + 'static,
+{
+    let run = move || catch_panic(|| Ok(work())).map_err(|e| ErrorCode::TaskFailed.reason(e))?;
+    if len < BLOCKING_MIN_BYTES {
+        return run();
+    }
+    tauri::async_runtime::spawn_blocking(run)
+        .await
+        .map_err(|e| ErrorCode::TaskFailed.reason(e))?
+}
+
+/// `json_validate`, trying simd-json on documents of `simd_min_bytes` or more.
+/// The parsers only run when it doesn't accept the document.
+fn validate(content: &str, simd_min_bytes: usize) -> ValidationResult {
+    validate_dialect(content, simd_min_bytes).0
+}
+
+fn validate_dialect(content: &str, simd_min_bytes: usize) -> (ValidationResult, bool) {
+    let (valid, strict, error) = if content.len() >= simd_min_bytes && json_simd::accepts(content) {
+        (true, true, None)
+    } else {
+        match serde_json::from_str::<Value>(content) {
+            Ok(_) => (true, true, None),
+            Err(_) if parse_to_value(content).is_ok() => (true, false, None),
+            Err(e) => (false, false, Some(e)),
+        }
+    };
+    let result = ValidationResult {
+        valid,
+        error_message: error.as_ref().map(format_error_description),
+        error_line: error.as_ref().map(|e| e.line()),
+        error_column: error.as_ref().map(|e| e.column()),
+        warnings: duplicate_key_warnings(content),
+    };
+    (result, strict)
+}
 
 /// Format JSON string (supports JSON5)
+///
+/// With `ascii_only`, every character above U+007F is written as a `\uXXXX`
+/// escape. Otherwise (the default) escapes such as `\u00e9` in the input are
+/// written back as literal UTF-8 characters.
+///
+/// Numbers keep their source spelling (`1E3`, `2.50`, `-0`) unless
+/// `normalize_numbers` is set.
+///
+/// `indent` is the number of spaces per level (0 minifies). With
+/// `indent_char` set to `"tab"`, each level is one tab instead.
+///
+/// With `compact_width`, arrays and objects whose single-line rendering fits
+/// within that many columns stay on one line.
+///
+/// A result of `CHUNKED_MIN_BYTES` or more is returned as a stored result.
 #[tauri::command]
-pub fn json_format(content: &str, indent: Option<usize>) -> Result<String, String> {
+pub async fn json_format(
+    app: AppHandle,
+    content: String,
+    indent: Option<usize>,
+    indent_char: Option<String>,
+    ascii_only: Option<bool>,
+    normalize_numbers: Option<bool>,
+    compact_width: Option<usize>,
+) -> Result<Delivered, AppError> {
+    let cache_app = app.clone();
+    let formatted = off_command_thread(content.len(), move || {
+        cache_app.state::<ParseCache>().format(
+            &content,
+            indent,
+            indent_char,
+            ascii_only,
+            normalize_numbers,
+            compact_width,
+        )
+    })
+    .await?;
+    Ok(deliver(&app, formatted))
+}
+
+/// `json_format` on the calling thread
+pub(crate) fn format_json(
+    content: &str,
+    indent: Option<usize>,
+    indent_char: Option<String>,
+    ascii_only: Option<bool>,
+    normalize_numbers: Option<bool>,
+    compact_width: Option<usize>,
+) -> Result<String, AppError> {
     let indent_size = indent.unwrap_or(2);
-    let value: Value = parse_to_value(content)?;
+    let indent_unit = indent_unit(indent_size, indent_char.as_deref())?;
+    if let Some(formatted) = format_streamed(
+        content,
+        indent_size,
+        &indent_unit,
+        ascii_only,
+        normalize_numbers,
+        compact_width,
+    ) {
+        return Ok(formatted);
+    }
+    let value = parse_to_value(content)?;
+    format_value(
+        Cow::Owned(value),
+        content,
+        indent_size,
+        &indent_unit,
+        ascii_only,
+        normalize_numbers,
+        compact_width,
+    )
+}
+
+/// `json_format` for a document opened with `open_document`
+#[tauri::command]
+pub fn json_format_document(
+    doc_id: String,
+    indent: Option<usize>,
+    indent_char: Option<String>,
+    ascii_only: Option<bool>,
+    normalize_numbers: Option<bool>,
+    compact_width: Option<usize>,
+    store: State<'_, DocumentStore>,
+) -> Result<String, AppError> {
+    let indent_size = indent.unwrap_or(2);
+    let indent_unit = indent_unit(indent_size, indent_char.as_deref())?;
+    store.read(&doc_id, |document| {
+        format_value(
+            Cow::Borrowed(&document.value),
+            &document.content,
+            indent_size,
+            &indent_unit,
+            ascii_only,
+            normalize_numbers,
+            compact_width,
+        )
+    })?
+}
+
+/// `json_format` as a background task; returns its id (see `spawn_task`)
+#[tauri::command]
+pub fn json_format_async(
+    window: Window,
+    content: String,
+    indent: Option<usize>,
+    indent_char: Option<String>,
+    ascii_only: Option<bool>,
+    normalize_numbers: Option<bool>,
+    compact_width: Option<usize>,
+) -> Result<String, AppError> {
+    let indent_size = indent.unwrap_or(2);
+    let indent_unit = indent_unit(indent_size, indent_char.as_deref())?;
+    Ok(spawn_task(window, "format", move |task| {
+        task.step(0, 2)?;
+        if let Some(formatted) = format_streamed(
+            &content,
+            indent_size,
+            &indent_unit,
+            ascii_only,
+            normalize_numbers,
+            compact_width,
+        ) {
+            return Ok(formatted);
+        }
+        let value = parse_to_value(&content)?;
+        task.step(1, 2)?;
+        Ok(format_value(
+            Cow::Owned(value),
+            &content,
+            indent_size,
+            &indent_unit,
+            ascii_only,
+            normalize_numbers,
+            compact_width,
+        )?)
+    }))
+}
+
+fn indent_unit(indent_size: usize, indent_char: Option<&str>) -> Result<String, AppError> {
+    match indent_char.unwrap_or("space") {
+        "space" => Ok(" ".repeat(indent_size)),
+        "tab" => Ok("\t".to_string()),
+        other => Err(ErrorCode::UnsupportedIndent.with("indent", other)),
+    }
+}
 
+/// `json_format` of a large strict JSON `content` without parsing it into a
+/// `Value`; `None` when the options or the document need the `Value`.
+fn format_streamed(
+    content: &str,
+    indent_size: usize,
+    indent_unit: &str,
+    ascii_only: Option<bool>,
+    normalize_numbers: Option<bool>,
+    compact_width: Option<usize>,
+) -> Option<String> {
+    if content.len() < STREAM_FORMAT_MIN_BYTES
+        || normalize_numbers.unwrap_or(false)
+        || (compact_width.is_some() && indent_size > 0)
+    {
+        return None;
+    }
+    let formatted = if indent_size == 0 {
+        transcode(content, RawNumbers::new(CompactFormatter, content))
+    } else {
+        let formatter = PrettyFormatter::with_indent(indent_unit.as_bytes());
+        transcode(content, RawNumbers::new(formatter, content))
+    }?;
+    Some(escape_if(formatted, ascii_only))
+}
+
+/// Write `value`, parsed from `content`, with the options of `json_format`.
+/// The value is only copied when its numbers are normalized.
+fn format_value(
+    mut value: Cow<'_, Value>,
+    content: &str,
+    indent_size: usize,
+    indent_unit: &str,
+    ascii_only: Option<bool>,
+    normalize_numbers: Option<bool>,
+    compact_width: Option<usize>,
+) -> Result<String, AppError> {
+    let source = if normalize_numbers.unwrap_or(false) {
+        json_writer::normalize_numbers(value.to_mut());
+        ""
+    } else {
+        content
+    };
     let formatted = if indent_size == 0 {
-        serde_json::to_string(&value)
+        write_value(&value, RawNumbers::new(CompactFormatter, source))
+    } else if let Some(width) = compact_width {
+        Ok(WidthWriter::new(indent_unit, width, source).write(&value))
     } else {
-        serde_json::to_string_pretty(&value)
+        let formatter = PrettyFormatter::with_indent(indent_unit.as_bytes());
+        write_value(&value, RawNumbers::new(formatter, source))
     };
 
-    formatted.map_err(|e| format!("JSON formatting error: {}", e))
+    formatted.map(|text| escape_if(text, ascii_only))
+}
+
+/// Minify JSON string (supports JSON5); `ascii_only` as for `json_format`
+#[tauri::command]
+pub async fn json_minify(content: String, ascii_only: Option<bool>) -> Result<String, AppError> {
+    off_command_thread(content.len(), move || minify_json(&content, ascii_only)).await
+}
+
+/// `json_minify` on the calling thread
+pub(crate) fn minify_json(content: &str, ascii_only: Option<bool>) -> Result<String, AppError> {
+    match minify_streamed(content, ascii_only) {
+        Some(minified) => Ok(minified),
+        None => minify_value(&parse_to_value(content)?, ascii_only),
+    }
 }
 
-/// Minify JSON string (supports JSON5)
+/// `json_minify` for a document opened with `open_document`
 #[tauri::command]
-pub fn json_minify(content: &str) -> Result<String, String> {
-    let value: Value = parse_to_value(content)?;
-    serde_json::to_string(&value).map_err(|e| format!("JSON minification error: {}", e))
+pub fn json_minify_document(
+    doc_id: String,
+    ascii_only: Option<bool>,
+    store: State<'_, DocumentStore>,
+) -> Result<String, AppError> {
+    store.read(&doc_id, |document| {
+        minify_value(&document.value, ascii_only)
+    })?
+}
+
+/// `json_minify` as a background task; returns its id (see `spawn_task`)
+#[tauri::command]
+pub fn json_minify_async(window: Window, content: String, ascii_only: Option<bool>) -> String {
+    spawn_task(window, "minify", move |task| {
+        task.step(0, 2)?;
+        if let Some(minified) = minify_streamed(&content, ascii_only) {
+            return Ok(minified);
+        }
+        let value = parse_to_value(&content)?;
+        task.step(1, 2)?;
+        Ok(minify_value(&value, ascii_only)?)
+    })
+}
+
+/// `json_minify` of a large strict JSON `content` without parsing it into a
+/// `Value`, like `format_streamed`.
+fn minify_streamed(content: &str, ascii_only: Option<bool>) -> Option<String> {
+    if content.len() < STREAM_FORMAT_MIN_BYTES {
+        return None;
+    }
+    transcode(content, CompactFormatter).map(|minified| escape_if(minified, ascii_only))
+}
+
+fn minify_value(value: &Value, ascii_only: Option<bool>) -> Result<String, AppError> {
+    serde_json::to_string(value)
+        .map(|text| escape_if(text, ascii_only))
+        .map_err(|e| ErrorCode::Minify.reason(e))
 }
 
 /// Escape string (convert string to JSON string format)
@@ -40,20 +394,273 @@ pub fn json_escape(content: &str) -> String {
 
 /// Unescape string (convert JSON string format to plain string)
 #[tauri::command]
-pub fn json_unescape(content: &str) -> Result<String, String> {
-    match serde_json::from_str::<String>(content) {
-        Ok(unescaped) => Ok(unescaped),
-        Err(e) => Err(format!("Unescape failed: {}", format_error_description(&e))),
+pub fn json_unescape(content: &str) -> Result<String, AppError> {
+    serde_json::from_str::<String>(content)
+        .map_err(|e| ErrorCode::Unescape.reason(format_error_description(&e)))
+}
+
+#[derive(Serialize)]
+pub struct UnescapeLevelsResult {
+    pub content: String,
+    /// Number of escape levels that were removed.
+    pub levels: usize,
+}
+
+/// Escape string `levels` times (e.g. 2 for JSON embedded in JSON in a string)
+#[tauri::command]
+pub fn json_escape_levels(content: &str, levels: usize) -> String {
+    (0..levels).fold(content.to_string(), |current, _| json_escape(&current))
+}
+
+/// Unescape string up to `levels` times, or for as long as the result is
+/// still a quoted JSON string when `levels` is omitted.
+///
+/// Content without surrounding quotes (such as `{\"a\":1}` copied out of a
+/// log line) is unescaped as a string body. Content that cannot be unescaped
+/// at all is returned unchanged with `levels: 0` instead of an error.
+#[tauri::command]
+pub fn json_unescape_levels(content: &str, levels: Option<usize>) -> UnescapeLevelsResult {
+    let mut current = content.to_string();
+    let mut peeled = 0;
+    while levels.is_none_or(|max| peeled < max) {
+        let next = match serde_json::from_str::<String>(&current) {
+            Ok(unescaped) => unescaped,
+            Err(_) if peeled == 0 => {
+                match serde_json::from_str::<String>(&format!("\"{current}\"")) {
+                    Ok(unescaped) if unescaped != current => unescaped,
+                    _ => break,
+                }
+            }
+            Err(_) => break,
+        };
+        current = next;
+        peeled += 1;
+    }
+    UnescapeLevelsResult {
+        content: current,
+        levels: peeled,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum StringLanguage {
+    Java,
+    Kotlin,
+    Python,
+    CSharp,
+    Shell,
+    JavaScript,
+}
+
+impl StringLanguage {
+    fn parse(name: &str) -> Result<Self, AppError> {
+        match name {
+            "java" => Ok(Self::Java),
+            "kotlin" => Ok(Self::Kotlin),
+            "python" => Ok(Self::Python),
+            "csharp" => Ok(Self::CSharp),
+            "shell" => Ok(Self::Shell),
+            "javascript" => Ok(Self::JavaScript),
+            _ => Err(ErrorCode::UnsupportedLanguage.with("language", name)),
+        }
+    }
+}
+
+/// Wrap `content` as a string literal for pasting into source code:
+/// - `java` / `kotlin`: double-quoted with backslash escapes (Kotlin also escapes `$`)
+/// - `python`: triple-quoted when multi-line, otherwise double-quoted
+/// - `csharp`: verbatim `@"..."` with doubled quotes
+/// - `shell`: single-quoted, splicing quotes as `'\''`
+/// - `javascript`: template literal with backticks and `${` escaped
+#[tauri::command]
+pub fn escape_for_language(content: &str, language: String) -> Result<String, AppError> {
+    let literal = match StringLanguage::parse(&language)? {
+        StringLanguage::Java => format!("\"{}\"", escape_c_like(content, false)),
+        StringLanguage::Kotlin => format!("\"{}\"", escape_c_like(content, true)),
+        StringLanguage::Python if content.contains('\n') => python_triple_quoted(content),
+        StringLanguage::Python => format!("\"{}\"", escape_c_like(content, false)),
+        StringLanguage::CSharp => format!("@\"{}\"", content.replace('"', "\"\"")),
+        StringLanguage::Shell => format!("'{}'", content.replace('\'', "'\\''")),
+        StringLanguage::JavaScript => format!(
+            "`{}`",
+            content
+                .replace('\\', "\\\\")
+                .replace('`', "\\`")
+                .replace("${", "\\${")
+        ),
+    };
+    Ok(literal)
+}
+
+/// Backslash escapes shared by Java, Kotlin and single-line Python strings.
+/// Non-ASCII characters stay literal since all three read UTF-8 sources.
+fn escape_c_like(content: &str, escape_dollar: bool) -> String {
+    let mut out = String::with_capacity(content.len() + 2);
+    for c in content.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '$' if escape_dollar => out.push_str("\\$"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
     }
+    out
+}
+
+/// Python triple-quoted string; newlines stay literal, backslashes and
+/// double quotes are escaped so no `"""` can end the literal early.
+fn python_triple_quoted(content: &str) -> String {
+    let mut out = String::from("\"\"\"");
+    for c in content.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push('\n'),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push_str("\"\"\"");
+    out
 }
 
 // ── Internal helpers ──────────────────────────────────────────────────
 
+/// Serialize `value` minified when `indent` is 0, otherwise pretty-printed
+/// with `indent` spaces per level.
+pub(crate) fn to_indented_string(value: &Value, indent: usize) -> Result<String, AppError> {
+    if indent == 0 {
+        return serde_json::to_string(value).map_err(|e| ErrorCode::Formatting.reason(e));
+    }
+    let indent = " ".repeat(indent);
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    value
+        .serialize(&mut serializer)
+        .map_err(|e| ErrorCode::Formatting.reason(e))?;
+    String::from_utf8(out).map_err(|e| ErrorCode::Formatting.reason(e))
+}
+
+pub(crate) fn escape_if(json: String, ascii_only: Option<bool>) -> String {
+    if ascii_only.unwrap_or(false) {
+        escape_non_ascii(&json)
+    } else {
+        json
+    }
+}
+
+/// Replace every non-ASCII character in serialized JSON with `\uXXXX`
+/// escapes, using surrogate pairs outside the BMP. Such characters can only
+/// occur inside strings, so the result stays valid JSON.
+fn escape_non_ascii(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    let mut units = [0u16; 2];
+    for c in json.chars() {
+        if c.is_ascii() {
+            out.push(c);
+            continue;
+        }
+        for unit in c.encode_utf16(&mut units) {
+            out.push_str(&format!("\\u{:04x}", unit));
+        }
+    }
+    out
+}
+
+enum Frame {
+    Object {
+        pointer: String,
+        seen: HashMap<String, (usize, usize)>,
+        key: Option<String>,
+        expect_key: bool,
+    },
+    Array {
+        pointer: String,
+        index: usize,
+    },
+}
+
+impl Frame {
+    /// Pointer of the value that starts at the current position.
+    fn child_pointer(&self) -> String {
+        match self {
+            Frame::Object { pointer, key, .. } => {
+                child_pointer(pointer, key.as_deref().unwrap_or(""))
+            }
+            Frame::Array { pointer, index } => index_pointer(pointer, *index),
+        }
+    }
+}
+
+/// Scan the raw tokens for keys repeated within the same object.
+pub(crate) fn duplicate_key_warnings(content: &str) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    let mut frames: Vec<Frame> = Vec::new();
+    for token in Lexer::new(content) {
+        let child = frames.last().map_or_else(String::new, Frame::child_pointer);
+        match token.kind {
+            TokenKind::BeginObject => frames.push(Frame::Object {
+                pointer: child,
+                seen: HashMap::new(),
+                key: None,
+                expect_key: true,
+            }),
+            TokenKind::BeginArray => frames.push(Frame::Array {
+                pointer: child,
+                index: 0,
+            }),
+            TokenKind::EndObject | TokenKind::EndArray => {
+                frames.pop();
+            }
+            TokenKind::Comma => match frames.last_mut() {
+                Some(Frame::Object { expect_key, .. }) => *expect_key = true,
+                Some(Frame::Array { index, .. }) => *index += 1,
+                None => {}
+            },
+            TokenKind::String(name) | TokenKind::Word(name) => {
+                let Some(Frame::Object {
+                    pointer,
+                    seen,
+                    key,
+                    expect_key: expect_key @ true,
+                }) = frames.last_mut()
+                else {
+                    continue;
+                };
+                *expect_key = false;
+                if let Some(&(first_line, first_column)) = seen.get(&name) {
+                    warnings.push(ValidationWarning {
+                        kind: WarningKind::DuplicateKey,
+                        message: format!(
+                            "Duplicate key \"{name}\" (first defined at line {first_line}, column {first_column})"
+                        ),
+                        line: token.line,
+                        column: token.column,
+                        pointer: pointer.clone(),
+                    });
+                } else {
+                    seen.insert(name.clone(), (token.line, token.column));
+                }
+                *key = Some(name);
+            }
+            _ => {}
+        }
+    }
+    warnings
+}
+
 /// Three-level fallback parsing chain: JSON → JSON5 → JSON5 (sanitized).
 ///
 /// Level 3 is needed because serde_json::Value cannot represent Infinity or NaN.
 /// We sanitize those tokens to null so the rest of the structure can still be parsed.
-fn parse_to_value(content: &str) -> Result<Value, String> {
+pub(crate) fn parse_to_value(content: &str) -> Result<Value, AppError> {
     if let Ok(v) = serde_json::from_str::<Value>(content) {
         return Ok(v);
     }
@@ -61,7 +668,18 @@ fn parse_to_value(content: &str) -> Result<Value, String> {
         return Ok(v);
     }
     let sanitized = sanitize_json5_special_values(content);
-    json5::from_str::<Value>(&sanitized).map_err(|e| format!("JSON/JSON5 parsing error: {}", e))
+    json5::from_str::<Value>(&sanitized).map_err(parse_error)
+}
+
+/// `Parse` with the line and column json5 reports, when it has them.
+fn parse_error(error: json5::Error) -> AppError {
+    let json5::Error::Message { location, .. } = &error;
+    let location = location.as_ref().map(|at| (at.line, at.column));
+    let error = ErrorCode::Parse.reason(&error);
+    match location {
+        Some((line, column)) => error.with("line", line).with("column", column),
+        None => error,
+    }
 }
 
 /// Replace JSON5 special numeric literals (Infinity, -Infinity, +Infinity, NaN)
@@ -168,7 +786,7 @@ fn matches_word(chars: &[char], pos: usize, word: &str) -> bool {
     true
 }
 
-fn format_error_description(e: &serde_json::Error) -> String {
+pub(crate) fn format_error_description(e: &serde_json::Error) -> String {
     let msg = e.to_string();
     if let Some(pos) = msg.find(" at line ") {
         msg[..pos].to_string()
@@ -179,13 +797,19 @@ fn format_error_description(e: &serde_json::Error) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::json_format;
+    use super::{
+        escape_for_language, format_json, json_escape_levels, json_minify, json_unescape_levels,
+        minify_json, off_command_thread, validate, validate_json, WarningKind, BLOCKING_MIN_BYTES,
+    };
+    use crate::error::{AppError, ErrorCode};
+    use std::sync::mpsc;
+    use std::time::Duration;
 
     #[test]
     fn json_format_preserves_object_key_order() {
         let input = r#"{"z":1,"a":2,"m":{"y":3,"b":4}}"#;
 
-        let formatted = json_format(input, Some(2)).unwrap();
+        let formatted = format_json(input, Some(2), None, None, None, None).unwrap();
 
         assert!(formatted.find(r#""z""#).unwrap() < formatted.find(r#""a""#).unwrap());
         assert!(formatted.find(r#""y""#).unwrap() < formatted.find(r#""b""#).unwrap());
@@ -195,9 +819,336 @@ mod tests {
     fn json5_format_preserves_object_key_order() {
         let input = "{z:1,a:2,m:{y:3,b:4}}";
 
-        let formatted = json_format(input, Some(2)).unwrap();
+        let formatted = format_json(input, Some(2), None, None, None, None).unwrap();
 
         assert!(formatted.find(r#""z""#).unwrap() < formatted.find(r#""a""#).unwrap());
         assert!(formatted.find(r#""y""#).unwrap() < formatted.find(r#""b""#).unwrap());
     }
+
+    #[test]
+    fn escape_levels_round_trip_exactly() {
+        let original = "{\"msg\": \"line\\nbreak \\\"quoted\\\"\"}";
+        let escaped = json_escape_levels(original, 3);
+
+        let explicit = json_unescape_levels(&escaped, Some(3));
+        assert_eq!(explicit.content, original);
+        assert_eq!(explicit.levels, 3);
+
+        let auto = json_unescape_levels(&escaped, None);
+        assert_eq!(auto.content, original);
+        assert_eq!(auto.levels, 3);
+    }
+
+    #[test]
+    fn unescape_levels_falls_back_for_unquoted_content() {
+        let bare = json_unescape_levels(r#"{\"a\":1}"#, None);
+        assert_eq!(bare.content, r#"{"a":1}"#);
+        assert_eq!(bare.levels, 1);
+
+        let plain = json_unescape_levels(r#"{"a":1}"#, Some(2));
+        assert_eq!(plain.content, r#"{"a":1}"#);
+        assert_eq!(plain.levels, 0);
+    }
+
+    #[test]
+    fn ascii_only_escapes_non_ascii_with_surrogate_pairs() {
+        let input = r#"{"name":"café 😀","emoji":"\ud83d\ude00"}"#;
+
+        assert_eq!(
+            minify_json(input, Some(true)).unwrap(),
+            r#"{"name":"caf\u00e9 \ud83d\ude00","emoji":"\ud83d\ude00"}"#
+        );
+        assert_eq!(
+            format_json(input, Some(2), None, Some(true), None, None).unwrap(),
+            "{\n  \"name\": \"caf\\u00e9 \\ud83d\\ude00\",\n  \"emoji\": \"\\ud83d\\ude00\"\n}"
+        );
+    }
+
+    #[test]
+    fn default_output_writes_unicode_escapes_as_literal_characters() {
+        let input = r#"{"name":"caf\u00e9","quote":"\u0022","emoji":"\ud83d\ude00"}"#;
+
+        assert_eq!(
+            minify_json(input, None).unwrap(),
+            r#"{"name":"café","quote":"\"","emoji":"😀"}"#
+        );
+    }
+
+    #[test]
+    fn escapes_string_literals_per_language() {
+        let content = "{\"path\": \"C:\\tmp\", \"cost\": \"${x}\", \"it's\": `a`}\nend";
+        let escape = |language: &str| escape_for_language(content, language.to_string()).unwrap();
+
+        assert_eq!(
+            escape("java"),
+            r#""{\"path\": \"C:\\tmp\", \"cost\": \"${x}\", \"it's\": `a`}\nend""#
+        );
+        assert_eq!(
+            escape("kotlin"),
+            r#""{\"path\": \"C:\\tmp\", \"cost\": \"\${x}\", \"it's\": `a`}\nend""#
+        );
+        assert_eq!(
+            escape("python"),
+            "\"\"\"{\\\"path\\\": \\\"C:\\\\tmp\\\", \\\"cost\\\": \\\"${x}\\\", \\\"it's\\\": `a`}\nend\"\"\""
+        );
+        assert_eq!(
+            escape("csharp"),
+            "@\"{\"\"path\"\": \"\"C:\\tmp\"\", \"\"cost\"\": \"\"${x}\"\", \"\"it's\"\": `a`}\nend\""
+        );
+        assert_eq!(
+            escape("shell"),
+            "'{\"path\": \"C:\\tmp\", \"cost\": \"${x}\", \"it'\\''s\": `a`}\nend'"
+        );
+        assert_eq!(
+            escape("javascript"),
+            "`{\"path\": \"C:\\\\tmp\", \"cost\": \"\\${x}\", \"it's\": \\`a\\`}\nend`"
+        );
+        assert_eq!(
+            escape_for_language("a\"b", "python".to_string()).unwrap(),
+            r#""a\"b""#
+        );
+        assert!(escape_for_language("x", "cobol".to_string()).is_err());
+    }
+
+    fn nested(depth: usize, open: &str, close: &str) -> String {
+        format!("{}1{}", open.repeat(depth), close.repeat(depth))
+    }
+
+    /// Documents where the parsers are easy to tell apart.
+    fn corpus() -> Vec<String> {
+        let mut corpus: Vec<String> = [
+            "{}",
+            "[]",
+            "0",
+            "-0",
+            "1E+2",
+            "[1e-400]",
+            "1e400",
+            "-1e400",
+            "123456789012345678901234567890",
+            "[18446744073709551616, -9223372036854775809]",
+            "1.7976931348623157e309",
+            "01",
+            "1.",
+            ".5",
+            "1e",
+            "NaN",
+            "Infinity",
+            "true",
+            "[true,false,null]",
+            "nul",
+            "",
+            " ",
+            "[1]\n",
+            "[1] x",
+            "\u{feff}[1]",
+            "[1,]",
+            r#"{"a":1,}"#,
+            r#"{"a":1,"a":2}"#,
+            "{a:1}",
+            "// c\n1",
+            r#""😀""#,
+            r#""\ud800""#,
+            r#""\udc00""#,
+            r#""\ud800x""#,
+            r#""\ud800A""#,
+            r#""\ud800𐀀""#,
+            r#"{"\udbff":1}"#,
+            r#""\\ud800""#,
+            r#""\\\ud800""#,
+            r#""\x""#,
+            r#""\u12""#,
+            "\"\t\"",
+            "\"\u{1}\"",
+            "\"\u{7f}é😀\"",
+            r#""\/\b\f\n\r\t\"\\""#,
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+        for depth in [127, 128, 129, 200] {
+            corpus.push(nested(depth, "[", "]"));
+            corpus.push(nested(depth, r#"{"a":"#, "}"));
+            corpus.push(nested(depth / 2, r#"[{"k":[0,"#, "]}]"));
+        }
+        corpus
+    }
+
+    #[test]
+    fn simd_validation_agrees_with_the_parsers() {
+        for document in corpus() {
+            let parsers = validate(&document, usize::MAX);
+            let simd = validate(&document, 0);
+            assert_eq!(simd.valid, parsers.valid, "{document:?}");
+            assert_eq!(simd.error_message, parsers.error_message);
+            assert_eq!(
+                (simd.error_line, simd.error_column),
+                (parsers.error_line, parsers.error_column)
+            );
+        }
+    }
+
+    #[test]
+    fn validate_reports_duplicate_keys_as_warnings() {
+        let input = "{\n  \"a\": 1,\n  \"list\": [{\"id\": 1, \"id\": 2}],\n  \"\\u0061\": 3\n}";
+
+        let result = validate_json(input);
+
+        assert!(result.valid);
+        assert_eq!(result.error_message, None);
+        assert_eq!(result.warnings.len(), 2);
+        let nested = &result.warnings[0];
+        assert_eq!(nested.kind, WarningKind::DuplicateKey);
+        assert_eq!(nested.pointer, "/list/0");
+        assert_eq!((nested.line, nested.column), (3, 22));
+        assert_eq!(
+            nested.message,
+            "Duplicate key \"id\" (first defined at line 3, column 13)"
+        );
+        assert_eq!(result.warnings[1].pointer, "");
+        assert_eq!(result.warnings[1].line, 4);
+    }
+
+    #[test]
+    fn validate_reports_syntax_errors_with_position() {
+        let result = validate_json("{\n  \"a\": 1\n  \"b\": 2\n}");
+
+        assert!(!result.valid);
+        assert_eq!(result.error_line, Some(3));
+        assert!(result.error_message.is_some());
+        assert!(result.warnings.is_empty());
+
+        assert!(validate_json("{a: 1, // note\n}").valid);
+    }
+
+    #[test]
+    fn format_and_minify_keep_big_numbers_exact() {
+        let input = r#"{"id": 12345678901234567890123, "snowflake": 9007199254740993, "price": 0.1000000000000000055, "huge": 1.5e+400, "tiny": -2e-400}"#;
+
+        let minified = minify_json(input, None).unwrap();
+        assert_eq!(
+            minified,
+            r#"{"id":12345678901234567890123,"snowflake":9007199254740993,"price":0.1000000000000000055,"huge":1.5e+400,"tiny":-2e-400}"#
+        );
+        let formatted = format_json(input, Some(2), None, None, None, None).unwrap();
+        assert!(formatted.contains(r#""id": 12345678901234567890123,"#));
+        assert!(formatted.contains(r#""price": 0.1000000000000000055,"#));
+        assert!(formatted.contains(r#""huge": 1.5e+400,"#));
+    }
+
+    #[test]
+    fn format_keeps_number_spelling_unless_normalized() {
+        let input = r#"{"e": 1E3, "f": 6.02e23, "price": 2.50, "zero": -0, "one": 1.0}"#;
+
+        assert_eq!(
+            format_json(input, Some(0), None, None, None, None).unwrap(),
+            r#"{"e":1E3,"f":6.02e23,"price":2.50,"zero":-0,"one":1.0}"#
+        );
+        assert_eq!(
+            format_json(input, Some(0), None, None, Some(true), None).unwrap(),
+            r#"{"e":1000.0,"f":6.02e+23,"price":2.5,"zero":0,"one":1.0}"#
+        );
+    }
+
+    #[test]
+    fn format_honors_indent_size_and_tabs() {
+        let input = r#"{"a":[1,{"b":null}]}"#;
+        let format = |indent: usize, indent_char: Option<&str>| {
+            format_json(
+                input,
+                Some(indent),
+                indent_char.map(String::from),
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(format(0, None), r#"{"a":[1,{"b":null}]}"#);
+        assert_eq!(
+            format(2, None),
+            "{\n  \"a\": [\n    1,\n    {\n      \"b\": null\n    }\n  ]\n}"
+        );
+        assert_eq!(
+            format(4, Some("space")),
+            "{\n    \"a\": [\n        1,\n        {\n            \"b\": null\n        }\n    ]\n}"
+        );
+        assert_eq!(
+            format(8, None),
+            "{\n        \"a\": [\n                1,\n                {\n                        \"b\": null\n                }\n        ]\n}"
+        );
+        assert_eq!(
+            format(1, Some("tab")),
+            "{\n\t\"a\": [\n\t\t1,\n\t\t{\n\t\t\t\"b\": null\n\t\t}\n\t]\n}"
+        );
+        assert!(format_json(input, Some(2), Some("nbsp".into()), None, None, None).is_err());
+    }
+
+    #[test]
+    fn compact_width_inlines_short_containers() {
+        let input = r#"{"bbox": [1, 2, 3, 4], "features": [{"id": 1}, {"id": 2}]}"#;
+
+        assert_eq!(
+            format_json(input, Some(2), None, None, None, Some(40)).unwrap(),
+            "{\n  \"bbox\": [1, 2, 3, 4],\n  \"features\": [{\"id\": 1}, {\"id\": 2}]\n}"
+        );
+        assert_eq!(
+            format_json(input, Some(2), None, None, None, Some(80)).unwrap(),
+            r#"{"bbox": [1, 2, 3, 4], "features": [{"id": 1}, {"id": 2}]}"#
+        );
+    }
+
+    #[test]
+    fn minify_preserves_object_key_order() {
+        let input = r#"{"version": 1, "name": "pkg", "dependencies": {"zod": "3", "axios": "1"}}"#;
+
+        assert_eq!(
+            minify_json(input, None).unwrap(),
+            r#"{"version":1,"name":"pkg","dependencies":{"zod":"3","axios":"1"}}"#
+        );
+    }
+
+    #[test]
+    fn large_inputs_leave_the_command_thread_free() {
+        // One thread, like the one that would run the commands: a slow format
+        // running there would starve the validation started after it.
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let (release, released) = mpsc::channel();
+        let slow_format = runtime.spawn(off_command_thread(BLOCKING_MIN_BYTES, move || {
+            released
+                .recv_timeout(Duration::from_secs(5))
+                .map_err(|e| ErrorCode::Formatting.reason(e))
+        }));
+        let content = format!("[{}0]", "0,".repeat(BLOCKING_MIN_BYTES));
+
+        let validated = runtime.block_on(async {
+            tokio::task::yield_now().await;
+            off_command_thread(content.len(), move || Ok(validate_json(&content))).await
+        });
+        release.send(()).unwrap();
+
+        assert!(validated.unwrap().valid);
+        assert!(runtime.block_on(slow_format).unwrap().is_ok());
+    }
+
+    #[test]
+    fn reports_errors_and_panics_of_large_inputs() {
+        let invalid = format!("[1,{}", " ".repeat(BLOCKING_MIN_BYTES));
+        let error = tauri::async_runtime::block_on(json_minify(invalid, None)).unwrap_err();
+        assert_eq!(error.code, ErrorCode::Parse);
+
+        for len in [0, BLOCKING_MIN_BYTES] {
+            let panicked: Result<(), AppError> =
+                tauri::async_runtime::block_on(off_command_thread(len, || panic!("too deep")));
+            let error = panicked.unwrap_err();
+            assert_eq!(error.code, ErrorCode::TaskFailed);
+            assert_eq!(
+                error.param("reason").and_then(|reason| reason.as_str()),
+                Some("Task panicked: too deep")
+            );
+        }
+    }
 }
@@ -0,0 +1,304 @@
+// JSON lint command
+//
+// Opinionated warnings about documents that are valid but probably not what
+// the author meant. Findings are reported in document order.
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::commands::json::parse_to_value;
+use crate::commands::transform::KeyCase;
+use crate::json_pointer::{child_pointer, index_pointer};
+use crate::json_value::value_type_name;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    MixedArrayTypes,
+    InconsistentKeyCase,
+    CaseInsensitiveDuplicateKeys,
+    StringifiedScalar,
+    EmptyContainer,
+    DuplicateAdjacentElements,
+}
+
+const ALL_RULES: [LintRule; 6] = [
+    LintRule::MixedArrayTypes,
+    LintRule::InconsistentKeyCase,
+    LintRule::CaseInsensitiveDuplicateKeys,
+    LintRule::StringifiedScalar,
+    LintRule::EmptyContainer,
+    LintRule::DuplicateAdjacentElements,
+];
+
+impl LintRule {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "mixed_array_types" => Ok(Self::MixedArrayTypes),
+            "inconsistent_key_case" => Ok(Self::InconsistentKeyCase),
+            "case_insensitive_duplicate_keys" => Ok(Self::CaseInsensitiveDuplicateKeys),
+            "stringified_scalar" => Ok(Self::StringifiedScalar),
+            "empty_container" => Ok(Self::EmptyContainer),
+            "duplicate_adjacent_elements" => Ok(Self::DuplicateAdjacentElements),
+            _ => Err(format!("Unsupported lint rule: {name}")),
+        }
+    }
+
+    fn severity(self) -> LintSeverity {
+        match self {
+            Self::StringifiedScalar | Self::EmptyContainer => LintSeverity::Info,
+            _ => LintSeverity::Warning,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    Warning,
+    Info,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub severity: LintSeverity,
+    pub pointer: String,
+    pub message: String,
+}
+
+/// Report structural smells in a JSON document
+///
+/// `rules` lists the rule ids to run (`mixed_array_types`,
+/// `inconsistent_key_case`, `case_insensitive_duplicate_keys`,
+/// `stringified_scalar`, `empty_container`, `duplicate_adjacent_elements`);
+/// all rules run when it is omitted.
+#[tauri::command]
+pub fn json_lint(content: &str, rules: Option<Vec<String>>) -> Result<Vec<LintFinding>, String> {
+    let rules = match rules {
+        Some(names) => names
+            .iter()
+            .map(|name| LintRule::parse(name))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => ALL_RULES.to_vec(),
+    };
+    let value = parse_to_value(content)?;
+    let mut linter = Linter {
+        rules,
+        findings: Vec::new(),
+    };
+    linter.visit(&value, "");
+    Ok(linter.findings)
+}
+
+struct Linter {
+    rules: Vec<LintRule>,
+    findings: Vec<LintFinding>,
+}
+
+impl Linter {
+    fn report(&mut self, rule: LintRule, pointer: &str, message: String) {
+        if self.rules.contains(&rule) {
+            self.findings.push(LintFinding {
+                rule,
+                severity: rule.severity(),
+                pointer: pointer.to_string(),
+                message,
+            });
+        }
+    }
+
+    fn visit(&mut self, value: &Value, pointer: &str) {
+        match value {
+            Value::String(text) => {
+                if let Some(kind) = stringified_kind(text) {
+                    let message = format!("String \"{text}\" looks like a {kind}");
+                    self.report(LintRule::StringifiedScalar, pointer, message);
+                }
+            }
+            Value::Array(items) => {
+                self.check_array(items, pointer);
+                for (index, item) in items.iter().enumerate() {
+                    self.visit(item, &index_pointer(pointer, index));
+                }
+            }
+            Value::Object(map) => {
+                self.check_object(map, pointer);
+                for (key, child) in map {
+                    self.visit(child, &child_pointer(pointer, key));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_array(&mut self, items: &[Value], pointer: &str) {
+        if items.is_empty() {
+            self.report(LintRule::EmptyContainer, pointer, "Empty array".to_string());
+            return;
+        }
+
+        // Nulls usually mark missing entries rather than a second type.
+        let mut types: Vec<&str> = Vec::new();
+        for item in items.iter().filter(|item| !item.is_null()) {
+            let name = value_type_name(item);
+            if !types.contains(&name) {
+                types.push(name);
+            }
+        }
+        if types.len() > 1 {
+            let message = format!("Array mixes value types: {}", types.join(", "));
+            self.report(LintRule::MixedArrayTypes, pointer, message);
+        }
+
+        if let Some((first, second)) = conflicting_key_cases(items) {
+            let message = format!(
+                "Objects in this array mix key casing styles, e.g. '{first}' and '{second}'"
+            );
+            self.report(LintRule::InconsistentKeyCase, pointer, message);
+        }
+
+        for index in 1..items.len() {
+            if items[index] == items[index - 1] {
+                let message = format!("Element {index} repeats the element before it");
+                self.report(
+                    LintRule::DuplicateAdjacentElements,
+                    &index_pointer(pointer, index),
+                    message,
+                );
+            }
+        }
+    }
+
+    fn check_object(&mut self, map: &Map<String, Value>, pointer: &str) {
+        if map.is_empty() {
+            self.report(
+                LintRule::EmptyContainer,
+                pointer,
+                "Empty object".to_string(),
+            );
+            return;
+        }
+
+        let mut seen: HashMap<String, &str> = HashMap::new();
+        for key in map.keys() {
+            if let Some(previous) = seen.insert(key.to_lowercase(), key) {
+                let message = format!("Keys '{previous}' and '{key}' differ only by case");
+                self.report(LintRule::CaseInsensitiveDuplicateKeys, pointer, message);
+            }
+        }
+    }
+}
+
+/// `"number"` or `"boolean"` when the string holds exactly such a literal.
+fn stringified_kind(text: &str) -> Option<&'static str> {
+    match text {
+        "true" | "false" => Some("boolean"),
+        _ if serde_json::from_str::<serde_json::Number>(text).is_ok() => Some("number"),
+        _ => None,
+    }
+}
+
+const KEY_CASES: [KeyCase; 4] = [
+    KeyCase::Camel,
+    KeyCase::Snake,
+    KeyCase::Kebab,
+    KeyCase::Pascal,
+];
+
+/// Bit set of the key cases `key` is already written in. Single lowercase
+/// words like `id` fit several cases and never conflict.
+fn key_case_mask(key: &str) -> u8 {
+    KEY_CASES
+        .iter()
+        .enumerate()
+        .filter(|(_, case)| case.apply(key) == key)
+        .fold(0, |mask, (bit, _)| mask | 1 << bit)
+}
+
+/// Two keys from the objects of `items` that no single casing style covers.
+fn conflicting_key_cases(items: &[Value]) -> Option<(&str, &str)> {
+    let mut common = u8::MAX;
+    let mut first: Option<&str> = None;
+    for key in items
+        .iter()
+        .filter_map(Value::as_object)
+        .flat_map(Map::keys)
+    {
+        let mask = key_case_mask(key);
+        // Keys such as `$ref` follow no style at all.
+        if mask == 0 {
+            continue;
+        }
+        if common & mask == 0 {
+            return first.map(|first| (first, key.as_str()));
+        }
+        common &= mask;
+        first.get_or_insert(key);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_lint, LintRule, LintSeverity};
+
+    fn findings(content: &str, rules: Option<Vec<String>>) -> Vec<(LintRule, String)> {
+        json_lint(content, rules)
+            .unwrap()
+            .into_iter()
+            .map(|finding| (finding.rule, finding.pointer))
+            .collect()
+    }
+
+    #[test]
+    fn reports_each_rule_with_pointers() {
+        let content = r#"{
+            "mixed": [1, "two", null],
+            "users": [{"userId": 1, "name": "a"}, {"user_id": 2}],
+            "flags": {"Name": "x", "name": "y"},
+            "counts": ["42", "true", "forty"],
+            "empty": {},
+            "repeats": [[1], [1], 2]
+        }"#;
+
+        assert_eq!(
+            findings(content, None),
+            [
+                (LintRule::MixedArrayTypes, "/mixed".to_string()),
+                (LintRule::InconsistentKeyCase, "/users".to_string()),
+                (LintRule::CaseInsensitiveDuplicateKeys, "/flags".to_string()),
+                (LintRule::StringifiedScalar, "/counts/0".to_string()),
+                (LintRule::StringifiedScalar, "/counts/1".to_string()),
+                (LintRule::EmptyContainer, "/empty".to_string()),
+                (LintRule::MixedArrayTypes, "/repeats".to_string()),
+                (
+                    LintRule::DuplicateAdjacentElements,
+                    "/repeats/1".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn runs_only_selected_rules() {
+        let content = r#"[{"id": 1, "createdAt": 2}, {"id": 1, "createdAt": 2}, {}]"#;
+
+        let result = json_lint(content, Some(vec!["empty-container".to_string()])).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].severity, LintSeverity::Info);
+        assert_eq!(result[0].pointer, "/2");
+
+        assert_eq!(
+            findings(content, None),
+            [
+                (LintRule::DuplicateAdjacentElements, "/1".to_string()),
+                (LintRule::EmptyContainer, "/2".to_string()),
+            ]
+        );
+        assert_eq!(
+            json_lint(content, Some(vec!["nope".to_string()])).unwrap_err(),
+            "Unsupported lint rule: nope"
+        );
+    }
+}
@@ -0,0 +1,311 @@
+// Diagnostic log
+//
+// Records from the `log` macros go to `jsonstudio.log` in the app log
+// directory, one JSON object per line. The file is rotated by size and the
+// last few are kept, so a user can attach them to a bug report. Only command
+// names, sizes and durations are logged, never document contents.
+use log::{LevelFilter, Log, Metadata, Record};
+use serde_json::{json, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::{AppHandle, Manager};
+
+use super::settings::{setting, update_settings, LOG_LEVEL_SETTING};
+
+const LOG_FILE: &str = "jsonstudio.log";
+/// Size at which the log is rotated.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept next to the current one: `jsonstudio.1.log` and so on.
+const ROTATED_LOGS: usize = 2;
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+static LOGGER: FileLogger = FileLogger {
+    file: Mutex::new(None),
+};
+
+struct FileLogger {
+    file: Mutex<Option<LogFile>>,
+}
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 + 1 > MAX_LOG_BYTES {
+            rotate(&self.path, ROTATED_LOGS)?;
+            *self = Self::open(self.path.clone())?;
+        }
+        writeln!(self.file, "{line}")?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if cfg!(debug_assertions) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+        let line = json!({
+            "time": chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            "level": record.level().as_str(),
+            "target": record.target(),
+            "message": record.args().to_string(),
+        });
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            let _ = file.write_line(&line.to_string());
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+/// Start logging to the app log directory at the saved level. Logging is only
+/// a diagnostic aid, so the app starts without it if the file can't be opened.
+pub(crate) fn init(app: &AppHandle) {
+    let level = setting::<String>(app, LOG_LEVEL_SETTING)
+        .and_then(|level| LevelFilter::from_str(&level).ok())
+        .unwrap_or(DEFAULT_LEVEL);
+    if log::set_logger(&LOGGER).is_err() {
+        return;
+    }
+    log::set_max_level(level);
+    match log_path(app).and_then(|path| LogFile::open(path).map_err(|e| e.to_string())) {
+        Ok(file) => *LOGGER.file.lock().unwrap() = Some(file),
+        Err(error) => eprintln!("Failed to open log file: {error}"),
+    }
+    log::info!("JSON Studio {} started", app.package_info().version);
+}
+
+fn log_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve log directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+    Ok(dir.join(LOG_FILE))
+}
+
+/// `jsonstudio.log` → `jsonstudio.{index}.log`
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}.{index}.log"))
+}
+
+/// Shift every rotated file up by one, dropping the oldest, and move the
+/// current file into the first slot.
+fn rotate(path: &Path, keep: usize) -> std::io::Result<()> {
+    if keep == 0 {
+        return fs::remove_file(path);
+    }
+    for index in (1..keep).rev() {
+        let from = rotated_path(path, index);
+        if from.exists() {
+            fs::rename(&from, rotated_path(path, index + 1))?;
+        }
+    }
+    fs::rename(path, rotated_path(path, 1))
+}
+
+/// The last `lines` lines across the current and rotated files, oldest first.
+fn recent_lines(path: &Path, lines: usize) -> Vec<String> {
+    let files = std::iter::once(path.to_path_buf())
+        .chain((1..=ROTATED_LOGS).map(|index| rotated_path(path, index)));
+    let mut recent: Vec<String> = Vec::new();
+    for file in files {
+        if recent.len() >= lines {
+            break;
+        }
+        let Ok(file) = File::open(file) else {
+            continue;
+        };
+        let mut older: Vec<String> = BufReader::new(file).lines().map_while(Result::ok).collect();
+        let skip = older.len().saturating_sub(lines - recent.len());
+        older.drain(..skip);
+        older.append(&mut recent);
+        recent = older;
+    }
+    recent
+}
+
+/// The last `lines` lines of the open log, for a crash report. Gives up
+/// rather than wait when the log is busy, since the panic may have happened
+/// while it was being written.
+pub(crate) fn recent_log_lines(lines: usize) -> Vec<String> {
+    let path = match LOGGER.file.try_lock() {
+        Ok(mut file) => file.as_mut().map(|file| {
+            let _ = file.file.flush();
+            file.path.clone()
+        }),
+        Err(_) => None,
+    };
+    path.map(|path| recent_lines(&path, lines))
+        .unwrap_or_default()
+}
+
+/// Wrap the command handler so every command logs when it starts and how
+/// long it took. Async commands return once they are scheduled, so their
+/// duration is only the synchronous part; commands log their own failures.
+pub(crate) fn instrument(
+    handler: impl Fn(Invoke) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        let bytes = payload_bytes(invoke.message.payload());
+        log::debug!(target: "command", "{command} started with {bytes} bytes");
+        let started = Instant::now();
+        let handled = handler(invoke);
+        log::info!(
+            target: "command",
+            "{command} returned after {} ms",
+            started.elapsed().as_millis()
+        );
+        handled
+    }
+}
+
+/// Approximate size of a command's arguments: raw bodies and the text of
+/// every string, which is where documents are.
+fn payload_bytes(payload: &InvokeBody) -> usize {
+    fn text_bytes(value: &Value) -> usize {
+        match value {
+            Value::String(text) => text.len(),
+            Value::Array(items) => items.iter().map(text_bytes).sum(),
+            Value::Object(map) => map.iter().map(|(k, v)| k.len() + text_bytes(v)).sum(),
+            _ => 8,
+        }
+    }
+    match payload {
+        InvokeBody::Json(value) => text_bytes(value),
+        InvokeBody::Raw(bytes) => bytes.len(),
+    }
+}
+
+/// Log a command's error before it goes back to the page.
+pub(crate) trait LogFailure {
+    fn logged(self, command: &str) -> Self;
+}
+
+impl<T, E: std::fmt::Display> LogFailure for Result<T, E> {
+    fn logged(self, command: &str) -> Self {
+        if let Err(error) = &self {
+            log::warn!(target: "command", "{command} failed: {error}");
+        }
+        self
+    }
+}
+
+/// The last `lines` lines of the log, oldest first; each is a JSON object
+/// with `time`, `level`, `target` and `message`
+#[tauri::command]
+pub fn get_recent_logs(app: AppHandle, lines: usize) -> Result<Vec<String>, String> {
+    log::logger().flush();
+    Ok(recent_lines(&log_path(&app)?, lines))
+}
+
+/// Path of the current log file
+#[tauri::command]
+pub fn get_log_file_path(app: AppHandle) -> Result<String, String> {
+    log_path(&app).map(|path| path.to_string_lossy().into_owned())
+}
+
+/// Set the lowest level written to the log (`off`, `error`, `warn`, `info`,
+/// `debug` or `trace`) and remember it
+#[tauri::command]
+pub fn set_log_level(app: AppHandle, level: String) -> Result<(), String> {
+    let filter =
+        LevelFilter::from_str(&level).map_err(|_| format!("Unsupported log level: {level}"))?;
+    log::set_max_level(filter);
+    log::info!("Log level set to {filter}");
+    update_settings(&app, |values| {
+        values.insert(LOG_LEVEL_SETTING.to_string(), json!(filter.as_str()));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{payload_bytes, recent_lines, rotate, rotated_path, LogFile, MAX_LOG_BYTES};
+    use serde_json::json;
+    use std::fs;
+    use tauri::ipc::InvokeBody;
+
+    #[test]
+    fn rotates_and_keeps_a_limited_number_of_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("jsonstudio.log");
+        for generation in ["a", "b", "c"] {
+            fs::write(&path, generation).unwrap();
+            rotate(&path, 2).unwrap();
+        }
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(rotated_path(&path, 1)).unwrap(), "c");
+        assert_eq!(fs::read_to_string(rotated_path(&path, 2)).unwrap(), "b");
+        assert!(!rotated_path(&path, 3).exists());
+    }
+
+    #[test]
+    fn rotates_before_a_line_would_pass_the_limit() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("jsonstudio.log");
+        fs::write(&path, "x".repeat(MAX_LOG_BYTES as usize - 3)).unwrap();
+        let mut file = LogFile::open(path.clone()).unwrap();
+
+        file.write_line("new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+        assert!(rotated_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn reads_recent_lines_across_rotated_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("jsonstudio.log");
+        fs::write(rotated_path(&path, 2), "1\n2\n").unwrap();
+        fs::write(rotated_path(&path, 1), "3\n4\n").unwrap();
+        fs::write(&path, "5\n").unwrap();
+
+        assert_eq!(recent_lines(&path, 2), ["4", "5"]);
+        assert_eq!(recent_lines(&path, 4), ["2", "3", "4", "5"]);
+        assert_eq!(recent_lines(&path, 10).len(), 5);
+        assert!(recent_lines(&dir.join("missing.log"), 3).is_empty());
+    }
+
+    #[test]
+    fn sizes_payloads_by_their_text() {
+        let payload = InvokeBody::Json(json!({ "content": "abcd", "indent": 2 }));
+        assert_eq!(
+            payload_bytes(&payload),
+            "content".len() + 4 + "indent".len() + 8
+        );
+        assert_eq!(payload_bytes(&InvokeBody::Raw(vec![0; 5])), 5);
+    }
+}
@@ -0,0 +1,231 @@
+// Memory-mapped files
+//
+// A file over the editor's size limit is mapped instead of read: the system
+// pages its bytes in as they are scanned, so validation, statistics and
+// subtree extraction run over the file without copying it to the heap. The
+// page gets a handle and a preview, and the file stays mapped until
+// `close_mapped_file`. Everything works on the text in place, so only UTF-8
+// files can be mapped; gzip and UTF-16 files are read as before.
+use memmap2::Mmap;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+use super::file::{preview_text, STREAMED_PREVIEW_BYTES};
+use super::json::{off_command_thread, validate_json, ValidationResult};
+use super::query::{extract_streamed, ExtractResult};
+use super::stats::{collect_stats, JsonStats};
+use crate::error::{AppError, ErrorCode};
+use crate::text_encoding;
+
+#[derive(Default)]
+pub(crate) struct MappedFiles {
+    files: Mutex<HashMap<String, Arc<MappedFile>>>,
+    next_id: AtomicU64,
+}
+
+pub(crate) struct MappedFile {
+    map: Mmap,
+    /// Where the text starts, after a BOM.
+    start: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MappedFileInfo {
+    pub handle: String,
+    /// Leading part of the file, cut at a character boundary.
+    pub preview: String,
+    /// Whether `preview` is shorter than the file.
+    pub truncated: bool,
+    pub total_bytes: u64,
+}
+
+/// Map `path` read-only.
+pub(crate) fn map_file(path: &Path) -> Result<Mmap, AppError> {
+    let file = File::open(path).map_err(|e| ErrorCode::ReadFile.reason(e))?;
+    // SAFETY: the map is only ever read. Another program truncating the file
+    // while it is mapped can still make a read fault; no read path rules
+    // that out.
+    unsafe { Mmap::map(&file) }.map_err(|e| ErrorCode::ReadFile.reason(e))
+}
+
+impl MappedFile {
+    fn open(path: &Path) -> Result<Self, AppError> {
+        let map = map_file(path)?;
+        let body = text_encoding::utf8_body(&map).ok_or_else(|| {
+            ErrorCode::ReadFile.reason("Only UTF-8 files can be opened in preview mode")
+        })?;
+        let file = Self {
+            start: map.len() - body.len(),
+            map,
+        };
+        file.text()?;
+        Ok(file)
+    }
+
+    /// The file's text. Checked on every use, since the file can change
+    /// under the map.
+    fn text(&self) -> Result<&str, AppError> {
+        std::str::from_utf8(&self.map[self.start..])
+            .map_err(|_| ErrorCode::ReadFile.reason("File is not valid UTF-8"))
+    }
+}
+
+impl MappedFiles {
+    fn open(&self, path: &Path) -> Result<MappedFileInfo, AppError> {
+        let file = MappedFile::open(path)?;
+        let total_bytes = file.map.len() as u64;
+        let truncated = file.map.len() > STREAMED_PREVIEW_BYTES;
+        let preview = preview_text(
+            &file.map[..file.map.len().min(STREAMED_PREVIEW_BYTES)],
+            truncated,
+        );
+        let handle = format!(
+            "mapped-{}",
+            self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+        );
+        self.files
+            .lock()
+            .unwrap()
+            .insert(handle.clone(), Arc::new(file));
+        Ok(MappedFileInfo {
+            handle,
+            preview,
+            truncated,
+            total_bytes,
+        })
+    }
+
+    fn get(&self, handle: &str) -> Result<Arc<MappedFile>, AppError> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(handle)
+            .cloned()
+            .ok_or_else(|| format!("Unknown file handle: {handle}").into())
+    }
+}
+
+/// The error for a file of `size` bytes over the editor's `limit`. When the
+/// file can be mapped, it is, and the error carries its `handle` and
+/// `preview` so the page can switch to preview mode.
+pub(crate) async fn file_too_large(
+    app: &AppHandle,
+    path: &Path,
+    size: u64,
+    limit: u64,
+) -> AppError {
+    let error = ErrorCode::FileTooLargeForEditing
+        .with("path", path.to_string_lossy())
+        .with("size", size)
+        .with("limit", limit);
+    let task_app = app.clone();
+    let path = path.to_path_buf();
+    match tokio::task::spawn_blocking(move || task_app.state::<MappedFiles>().open(&path)).await {
+        Ok(Ok(info)) => error
+            .with("handle", info.handle)
+            .with("preview", info.preview)
+            .with("truncated", info.truncated),
+        Ok(Err(cause)) => {
+            log::warn!("Large file could not be mapped: {cause}");
+            error
+        }
+        Err(cause) => {
+            log::warn!("Large file could not be mapped: {cause}");
+            error
+        }
+    }
+}
+
+/// Run `work` on the text of the mapped file `handle`, off the command thread.
+async fn with_mapped<T: Send + 'static>(
+    app: AppHandle,
+    handle: &str,
+    work: impl FnOnce(&str) -> Result<T, AppError> + Send + 'static,
+) -> Result<T, AppError> {
+    let file = app.state::<MappedFiles>().get(handle)?;
+    off_command_thread(file.map.len(), move || work(file.text()?)).await
+}
+
+/// Map a UTF-8 file without reading it into memory; returns its handle
+#[tauri::command]
+pub async fn open_mapped_file(app: AppHandle, path: String) -> Result<MappedFileInfo, AppError> {
+    let path = PathBuf::from(path);
+    tokio::task::spawn_blocking(move || app.state::<MappedFiles>().open(&path))
+        .await
+        .map_err(|e| ErrorCode::TaskFailed.reason(e))?
+}
+
+/// Unmap a file; returns whether the handle was open
+#[tauri::command]
+pub fn close_mapped_file(app: AppHandle, handle: String) -> bool {
+    app.state::<MappedFiles>()
+        .files
+        .lock()
+        .unwrap()
+        .remove(&handle)
+        .is_some()
+}
+
+/// `json_validate` for a mapped file
+#[tauri::command]
+pub async fn json_validate_mapped(
+    app: AppHandle,
+    handle: String,
+) -> Result<ValidationResult, AppError> {
+    with_mapped(app, &handle, |text| Ok(validate_json(text))).await
+}
+
+/// `json_stats` for a mapped file
+#[tauri::command]
+pub async fn json_stats_mapped(app: AppHandle, handle: String) -> Result<JsonStats, AppError> {
+    with_mapped(app, &handle, |text| Ok(collect_stats(text))).await
+}
+
+/// `json_extract` for a mapped file; only the subtree is parsed into memory
+/// when the file is strict JSON
+#[tauri::command]
+pub async fn json_extract_mapped(
+    app: AppHandle,
+    handle: String,
+    pointer: String,
+    indent: Option<usize>,
+) -> Result<ExtractResult, AppError> {
+    with_mapped(app, &handle, move |text| {
+        Ok(extract_streamed(text, pointer, indent)?)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MappedFiles;
+    use std::fs;
+
+    #[test]
+    fn maps_utf8_files_without_their_bom() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("big.json");
+        fs::write(&path, b"\xEF\xBB\xBF{\"name\": \"Zo\xC3\xAB\"}").unwrap();
+        let files = MappedFiles::default();
+
+        let info = files.open(&path).unwrap();
+
+        assert_eq!(info.preview, "{\"name\": \"Zoë\"}");
+        assert!(!info.truncated);
+        assert_eq!(info.total_bytes, 19);
+        let file = files.get(&info.handle).unwrap();
+        assert_eq!(file.text().unwrap(), "{\"name\": \"Zoë\"}");
+
+        let utf16 = dir.join("utf16.json");
+        fs::write(&utf16, b"\xFF\xFE{\0}\0").unwrap();
+        assert!(files.open(&utf16).is_err());
+        assert!(files.open(&dir.join("missing.json")).is_err());
+        assert!(files.get("mapped-99").is_err());
+    }
+}
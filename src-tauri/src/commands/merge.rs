@@ -0,0 +1,334 @@
+// JSON merge commands
+use serde_json::{Map, Value};
+
+use super::json::parse_to_value;
+use crate::json_pointer::child_pointer;
+
+#[derive(Clone, Copy)]
+enum ArrayStrategy {
+    Replace,
+    Concat,
+    MergeByIndex,
+}
+
+impl ArrayStrategy {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "replace" => Ok(Self::Replace),
+            "concat" => Ok(Self::Concat),
+            "merge_by_index" => Ok(Self::MergeByIndex),
+            _ => Err(format!("Unsupported array strategy: {name}")),
+        }
+    }
+}
+
+/// Recursively merge `overlay` onto `base`. The overlay wins on scalar and
+/// type conflicts; `array_strategy` is one of `replace`, `concat`, or
+/// `merge_by_index`. With `null_deletes`, overlay nulls remove keys (RFC 7386
+/// style) instead of being kept as explicit nulls.
+#[tauri::command]
+pub fn json_merge(
+    base: &str,
+    overlay: &str,
+    array_strategy: String,
+    null_deletes: Option<bool>,
+) -> Result<String, String> {
+    let strategy = ArrayStrategy::parse(&array_strategy)?;
+    let mut merged = parse_labeled(base, "Base")?;
+    let overlay = parse_labeled(overlay, "Overlay")?;
+    deep_merge(
+        &mut merged,
+        overlay,
+        strategy,
+        null_deletes.unwrap_or(false),
+    );
+    serde_json::to_string_pretty(&merged).map_err(|e| format!("JSON formatting error: {}", e))
+}
+
+fn parse_labeled(content: &str, label: &str) -> Result<Value, String> {
+    parse_to_value(content).map_err(|e| format!("{label} document: {e}"))
+}
+
+fn deep_merge(base: &mut Value, overlay: Value, strategy: ArrayStrategy, null_deletes: bool) {
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, value) in overlay_map {
+                if value.is_null() && null_deletes {
+                    base_map.shift_remove(&key);
+                    continue;
+                }
+                match base_map.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value, strategy, null_deletes),
+                    None => {
+                        base_map.insert(key, without_deleting_nulls(value, strategy, null_deletes));
+                    }
+                }
+            }
+        }
+        (Value::Array(base_items), Value::Array(overlay_items)) => match strategy {
+            ArrayStrategy::Replace => *base_items = overlay_items,
+            ArrayStrategy::Concat => base_items.extend(overlay_items),
+            ArrayStrategy::MergeByIndex => {
+                for (index, value) in overlay_items.into_iter().enumerate() {
+                    match base_items.get_mut(index) {
+                        Some(existing) => deep_merge(existing, value, strategy, null_deletes),
+                        None => base_items.push(value),
+                    }
+                }
+            }
+        },
+        (base, overlay) => *base = without_deleting_nulls(overlay, strategy, null_deletes),
+    }
+}
+
+/// An object copied over wholesale still has its delete markers applied, so
+/// `{"a": {"b": null}}` never introduces an explicit null when nulls delete.
+fn without_deleting_nulls(value: Value, strategy: ArrayStrategy, null_deletes: bool) -> Value {
+    if !null_deletes || !value.is_object() {
+        return value;
+    }
+    let mut fresh = Value::Object(Map::new());
+    deep_merge(&mut fresh, value, strategy, null_deletes);
+    fresh
+}
+
+/// Apply an RFC 7386 JSON Merge Patch to `target`.
+#[tauri::command]
+pub fn json_merge_patch(target: &str, patch: &str) -> Result<String, String> {
+    let mut document = parse_labeled(target, "Target")?;
+    let patch = parse_labeled(patch, "Patch")?;
+    apply_merge_patch(&mut document, patch);
+    serde_json::to_string_pretty(&document).map_err(|e| format!("JSON formatting error: {}", e))
+}
+
+/// Compute the minimal RFC 7386 merge patch that turns `source` into `target`.
+#[tauri::command]
+pub fn json_merge_patch_generate(source: &str, target: &str) -> Result<String, String> {
+    let source = parse_labeled(source, "Source")?;
+    let target = parse_labeled(target, "Target")?;
+    let patch = generate_merge_patch(&source, &target, "")?;
+    serde_json::to_string_pretty(&patch).map_err(|e| format!("JSON formatting error: {}", e))
+}
+
+/// RFC 7386 §2: object patches merge member by member, nulls delete, and
+/// anything else (including arrays) replaces the target wholesale.
+fn apply_merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch_map) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target_map) = target {
+        for (key, value) in patch_map {
+            if value.is_null() {
+                target_map.shift_remove(&key);
+            } else {
+                apply_merge_patch(target_map.entry(key).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+fn generate_merge_patch(source: &Value, target: &Value, pointer: &str) -> Result<Value, String> {
+    let (Value::Object(source_map), Value::Object(target_map)) = (source, target) else {
+        return replacement_patch(target, pointer);
+    };
+
+    let mut patch = Map::new();
+    for key in source_map.keys() {
+        if !target_map.contains_key(key) {
+            patch.insert(key.clone(), Value::Null);
+        }
+    }
+    for (key, target_value) in target_map {
+        let member_pointer = child_pointer(pointer, key);
+        match source_map.get(key) {
+            Some(source_value) if source_value == target_value => {}
+            Some(source_value) => {
+                patch.insert(
+                    key.clone(),
+                    generate_merge_patch(source_value, target_value, &member_pointer)?,
+                );
+            }
+            None => {
+                patch.insert(
+                    key.clone(),
+                    replacement_patch(target_value, &member_pointer)?,
+                );
+            }
+        }
+    }
+    Ok(Value::Object(patch))
+}
+
+/// A value copied into the patch verbatim. Merge patches use null as the
+/// delete marker, so an object member that must become an explicit null
+/// cannot be expressed and is reported instead of silently dropped.
+fn replacement_patch(target: &Value, pointer: &str) -> Result<Value, String> {
+    match target {
+        Value::Null if !pointer.is_empty() => Err(format!(
+            "JSON Merge Patch cannot set \"{pointer}\" to null; use a JSON Patch instead"
+        )),
+        Value::Object(map) => {
+            for (key, value) in map {
+                replacement_patch(value, &child_pointer(pointer, key))?;
+            }
+            Ok(target.clone())
+        }
+        _ => Ok(target.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_merge_patch, generate_merge_patch, json_merge, json_merge_patch};
+    use serde_json::{json, Value};
+
+    fn merge(base: Value, overlay: Value, strategy: &str, null_deletes: bool) -> Value {
+        let merged = json_merge(
+            &base.to_string(),
+            &overlay.to_string(),
+            strategy.to_string(),
+            Some(null_deletes),
+        )
+        .unwrap();
+        serde_json::from_str(&merged).unwrap()
+    }
+
+    #[test]
+    fn overlay_wins_on_scalar_conflicts_and_objects_merge_recursively() {
+        let base = json!({"db": {"host": "localhost", "port": 5432}, "debug": false});
+        let overlay = json!({"db": {"host": "prod.internal"}, "debug": true, "extra": 1});
+
+        assert_eq!(
+            merge(base, overlay, "replace", false),
+            json!({"db": {"host": "prod.internal", "port": 5432}, "debug": true, "extra": 1})
+        );
+    }
+
+    #[test]
+    fn applies_array_strategies() {
+        let base = json!({"list": [{"a": 1}, 2, 3]});
+        let overlay = json!({"list": [{"b": 2}, 20]});
+
+        assert_eq!(
+            merge(base.clone(), overlay.clone(), "replace", false),
+            json!({"list": [{"b": 2}, 20]})
+        );
+        assert_eq!(
+            merge(base.clone(), overlay.clone(), "concat", false),
+            json!({"list": [{"a": 1}, 2, 3, {"b": 2}, 20]})
+        );
+        assert_eq!(
+            merge(base, overlay, "merge_by_index", false),
+            json!({"list": [{"a": 1, "b": 2}, 20, 3]})
+        );
+    }
+
+    #[test]
+    fn nulls_delete_keys_only_when_requested() {
+        let base = json!({"keep": 1, "drop": 2});
+        let overlay = json!({"drop": null, "new": {"x": null, "y": 1}});
+
+        assert_eq!(
+            merge(base.clone(), overlay.clone(), "replace", true),
+            json!({"keep": 1, "new": {"y": 1}})
+        );
+        assert_eq!(
+            merge(base, overlay, "replace", false),
+            json!({"keep": 1, "drop": null, "new": {"x": null, "y": 1}})
+        );
+    }
+
+    #[test]
+    fn merge_patch_follows_rfc_7386_examples() {
+        let cases = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (
+                json!({"a": "b"}),
+                json!({"b": "c"}),
+                json!({"a": "b", "b": "c"}),
+            ),
+            (json!({"a": "b"}), json!({"a": null}), json!({})),
+            (
+                json!({"a": "b", "b": "c"}),
+                json!({"a": null}),
+                json!({"b": "c"}),
+            ),
+            (json!({"a": ["b"]}), json!({"a": "c"}), json!({"a": "c"})),
+            (json!({"a": "c"}), json!({"a": ["b"]}), json!({"a": ["b"]})),
+            (
+                json!({"a": {"b": "c"}}),
+                json!({"a": {"b": "d", "c": null}}),
+                json!({"a": {"b": "d"}}),
+            ),
+            (
+                json!({"a": [{"b": "c"}]}),
+                json!({"a": [1]}),
+                json!({"a": [1]}),
+            ),
+            (json!(["a", "b"]), json!(["c", "d"]), json!(["c", "d"])),
+            (json!({"a": "b"}), json!(["c"]), json!(["c"])),
+            (json!({"a": "foo"}), json!(null), json!(null)),
+            (json!({"a": "foo"}), json!("bar"), json!("bar")),
+            (
+                json!({"e": null}),
+                json!({"a": 1}),
+                json!({"e": null, "a": 1}),
+            ),
+            (
+                json!([1, 2]),
+                json!({"a": "b", "c": null}),
+                json!({"a": "b"}),
+            ),
+            (
+                json!({}),
+                json!({"a": {"bb": {"ccc": null}}}),
+                json!({"a": {"bb": {}}}),
+            ),
+        ];
+
+        for (target, patch, expected) in cases {
+            let result = json_merge_patch(&target.to_string(), &patch.to_string()).unwrap();
+            assert_eq!(serde_json::from_str::<Value>(&result).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn generated_merge_patches_round_trip() {
+        let pairs = [
+            (
+                json!({"a": 1, "b": {"c": 2, "d": 3}}),
+                json!({"a": 1, "b": {"c": 5}, "e": [1]}),
+            ),
+            (json!({"list": [1, 2, 3]}), json!({"list": [1, 3]})),
+            (json!({"a": {"b": 1}}), json!({"a": "flat"})),
+            (json!("scalar"), json!({"now": {"an": "object"}})),
+            (json!({"a": 1}), json!([1, null])),
+            (json!({"same": true}), json!({"same": true})),
+        ];
+
+        for (source, target) in pairs {
+            let patch = generate_merge_patch(&source, &target, "").unwrap();
+            let mut applied = source.clone();
+            apply_merge_patch(&mut applied, patch);
+            assert_eq!(applied, target);
+        }
+    }
+
+    #[test]
+    fn generate_rejects_targets_that_need_explicit_nulls() {
+        let error = generate_merge_patch(&json!({"a": 1}), &json!({"a": null}), "").unwrap_err();
+
+        assert!(error.contains("\"/a\""));
+    }
+
+    #[test]
+    fn reports_which_input_failed_to_parse() {
+        let error = json_merge("{}", "{oops", "replace".to_string(), None).unwrap_err();
+
+        assert!(error.starts_with("Overlay document:"));
+    }
+}
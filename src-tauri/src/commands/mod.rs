@@ -2,7 +2,40 @@ pub mod json;
 pub mod window;
 pub mod shortcuts;
 pub mod file;
+pub mod mapped;
+pub mod results;
+pub mod parse_cache;
+pub mod incremental;
 pub mod file_watcher;
 pub mod convert;
 pub mod codegen;
 pub mod export_image;
+pub mod transform;
+pub mod merge;
+pub mod patch;
+pub mod diff;
+pub mod query;
+pub mod canonical;
+pub mod repair;
+pub mod validate;
+pub mod stats;
+pub mod ndjson;
+pub mod lint;
+pub mod schema_diff;
+pub mod source_map;
+pub mod breadcrumb;
+pub mod document;
+pub mod store;
+pub mod drafts;
+pub mod archive;
+pub mod report;
+pub mod extract;
+pub mod history;
+pub mod clipboard_history;
+pub mod quick_peek;
+pub mod captures;
+pub mod progress;
+pub mod settings;
+pub mod tasks;
+pub mod logging;
+pub mod crash;
@@ -0,0 +1,378 @@
+// NDJSON (JSON Lines) commands
+//
+// Every line of the input is a JSON document of its own, so lines are
+// checked and rewritten in parallel with rayon. They are taken a chunk at a
+// time: a chunk's results are handed on in line order before the next chunk
+// starts, which keeps the results in memory bounded by one chunk rather than
+// the whole input. Line numbers are 1-based and count blank lines, which
+// hold no record. A final line without a newline is a record like the others,
+// and a `\r` before a newline is dropped.
+use rayon::prelude::*;
+use serde::de::IgnoredAny;
+use serde::Serialize;
+use serde_json::ser::CompactFormatter;
+use serde_json::Value;
+
+use super::json::{escape_if, format_error_description, off_command_thread};
+use super::stats::collect_stats;
+use crate::error::{AppError, ErrorCode};
+use crate::json_writer::{write_value, RawNumbers};
+
+/// Lines processed together; their results are held until the chunk is done.
+const CHUNK_LINES: usize = 16 * 1024;
+
+#[derive(Serialize, Debug)]
+pub struct LineError {
+    pub line: usize,
+    /// Column within the line.
+    pub column: usize,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct NdjsonValidation {
+    pub valid: bool,
+    /// Non-blank lines.
+    pub records: usize,
+    pub errors: Vec<LineError>,
+    /// Whether checking stopped after reaching `max_errors`.
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct LineMetric {
+    pub line: usize,
+    pub length: usize,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct NdjsonStats {
+    /// Non-blank lines.
+    pub records: usize,
+    pub invalid_records: usize,
+    pub first_invalid_line: Option<usize>,
+    /// Object members across the valid records.
+    pub key_count: usize,
+    /// Deepest nesting of any valid record.
+    pub depth: usize,
+    /// First record with that nesting.
+    pub deepest_record: Option<LineMetric>,
+    /// First of the largest records, measured in bytes.
+    pub largest_record: Option<LineMetric>,
+}
+
+/// Returned by a sink to stop before the last record.
+struct Stop;
+
+/// Run `work` on every record of `content` in parallel and hand each result
+/// to `sink` with its line number, in line order.
+fn for_each_record<T, W, S>(content: &str, work: W, mut sink: S) -> Result<(), Stop>
+where
+    T: Send,
+    W: Fn(&str) -> T + Sync,
+    S: FnMut(usize, T) -> Result<(), Stop>,
+{
+    let mut records = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    loop {
+        let chunk: Vec<(usize, &str)> = records.by_ref().take(CHUNK_LINES).collect();
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        let results: Vec<T> = chunk.par_iter().map(|(_, line)| work(line)).collect();
+        for ((index, _), result) in chunk.iter().zip(results) {
+            sink(index + 1, result)?;
+        }
+    }
+}
+
+fn line_error(line: usize, error: &serde_json::Error) -> LineError {
+    LineError {
+        line,
+        column: error.column(),
+        message: format_error_description(error),
+    }
+}
+
+/// Check each line of `content` and report up to `max_errors` invalid ones
+#[tauri::command]
+pub async fn ndjson_validate(
+    content: String,
+    max_errors: usize,
+) -> Result<NdjsonValidation, AppError> {
+    off_command_thread(content.len(), move || {
+        Ok(validate_lines(&content, max_errors))
+    })
+    .await
+}
+
+fn validate_lines(content: &str, max_errors: usize) -> NdjsonValidation {
+    let max_errors = max_errors.max(1);
+    let mut errors = Vec::new();
+    let truncated = for_each_record(
+        content,
+        |line| serde_json::from_str::<IgnoredAny>(line).err(),
+        |line, error| {
+            let Some(error) = error else {
+                return Ok(());
+            };
+            if errors.len() >= max_errors {
+                return Err(Stop);
+            }
+            errors.push(line_error(line, &error));
+            Ok(())
+        },
+    )
+    .is_err();
+    NdjsonValidation {
+        valid: errors.is_empty(),
+        records: content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count(),
+        errors,
+        truncated,
+    }
+}
+
+/// Rewrite each line of `content` minified, one record per line
+///
+/// Numbers keep their source spelling; `ascii_only` as for `json_format`.
+/// Fails on the first line that isn't JSON.
+#[tauri::command]
+pub async fn ndjson_format(content: String, ascii_only: Option<bool>) -> Result<String, AppError> {
+    off_command_thread(content.len(), move || {
+        let mut out = String::with_capacity(content.len());
+        write_records(&content, ascii_only, |record| {
+            out.push_str(&record);
+            out.push('\n');
+        })?;
+        Ok(out)
+    })
+    .await
+}
+
+/// Collect the lines of `content` into a JSON array, one element per line
+/// indented by `indent` spaces (2 by default); 0 writes it on one line.
+#[tauri::command]
+pub async fn ndjson_to_array(content: String, indent: Option<usize>) -> Result<String, AppError> {
+    off_command_thread(content.len(), move || {
+        let indent = " ".repeat(indent.unwrap_or(2));
+        let newline = if indent.is_empty() { "" } else { "\n" };
+        let mut out = String::with_capacity(content.len() + 2);
+        out.push('[');
+        let mut first = true;
+        write_records(&content, None, |record| {
+            if !first {
+                out.push(',');
+            }
+            first = false;
+            out.push_str(newline);
+            out.push_str(&indent);
+            out.push_str(&record);
+        })?;
+        if !first {
+            out.push_str(newline);
+        }
+        out.push(']');
+        Ok(out)
+    })
+    .await
+}
+
+/// Hand every record of `content`, minified, to `write` in line order.
+fn write_records(
+    content: &str,
+    ascii_only: Option<bool>,
+    mut write: impl FnMut(String),
+) -> Result<(), AppError> {
+    let mut failure = None;
+    let _ = for_each_record(
+        content,
+        |line| {
+            serde_json::from_str::<Value>(line)
+                .map_err(|e| format_error_description(&e))
+                .and_then(|value| {
+                    write_value(&value, RawNumbers::new(CompactFormatter, line))
+                        .map_err(|e| e.to_string())
+                })
+                .map(|record| escape_if(record, ascii_only))
+        },
+        |line, record| match record {
+            Ok(record) => {
+                write(record);
+                Ok(())
+            }
+            Err(reason) => {
+                failure = Some(
+                    ErrorCode::InvalidNdjsonLine
+                        .reason(reason)
+                        .with("line", line),
+                );
+                Err(Stop)
+            }
+        },
+    );
+    failure.map_or(Ok(()), Err)
+}
+
+/// Statistics over the lines of `content`, each measured like `json_stats`
+#[tauri::command]
+pub async fn ndjson_stats(content: String) -> Result<NdjsonStats, AppError> {
+    off_command_thread(content.len(), move || Ok(line_stats(&content))).await
+}
+
+fn line_stats(content: &str) -> NdjsonStats {
+    let mut stats = NdjsonStats {
+        records: 0,
+        invalid_records: 0,
+        first_invalid_line: None,
+        key_count: 0,
+        depth: 0,
+        deepest_record: None,
+        largest_record: None,
+    };
+    let _ = for_each_record(
+        content,
+        |line| {
+            let record = collect_stats(line);
+            // A JSON5 record doesn't belong in NDJSON.
+            (record.valid && record.format_type == "JSON").then_some(record)
+        },
+        |line, record| {
+            stats.records += 1;
+            let Some(record) = record else {
+                stats.invalid_records += 1;
+                stats.first_invalid_line.get_or_insert(line);
+                return Ok(());
+            };
+            stats.key_count += record.key_count;
+            if stats.deepest_record.is_none() || record.depth > stats.depth {
+                stats.depth = record.depth;
+                stats.deepest_record = Some(LineMetric {
+                    line,
+                    length: record.depth,
+                });
+            }
+            if is_larger(&stats.largest_record, record.byte_size) {
+                stats.largest_record = Some(LineMetric {
+                    line,
+                    length: record.byte_size,
+                });
+            }
+            Ok(())
+        },
+    );
+    stats
+}
+
+fn is_larger(current: &Option<LineMetric>, length: usize) -> bool {
+    current.as_ref().is_none_or(|metric| length > metric.length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        line_stats, ndjson_format, ndjson_to_array, validate_lines, write_records, LineMetric,
+        CHUNK_LINES,
+    };
+    use crate::error::ErrorCode;
+
+    /// More records than fit one chunk, each naming its line.
+    fn numbered_records(count: usize) -> String {
+        (1..=count)
+            .map(|line| format!("{{\"line\": {line}}}\n"))
+            .collect()
+    }
+
+    #[test]
+    fn validates_each_line_and_counts_blank_ones() {
+        let content = "{\"a\": 1}\r\n\n[1, 2,]\n  \ntrue\n{\"b\": }";
+
+        let result = validate_lines(content, 10);
+
+        assert!(!result.valid);
+        assert_eq!(result.records, 4);
+        assert_eq!(
+            result
+                .errors
+                .iter()
+                .map(|error| (error.line, error.column))
+                .collect::<Vec<_>>(),
+            [(3, 7), (6, 7)]
+        );
+        assert!(!result.truncated);
+
+        let truncated = validate_lines(content, 1);
+        assert_eq!(truncated.errors.len(), 1);
+        assert!(truncated.truncated);
+        assert!(validate_lines("1\n2\n", 10).valid);
+    }
+
+    #[test]
+    fn keeps_line_order_across_chunks() {
+        let count = CHUNK_LINES * 2 + 3;
+        let content = numbered_records(count);
+
+        let formatted =
+            tauri::async_runtime::block_on(ndjson_format(content.clone(), None)).unwrap();
+        let expected: String = (1..=count)
+            .map(|line| format!("{{\"line\":{line}}}\n"))
+            .collect();
+        assert_eq!(formatted, expected);
+
+        let mut lines = Vec::new();
+        write_records(content.trim_end(), None, |record| lines.push(record)).unwrap();
+        assert_eq!(lines.len(), count);
+        assert_eq!(lines[count - 1], format!("{{\"line\":{count}}}"));
+    }
+
+    #[test]
+    fn collects_lines_into_an_array() {
+        let content = "{\"n\": 1E3, \"s\": \"é\"}\n\n[]\n".to_string();
+
+        assert_eq!(
+            tauri::async_runtime::block_on(ndjson_to_array(content.clone(), None)).unwrap(),
+            "[\n  {\"n\":1E3,\"s\":\"é\"},\n  []\n]"
+        );
+        assert_eq!(
+            tauri::async_runtime::block_on(ndjson_to_array(content, Some(0))).unwrap(),
+            "[{\"n\":1E3,\"s\":\"é\"},[]]"
+        );
+        assert_eq!(
+            tauri::async_runtime::block_on(ndjson_to_array(String::new(), None)).unwrap(),
+            "[]"
+        );
+
+        let error =
+            tauri::async_runtime::block_on(ndjson_format("1\n{a: 1}\n".into(), None)).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidNdjsonLine);
+        assert_eq!(error.param("line"), Some(&2.into()));
+    }
+
+    #[test]
+    fn sums_record_stats() {
+        let content = "{\"a\": {\"b\": [1]}}\n{a: 1}\n\n{\"c\": 1, \"d\": \"long text\"}";
+
+        let stats = line_stats(content);
+
+        assert_eq!(stats.records, 3);
+        assert_eq!(stats.invalid_records, 1);
+        assert_eq!(stats.first_invalid_line, Some(2));
+        assert_eq!(stats.key_count, 4);
+        assert_eq!(stats.depth, 3);
+        assert_eq!(
+            stats.deepest_record,
+            Some(LineMetric { line: 1, length: 3 })
+        );
+        assert_eq!(
+            stats.largest_record,
+            Some(LineMetric {
+                line: 4,
+                length: 26
+            })
+        );
+    }
+}
@@ -0,0 +1,343 @@
+// Parse results by content
+//
+// The editor validates its text on every pause in typing, and the status bar
+// and format actions parse the same text again. Results of `json_validate`,
+// `json_stats` and `json_format` are kept here under an xxh3 hash of the
+// content, which takes a few milliseconds for 100 MB against the seconds a
+// parse takes, so a repeated call on unchanged text costs one hash. The key
+// also holds the content's length and the command's options; the oldest
+// results are dropped once the kept results pass `CACHE_MAX_BYTES`.
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::Mutex;
+use xxhash_rust::xxh3::xxh3_128;
+
+use super::json::{format_json, validate_json, ValidationResult};
+use super::stats::{collect_stats, JsonStats};
+use crate::error::AppError;
+
+/// Total size of the results kept.
+const CACHE_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+pub struct ParseCache {
+    lru: Mutex<Lru>,
+}
+
+#[derive(Hash, PartialEq, Eq, Clone)]
+struct Key {
+    hash: u128,
+    len: usize,
+    query: Query,
+}
+
+#[derive(Hash, PartialEq, Eq, Clone)]
+enum Query {
+    Validate,
+    Stats,
+    Format {
+        indent: Option<usize>,
+        indent_char: Option<String>,
+        ascii_only: Option<bool>,
+        normalize_numbers: Option<bool>,
+        compact_width: Option<usize>,
+    },
+}
+
+#[derive(Clone)]
+enum Cached {
+    Validation(ValidationResult),
+    Stats(Box<JsonStats>),
+    Formatted(Result<String, AppError>),
+}
+
+impl Cached {
+    /// Rough memory held by the result.
+    fn cost(&self) -> usize {
+        let held = match self {
+            Self::Validation(result) => serde_json::to_vec(result).map_or(0, |json| json.len()),
+            Self::Stats(stats) => serde_json::to_vec(stats).map_or(0, |json| json.len()),
+            Self::Formatted(Ok(text)) => text.len(),
+            Self::Formatted(Err(_)) => 0,
+        };
+        held + size_of::<(Key, Entry)>()
+    }
+}
+
+struct Entry {
+    value: Cached,
+    cost: usize,
+    last_used: u64,
+}
+
+struct Lru {
+    entries: HashMap<Key, Entry>,
+    max_bytes: usize,
+    used_bytes: usize,
+    clock: u64,
+}
+
+impl Lru {
+    fn get(&mut self, key: &Key) -> Option<Cached> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.clock;
+        Some(entry.value.clone())
+    }
+
+    fn insert(&mut self, key: Key, value: Cached) {
+        let cost = value.cost();
+        // One result shouldn't push out everything else.
+        if cost > self.max_bytes / 4 {
+            return;
+        }
+        if let Some(old) = self.entries.remove(&key) {
+            self.used_bytes -= old.cost;
+        }
+        while self.used_bytes + cost > self.max_bytes {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.used_bytes -= self.entries.remove(&oldest).map_or(0, |entry| entry.cost);
+        }
+        self.clock += 1;
+        self.used_bytes += cost;
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                cost,
+                last_used: self.clock,
+            },
+        );
+    }
+}
+
+impl Default for ParseCache {
+    fn default() -> Self {
+        Self::with_max_bytes(CACHE_MAX_BYTES)
+    }
+}
+
+impl ParseCache {
+    fn with_max_bytes(max_bytes: usize) -> Self {
+        Self {
+            lru: Mutex::new(Lru {
+                entries: HashMap::new(),
+                max_bytes,
+                used_bytes: 0,
+                clock: 0,
+            }),
+        }
+    }
+
+    fn cached(&self, content: &str, query: Query, compute: impl FnOnce() -> Cached) -> Cached {
+        let key = Key {
+            hash: xxh3_128(content.as_bytes()),
+            len: content.len(),
+            query,
+        };
+        if let Some(value) = self.lru.lock().unwrap().get(&key) {
+            return value;
+        }
+        // Computed without the lock, so other documents aren't held up.
+        let value = compute();
+        self.lru.lock().unwrap().insert(key, value.clone());
+        value
+    }
+
+    /// `validate_json`, from the cache when `content` was validated before.
+    pub(crate) fn validate(&self, content: &str) -> ValidationResult {
+        match self.cached(content, Query::Validate, || {
+            Cached::Validation(validate_json(content))
+        }) {
+            Cached::Validation(result) => result,
+            _ => unreachable!("validation entries are keyed by Query::Validate"),
+        }
+    }
+
+    /// `collect_stats`, from the cache when it ran on `content` before.
+    pub(crate) fn stats(&self, content: &str) -> JsonStats {
+        match self.cached(content, Query::Stats, || {
+            Cached::Stats(Box::new(collect_stats(content)))
+        }) {
+            Cached::Stats(stats) => *stats,
+            _ => unreachable!("stats entries are keyed by Query::Stats"),
+        }
+    }
+
+    /// `format_json`, from the cache when `content` was formatted the same
+    /// way before.
+    pub(crate) fn format(
+        &self,
+        content: &str,
+        indent: Option<usize>,
+        indent_char: Option<String>,
+        ascii_only: Option<bool>,
+        normalize_numbers: Option<bool>,
+        compact_width: Option<usize>,
+    ) -> Result<String, AppError> {
+        let query = Query::Format {
+            indent,
+            indent_char: indent_char.clone(),
+            ascii_only,
+            normalize_numbers,
+            compact_width,
+        };
+        let formatted = self.cached(content, query, || {
+            Cached::Formatted(format_json(
+                content,
+                indent,
+                indent_char,
+                ascii_only,
+                normalize_numbers,
+                compact_width,
+            ))
+        });
+        match formatted {
+            Cached::Formatted(result) => result,
+            _ => unreachable!("format entries are keyed by Query::Format"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cached, ParseCache, Query};
+    use crate::commands::json::{format_json, validate_json};
+    use crate::commands::stats::collect_stats;
+    use std::cell::Cell;
+    use std::time::Instant;
+    use xxhash_rust::xxh3::xxh3_128;
+
+    fn counted(cache: &ParseCache, content: &str, runs: &Cell<usize>) -> Cached {
+        cache.cached(content, Query::Validate, || {
+            runs.set(runs.get() + 1);
+            Cached::Validation(validate_json(content))
+        })
+    }
+
+    #[test]
+    fn reuses_results_for_the_same_content_and_options() {
+        let cache = ParseCache::default();
+        let runs = Cell::new(0);
+
+        counted(&cache, r#"{"a": 1}"#, &runs);
+        counted(&cache, r#"{"a": 1}"#, &runs);
+        assert_eq!(runs.get(), 1);
+        counted(&cache, r#"{"a": 2}"#, &runs);
+        assert_eq!(runs.get(), 2);
+
+        let invalid = cache.validate("[1,");
+        assert!(!invalid.valid);
+        assert_eq!(cache.validate("[1,").error_column, invalid.error_column);
+        assert_eq!(
+            serde_json::to_value(cache.stats("[1, {}]")).unwrap(),
+            serde_json::to_value(collect_stats("[1, {}]")).unwrap()
+        );
+
+        let two = cache
+            .format("[1]", Some(2), None, None, None, None)
+            .unwrap();
+        let four = cache
+            .format("[1]", Some(4), None, None, None, None)
+            .unwrap();
+        assert_eq!(
+            two,
+            format_json("[1]", Some(2), None, None, None, None).unwrap()
+        );
+        assert_eq!(
+            four,
+            format_json("[1]", Some(4), None, None, None, None).unwrap()
+        );
+        assert_ne!(two, four);
+        assert!(cache.format("[1,", None, None, None, None, None).is_err());
+    }
+
+    #[test]
+    fn drops_the_least_recently_used_results_past_the_byte_limit() {
+        let document = |n: usize| format!("[{}]", "1,".repeat(n) + "1");
+        let cost = Cached::Formatted(Ok(document(0))).cost();
+        // Room for four entries of this size.
+        let cache = ParseCache::with_max_bytes(cost * 9 / 2);
+        let runs = Cell::new(0);
+        let fill = |content: &str| {
+            cache.cached(content, Query::Stats, || {
+                runs.set(runs.get() + 1);
+                Cached::Formatted(Ok(document(0)))
+            });
+        };
+
+        for content in ["a", "b", "c", "d", "a", "e"] {
+            fill(content);
+        }
+        assert_eq!(runs.get(), 5);
+        // "b" was the least recently used, so it was dropped for "e".
+        for content in ["a", "c", "d", "e"] {
+            fill(content);
+        }
+        assert_eq!(runs.get(), 5);
+        fill("b");
+        assert_eq!(runs.get(), 6);
+
+        let lru = cache.lru.lock().unwrap();
+        assert!(lru.used_bytes <= lru.max_bytes);
+        assert_eq!(
+            lru.used_bytes,
+            lru.entries.values().map(|e| e.cost).sum::<usize>()
+        );
+        drop(lru);
+
+        // A result larger than a quarter of the cache isn't kept.
+        let small = ParseCache::with_max_bytes(cost * 2);
+        small
+            .format(&document(cost), None, None, None, None, None)
+            .unwrap();
+        assert!(small.lru.lock().unwrap().entries.is_empty());
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture parse_cache`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn parse_cache_hits_on_a_100mb_document() {
+        let mut content = String::from("[");
+        let mut index = 0;
+        while content.len() < 100 << 20 {
+            if index > 0 {
+                content.push(',');
+            }
+            content.push_str(&format!(
+                r#"{{"id":{index},"name":"user {index}","tags":["a","b\n"],"score":1.5E3,"active":true}}"#
+            ));
+            index += 1;
+        }
+        content.push(']');
+        let cache = ParseCache::default();
+
+        let timed = |run: &dyn Fn()| {
+            let started = Instant::now();
+            run();
+            started.elapsed()
+        };
+
+        let hash_time = timed(&|| {
+            xxh3_128(content.as_bytes());
+        });
+        let validate = || assert!(cache.validate(&content).valid);
+        let (validate_miss, validate_hit) = (timed(&validate), timed(&validate));
+        let stats = || assert!(cache.stats(&content).valid);
+        let (stats_miss, stats_hit) = (timed(&stats), timed(&stats));
+
+        println!(
+            "{} MB: xxh3 {hash_time:?}; validate miss {validate_miss:?}, hit {validate_hit:?}; \
+             stats miss {stats_miss:?}, hit {stats_hit:?}",
+            content.len() >> 20
+        );
+        assert!(validate_hit < validate_miss / 10);
+        assert!(stats_hit < stats_miss / 10);
+    }
+}
@@ -0,0 +1,592 @@
+// RFC 6902 JSON Patch commands
+use json_patch::{PatchErrorKind, PatchOperation};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, VecDeque};
+
+use super::json::{parse_to_value, to_indented_string};
+use crate::json_pointer::{child_pointer, index_pointer};
+
+/// Arrays whose changed middle sections would need a larger LCS table than this
+/// fall back to pairing elements by position.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchFailureKind {
+    /// The patch document itself is not a valid list of operations.
+    Malformed,
+    /// A `test` operation did not match the document.
+    TestFailed,
+    /// `path` or `from` does not point at a usable location.
+    InvalidPath,
+}
+
+#[derive(Serialize)]
+pub struct PatchApplyResult {
+    pub success: bool,
+    pub content: Option<String>,
+    pub failed_operation: Option<usize>,
+    pub failure_kind: Option<PatchFailureKind>,
+    pub error_message: Option<String>,
+}
+
+impl PatchApplyResult {
+    fn failed(operation: Option<usize>, kind: PatchFailureKind, message: String) -> Self {
+        Self {
+            success: false,
+            content: None,
+            failed_operation: operation,
+            failure_kind: Some(kind),
+            error_message: Some(message),
+        }
+    }
+}
+
+/// Apply an RFC 6902 JSON Patch. Application is atomic: on failure nothing is
+/// applied and the result names the failing operation index and the reason.
+#[tauri::command]
+pub fn json_patch_apply(document: &str, patch: &str) -> Result<PatchApplyResult, String> {
+    let mut value = parse_to_value(document).map_err(|e| format!("Target document: {e}"))?;
+    let patch_value = parse_to_value(patch).map_err(|e| format!("Patch document: {e}"))?;
+
+    let operations = match parse_operations(patch_value) {
+        Ok(operations) => operations,
+        Err((index, message)) => {
+            return Ok(PatchApplyResult::failed(
+                index,
+                PatchFailureKind::Malformed,
+                message,
+            ));
+        }
+    };
+
+    if let Err(error) = json_patch::patch(&mut value, &operations) {
+        let kind = match error.kind {
+            PatchErrorKind::TestFailed => PatchFailureKind::TestFailed,
+            _ => PatchFailureKind::InvalidPath,
+        };
+        let message = format!(
+            "Operation {} ({}) failed at \"{}\": {}",
+            error.operation,
+            operation_name(&operations[error.operation]),
+            error.path,
+            error.kind
+        );
+        return Ok(PatchApplyResult::failed(
+            Some(error.operation),
+            kind,
+            message,
+        ));
+    }
+
+    let content = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("JSON formatting error: {}", e))?;
+    Ok(PatchApplyResult {
+        success: true,
+        content: Some(content),
+        failed_operation: None,
+        failure_kind: None,
+        error_message: None,
+    })
+}
+
+/// Deserialize operations one at a time so a malformed entry can be reported
+/// by index instead of as a generic deserialization error.
+fn parse_operations(patch: Value) -> Result<Vec<PatchOperation>, (Option<usize>, String)> {
+    let Value::Array(items) = patch else {
+        return Err((
+            None,
+            "A JSON Patch must be an array of operations".to_string(),
+        ));
+    };
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            serde_json::from_value::<PatchOperation>(item)
+                .map_err(|e| (Some(index), format!("Operation {index} is malformed: {e}")))
+        })
+        .collect()
+}
+
+fn operation_name(operation: &PatchOperation) -> &'static str {
+    match operation {
+        PatchOperation::Add(_) => "add",
+        PatchOperation::Remove(_) => "remove",
+        PatchOperation::Replace(_) => "replace",
+        PatchOperation::Move(_) => "move",
+        PatchOperation::Copy(_) => "copy",
+        PatchOperation::Test(_) => "test",
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PatchGenerateOptions {
+    /// Emit `move` for array elements that only changed position.
+    pub detect_moves: bool,
+    /// Precede every `remove`, `replace` and `move` with a `test` of the old value.
+    pub include_tests: bool,
+    /// Spaces per indentation level; 0 produces minified output.
+    pub indent: usize,
+}
+
+/// Generate an RFC 6902 JSON Patch that turns `source` into `target`.
+#[tauri::command]
+pub fn json_patch_generate(
+    source: &str,
+    target: &str,
+    options: PatchGenerateOptions,
+) -> Result<String, String> {
+    let source = parse_to_value(source).map_err(|e| format!("Source document: {e}"))?;
+    let target = parse_to_value(target).map_err(|e| format!("Target document: {e}"))?;
+    let operations = generate_patch(&source, &target, &options);
+    Ok(to_indented_string(
+        &Value::Array(operations),
+        options.indent,
+    )?)
+}
+
+fn generate_patch(source: &Value, target: &Value, options: &PatchGenerateOptions) -> Vec<Value> {
+    let mut generator = PatchGenerator {
+        options,
+        operations: Vec::new(),
+    };
+    generator.diff(source, target, "");
+    generator.operations
+}
+
+/// Where an element of the target array comes from.
+#[derive(Clone, Copy)]
+enum Slot {
+    /// Part of the longest common subsequence; stays where it is.
+    Kept(usize),
+    /// Paired with a source element in the same gap and diffed recursively.
+    Changed(usize),
+    /// An identical source element relocated with a `move`.
+    Moved(usize),
+    Added,
+}
+
+struct PatchGenerator<'a> {
+    options: &'a PatchGenerateOptions,
+    operations: Vec<Value>,
+}
+
+impl PatchGenerator<'_> {
+    fn test(&mut self, path: &str, value: &Value) {
+        if self.options.include_tests {
+            self.operations
+                .push(json!({"op": "test", "path": path, "value": value}));
+        }
+    }
+
+    fn add(&mut self, path: &str, value: &Value) {
+        self.operations
+            .push(json!({"op": "add", "path": path, "value": value}));
+    }
+
+    fn remove(&mut self, path: &str, old: &Value) {
+        self.test(path, old);
+        self.operations.push(json!({"op": "remove", "path": path}));
+    }
+
+    fn replace(&mut self, path: &str, old: &Value, value: &Value) {
+        self.test(path, old);
+        self.operations
+            .push(json!({"op": "replace", "path": path, "value": value}));
+    }
+
+    fn relocate(&mut self, from: &str, path: &str, value: &Value) {
+        self.test(from, value);
+        self.operations
+            .push(json!({"op": "move", "from": from, "path": path}));
+    }
+
+    fn diff(&mut self, source: &Value, target: &Value, pointer: &str) {
+        if source == target {
+            return;
+        }
+        match (source, target) {
+            (Value::Object(source_map), Value::Object(target_map)) => {
+                self.diff_objects(source_map, target_map, pointer)
+            }
+            (Value::Array(source_items), Value::Array(target_items)) => {
+                self.diff_arrays(source_items, target_items, pointer)
+            }
+            _ => self.replace(pointer, source, target),
+        }
+    }
+
+    fn diff_objects(
+        &mut self,
+        source: &Map<String, Value>,
+        target: &Map<String, Value>,
+        pointer: &str,
+    ) {
+        for (key, value) in source {
+            if !target.contains_key(key) {
+                self.remove(&child_pointer(pointer, key), value);
+            }
+        }
+        for (key, value) in target {
+            let member_pointer = child_pointer(pointer, key);
+            match source.get(key) {
+                Some(existing) => self.diff(existing, value, &member_pointer),
+                None => self.add(&member_pointer, value),
+            }
+        }
+    }
+
+    /// Removals run back to front so indexes stay valid, then moved elements
+    /// are placed after their target predecessor, and finally additions and
+    /// nested edits run front to back at their final indexes.
+    fn diff_arrays(&mut self, source: &[Value], target: &[Value], pointer: &str) {
+        let slots = plan_array(source, target, self.options.detect_moves);
+
+        let mut claimed = vec![false; source.len()];
+        for slot in &slots {
+            if let Slot::Kept(i) | Slot::Changed(i) | Slot::Moved(i) = *slot {
+                claimed[i] = true;
+            }
+        }
+        let mut current: Vec<usize> = (0..source.len()).collect();
+        for i in (0..source.len()).rev() {
+            if !claimed[i] {
+                self.remove(&index_pointer(pointer, i), &source[i]);
+                current.remove(i);
+            }
+        }
+
+        let order: Vec<(usize, bool)> = slots
+            .iter()
+            .filter_map(|slot| match *slot {
+                Slot::Kept(i) | Slot::Changed(i) => Some((i, false)),
+                Slot::Moved(i) => Some((i, true)),
+                Slot::Added => None,
+            })
+            .collect();
+        for (k, &(i, moved)) in order.iter().enumerate() {
+            if !moved {
+                continue;
+            }
+            let from = position_of(&current, i);
+            current.remove(from);
+            let to = match k {
+                0 => 0,
+                _ => position_of(&current, order[k - 1].0) + 1,
+            };
+            current.insert(to, i);
+            if from != to {
+                self.relocate(
+                    &index_pointer(pointer, from),
+                    &index_pointer(pointer, to),
+                    &source[i],
+                );
+            }
+        }
+
+        for (j, slot) in slots.iter().enumerate() {
+            match *slot {
+                Slot::Added => self.add(&index_pointer(pointer, j), &target[j]),
+                Slot::Changed(i) => self.diff(&source[i], &target[j], &index_pointer(pointer, j)),
+                Slot::Kept(_) | Slot::Moved(_) => {}
+            }
+        }
+    }
+}
+
+fn position_of(current: &[usize], source_index: usize) -> usize {
+    current
+        .iter()
+        .position(|&i| i == source_index)
+        .expect("planned source element is present")
+}
+
+/// Decide, for every target element, which source element (if any) it comes
+/// from. Common prefixes and suffixes are matched directly; the rest goes
+/// through an LCS so a single insertion doesn't turn into a run of replaces.
+fn plan_array(source: &[Value], target: &[Value], detect_moves: bool) -> Vec<Slot> {
+    let prefix = source
+        .iter()
+        .zip(target)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = source[prefix..]
+        .iter()
+        .rev()
+        .zip(target[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let source_end = source.len() - suffix;
+    let target_end = target.len() - suffix;
+
+    let mut slots: Vec<Option<Slot>> = vec![None; target.len()];
+    let mut claimed = vec![false; source.len()];
+    for offset in 0..prefix {
+        claim(&mut slots, &mut claimed, offset, Slot::Kept(offset), offset);
+    }
+    for offset in 0..suffix {
+        claim(
+            &mut slots,
+            &mut claimed,
+            target_end + offset,
+            Slot::Kept(source_end + offset),
+            source_end + offset,
+        );
+    }
+
+    // Intern the middle elements so the LCS and move matching compare ids.
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    let mut intern = |value: &Value| {
+        let next = ids.len();
+        *ids.entry(value.to_string()).or_insert(next)
+    };
+    let source_ids: Vec<usize> = source[prefix..source_end].iter().map(&mut intern).collect();
+    let target_ids: Vec<usize> = target[prefix..target_end].iter().map(&mut intern).collect();
+
+    let mut anchors = Vec::new();
+    for (i, j) in longest_common_subsequence(&source_ids, &target_ids) {
+        claim(
+            &mut slots,
+            &mut claimed,
+            prefix + j,
+            Slot::Kept(prefix + i),
+            prefix + i,
+        );
+        anchors.push((prefix + i, prefix + j));
+    }
+    anchors.push((source_end, target_end));
+
+    if detect_moves {
+        let mut unclaimed: HashMap<usize, VecDeque<usize>> = HashMap::new();
+        for (offset, id) in source_ids.iter().enumerate() {
+            if !claimed[prefix + offset] {
+                unclaimed.entry(*id).or_default().push_back(prefix + offset);
+            }
+        }
+        for (offset, id) in target_ids.iter().enumerate() {
+            let j = prefix + offset;
+            if slots[j].is_some() {
+                continue;
+            }
+            if let Some(i) = unclaimed.get_mut(id).and_then(VecDeque::pop_front) {
+                claim(&mut slots, &mut claimed, j, Slot::Moved(i), i);
+            }
+        }
+    }
+
+    // Within each gap between anchors, pair leftover elements in order.
+    let (mut source_start, mut target_start) = (prefix, prefix);
+    for (anchor_i, anchor_j) in anchors {
+        let sources: Vec<usize> = (source_start..anchor_i).filter(|&i| !claimed[i]).collect();
+        let targets: Vec<usize> = (target_start..anchor_j)
+            .filter(|&j| slots[j].is_none())
+            .collect();
+        for (i, j) in sources.into_iter().zip(targets) {
+            claim(&mut slots, &mut claimed, j, Slot::Changed(i), i);
+        }
+        source_start = anchor_i + 1;
+        target_start = anchor_j + 1;
+    }
+
+    slots
+        .into_iter()
+        .map(|slot| slot.unwrap_or(Slot::Added))
+        .collect()
+}
+
+fn claim(slots: &mut [Option<Slot>], claimed: &mut [bool], j: usize, slot: Slot, i: usize) {
+    slots[j] = Some(slot);
+    claimed[i] = true;
+}
+
+/// Index pairs of a longest common subsequence, or none when the table would
+/// exceed `MAX_LCS_CELLS`.
+fn longest_common_subsequence(a: &[usize], b: &[usize]) -> Vec<(usize, usize)> {
+    if a.is_empty() || b.is_empty() || a.len().saturating_mul(b.len()) > MAX_LCS_CELLS {
+        return Vec::new();
+    }
+    let width = b.len() + 1;
+    let mut lengths = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i * width + j] = if a[i] == b[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generate_patch, json_patch_apply, json_patch_generate, PatchFailureKind,
+        PatchGenerateOptions,
+    };
+    use serde_json::{json, Value};
+
+    fn apply(document: Value, patch: Value) -> super::PatchApplyResult {
+        json_patch_apply(&document.to_string(), &patch.to_string()).unwrap()
+    }
+
+    #[test]
+    fn applies_all_operation_kinds() {
+        let result = apply(
+            json!({"a": 1, "list": [1, 2], "obj": {"x": true}}),
+            json!([
+                {"op": "test", "path": "/a", "value": 1},
+                {"op": "add", "path": "/list/-", "value": 3},
+                {"op": "replace", "path": "/a", "value": 2},
+                {"op": "copy", "from": "/a", "path": "/b"},
+                {"op": "move", "from": "/obj/x", "path": "/moved"},
+                {"op": "remove", "path": "/list/0"}
+            ]),
+        );
+
+        assert!(result.success);
+        assert_eq!(
+            serde_json::from_str::<Value>(&result.content.unwrap()).unwrap(),
+            json!({"a": 2, "list": [2, 3], "obj": {}, "b": 2, "moved": true})
+        );
+    }
+
+    #[test]
+    fn test_failures_are_reported_with_the_operation_index() {
+        let result = apply(
+            json!({"a": 1}),
+            json!([
+                {"op": "replace", "path": "/a", "value": 5},
+                {"op": "test", "path": "/a", "value": 1}
+            ]),
+        );
+
+        assert!(!result.success);
+        assert_eq!(result.failed_operation, Some(1));
+        assert_eq!(result.failure_kind, Some(PatchFailureKind::TestFailed));
+    }
+
+    #[test]
+    fn distinguishes_malformed_patches_and_bad_paths() {
+        let malformed = apply(
+            json!({}),
+            json!([{"op": "add", "path": "/a", "value": 1}, {"op": "jump"}]),
+        );
+        assert_eq!(malformed.failure_kind, Some(PatchFailureKind::Malformed));
+        assert_eq!(malformed.failed_operation, Some(1));
+
+        let bad_path = apply(json!({}), json!([{"op": "remove", "path": "/missing"}]));
+        assert_eq!(bad_path.failure_kind, Some(PatchFailureKind::InvalidPath));
+        assert_eq!(bad_path.failed_operation, Some(0));
+    }
+
+    fn round_trip(source: Value, target: Value, options: &PatchGenerateOptions) -> Vec<Value> {
+        let operations = generate_patch(&source, &target, options);
+        let patch: json_patch::Patch =
+            serde_json::from_value(Value::Array(operations.clone())).unwrap();
+        let mut applied = source;
+        json_patch::patch(&mut applied, &patch).unwrap();
+        assert_eq!(applied, target);
+        operations
+    }
+
+    #[test]
+    fn generated_patches_reproduce_the_target() {
+        let pairs = [
+            (
+                json!({"a": 1, "b": {"c": [1, 2]}}),
+                json!({"b": {"c": [2, 3]}, "d": null}),
+            ),
+            (json!([1, 2, 3, 4, 5]), json!([5, 4, 3, 2, 1])),
+            (
+                json!([{"id": 1}, {"id": 2}]),
+                json!([{"id": 2, "x": 1}, {"id": 3}]),
+            ),
+            (json!({"a": [1, 2]}), json!({"a": "flat"})),
+            (json!("scalar"), json!([1])),
+            (json!({"a~/b": 1}), json!({"a~/b": 2})),
+        ];
+        for detect_moves in [false, true] {
+            let options = PatchGenerateOptions {
+                detect_moves,
+                include_tests: true,
+                indent: 0,
+            };
+            for (source, target) in pairs.clone() {
+                round_trip(source, target, &options);
+            }
+        }
+    }
+
+    #[test]
+    fn insertions_near_the_front_produce_a_single_add() {
+        let source: Vec<u32> = (0..50).collect();
+        let mut target = source.clone();
+        target.insert(1, 999);
+
+        let operations = round_trip(
+            json!(source),
+            json!(target),
+            &PatchGenerateOptions::default(),
+        );
+
+        assert_eq!(
+            operations,
+            vec![json!({"op": "add", "path": "/1", "value": 999})]
+        );
+    }
+
+    #[test]
+    fn detects_moves_and_adds_tests_when_asked() {
+        let source = json!({"list": ["a", "b", "c", "d"]});
+        let target = json!({"list": ["b", "c", "d", "a"]});
+        let options = PatchGenerateOptions {
+            detect_moves: true,
+            include_tests: true,
+            indent: 0,
+        };
+
+        assert_eq!(
+            round_trip(source.clone(), target.clone(), &options),
+            vec![
+                json!({"op": "test", "path": "/list/0", "value": "a"}),
+                json!({"op": "move", "from": "/list/0", "path": "/list/3"}),
+            ]
+        );
+
+        let without_moves = round_trip(source, target, &PatchGenerateOptions::default());
+        assert_eq!(without_moves.len(), 2);
+        assert_eq!(without_moves[0]["op"], "remove");
+    }
+
+    #[test]
+    fn output_follows_the_indent_option() {
+        let minified = json_patch_generate("{}", r#"{"a":1}"#, PatchGenerateOptions::default());
+        assert_eq!(minified.unwrap(), r#"[{"op":"add","path":"/a","value":1}]"#);
+
+        let options = PatchGenerateOptions {
+            indent: 4,
+            ..Default::default()
+        };
+        let pretty = json_patch_generate("{}", r#"{"a":1}"#, options).unwrap();
+        assert!(pretty.contains("\n        \"op\": \"add\""));
+    }
+}
@@ -0,0 +1,111 @@
+// Taskbar and dock progress
+//
+// Long operations show their progress on the window's taskbar button (Windows),
+// dock icon (macOS) or launcher entry (Linux with libunity), so it stays
+// visible while the window is in the background.
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::Window;
+
+/// Marks `ProgressGuard::last_percent` as not yet shown.
+const NO_PERCENT: u64 = u64::MAX;
+
+/// Show `state` (`none`, `indeterminate`, `normal` or `error`) on the
+/// window's taskbar button or dock icon, with `value` as a percentage for
+/// `normal` and `error`
+#[tauri::command]
+pub fn set_progress(window: Window, state: String, value: Option<f64>) -> Result<(), String> {
+    let status = parse_status(&state)?;
+    let progress = value.map(|value| value.clamp(0.0, 100.0).round() as u64);
+    window
+        .set_progress_bar(ProgressBarState {
+            status: Some(status),
+            progress,
+        })
+        .map_err(|e| e.to_string())
+}
+
+fn parse_status(state: &str) -> Result<ProgressBarStatus, String> {
+    match state {
+        "none" => Ok(ProgressBarStatus::None),
+        "indeterminate" => Ok(ProgressBarStatus::Indeterminate),
+        "normal" => Ok(ProgressBarStatus::Normal),
+        "error" => Ok(ProgressBarStatus::Error),
+        _ => Err(format!("Unsupported progress state: {state}")),
+    }
+}
+
+/// `done` out of `total` as a whole percentage; an empty total counts as done.
+pub(crate) fn percent(done: u64, total: u64) -> u64 {
+    if total == 0 {
+        return 100;
+    }
+    (done.min(total) as u128 * 100 / total as u128) as u64
+}
+
+/// Progress of one long operation, cleared when dropped so an early return,
+/// an error or a cancelled task can't leave it behind.
+pub(crate) struct ProgressGuard {
+    window: Window,
+    last_percent: AtomicU64,
+}
+
+impl ProgressGuard {
+    /// Start with an indeterminate indicator until the first `update`.
+    pub(crate) fn start(window: Window) -> Self {
+        let guard = Self {
+            window,
+            last_percent: AtomicU64::new(NO_PERCENT),
+        };
+        guard.set(ProgressBarStatus::Indeterminate, None);
+        guard
+    }
+
+    /// Show `done` out of `total`; repeated percentages aren't sent again.
+    pub(crate) fn update(&self, done: u64, total: u64) {
+        let percent = percent(done, total);
+        if self.last_percent.swap(percent, Ordering::Relaxed) != percent {
+            self.set(ProgressBarStatus::Normal, Some(percent));
+        }
+    }
+
+    fn set(&self, status: ProgressBarStatus, progress: Option<u64>) {
+        // Unsupported desktops reject this; progress is only a hint.
+        let _ = self.window.set_progress_bar(ProgressBarState {
+            status: Some(status),
+            progress,
+        });
+    }
+}
+
+impl Drop for ProgressGuard {
+    fn drop(&mut self) {
+        self.set(ProgressBarStatus::None, None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_status, percent};
+
+    #[test]
+    fn parses_the_supported_states() {
+        for state in ["none", "indeterminate", "normal", "error"] {
+            assert!(parse_status(state).is_ok());
+        }
+        assert_eq!(
+            parse_status("paused").unwrap_err(),
+            "Unsupported progress state: paused"
+        );
+    }
+
+    #[test]
+    fn computes_whole_percentages() {
+        assert_eq!(percent(0, 9), 0);
+        assert_eq!(percent(4, 9), 44);
+        assert_eq!(percent(9, 9), 100);
+        assert_eq!(percent(12, 9), 100);
+        assert_eq!(percent(0, 0), 100);
+        assert_eq!(percent(u64::MAX - 1, u64::MAX), 99);
+    }
+}
@@ -0,0 +1,180 @@
+use serde::Serialize;
+use serde_json::{Number, Value};
+use std::collections::HashSet;
+
+use crate::json_pointer::lookup_field_path;
+use crate::json_value::{canonical_number, value_type_name};
+
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    Number,
+    String,
+    /// No element had a usable value.
+    None,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Aggregate {
+    pub field_type: FieldType,
+    pub count: usize,
+    /// Elements where the field was missing or of another type.
+    pub skipped: usize,
+    pub min: Option<Value>,
+    pub max: Option<Value>,
+    pub sum: Option<f64>,
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    pub distinct_count: usize,
+}
+
+/// Summarize `field` (a dot-separated path) across the elements of `items`.
+/// The first number or string found decides the field type; elements with
+/// any other type are skipped. Strings get count, lexicographic min/max and
+/// distinct count only.
+pub(crate) fn aggregate_field(items: &Value, field: &str) -> Result<Aggregate, String> {
+    let Value::Array(items) = items else {
+        return Err(format!(
+            "Expected an array at the target pointer, found {}",
+            value_type_name(items)
+        ));
+    };
+
+    let values: Vec<Option<&Value>> = items
+        .iter()
+        .map(|item| lookup_field_path(item, field))
+        .collect();
+    let field_type = values
+        .iter()
+        .flatten()
+        .find_map(|value| match value {
+            Value::Number(_) => Some(FieldType::Number),
+            Value::String(_) => Some(FieldType::String),
+            _ => None,
+        })
+        .unwrap_or(FieldType::None);
+
+    let aggregate = match field_type {
+        FieldType::Number => aggregate_numbers(&values),
+        FieldType::String => aggregate_strings(&values),
+        FieldType::None => Aggregate {
+            field_type,
+            count: 0,
+            skipped: items.len(),
+            min: None,
+            max: None,
+            sum: None,
+            mean: None,
+            median: None,
+            distinct_count: 0,
+        },
+    };
+    Ok(aggregate)
+}
+
+fn aggregate_numbers(values: &[Option<&Value>]) -> Aggregate {
+    let numbers: Vec<&Number> = values
+        .iter()
+        .filter_map(|value| match value {
+            Some(Value::Number(number)) => Some(number),
+            _ => None,
+        })
+        .collect();
+    let distinct_count = numbers
+        .iter()
+        .map(|number| canonical_number(number))
+        .collect::<HashSet<_>>()
+        .len();
+
+    let mut floats: Vec<f64> = numbers.iter().filter_map(|n| n.as_f64()).collect();
+    floats.sort_by(f64::total_cmp);
+    let sum: f64 = floats.iter().sum();
+    let count = floats.len();
+    let median = match count {
+        0 => None,
+        _ if count % 2 == 1 => Some(floats[count / 2]),
+        _ => Some((floats[count / 2 - 1] + floats[count / 2]) / 2.0),
+    };
+    // Report min/max as the original numbers so integers stay integers.
+    let by_value = |a: &&&Number, b: &&&Number| {
+        let (a, b) = (a.as_f64().unwrap_or(0.0), b.as_f64().unwrap_or(0.0));
+        a.total_cmp(&b)
+    };
+    let min = numbers.iter().min_by(by_value);
+    let max = numbers.iter().max_by(by_value);
+
+    Aggregate {
+        field_type: FieldType::Number,
+        count,
+        skipped: values.len() - count,
+        min: min.map(|n| Value::Number((*n).clone())),
+        max: max.map(|n| Value::Number((*n).clone())),
+        sum: Some(sum),
+        mean: (count > 0).then(|| sum / count as f64),
+        median,
+        distinct_count,
+    }
+}
+
+fn aggregate_strings(values: &[Option<&Value>]) -> Aggregate {
+    let strings: Vec<&str> = values
+        .iter()
+        .filter_map(|value| value.and_then(Value::as_str))
+        .collect();
+    let count = strings.len();
+    Aggregate {
+        field_type: FieldType::String,
+        count,
+        skipped: values.len() - count,
+        min: strings.iter().min().map(|s| Value::from(*s)),
+        max: strings.iter().max().map(|s| Value::from(*s)),
+        sum: None,
+        mean: None,
+        median: None,
+        distinct_count: strings.iter().collect::<HashSet<_>>().len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{aggregate_field, FieldType};
+    use serde_json::json;
+
+    #[test]
+    fn aggregates_nested_numeric_fields_and_counts_skips() {
+        let items = json!([
+            {"metrics": {"latency_ms": 30}},
+            {"metrics": {"latency_ms": 10}},
+            {"metrics": {"latency_ms": 20.5}},
+            {"metrics": {"latency_ms": 10}},
+            {"metrics": {"latency_ms": "n/a"}},
+            {"metrics": {}}
+        ]);
+
+        let result = aggregate_field(&items, "metrics.latency_ms").unwrap();
+
+        assert_eq!(result.field_type, FieldType::Number);
+        assert_eq!(result.count, 4);
+        assert_eq!(result.skipped, 2);
+        assert_eq!(result.min, Some(json!(10)));
+        assert_eq!(result.max, Some(json!(30)));
+        assert_eq!(result.sum, Some(70.5));
+        assert_eq!(result.mean, Some(17.625));
+        assert_eq!(result.median, Some(15.25));
+        assert_eq!(result.distinct_count, 3);
+    }
+
+    #[test]
+    fn aggregates_string_fields() {
+        let items = json!([{"s": "b"}, {"s": "a"}, {"s": "b"}, {"s": 1}]);
+
+        let result = aggregate_field(&items, "s").unwrap();
+
+        assert_eq!(result.field_type, FieldType::String);
+        assert_eq!(result.count, 3);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.min, Some(json!("a")));
+        assert_eq!(result.distinct_count, 2);
+        assert_eq!(result.mean, None);
+    }
+}
@@ -0,0 +1,148 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::json_pointer::{child_pointer, index_pointer, lookup_field_path};
+use crate::json_value::{canonical_key, value_preview};
+
+const PREVIEW_CHARS: usize = 120;
+
+#[derive(Serialize, Debug)]
+pub struct DuplicateGroup {
+    /// Pointer of the array holding the duplicates.
+    pub array_pointer: String,
+    /// Pointers of every element in the group, first occurrence first.
+    pub pointers: Vec<String>,
+    pub count: usize,
+    /// The compared value: the whole element, or the value at `key`.
+    pub preview: String,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct DuplicateReport {
+    pub groups: Vec<DuplicateGroup>,
+    pub arrays_scanned: usize,
+    /// Elements that `json_dedupe_array` would remove.
+    pub removable_count: usize,
+}
+
+/// Group duplicate elements of `array` (or of every array below it when
+/// `recursive`) using the same comparison as `json_dedupe_array`: deep
+/// equality with JSON number semantics, or the value at the dot-separated
+/// `key` path. Elements without that field are never duplicates.
+///
+/// Each array is scanned once with a hash map keyed by canonical rendering.
+pub(crate) fn find_duplicates(
+    value: &Value,
+    pointer: &str,
+    key: Option<&str>,
+    recursive: bool,
+) -> DuplicateReport {
+    let mut report = DuplicateReport::default();
+    walk(value, pointer, key, recursive, &mut report);
+    report
+}
+
+fn walk(
+    value: &Value,
+    pointer: &str,
+    key: Option<&str>,
+    recursive: bool,
+    report: &mut DuplicateReport,
+) {
+    match value {
+        Value::Array(items) => {
+            scan_array(items, pointer, key, report);
+            if recursive {
+                for (index, item) in items.iter().enumerate() {
+                    walk(item, &index_pointer(pointer, index), key, recursive, report);
+                }
+            }
+        }
+        Value::Object(map) if recursive => {
+            for (name, child) in map {
+                walk(child, &child_pointer(pointer, name), key, recursive, report);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn scan_array(items: &[Value], pointer: &str, key: Option<&str>, report: &mut DuplicateReport) {
+    report.arrays_scanned += 1;
+    let mut first_seen: HashMap<String, usize> = HashMap::new();
+    let mut groups: Vec<(&Value, Vec<usize>)> = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let compared = match key {
+            Some(path) => lookup_field_path(item, path),
+            None => Some(item),
+        };
+        let Some(compared) = compared else {
+            continue;
+        };
+        let next_group = groups.len();
+        let group = *first_seen
+            .entry(canonical_key(compared))
+            .or_insert(next_group);
+        if group == next_group {
+            groups.push((compared, vec![index]));
+        } else {
+            groups[group].1.push(index);
+        }
+    }
+
+    for (compared, indexes) in groups.into_iter().filter(|(_, indexes)| indexes.len() > 1) {
+        report.removable_count += indexes.len() - 1;
+        report.groups.push(DuplicateGroup {
+            array_pointer: pointer.to_string(),
+            pointers: indexes
+                .iter()
+                .map(|&index| index_pointer(pointer, index))
+                .collect(),
+            count: indexes.len(),
+            preview: value_preview(compared, PREVIEW_CHARS),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_duplicates;
+    use serde_json::json;
+
+    #[test]
+    fn groups_deep_duplicates_with_json_number_semantics() {
+        let value = json!([1, {"a": 1, "b": 2}, 1.0, "1", {"b": 2, "a": 1}, 1]);
+
+        let report = find_duplicates(&value, "", None, false);
+
+        assert_eq!(report.arrays_scanned, 1);
+        assert_eq!(report.removable_count, 3);
+        let pointers: Vec<_> = report.groups.iter().map(|group| &group.pointers).collect();
+        assert_eq!(pointers, [&vec!["/0", "/2", "/5"], &vec!["/1", "/4"]]);
+        assert_eq!(report.groups[1].preview, r#"{"a":1,"b":2}"#);
+    }
+
+    #[test]
+    fn groups_by_key_path_across_every_array() {
+        let value = json!({
+            "users": [{"id": {"n": 1}}, {"id": {"n": 2}}, {"id": {"n": 1}}, {}],
+            "nested": {"tags": [["x"], ["x"]]}
+        });
+
+        let by_key = find_duplicates(&value, "", Some("id.n"), true);
+        assert_eq!(by_key.arrays_scanned, 4);
+        assert_eq!(by_key.groups.len(), 1);
+        assert_eq!(by_key.groups[0].array_pointer, "/users");
+        assert_eq!(by_key.groups[0].pointers, ["/users/0", "/users/2"]);
+        assert_eq!(by_key.groups[0].preview, "1");
+
+        let deep = find_duplicates(&value, "", None, true);
+        assert_eq!(deep.groups.len(), 2);
+        assert_eq!(deep.groups[0].pointers, ["/users/0", "/users/2"]);
+        assert_eq!(
+            deep.groups[1].pointers,
+            ["/nested/tags/0", "/nested/tags/1"]
+        );
+    }
+}
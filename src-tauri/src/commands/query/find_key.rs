@@ -0,0 +1,135 @@
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::json_pointer::{child_pointer, index_pointer};
+use crate::json_value::value_type_name;
+
+#[derive(Serialize, Debug)]
+pub struct KeyMatch {
+    pub pointer: String,
+    pub key: String,
+    pub value_type: String,
+    /// Sibling keys that differ from this one only in case, e.g. `userID`
+    /// next to `userId`.
+    pub case_variants: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct KeySearch {
+    pub total_count: usize,
+    pub matches: Vec<KeyMatch>,
+    /// Every distinct key spelling that matched, in document order.
+    pub distinct_keys: Vec<String>,
+    pub truncated: bool,
+}
+
+pub(crate) enum KeyMatcher {
+    Exact(String),
+    Pattern(Regex),
+}
+
+impl KeyMatcher {
+    /// Exact matching compares whole keys; otherwise `key` is a
+    /// case-insensitive regex, so plain text matches as a substring.
+    pub(crate) fn new(key: &str, exact: bool) -> Result<Self, String> {
+        if exact {
+            return Ok(Self::Exact(key.to_string()));
+        }
+        RegexBuilder::new(key)
+            .case_insensitive(true)
+            .build()
+            .map(Self::Pattern)
+            .map_err(|error| format!("Invalid key pattern: {error}"))
+    }
+
+    fn is_match(&self, key: &str) -> bool {
+        match self {
+            Self::Exact(expected) => key == expected,
+            Self::Pattern(pattern) => pattern.is_match(key),
+        }
+    }
+}
+
+/// Collect every location whose final pointer segment is a matching object
+/// key, keeping at most `limit` detailed matches.
+pub(crate) fn find_keys(value: &Value, matcher: &KeyMatcher, limit: usize) -> KeySearch {
+    let mut search = KeySearch::default();
+    walk(value, "", matcher, limit, &mut search);
+    search.truncated = search.total_count > search.matches.len();
+    search
+}
+
+fn walk(value: &Value, pointer: &str, matcher: &KeyMatcher, limit: usize, search: &mut KeySearch) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = child_pointer(pointer, key);
+                if matcher.is_match(key) {
+                    search.total_count += 1;
+                    if !search.distinct_keys.contains(key) {
+                        search.distinct_keys.push(key.clone());
+                    }
+                    if search.matches.len() < limit {
+                        let case_variants = map
+                            .keys()
+                            .filter(|other| *other != key && other.eq_ignore_ascii_case(key))
+                            .cloned()
+                            .collect();
+                        search.matches.push(KeyMatch {
+                            pointer: child_path.clone(),
+                            key: key.clone(),
+                            value_type: value_type_name(child).to_string(),
+                            case_variants,
+                        });
+                    }
+                }
+                walk(child, &child_path, matcher, limit, search);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                walk(item, &index_pointer(pointer, index), matcher, limit, search);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_keys, KeyMatcher};
+    use serde_json::json;
+
+    #[test]
+    fn finds_exact_keys_and_flags_case_variants() {
+        let value = json!({
+            "userId": 1,
+            "userID": 1,
+            "items": [{"userId": "a"}, {"nested": {"userId": null}}]
+        });
+
+        let search = find_keys(&value, &KeyMatcher::new("userId", true).unwrap(), 100);
+
+        let pointers: Vec<_> = search.matches.iter().map(|m| m.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            ["/userId", "/items/0/userId", "/items/1/nested/userId"]
+        );
+        assert_eq!(search.matches[0].case_variants, ["userID"]);
+        assert!(search.matches[1].case_variants.is_empty());
+        assert_eq!(search.matches[2].value_type, "null");
+    }
+
+    #[test]
+    fn pattern_matching_is_case_insensitive_and_capped() {
+        let value = json!([{"created_at": 1}, {"updatedAt": 2}, {"name": "x"}, {"AT": 3}]);
+
+        let search = find_keys(&value, &KeyMatcher::new("at$", false).unwrap(), 2);
+
+        assert_eq!(search.total_count, 3);
+        assert_eq!(search.matches.len(), 2);
+        assert!(search.truncated);
+        assert_eq!(search.distinct_keys, ["created_at", "updatedAt", "AT"]);
+    }
+}
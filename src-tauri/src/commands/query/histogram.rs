@@ -0,0 +1,115 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::json_pointer::lookup_field_path;
+use crate::json_value::{canonical_key, value_type_name};
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct HistogramBucket {
+    /// Canonical JSON rendering: `1` and `1.0` share a bucket, `"1"` doesn't.
+    pub value: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Histogram {
+    /// Most frequent values first; ties keep document order.
+    pub buckets: Vec<HistogramBucket>,
+    /// Elements whose value fell outside the top `top_n` buckets.
+    pub other_count: usize,
+    /// Distinct values folded into `other_count`.
+    pub other_distinct: usize,
+    /// Elements without the field.
+    pub missing: usize,
+    pub total: usize,
+}
+
+/// Count the values of `field` (a dot-separated path) across the elements of
+/// `items`, keeping the `top_n` most frequent as buckets.
+pub(crate) fn histogram(
+    items: &Value,
+    field: &str,
+    top_n: Option<usize>,
+) -> Result<Histogram, String> {
+    let Value::Array(items) = items else {
+        return Err(format!(
+            "Expected an array at the target pointer, found {}",
+            value_type_name(items)
+        ));
+    };
+
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut buckets: Vec<HistogramBucket> = Vec::new();
+    let mut missing = 0;
+    for item in items {
+        let Some(value) = lookup_field_path(item, field) else {
+            missing += 1;
+            continue;
+        };
+        let key = canonical_key(value);
+        match positions.get(&key) {
+            Some(&position) => buckets[position].count += 1,
+            None => {
+                positions.insert(key.clone(), buckets.len());
+                buckets.push(HistogramBucket {
+                    value: key,
+                    count: 1,
+                });
+            }
+        }
+    }
+
+    // Stable sort, so equal counts stay in order of first appearance.
+    buckets.sort_by_key(|bucket| std::cmp::Reverse(bucket.count));
+    let other = buckets.split_off(top_n.unwrap_or(usize::MAX).min(buckets.len()));
+    Ok(Histogram {
+        buckets,
+        other_count: other.iter().map(|bucket| bucket.count).sum(),
+        other_distinct: other.len(),
+        missing,
+        total: items.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::histogram;
+    use serde_json::json;
+
+    #[test]
+    fn buckets_by_canonical_value_with_other_and_missing() {
+        let items = json!([
+            {"meta": {"status": 1}},
+            {"meta": {"status": "1"}},
+            {"meta": {"status": 1.0}},
+            {"meta": {"status": null}},
+            {"meta": {"status": "ok"}},
+            {"meta": {"status": "ok"}},
+            {"meta": {}},
+            {"meta": {"status": [2]}}
+        ]);
+
+        let all = histogram(&items, "meta.status", None).unwrap();
+        let buckets: Vec<_> = all
+            .buckets
+            .iter()
+            .map(|bucket| (bucket.value.as_str(), bucket.count))
+            .collect();
+        assert_eq!(
+            buckets,
+            [
+                ("1", 2),
+                ("\"ok\"", 2),
+                ("\"1\"", 1),
+                ("null", 1),
+                ("[2]", 1)
+            ]
+        );
+        assert_eq!((all.missing, all.total, all.other_count), (1, 8, 0));
+
+        let top = histogram(&items, "meta.status", Some(2)).unwrap();
+        assert_eq!(top.buckets.len(), 2);
+        assert_eq!((top.other_count, top.other_distinct), (3, 3));
+    }
+}
@@ -0,0 +1,322 @@
+// JSON query commands
+//
+// Read-only views over a document: each command parses the input with the
+// same JSON → JSON5 fallback chain as `json_format` and reports on it without
+// changing the editor content. The `_document` variants work on a document
+// opened with `open_document` instead of parsing the text again.
+
+mod aggregate;
+mod duplicates;
+mod find_key;
+mod histogram;
+mod sample;
+mod subtree;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::State;
+
+use super::document::DocumentStore;
+use super::json::{parse_to_value, to_indented_string};
+use crate::json_pointer::resolve;
+use crate::json_value::value_type_name;
+use aggregate::{aggregate_field, Aggregate};
+use duplicates::{find_duplicates, DuplicateReport};
+use find_key::{find_keys, KeyMatcher, KeySearch};
+use histogram::{histogram, Histogram};
+use sample::{sample_value, Elision, SampleLimits};
+use subtree::subtree_at;
+
+#[derive(Serialize)]
+pub struct SampleResult {
+    pub content: String,
+    pub elisions: Vec<Elision>,
+}
+
+/// Build a truncated preview of a large document: long arrays keep their first
+/// `max_array_items` items plus a marker, long strings are cut with an
+/// ellipsis, and containers nested `max_depth` levels deep become placeholders.
+#[tauri::command]
+pub fn json_sample(
+    content: &str,
+    max_array_items: usize,
+    max_string_len: usize,
+    max_depth: Option<usize>,
+) -> Result<SampleResult, String> {
+    sample(
+        parse_to_value(content)?,
+        max_array_items,
+        max_string_len,
+        max_depth,
+    )
+}
+
+/// `json_sample` for a document opened with `open_document`
+#[tauri::command]
+pub fn json_sample_document(
+    doc_id: String,
+    max_array_items: usize,
+    max_string_len: usize,
+    max_depth: Option<usize>,
+    store: State<'_, DocumentStore>,
+) -> Result<SampleResult, String> {
+    let value = store.read(&doc_id, |document| document.value.clone())?;
+    sample(value, max_array_items, max_string_len, max_depth)
+}
+
+fn sample(
+    mut value: Value,
+    max_array_items: usize,
+    max_string_len: usize,
+    max_depth: Option<usize>,
+) -> Result<SampleResult, String> {
+    let elisions = sample_value(
+        &mut value,
+        &SampleLimits {
+            max_array_items,
+            max_string_len,
+            max_depth,
+        },
+    );
+    let content = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("JSON formatting error: {}", e))?;
+    Ok(SampleResult { content, elisions })
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExtractResult {
+    pub content: String,
+    pub value_type: String,
+    /// Members or items directly inside the extracted value; 0 for scalars.
+    pub child_count: usize,
+    pub byte_size: usize,
+}
+
+/// Return the subtree at an RFC 6901 pointer as a standalone document.
+/// `indent` defaults to 2; 0 produces minified output.
+#[tauri::command]
+pub fn json_extract(
+    content: &str,
+    pointer: String,
+    indent: Option<usize>,
+) -> Result<ExtractResult, String> {
+    extract(&parse_to_value(content)?, &pointer, indent)
+}
+
+/// `json_extract` for a document opened with `open_document`
+#[tauri::command]
+pub fn json_extract_document(
+    doc_id: String,
+    pointer: String,
+    indent: Option<usize>,
+    store: State<'_, DocumentStore>,
+) -> Result<ExtractResult, String> {
+    store.read(&doc_id, |document| {
+        extract(&document.value, &pointer, indent)
+    })?
+}
+
+/// `json_extract` that only builds the subtree when `content` is strict JSON,
+/// for documents too large to parse whole.
+pub(crate) fn extract_streamed(
+    content: &str,
+    pointer: String,
+    indent: Option<usize>,
+) -> Result<ExtractResult, String> {
+    match subtree_at(content, &pointer) {
+        Some(subtree) => extract(&subtree?, "", indent),
+        None => json_extract(content, pointer, indent),
+    }
+}
+
+fn extract(value: &Value, pointer: &str, indent: Option<usize>) -> Result<ExtractResult, String> {
+    let subtree = resolve(value, pointer)?;
+    let child_count = match subtree {
+        Value::Object(map) => map.len(),
+        Value::Array(items) => items.len(),
+        _ => 0,
+    };
+    let content = to_indented_string(subtree, indent.unwrap_or(2))?;
+    Ok(ExtractResult {
+        byte_size: content.len(),
+        content,
+        value_type: value_type_name(subtree).to_string(),
+        child_count,
+    })
+}
+
+/// Detailed matches returned by `json_find_key`; `total_count` keeps counting.
+const MAX_KEY_MATCHES: usize = 1000;
+
+/// Locate every occurrence of an object key. With `exact` unset, `key` is a
+/// case-insensitive regex (plain text matches as a substring).
+#[tauri::command]
+pub fn json_find_key(content: &str, key: String, exact: bool) -> Result<KeySearch, String> {
+    let matcher = KeyMatcher::new(&key, exact)?;
+    let value = parse_to_value(content)?;
+    Ok(find_keys(&value, &matcher, MAX_KEY_MATCHES))
+}
+
+/// `json_find_key` for a document opened with `open_document`
+#[tauri::command]
+pub fn json_find_key_document(
+    doc_id: String,
+    key: String,
+    exact: bool,
+    store: State<'_, DocumentStore>,
+) -> Result<KeySearch, String> {
+    let matcher = KeyMatcher::new(&key, exact)?;
+    store.read(&doc_id, |document| {
+        find_keys(&document.value, &matcher, MAX_KEY_MATCHES)
+    })
+}
+
+/// Compute count, min, max, sum, mean, median and distinct count for
+/// `field` across the array at `array_pointer`.
+#[tauri::command]
+pub fn json_aggregate(
+    content: &str,
+    array_pointer: String,
+    field: String,
+) -> Result<Aggregate, String> {
+    let value = parse_to_value(content)?;
+    aggregate_field(resolve(&value, &array_pointer)?, &field)
+}
+
+/// `json_aggregate` for a document opened with `open_document`
+#[tauri::command]
+pub fn json_aggregate_document(
+    doc_id: String,
+    array_pointer: String,
+    field: String,
+    store: State<'_, DocumentStore>,
+) -> Result<Aggregate, String> {
+    store.read(&doc_id, |document| {
+        aggregate_field(resolve(&document.value, &array_pointer)?, &field)
+    })?
+}
+
+/// Count how often each value of `field` occurs across the array at
+/// `array_pointer`, most frequent first. Values beyond the `top_n` most
+/// frequent are summed into an `other` bucket.
+#[tauri::command]
+pub fn json_histogram(
+    content: &str,
+    array_pointer: String,
+    field: String,
+    top_n: Option<usize>,
+) -> Result<Histogram, String> {
+    let value = parse_to_value(content)?;
+    histogram(resolve(&value, &array_pointer)?, &field, top_n)
+}
+
+/// `json_histogram` for a document opened with `open_document`
+#[tauri::command]
+pub fn json_histogram_document(
+    doc_id: String,
+    array_pointer: String,
+    field: String,
+    top_n: Option<usize>,
+    store: State<'_, DocumentStore>,
+) -> Result<Histogram, String> {
+    store.read(&doc_id, |document| {
+        histogram(resolve(&document.value, &array_pointer)?, &field, top_n)
+    })?
+}
+
+/// Report groups of duplicate elements in the array at `array_pointer`, or
+/// in every array of the document when it is omitted. Elements are compared
+/// like `json_dedupe_array`: deeply, or by the dot-separated `key` path.
+#[tauri::command]
+pub fn json_find_duplicates(
+    content: &str,
+    array_pointer: Option<String>,
+    key: Option<String>,
+) -> Result<DuplicateReport, String> {
+    duplicates(&parse_to_value(content)?, array_pointer, key)
+}
+
+/// `json_find_duplicates` for a document opened with `open_document`
+#[tauri::command]
+pub fn json_find_duplicates_document(
+    doc_id: String,
+    array_pointer: Option<String>,
+    key: Option<String>,
+    store: State<'_, DocumentStore>,
+) -> Result<DuplicateReport, String> {
+    store.read(&doc_id, |document| {
+        duplicates(&document.value, array_pointer, key)
+    })?
+}
+
+fn duplicates(
+    value: &Value,
+    array_pointer: Option<String>,
+    key: Option<String>,
+) -> Result<DuplicateReport, String> {
+    let key = key.as_deref().filter(|key| !key.is_empty());
+    match array_pointer {
+        Some(pointer) => {
+            let target = resolve(value, &pointer)?;
+            if !target.is_array() {
+                return Err(format!(
+                    "Expected an array at the target pointer, found {}",
+                    value_type_name(target)
+                ));
+            }
+            Ok(find_duplicates(target, &pointer, key, false))
+        }
+        None => Ok(find_duplicates(value, "", key, true)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_extract, json_find_duplicates};
+
+    const DOCUMENT: &str = r#"{"users": [{"name": "Ada", "tags": ["a", "b"]}], "count": 1}"#;
+
+    #[test]
+    fn extracts_objects_array_items_scalars_and_the_root() {
+        let user = json_extract(DOCUMENT, "/users/0".to_string(), Some(0)).unwrap();
+        assert_eq!(user.content, r#"{"name":"Ada","tags":["a","b"]}"#);
+        assert_eq!(user.value_type, "object");
+        assert_eq!(user.child_count, 2);
+        assert_eq!(user.byte_size, user.content.len());
+
+        let scalar = json_extract(DOCUMENT, "/users/0/name".to_string(), None).unwrap();
+        assert_eq!(scalar.content, "\"Ada\"");
+        assert_eq!(scalar.child_count, 0);
+
+        let root = json_extract(DOCUMENT, String::new(), None).unwrap();
+        assert_eq!(root.value_type, "object");
+        assert!(root.content.contains("\n  \"users\""));
+    }
+
+    #[test]
+    fn reports_unresolvable_pointers_precisely() {
+        let error = json_extract(DOCUMENT, "/users/3".to_string(), None).unwrap_err();
+
+        assert!(error.contains("index 3 is out of bounds for the array at \"/users\""));
+    }
+
+    #[test]
+    fn finds_duplicates_in_one_array_or_the_whole_document() {
+        let content = r#"{"a": [1, 1], "b": {"c": [{"id": 7}, {"id": 7, "x": 1}]}}"#;
+
+        let one = json_find_duplicates(content, Some("/b/c".to_string()), Some("id".to_string()))
+            .unwrap();
+        assert_eq!(one.arrays_scanned, 1);
+        assert_eq!(one.groups[0].pointers, ["/b/c/0", "/b/c/1"]);
+
+        let all = json_find_duplicates(content, None, None).unwrap();
+        assert_eq!(all.arrays_scanned, 2);
+        assert_eq!(all.groups.len(), 1);
+        assert_eq!(all.groups[0].array_pointer, "/a");
+
+        assert_eq!(
+            json_find_duplicates(content, Some("/b".to_string()), None).unwrap_err(),
+            "Expected an array at the target pointer, found object"
+        );
+    }
+}
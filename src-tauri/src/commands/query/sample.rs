@@ -0,0 +1,157 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::json_pointer::{child_pointer, index_pointer};
+
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ElisionKind {
+    /// Array items after the first `max_array_items` were dropped.
+    ArrayItems,
+    /// Characters after the first `max_string_len` were cut.
+    String,
+    /// A container below `max_depth` was replaced by a placeholder.
+    Depth,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Elision {
+    pub pointer: String,
+    pub kind: ElisionKind,
+    /// Items, characters, or direct children that were left out.
+    pub count: usize,
+}
+
+pub(crate) struct SampleLimits {
+    pub max_array_items: usize,
+    pub max_string_len: usize,
+    pub max_depth: Option<usize>,
+}
+
+/// Truncate `value` in place for preview. Kept array items stay at their
+/// original indexes, so every reported pointer is valid in both documents.
+pub(crate) fn sample_value(value: &mut Value, limits: &SampleLimits) -> Vec<Elision> {
+    let mut elisions = Vec::new();
+    sample_at(value, limits, "", 0, &mut elisions);
+    elisions
+}
+
+fn sample_at(
+    value: &mut Value,
+    limits: &SampleLimits,
+    pointer: &str,
+    depth: usize,
+    elisions: &mut Vec<Elision>,
+) {
+    let too_deep = limits.max_depth.is_some_and(|max| depth >= max);
+    match value {
+        Value::Object(map) if too_deep => {
+            elisions.push(depth_elision(pointer, map.len()));
+            *value = Value::String(format!("…object with {} keys", map.len()));
+        }
+        Value::Array(items) if too_deep => {
+            elisions.push(depth_elision(pointer, items.len()));
+            *value = Value::String(format!("…array with {} items", items.len()));
+        }
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                sample_at(
+                    child,
+                    limits,
+                    &child_pointer(pointer, key),
+                    depth + 1,
+                    elisions,
+                );
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().take(limits.max_array_items).enumerate() {
+                sample_at(
+                    item,
+                    limits,
+                    &index_pointer(pointer, index),
+                    depth + 1,
+                    elisions,
+                );
+            }
+            if items.len() > limits.max_array_items {
+                let omitted = items.len() - limits.max_array_items;
+                items.truncate(limits.max_array_items);
+                items.push(Value::String(format!("…and {omitted} more items")));
+                elisions.push(Elision {
+                    pointer: pointer.to_string(),
+                    kind: ElisionKind::ArrayItems,
+                    count: omitted,
+                });
+            }
+        }
+        Value::String(text) => {
+            if let Some((cut, _)) = text.char_indices().nth(limits.max_string_len) {
+                let omitted = text[cut..].chars().count();
+                text.truncate(cut);
+                text.push('…');
+                elisions.push(Elision {
+                    pointer: pointer.to_string(),
+                    kind: ElisionKind::String,
+                    count: omitted,
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+fn depth_elision(pointer: &str, children: usize) -> Elision {
+    Elision {
+        pointer: pointer.to_string(),
+        kind: ElisionKind::Depth,
+        count: children,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sample_value, ElisionKind, SampleLimits};
+    use serde_json::json;
+
+    #[test]
+    fn truncates_arrays_and_strings_with_markers() {
+        let mut value = json!({"items": (0..10).collect::<Vec<_>>(), "name": "abcdefgh"});
+        let limits = SampleLimits {
+            max_array_items: 3,
+            max_string_len: 4,
+            max_depth: None,
+        };
+
+        let elisions = sample_value(&mut value, &limits);
+
+        assert_eq!(
+            value,
+            json!({"items": [0, 1, 2, "…and 7 more items"], "name": "abcd…"})
+        );
+        assert_eq!(elisions.len(), 2);
+        assert_eq!(elisions[0].pointer, "/items");
+        assert_eq!(elisions[0].count, 7);
+        assert_eq!(elisions[1].kind, ElisionKind::String);
+        assert_eq!(elisions[1].count, 4);
+    }
+
+    #[test]
+    fn replaces_deep_containers_with_placeholders() {
+        let mut value = json!({"a": {"b": {"c": 1, "d": 2}, "leaf": true}});
+        let limits = SampleLimits {
+            max_array_items: 100,
+            max_string_len: 100,
+            max_depth: Some(2),
+        };
+
+        let elisions = sample_value(&mut value, &limits);
+
+        assert_eq!(
+            value,
+            json!({"a": {"b": "…object with 2 keys", "leaf": true}})
+        );
+        assert_eq!(elisions[0].pointer, "/a/b");
+        assert_eq!(elisions[0].kind, ElisionKind::Depth);
+    }
+}
@@ -0,0 +1,169 @@
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+
+use crate::json_pointer::{
+    child_pointer, missing_key, not_a_container, parse_index, parse_pointer,
+};
+use crate::json_writer::NUMBER_TOKEN;
+
+/// The value at `pointer` in strict JSON `content`, built while parsing: the
+/// parser skips everything outside the subtree, so only the subtree is held.
+/// The error for a pointer that doesn't resolve is the one `resolve` gives.
+///
+/// `None` when `content` isn't strict JSON.
+pub(crate) fn subtree_at(content: &str, pointer: &str) -> Option<Result<Value, String>> {
+    let tokens = match parse_pointer(pointer) {
+        Ok(tokens) => tokens,
+        Err(error) => return Some(Err(error)),
+    };
+    let mut deserializer = serde_json::Deserializer::from_str(content);
+    let found = Subtree {
+        pointer,
+        tokens: &tokens,
+        walked: String::new(),
+    }
+    .deserialize(&mut deserializer)
+    .ok()?;
+    deserializer.end().ok()?;
+    Some(found)
+}
+
+/// Looks for the rest of a pointer inside the value being parsed.
+struct Subtree<'a> {
+    pointer: &'a str,
+    tokens: &'a [String],
+    /// Pointer of the value being parsed.
+    walked: String,
+}
+
+impl<'a> Subtree<'a> {
+    fn child(&self, token: &str) -> Subtree<'a> {
+        Subtree {
+            pointer: self.pointer,
+            tokens: &self.tokens[1..],
+            walked: child_pointer(&self.walked, token),
+        }
+    }
+
+    fn scalar<E>(self, value: Value) -> Result<Result<Value, String>, E> {
+        Ok(Err(not_a_container(self.pointer, &value, &self.walked)))
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Subtree<'_> {
+    type Value = Result<Value, String>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        if self.tokens.is_empty() {
+            return Value::deserialize(deserializer).map(Ok);
+        }
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Subtree<'_> {
+    type Value = Result<Value, String>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.scalar(Value::Null)
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+        self.scalar(Value::Bool(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        self.scalar(value.into())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        self.scalar(value.into())
+    }
+
+    fn visit_str<E: de::Error>(self, _: &str) -> Result<Self::Value, E> {
+        self.scalar(Value::String(String::new()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let token = &self.tokens[0];
+        // Only the form of the token is checked here; the length comes last.
+        let wanted = parse_index(token, usize::MAX, self.pointer, &self.walked).ok();
+        let mut found = None;
+        let mut len = 0;
+        loop {
+            if Some(len) == wanted {
+                match seq.next_element_seed(self.child(token))? {
+                    Some(subtree) => found = Some(subtree),
+                    None => break,
+                }
+            } else if seq.next_element::<IgnoredAny>()?.is_none() {
+                break;
+            }
+            len += 1;
+        }
+        Ok(found.unwrap_or_else(|| {
+            Err(parse_index(token, len, self.pointer, &self.walked)
+                .err()
+                .unwrap_or_default())
+        }))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let token = &self.tokens[0];
+        let mut found = None;
+        while let Some(key) = map.next_key::<String>()? {
+            if key == NUMBER_TOKEN {
+                map.next_value::<IgnoredAny>()?;
+                return self.scalar(0.into());
+            }
+            // A repeated key replaces the earlier value, as in a `Value`.
+            if key == *token {
+                found = Some(map.next_value_seed(self.child(token))?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(found.unwrap_or_else(|| Err(missing_key(self.pointer, token, &self.walked))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::subtree_at;
+    use crate::json_pointer::resolve;
+    use serde_json::Value;
+
+    #[test]
+    fn matches_resolving_the_parsed_document() {
+        let content =
+            r#"{"a": [10, {"b/c": [true, 1.50, "x"]}], "d": {"e": null}, "d": {"f": 2}, "": 0}"#;
+        let value: Value = serde_json::from_str(content).unwrap();
+        for pointer in [
+            "",
+            "/a",
+            "/a/1",
+            "/a/1/b~1c/1",
+            "/d",
+            "/d/f",
+            "/d/e",
+            "/",
+            "/a/2",
+            "/a/01",
+            "/a/-",
+            "/a/0/x",
+            "/a/1/b~1c/2/y",
+            "/zz",
+            "a",
+        ] {
+            let expected = resolve(&value, pointer).cloned();
+            assert_eq!(subtree_at(content, pointer), Some(expected), "{pointer}");
+        }
+        assert_eq!(subtree_at("{a: 1}", "/a"), None);
+    }
+}
@@ -0,0 +1,128 @@
+// Quick peek window
+//
+// A small borderless, always-on-top window that shows the clipboard JSON
+// formatted and read-only next to the cursor. It is created on first use and
+// hidden, not closed, when dismissed, so later peeks reuse it.
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Emitter, Manager, PhysicalPosition, State, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder,
+};
+
+use super::json::format_json;
+use super::shortcuts::read_clipboard;
+use super::window::devtools_allowed;
+
+const QUICK_PEEK_LABEL: &str = "quick-peek";
+/// Logical size of the window.
+const PEEK_WIDTH: f64 = 520.0;
+const PEEK_HEIGHT: f64 = 360.0;
+/// Gap between the cursor and the window, in physical pixels.
+const CURSOR_OFFSET: i32 = 12;
+
+/// Content shown by the quick peek window, kept for a window that is still
+/// loading when it is emitted.
+#[derive(Default)]
+pub struct QuickPeek(Mutex<Option<String>>);
+
+/// Show the clipboard JSON formatted in the quick peek window near the cursor
+#[tauri::command]
+pub async fn show_quick_peek(app: AppHandle, peek: State<'_, QuickPeek>) -> Result<(), String> {
+    let formatted = format_json(&read_clipboard(&app)?, None, None, None, None, None)?;
+    *peek.0.lock().unwrap() = Some(formatted.clone());
+
+    let window = quick_peek_window(&app)?;
+    if let Err(error) = move_near_cursor(&app, &window) {
+        log::warn!("Failed to position quick peek: {error}");
+    }
+    window.show().map_err(|e| e.to_string())?;
+    // Focus is needed for Escape and blur to dismiss it; hiding the window
+    // hands focus back to the app that was in front.
+    window.set_focus().map_err(|e| e.to_string())?;
+    window
+        .emit("quick-peek-content", formatted)
+        .map_err(|e| e.to_string())
+}
+
+/// The content last sent to the quick peek window, read when it loads
+#[tauri::command]
+pub fn get_quick_peek_content(peek: State<'_, QuickPeek>) -> Option<String> {
+    peek.0.lock().unwrap().clone()
+}
+
+fn quick_peek_window(app: &AppHandle) -> Result<WebviewWindow, String> {
+    if let Some(window) = app.get_webview_window(QUICK_PEEK_LABEL) {
+        return Ok(window);
+    }
+    WebviewWindowBuilder::new(app, QUICK_PEEK_LABEL, WebviewUrl::App("quick-peek".into()))
+        .title("Quick Peek")
+        .inner_size(PEEK_WIDTH, PEEK_HEIGHT)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .focused(false)
+        .devtools(devtools_allowed(app))
+        .build()
+        .map_err(|e| format!("Failed to create quick peek window: {}", e))
+}
+
+fn move_near_cursor(app: &AppHandle, window: &WebviewWindow) -> Result<(), String> {
+    let cursor = app.cursor_position().map_err(|e| e.to_string())?;
+    let Some(monitor) = app
+        .monitor_from_point(cursor.x, cursor.y)
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(());
+    };
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    let (x, y) = peek_position(
+        (cursor.x as i32, cursor.y as i32),
+        ((PEEK_WIDTH * scale) as i32, (PEEK_HEIGHT * scale) as i32),
+        (
+            area.position.x,
+            area.position.y,
+            area.size.width as i32,
+            area.size.height as i32,
+        ),
+    );
+    window
+        .set_position(PhysicalPosition::new(x, y))
+        .map_err(|e| e.to_string())
+}
+
+/// Top-left corner for a window of `size` below and right of `cursor`,
+/// flipped to the other side of the cursor where it would leave `area`
+/// (`x, y, width, height`) and clamped inside it.
+fn peek_position(cursor: (i32, i32), size: (i32, i32), area: (i32, i32, i32, i32)) -> (i32, i32) {
+    let place = |cursor: i32, size: i32, start: i32, length: i32| {
+        let after = cursor + CURSOR_OFFSET;
+        let position = if after + size <= start + length {
+            after
+        } else {
+            cursor - CURSOR_OFFSET - size
+        };
+        position.min(start + length - size).max(start)
+    };
+    (
+        place(cursor.0, size.0, area.0, area.2),
+        place(cursor.1, size.1, area.1, area.3),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::peek_position;
+
+    #[test]
+    fn places_the_window_beside_the_cursor_inside_the_work_area() {
+        let area = (0, 25, 1920, 1055);
+        assert_eq!(peek_position((100, 100), (500, 300), area), (112, 112));
+        // Near the bottom-right corner it opens above and left of the cursor.
+        assert_eq!(peek_position((1900, 1000), (500, 300), area), (1388, 688));
+        // A window larger than the space on either side stays on screen.
+        assert_eq!(peek_position((10, 600), (500, 900), area), (22, 25));
+    }
+}
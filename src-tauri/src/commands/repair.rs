@@ -0,0 +1,523 @@
+// Best-effort JSON repair command
+//
+// The repairer walks the input once, copying it to the output while fixing
+// common mistakes as it meets them. Whitespace is kept so the repaired text
+// lines up with the original. It is only ever run on request and is never
+// part of the json_format fallback chain.
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::json_simd::SERDE_MAX_DEPTH;
+
+#[derive(Serialize, Debug)]
+pub struct RepairFix {
+    /// 1-based position in the original input.
+    pub line: usize,
+    pub column: usize,
+    pub description: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RepairResult {
+    pub content: String,
+    pub fixes: Vec<RepairFix>,
+    /// Whether `content` parses as strict JSON.
+    pub valid: bool,
+    /// The problem that stopped the repair, when it couldn't finish.
+    pub error: Option<String>,
+}
+
+/// Fix trailing commas, single quotes, unquoted keys, comments, Python-style
+/// literals and unclosed strings/brackets at EOF. Every change is listed in
+/// `fixes`; when the input can't be fully repaired the best partial result is
+/// returned with `error` set.
+#[tauri::command]
+pub fn json_repair(content: &str) -> RepairResult {
+    let mut repairer = Repairer::new(content);
+    let mut error = repairer.repair_document().err();
+    if error.is_some() {
+        repairer.close_partial();
+    }
+    let valid = serde_json::from_str::<Value>(&repairer.out).is_ok();
+    if !valid && error.is_none() {
+        error = Some("Repaired content is still not valid JSON".to_string());
+    }
+    RepairResult {
+        content: repairer.out,
+        fixes: repairer.fixes,
+        valid,
+        error,
+    }
+}
+
+struct Repairer {
+    chars: Vec<char>,
+    pos: usize,
+    line_starts: Vec<usize>,
+    out: String,
+    fixes: Vec<RepairFix>,
+    /// Closing characters of the containers currently open.
+    open: Vec<char>,
+}
+
+impl Repairer {
+    fn new(content: &str) -> Self {
+        let chars: Vec<char> = content.chars().collect();
+        let line_starts = std::iter::once(0)
+            .chain(
+                chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &c)| c == '\n')
+                    .map(|(index, _)| index + 1),
+            )
+            .collect();
+        Self {
+            chars,
+            pos: 0,
+            line_starts,
+            out: String::new(),
+            fixes: Vec::new(),
+            open: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn line_column(&self, pos: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|&start| start <= pos);
+        (line, pos - self.line_starts[line - 1] + 1)
+    }
+
+    fn fix_at(&mut self, pos: usize, description: impl Into<String>) {
+        let (line, column) = self.line_column(pos);
+        self.fixes.push(RepairFix {
+            line,
+            column,
+            description: description.into(),
+        });
+    }
+
+    fn fix(&mut self, description: impl Into<String>) {
+        self.fix_at(self.pos, description);
+    }
+
+    fn error(&self, message: &str) -> String {
+        let (line, column) = self.line_column(self.pos);
+        format!("{message} at line {line} column {column}")
+    }
+
+    fn repair_document(&mut self) -> Result<(), String> {
+        let leading = self.skip_trivia();
+        if self.peek().is_none() {
+            return Err("Document is empty".to_string());
+        }
+        self.out.push_str(&leading);
+        self.repair_value()?;
+        let trailing = self.skip_trivia();
+        self.out.push_str(&trailing);
+        if self.peek().is_some() {
+            return Err(self.error("Unexpected content after the document"));
+        }
+        Ok(())
+    }
+
+    /// Skip whitespace and comments, returning the whitespace to copy over.
+    fn skip_trivia(&mut self) -> String {
+        let mut whitespace = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                whitespace.push(c);
+                self.pos += 1;
+            } else if c == '/' && self.peek_at(1) == Some('/') {
+                self.fix("Removed comment");
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+            } else if c == '/' && self.peek_at(1) == Some('*') {
+                self.fix("Removed comment");
+                self.pos += 2;
+                while self.peek().is_some()
+                    && !(self.peek() == Some('*') && self.peek_at(1) == Some('/'))
+                {
+                    self.pos += 1;
+                }
+                self.pos = (self.pos + 2).min(self.chars.len());
+            } else {
+                break;
+            }
+        }
+        whitespace
+    }
+
+    fn repair_value(&mut self) -> Result<(), String> {
+        match self.peek() {
+            None => {
+                self.fix("Inserted null for a missing value");
+                self.out.push_str("null");
+                Ok(())
+            }
+            Some('{') => self.repair_container('{', '}'),
+            Some('[') => self.repair_container('[', ']'),
+            Some(quote @ ('"' | '\'')) => {
+                self.repair_string(quote);
+                Ok(())
+            }
+            Some(c) if c == '-' || c == '+' || c == '.' || c.is_ascii_digit() => {
+                self.repair_number()
+            }
+            Some(c) if is_word_char(c) => {
+                self.repair_word();
+                Ok(())
+            }
+            Some(c) => Err(self.error(&format!("Unexpected character '{c}'"))),
+        }
+    }
+
+    /// Objects and arrays share one loop; `{` additionally reads a key and colon.
+    fn repair_container(&mut self, opener: char, closer: char) -> Result<(), String> {
+        if self.open.len() == SERDE_MAX_DEPTH {
+            return Err(self.error(&format!("Nesting is deeper than {SERDE_MAX_DEPTH} levels")));
+        }
+        let is_object = opener == '{';
+        self.pos += 1;
+        self.out.push(opener);
+        self.open.push(closer);
+        loop {
+            let mut whitespace = self.skip_trivia();
+            while self.peek() == Some(',') {
+                self.fix("Removed extra comma");
+                self.pos += 1;
+                whitespace.push_str(&self.skip_trivia());
+            }
+            self.out.push_str(&whitespace);
+            match self.peek() {
+                None => {
+                    self.close_at_eof(closer);
+                    return Ok(());
+                }
+                Some(c) if c == closer => {
+                    self.close(closer);
+                    return Ok(());
+                }
+                _ => {}
+            }
+
+            if is_object {
+                self.repair_key()?;
+                let before_colon = self.skip_trivia();
+                self.out.push_str(&before_colon);
+                if self.peek() == Some(':') {
+                    self.pos += 1;
+                } else {
+                    self.fix("Inserted missing colon");
+                }
+                self.out.push(':');
+                let after_colon = self.skip_trivia();
+                self.out.push_str(&after_colon);
+            }
+            self.repair_value()?;
+
+            let before = self.skip_trivia();
+            match self.peek() {
+                Some(',') => {
+                    let comma = self.pos;
+                    self.pos += 1;
+                    let after = self.skip_trivia();
+                    if self.peek() == Some(closer) || self.peek().is_none() {
+                        self.fix_at(comma, "Removed trailing comma");
+                        self.out.push_str(&before);
+                        self.out.push_str(&after);
+                    } else {
+                        self.out.push_str(&before);
+                        self.out.push(',');
+                        self.out.push_str(&after);
+                    }
+                }
+                Some(c) if c == closer => {
+                    self.out.push_str(&before);
+                    self.close(closer);
+                    return Ok(());
+                }
+                None => {
+                    self.out.push_str(&before);
+                    self.close_at_eof(closer);
+                    return Ok(());
+                }
+                // The other closer: this container was never closed.
+                Some('}' | ']') => {
+                    self.fix(format!("Inserted missing '{closer}'"));
+                    self.out.push_str(&before);
+                    self.out.push(closer);
+                    self.open.pop();
+                    return Ok(());
+                }
+                Some(_) => {
+                    self.fix("Inserted missing comma");
+                    self.out.push(',');
+                    self.out.push_str(&before);
+                }
+            }
+        }
+    }
+
+    fn close(&mut self, closer: char) {
+        self.pos += 1;
+        self.out.push(closer);
+        self.open.pop();
+    }
+
+    fn close_at_eof(&mut self, closer: char) {
+        self.fix(format!("Inserted missing '{closer}' at end of input"));
+        self.out.push(closer);
+        self.open.pop();
+    }
+
+    fn repair_key(&mut self) -> Result<(), String> {
+        match self.peek() {
+            Some(quote @ ('"' | '\'')) => {
+                self.repair_string(quote);
+                Ok(())
+            }
+            Some(c) if is_word_char(c) => {
+                self.fix("Quoted unquoted key");
+                let word = self.read_word();
+                self.push_quoted(&word);
+                Ok(())
+            }
+            _ => Err(self.error("Expected an object key")),
+        }
+    }
+
+    fn repair_string(&mut self, quote: char) {
+        if quote == '\'' {
+            self.fix("Replaced single quotes with double quotes");
+        }
+        self.pos += 1;
+        self.out.push('"');
+        loop {
+            let Some(c) = self.peek() else {
+                self.fix("Closed unterminated string");
+                self.out.push('"');
+                return;
+            };
+            self.pos += 1;
+            match c {
+                _ if c == quote => break,
+                '\\' => match self.peek() {
+                    Some('\'') => {
+                        self.pos += 1;
+                        self.out.push('\'');
+                    }
+                    Some(escaped) => {
+                        self.pos += 1;
+                        self.out.push('\\');
+                        self.out.push(escaped);
+                    }
+                    None => {}
+                },
+                '"' => self.out.push_str("\\\""),
+                '\n' => {
+                    self.fix_at(self.pos - 1, "Escaped line break inside string");
+                    self.out.push_str("\\n");
+                }
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                c if c.is_control() => self.out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+
+    fn repair_number(&mut self) -> Result<(), String> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+        {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        if text == "-" && self.peek().is_some_and(is_word_char) {
+            let word = self.read_word();
+            self.fix_at(start, format!("Replaced -{word} with null"));
+            self.out.push_str("null");
+            return Ok(());
+        }
+        let mut number = text.trim_start_matches('+').to_string();
+        if number.starts_with('.') || number.starts_with("-.") {
+            number = number.replacen('.', "0.", 1);
+        }
+        if number.ends_with('.') {
+            number.push('0');
+        }
+        if serde_json::from_str::<serde_json::Number>(&number).is_err() {
+            self.pos = start;
+            return Err(self.error(&format!("Invalid number '{text}'")));
+        }
+        if number != text {
+            self.fix_at(start, format!("Rewrote number {text} as {number}"));
+        }
+        self.out.push_str(&number);
+        Ok(())
+    }
+
+    fn repair_word(&mut self) {
+        let start = self.pos;
+        let word = self.read_word();
+        let replacement = match word.as_str() {
+            "true" | "false" | "null" => {
+                self.out.push_str(&word);
+                return;
+            }
+            "True" => "true",
+            "False" => "false",
+            "None" | "NaN" | "Infinity" | "undefined" => "null",
+            _ => {
+                self.fix_at(start, format!("Quoted bare word {word}"));
+                self.push_quoted(&word);
+                return;
+            }
+        };
+        self.fix_at(start, format!("Replaced {word} with {replacement}"));
+        self.out.push_str(replacement);
+    }
+
+    fn read_word(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(is_word_char) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn push_quoted(&mut self, word: &str) {
+        self.out.push('"');
+        self.out.push_str(word);
+        self.out.push('"');
+    }
+
+    /// After an unrecoverable error, cut the output back to the last complete
+    /// value and close every open container.
+    fn close_partial(&mut self) {
+        let trimmed = self.out.trim_end().len();
+        self.out.truncate(trimmed);
+        if self.out.ends_with(',') {
+            self.out.pop();
+        } else if self.out.ends_with(':') {
+            self.out.push_str(" null");
+        }
+        while let Some(closer) = self.open.pop() {
+            self.out.push(closer);
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_repair;
+    use crate::json_simd::SERDE_MAX_DEPTH;
+
+    fn descriptions(content: &str) -> Vec<String> {
+        json_repair(content)
+            .fixes
+            .into_iter()
+            .map(|fix| format!("{}:{} {}", fix.line, fix.column, fix.description))
+            .collect()
+    }
+
+    #[test]
+    fn fixes_common_hand_editing_mistakes() {
+        let input = "{\n  // settings\n  name: 'it\\'s',\n  \"on\": True,\n  \"tags\": [1, 2,],\n  \"x\": None,\n}";
+
+        let result = json_repair(input);
+
+        assert!(result.valid, "{}", result.content);
+        assert_eq!(result.error, None);
+        assert_eq!(
+            result.content,
+            "{\n  \n  \"name\": \"it's\",\n  \"on\": true,\n  \"tags\": [1, 2],\n  \"x\": null\n}"
+        );
+        assert_eq!(
+            descriptions(input),
+            [
+                "2:3 Removed comment",
+                "3:3 Quoted unquoted key",
+                "3:9 Replaced single quotes with double quotes",
+                "4:9 Replaced True with true",
+                "5:16 Removed trailing comma",
+                "6:8 Replaced None with null",
+                "6:12 Removed trailing comma",
+            ]
+        );
+    }
+
+    #[test]
+    fn closes_truncated_documents() {
+        let result = json_repair(r#"{"items": [{"id": 1}, {"id": 2, "name": "tru"#);
+
+        assert!(result.valid);
+        assert_eq!(
+            result.content,
+            r#"{"items": [{"id": 1}, {"id": 2, "name": "tru"}]}"#
+        );
+        assert_eq!(result.fixes.len(), 4);
+    }
+
+    #[test]
+    fn inserts_missing_commas_and_closers() {
+        let result = json_repair(r#"[{"a": 1 "b": 2]"#);
+
+        assert!(result.valid);
+        assert_eq!(result.content, r#"[{"a": 1, "b": 2}]"#);
+        assert_eq!(
+            descriptions(r#"[{"a": 1 "b": 2]"#),
+            ["1:10 Inserted missing comma", "1:16 Inserted missing '}'"]
+        );
+    }
+
+    #[test]
+    fn returns_partial_result_with_remaining_error() {
+        let result = json_repair(r#"{"a": [1, 2], "b": @oops}"#);
+
+        assert!(result.valid);
+        assert_eq!(result.content, r#"{"a": [1, 2], "b": null}"#);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Unexpected character '@' at line 1 column 20")
+        );
+    }
+
+    #[test]
+    fn stops_at_the_depth_limit_instead_of_recursing() {
+        let result = json_repair(&"[".repeat(100_000));
+
+        assert!(result.valid);
+        assert_eq!(
+            result.content,
+            format!(
+                "{}{}",
+                "[".repeat(SERDE_MAX_DEPTH),
+                "]".repeat(SERDE_MAX_DEPTH)
+            )
+        );
+        assert_eq!(
+            result.error,
+            Some(format!(
+                "Nesting is deeper than {SERDE_MAX_DEPTH} levels at line 1 column {}",
+                SERDE_MAX_DEPTH + 1
+            ))
+        );
+    }
+}
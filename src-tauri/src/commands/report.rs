@@ -0,0 +1,478 @@
+// Report export command
+//
+// Renders a validation result, document statistics or a diff as a Markdown
+// or standalone HTML file. Each report is first laid out as titled sections
+// of paragraphs and tables, which the two templates then render.
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use super::diff::{DiffKind, DiffResult};
+use super::file::{file_dialog, remember_parent};
+use super::json::ValidationResult;
+use super::stats::{JsonStats, PointerMetric};
+
+const HTML_STYLE: &str =
+    "body{font-family:-apple-system,BlinkMacSystemFont,\"Segoe UI\",sans-serif;\
+margin:2rem auto;max-width:960px;padding:0 1rem;color:#1f2328}\
+h1{font-size:1.6rem}h2{font-size:1.2rem;margin-top:2rem}\
+.meta{color:#656d76}\
+table{border-collapse:collapse;width:100%}\
+th,td{border:1px solid #d0d7de;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#f6f8fa}\
+td{font-family:ui-monospace,SFMono-Regular,Menlo,monospace;font-size:0.9em;word-break:break-all}";
+
+/// Any of the results the report can be built from, told apart by shape.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReportInput {
+    Diff(DiffResult),
+    Stats(Box<JsonStats>),
+    Validation(ValidationResult),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            _ => Err(format!("Unsupported report format: {name}")),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+struct Report {
+    title: &'static str,
+    sections: Vec<Section>,
+}
+
+#[derive(Default)]
+struct Section {
+    heading: Option<String>,
+    paragraphs: Vec<String>,
+    table: Option<Table>,
+}
+
+struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Render a validation, stats or diff result and save it through a save dialog
+///
+/// `format` is `markdown` or `html`; the HTML file has its styles inline so
+/// it can be shared on its own. Returns the saved path, or `None` when the
+/// dialog is cancelled.
+#[tauri::command]
+pub async fn export_report(
+    app: AppHandle,
+    report_json: String,
+    format: String,
+) -> Result<Option<String>, String> {
+    let format = ReportFormat::parse(&format)?;
+    let generated = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let output = render_report(&report_json, format, &generated)?;
+
+    let extension = format.extension();
+    let file_path = file_dialog(&app)
+        .add_filter("Report", &[extension])
+        .set_file_name(format!("report.{extension}"))
+        .blocking_save_file();
+
+    match file_path {
+        Some(path) => {
+            let path_str = path.to_string();
+            remember_parent(&app, &path_str);
+            tokio::fs::write(&path_str, output)
+                .await
+                .map_err(|e| format!("Failed to save file: {}", e))?;
+            Ok(Some(path_str))
+        }
+        None => Ok(None),
+    }
+}
+
+fn render_report(
+    report_json: &str,
+    format: ReportFormat,
+    generated: &str,
+) -> Result<String, String> {
+    let input: ReportInput = serde_json::from_str(report_json).map_err(|_| {
+        "Report must be a validation result, document statistics or a diff result".to_string()
+    })?;
+    let report = match input {
+        ReportInput::Diff(diff) => diff_report(&diff),
+        ReportInput::Stats(stats) => stats_report(&stats),
+        ReportInput::Validation(result) => validation_report(&result),
+    };
+    Ok(match format {
+        ReportFormat::Markdown => render_markdown(&report, generated),
+        ReportFormat::Html => render_html(&report, generated),
+    })
+}
+
+fn validation_report(result: &ValidationResult) -> Report {
+    let mut sections = vec![Section {
+        paragraphs: validation_paragraphs(result),
+        ..Section::default()
+    }];
+    if !result.warnings.is_empty() {
+        sections.push(Section {
+            heading: Some(format!("Warnings ({})", result.warnings.len())),
+            table: Some(Table {
+                headers: vec!["Line", "Column", "Pointer", "Message"],
+                rows: result
+                    .warnings
+                    .iter()
+                    .map(|warning| {
+                        vec![
+                            warning.line.to_string(),
+                            warning.column.to_string(),
+                            pointer_label(&warning.pointer),
+                            warning.message.clone(),
+                        ]
+                    })
+                    .collect(),
+            }),
+            ..Section::default()
+        });
+    }
+    Report {
+        title: "Validation report",
+        sections,
+    }
+}
+
+fn validation_paragraphs(result: &ValidationResult) -> Vec<String> {
+    let mut paragraphs = vec![format!(
+        "Result: {}",
+        if result.valid { "valid" } else { "invalid" }
+    )];
+    if let Some(message) = &result.error_message {
+        let location = match (result.error_line, result.error_column) {
+            (Some(line), Some(column)) => format!(" (line {line}, column {column})"),
+            _ => String::new(),
+        };
+        paragraphs.push(format!("Error: {message}{location}"));
+    }
+    paragraphs
+}
+
+fn stats_report(stats: &JsonStats) -> Report {
+    let mut overview = vec![
+        metric_row("Valid", if stats.valid { "yes" } else { "no" }),
+        metric_row("Format", &stats.format_type),
+        metric_row("Size", &format!("{} bytes", stats.byte_size)),
+    ];
+    if let Some(minified) = stats.minified_byte_size {
+        overview.push(metric_row("Minified size", &format!("{minified} bytes")));
+    }
+    overview.extend([
+        metric_row("Lines", &stats.line_count.to_string()),
+        metric_row("Characters", &stats.char_count.to_string()),
+        metric_row("Keys", &stats.key_count.to_string()),
+        metric_row("Depth", &stats.depth.to_string()),
+    ]);
+    let mut sections = vec![Section {
+        heading: Some("Overview".to_string()),
+        table: Some(metric_table(overview)),
+        ..Section::default()
+    }];
+
+    if let Some(breakdown) = &stats.breakdown {
+        let mut rows = vec![
+            metric_row("Objects", &breakdown.objects.to_string()),
+            metric_row("Arrays", &breakdown.arrays.to_string()),
+            metric_row("Array elements", &breakdown.array_elements.to_string()),
+            metric_row("Strings", &breakdown.strings.to_string()),
+            metric_row("Numbers", &breakdown.numbers.to_string()),
+            metric_row("Booleans", &breakdown.booleans.to_string()),
+            metric_row("Nulls", &breakdown.nulls.to_string()),
+        ];
+        if let Some(metric) = &breakdown.longest_array {
+            rows.push(metric_row(
+                "Longest array",
+                &pointer_metric(metric, "items"),
+            ));
+        }
+        if let Some(metric) = &breakdown.longest_string {
+            rows.push(metric_row(
+                "Longest string",
+                &pointer_metric(metric, "characters"),
+            ));
+        }
+        rows.push(metric_row(
+            "Deepest node",
+            &pointer_label(&breakdown.deepest_pointer),
+        ));
+        sections.push(Section {
+            heading: Some("Breakdown".to_string()),
+            table: Some(metric_table(rows)),
+            ..Section::default()
+        });
+    }
+
+    if let Some(error) = &stats.error_info {
+        sections.push(Section {
+            heading: Some("Validation".to_string()),
+            paragraphs: validation_paragraphs(error),
+            ..Section::default()
+        });
+    }
+    Report {
+        title: "Document statistics",
+        sections,
+    }
+}
+
+fn diff_report(diff: &DiffResult) -> Report {
+    let summary = &diff.summary;
+    let mut paragraphs = vec![format!(
+        "{} differences: {} added, {} removed, {} changed, {} type changed",
+        summary.total, summary.added, summary.removed, summary.changed, summary.type_changed
+    )];
+    if diff.truncated {
+        paragraphs.push(format!(
+            "Only the first {} differences are listed.",
+            diff.entries.len()
+        ));
+    }
+    let mut sections = vec![Section {
+        paragraphs,
+        ..Section::default()
+    }];
+    if !diff.entries.is_empty() {
+        sections.push(Section {
+            heading: Some("Differences".to_string()),
+            table: Some(Table {
+                headers: vec!["Pointer", "Change", "Left", "Right"],
+                rows: diff
+                    .entries
+                    .iter()
+                    .map(|entry| {
+                        vec![
+                            pointer_label(&entry.pointer),
+                            diff_kind_label(entry.kind).to_string(),
+                            entry.left_preview.clone().unwrap_or_default(),
+                            entry.right_preview.clone().unwrap_or_default(),
+                        ]
+                    })
+                    .collect(),
+            }),
+            ..Section::default()
+        });
+    }
+    Report {
+        title: "Diff report",
+        sections,
+    }
+}
+
+fn diff_kind_label(kind: DiffKind) -> &'static str {
+    match kind {
+        DiffKind::Added => "added",
+        DiffKind::Removed => "removed",
+        DiffKind::Changed => "changed",
+        DiffKind::TypeChanged => "type changed",
+    }
+}
+
+fn metric_row(name: &str, value: &str) -> Vec<String> {
+    vec![name.to_string(), value.to_string()]
+}
+
+fn metric_table(rows: Vec<Vec<String>>) -> Table {
+    Table {
+        headers: vec!["Metric", "Value"],
+        rows,
+    }
+}
+
+fn pointer_metric(metric: &PointerMetric, unit: &str) -> String {
+    format!(
+        "{} ({} {unit})",
+        pointer_label(&metric.pointer),
+        metric.length
+    )
+}
+
+/// The root pointer is empty, which reads as a missing value in a table.
+fn pointer_label(pointer: &str) -> String {
+    if pointer.is_empty() {
+        "(root)".to_string()
+    } else {
+        pointer.to_string()
+    }
+}
+
+fn render_markdown(report: &Report, generated: &str) -> String {
+    let mut out = format!(
+        "# {}\n\nGenerated by JsonStudio on {generated}\n",
+        report.title
+    );
+    for section in &report.sections {
+        if let Some(heading) = &section.heading {
+            out.push_str(&format!("\n## {}\n", markdown_escape(heading)));
+        }
+        for paragraph in &section.paragraphs {
+            out.push_str(&format!("\n{}\n", markdown_escape(paragraph)));
+        }
+        if let Some(table) = &section.table {
+            out.push('\n');
+            out.push_str(&markdown_row(table.headers.iter().copied()));
+            out.push_str(&markdown_row(table.headers.iter().map(|_| "---")));
+            for row in &table.rows {
+                out.push_str(&markdown_row(row.iter().map(String::as_str)));
+            }
+        }
+    }
+    out
+}
+
+fn markdown_row<'a>(cells: impl Iterator<Item = &'a str>) -> String {
+    let cells: Vec<String> = cells
+        .map(|cell| markdown_escape(cell).replace('|', "\\|"))
+        .collect();
+    format!("| {} |\n", cells.join(" | "))
+}
+
+/// Keep previews of user data from turning into Markdown markup.
+fn markdown_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\r' | '\n' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn render_html(report: &Report, generated: &str) -> String {
+    let title = html_escape(report.title);
+    let mut body = format!(
+        "<h1>{title}</h1>\n<p class=\"meta\">Generated by JsonStudio on {}</p>\n",
+        html_escape(generated)
+    );
+    for section in &report.sections {
+        if let Some(heading) = &section.heading {
+            body.push_str(&format!("<h2>{}</h2>\n", html_escape(heading)));
+        }
+        for paragraph in &section.paragraphs {
+            body.push_str(&format!("<p>{}</p>\n", html_escape(paragraph)));
+        }
+        if let Some(table) = &section.table {
+            body.push_str("<table>\n<tr>");
+            for header in &table.headers {
+                body.push_str(&format!("<th>{}</th>", html_escape(header)));
+            }
+            body.push_str("</tr>\n");
+            for row in &table.rows {
+                body.push_str("<tr>");
+                for cell in row {
+                    body.push_str(&format!("<td>{}</td>", html_escape(cell)));
+                }
+                body.push_str("</tr>\n");
+            }
+            body.push_str("</table>\n");
+        }
+    }
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n"
+    )
+}
+
+fn html_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_report, ReportFormat};
+    use crate::commands::diff::{json_diff, DiffOptions};
+    use crate::commands::json::validate_json;
+    use crate::commands::stats::collect_stats;
+
+    const GENERATED: &str = "2024-01-02 03:04";
+
+    fn render<T: serde::Serialize>(report: &T, format: ReportFormat) -> String {
+        render_report(&serde_json::to_string(report).unwrap(), format, GENERATED).unwrap()
+    }
+
+    #[test]
+    fn renders_validation_results_as_markdown() {
+        let markdown = render(
+            &validate_json("{\"a\": 1, \"a\": 2}"),
+            ReportFormat::Markdown,
+        );
+        assert!(markdown.starts_with(
+            "# Validation report\n\nGenerated by JsonStudio on 2024-01-02 03:04\n\nResult: valid\n"
+        ));
+        assert!(markdown.contains("## Warnings (1)\n\n| Line | Column | Pointer | Message |\n| --- | --- | --- | --- |\n| 1 | 10 | (root) |"));
+
+        let invalid = render(&validate_json("{\"a\": }"), ReportFormat::Markdown);
+        assert!(invalid.contains("Result: invalid\n\nError: "));
+        assert!(invalid.contains("(line 1, column 7)"));
+    }
+
+    #[test]
+    fn renders_stats_and_diffs_as_standalone_html() {
+        let html = render(&collect_stats("{\"a\": [1, 2]}"), ReportFormat::Html);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>") && !html.contains("<link") && !html.contains("<script"));
+        assert!(html.contains("<title>Document statistics</title>"));
+        assert!(html.contains("<tr><td>Longest array</td><td>/a (2 items)</td></tr>"));
+
+        let diff = json_diff(
+            "{\"tag\": \"<b>\"}",
+            "{\"tag\": \"a|b\", \"new\": 1}",
+            DiffOptions::default(),
+        )
+        .unwrap();
+        let html = render(&diff, ReportFormat::Html);
+        assert!(
+            html.contains("<p>2 differences: 1 added, 0 removed, 1 changed, 0 type changed</p>")
+        );
+        assert!(html.contains("<td>&quot;&lt;b&gt;&quot;</td>"));
+
+        let markdown = render(&diff, ReportFormat::Markdown);
+        assert!(markdown.contains("| /tag | changed | \"\\<b\\>\" | \"a\\|b\" |"));
+    }
+
+    #[test]
+    fn rejects_unknown_reports_and_formats() {
+        assert!(render_report("{\"foo\": 1}", ReportFormat::Html, GENERATED).is_err());
+        assert_eq!(
+            ReportFormat::parse("pdf").unwrap_err(),
+            "Unsupported report format: pdf"
+        );
+    }
+}
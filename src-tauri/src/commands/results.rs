@@ -0,0 +1,275 @@
+// Large command results
+//
+// A result of hundreds of megabytes returned from a command is serialized
+// into one IPC message, which stalls the webview and can fail outright. A
+// text result of `CHUNKED_MIN_BYTES` or more is kept here instead: the
+// command returns its `result_id`, size and a preview, and the page pulls
+// the text with `read_result_chunk`. Reading the last chunk releases the
+// result; one nobody reads for `RESULT_TTL` is dropped.
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use super::file::{preview_text, STREAMED_PREVIEW_BYTES};
+use crate::error::{AppError, ErrorCode};
+use crate::text_encoding::DecodedText;
+
+/// Results this large are kept for `read_result_chunk`.
+pub(crate) const CHUNKED_MIN_BYTES: usize = 8 * 1024 * 1024;
+/// Largest chunk `read_result_chunk` returns.
+const MAX_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+/// How long a result is kept after it was stored or last read.
+const RESULT_TTL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Default)]
+pub struct ResultStore {
+    results: Mutex<HashMap<String, StoredText>>,
+    next_id: AtomicU64,
+}
+
+struct StoredText {
+    text: Arc<String>,
+    last_read: Instant,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StoredResult {
+    pub result_id: String,
+    /// Length of the text in bytes.
+    pub size: usize,
+    /// Leading part of the text, cut at a character boundary.
+    pub preview: String,
+}
+
+/// A text result: the text itself, or where to read it from.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Delivered {
+    Inline(String),
+    Stored(StoredResult),
+}
+
+/// A file's text, with `content` left empty when the text was stored.
+#[derive(Serialize, Debug)]
+pub struct DeliveredText {
+    #[serde(flatten)]
+    pub text: DecodedText,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stored: Option<StoredResult>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ResultChunk {
+    pub text: String,
+    /// Offset of the next chunk; the size of the result after the last one.
+    pub next_offset: usize,
+    pub done: bool,
+}
+
+impl ResultStore {
+    fn store(&self, text: String, now: Instant) -> StoredResult {
+        let size = text.len();
+        let preview = preview_text(
+            &text.as_bytes()[..size.min(STREAMED_PREVIEW_BYTES)],
+            size > STREAMED_PREVIEW_BYTES,
+        );
+        let result_id = format!(
+            "result-{}",
+            self.next_id.fetch_add(1, Ordering::Relaxed) + 1
+        );
+        self.results.lock().unwrap().insert(
+            result_id.clone(),
+            StoredText {
+                text: Arc::new(text),
+                last_read: now,
+            },
+        );
+        StoredResult {
+            result_id,
+            size,
+            preview,
+        }
+    }
+
+    /// Up to `len` bytes from `offset`, cut back to a character boundary.
+    fn chunk(
+        &self,
+        result_id: &str,
+        offset: usize,
+        len: usize,
+        now: Instant,
+    ) -> Result<ResultChunk, AppError> {
+        let text = {
+            let mut results = self.results.lock().unwrap();
+            let stored = results
+                .get_mut(result_id)
+                .ok_or_else(|| ErrorCode::ResultExpired.with("result_id", result_id))?;
+            stored.last_read = now;
+            Arc::clone(&stored.text)
+        };
+        if !text.is_char_boundary(offset) {
+            return Err(format!("Offset {offset} is not at a character boundary").into());
+        }
+        let mut end = offset
+            .saturating_add(len.min(MAX_CHUNK_BYTES))
+            .min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        // A chunk shorter than its first character still returns that character.
+        if end == offset && end < text.len() {
+            end = (end + 1..=text.len())
+                .find(|&end| text.is_char_boundary(end))
+                .unwrap_or(text.len());
+        }
+        let done = end == text.len();
+        if done {
+            self.results.lock().unwrap().remove(result_id);
+        }
+        Ok(ResultChunk {
+            text: text[offset..end].to_string(),
+            next_offset: end,
+            done,
+        })
+    }
+
+    fn release(&self, result_id: &str) -> bool {
+        self.results.lock().unwrap().remove(result_id).is_some()
+    }
+
+    /// Time left before `result_id` expires. Drops it, and returns `None`,
+    /// when that time is up or the result is already gone.
+    fn time_left(&self, result_id: &str, now: Instant) -> Option<Duration> {
+        let mut results = self.results.lock().unwrap();
+        let left = RESULT_TTL.checked_sub(now.duration_since(results.get(result_id)?.last_read));
+        match left {
+            Some(left) if !left.is_zero() => Some(left),
+            _ => {
+                results.remove(result_id);
+                None
+            }
+        }
+    }
+}
+
+/// Return `text` as it is, or store it when it is too large for one message.
+pub(crate) fn deliver(app: &AppHandle, text: String) -> Delivered {
+    match stored_if_large(app, text) {
+        Ok(text) => Delivered::Inline(text),
+        Err(stored) => Delivered::Stored(stored),
+    }
+}
+
+/// `deliver` for the text of a file.
+pub(crate) fn deliver_text(app: &AppHandle, mut text: DecodedText) -> DeliveredText {
+    let stored = stored_if_large(app, std::mem::take(&mut text.content))
+        .map(|content| text.content = content)
+        .err();
+    DeliveredText { text, stored }
+}
+
+fn stored_if_large(app: &AppHandle, text: String) -> Result<String, StoredResult> {
+    if text.len() < CHUNKED_MIN_BYTES {
+        return Ok(text);
+    }
+    let stored = app.state::<ResultStore>().store(text, Instant::now());
+    expire_later(app.clone(), stored.result_id.clone());
+    Err(stored)
+}
+
+fn expire_later(app: AppHandle, result_id: String) {
+    tauri::async_runtime::spawn(async move {
+        let mut wait = RESULT_TTL;
+        loop {
+            tokio::time::sleep(wait).await;
+            match app
+                .state::<ResultStore>()
+                .time_left(&result_id, Instant::now())
+            {
+                Some(left) => wait = left,
+                None => break,
+            }
+        }
+    });
+}
+
+/// Read part of a stored result; `offset` and `len` are in bytes
+#[tauri::command]
+pub fn read_result_chunk(
+    app: AppHandle,
+    result_id: String,
+    offset: usize,
+    len: usize,
+) -> Result<ResultChunk, AppError> {
+    app.state::<ResultStore>()
+        .chunk(&result_id, offset, len, Instant::now())
+}
+
+/// Drop a stored result that won't be read to the end; returns whether it
+/// was still kept
+#[tauri::command]
+pub fn release_result(app: AppHandle, result_id: String) -> bool {
+    app.state::<ResultStore>().release(&result_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResultStore, RESULT_TTL};
+    use crate::error::ErrorCode;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn reads_a_stored_result_in_chunks_at_character_boundaries() {
+        let store = ResultStore::default();
+        let now = Instant::now();
+        let stored = store.store("aé😀b".to_string(), now);
+        assert_eq!((stored.result_id.as_str(), stored.size), ("result-1", 8));
+        assert_eq!(stored.preview, "aé😀b");
+
+        let mut text = String::new();
+        let mut offset = 0;
+        loop {
+            let chunk = store.chunk(&stored.result_id, offset, 2, now).unwrap();
+            text.push_str(&chunk.text);
+            offset = chunk.next_offset;
+            if chunk.done {
+                break;
+            }
+        }
+        assert_eq!(text, "aé😀b");
+        assert_eq!(offset, 8);
+        // The last chunk released the result.
+        let expired = store.chunk(&stored.result_id, 0, 2, now).unwrap_err();
+        assert_eq!(expired.code, ErrorCode::ResultExpired);
+
+        let stored = store.store("é".to_string(), now);
+        assert!(store.chunk(&stored.result_id, 1, 2, now).is_err());
+        assert!(store.release(&stored.result_id));
+        assert!(!store.release(&stored.result_id));
+    }
+
+    #[test]
+    fn drops_results_left_unread() {
+        let store = ResultStore::default();
+        let start = Instant::now();
+        let stored = store.store("[1, 2]".to_string(), start);
+
+        let read_at = start + Duration::from_secs(60);
+        store.chunk(&stored.result_id, 0, 1, read_at).unwrap();
+        assert_eq!(
+            store.time_left(&stored.result_id, read_at),
+            Some(RESULT_TTL)
+        );
+        assert!(store
+            .time_left(&stored.result_id, start + RESULT_TTL)
+            .is_some());
+        assert_eq!(
+            store.time_left(&stored.result_id, read_at + RESULT_TTL),
+            None
+        );
+        assert!(!store.release(&stored.result_id));
+    }
+}
@@ -0,0 +1,199 @@
+// Schema drift command
+//
+// Compares the inferred structure of two documents rather than their values:
+// which fields appeared, which disappeared, and which changed type. Arrays
+// are compared through their merged element shape, reported under a `*`
+// pointer token that stands for every element.
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::Window;
+
+use super::codegen::{infer_schema, InferredSchema, JsonType};
+use super::diff::DiffKind;
+use super::json::parse_to_value;
+use super::tasks::spawn_task;
+use crate::json_pointer::child_pointer;
+
+#[derive(Serialize, Debug)]
+pub struct SchemaChange {
+    pub pointer: String,
+    /// `added`, `removed` or `type_changed`.
+    pub kind: DiffKind,
+    pub before_type: Option<String>,
+    pub after_type: Option<String>,
+}
+
+/// Report fields added, removed, or retyped between two documents' shapes
+#[tauri::command]
+pub fn json_schema_diff(left: &str, right: &str) -> Result<Vec<SchemaChange>, String> {
+    let left = infer_schema(&parse_to_value(left)?);
+    let right = infer_schema(&parse_to_value(right)?);
+    Ok(schema_changes(&left, &right))
+}
+
+/// `json_schema_diff` as a background task; returns its id (see `spawn_task`)
+#[tauri::command]
+pub fn json_schema_diff_async(window: Window, left: String, right: String) -> String {
+    spawn_task(window, "schema_diff", move |task| {
+        task.step(0, 3)?;
+        let left = infer_schema(&parse_to_value(&left)?);
+        task.step(1, 3)?;
+        let right = infer_schema(&parse_to_value(&right)?);
+        task.step(2, 3)?;
+        Ok(schema_changes(&left, &right))
+    })
+}
+
+fn schema_changes(left: &InferredSchema, right: &InferredSchema) -> Vec<SchemaChange> {
+    let mut differ = SchemaDiffer {
+        left,
+        right,
+        changes: Vec::new(),
+    };
+    differ.compare(&left.root, &right.root, "");
+    differ.changes
+}
+
+struct SchemaDiffer<'a> {
+    left: &'a InferredSchema,
+    right: &'a InferredSchema,
+    changes: Vec<SchemaChange>,
+}
+
+impl SchemaDiffer<'_> {
+    fn compare(&mut self, before: &JsonType, after: &JsonType, pointer: &str) {
+        let (before_name, after_name) = (type_label(before), type_label(after));
+        if before_name != after_name {
+            self.changes.push(SchemaChange {
+                pointer: pointer.to_string(),
+                kind: DiffKind::TypeChanged,
+                before_type: Some(before_name),
+                after_type: Some(after_name),
+            });
+        }
+
+        match (required(before), required(after)) {
+            (JsonType::Object(left_name), JsonType::Object(right_name)) => {
+                let empty = BTreeMap::new();
+                let left_fields = self.left.structs.get(left_name).unwrap_or(&empty);
+                let right_fields = self.right.structs.get(right_name).unwrap_or(&empty);
+                self.compare_fields(left_fields, right_fields, pointer);
+            }
+            (JsonType::Array(left_item), JsonType::Array(right_item)) => {
+                self.compare(left_item, right_item, &format!("{pointer}/*"));
+            }
+            _ => {}
+        }
+    }
+
+    fn compare_fields(
+        &mut self,
+        before: &BTreeMap<String, JsonType>,
+        after: &BTreeMap<String, JsonType>,
+        pointer: &str,
+    ) {
+        for (key, before_type) in before {
+            let field_pointer = child_pointer(pointer, key);
+            match after.get(key) {
+                Some(after_type) => self.compare(before_type, after_type, &field_pointer),
+                None => self.changes.push(SchemaChange {
+                    pointer: field_pointer,
+                    kind: DiffKind::Removed,
+                    before_type: Some(type_label(before_type)),
+                    after_type: None,
+                }),
+            }
+        }
+        for (key, after_type) in after {
+            if !before.contains_key(key) {
+                self.changes.push(SchemaChange {
+                    pointer: child_pointer(pointer, key),
+                    kind: DiffKind::Added,
+                    before_type: None,
+                    after_type: Some(type_label(after_type)),
+                });
+            }
+        }
+    }
+}
+
+fn required(json_type: &JsonType) -> &JsonType {
+    match json_type {
+        JsonType::Optional(inner) => inner,
+        other => other,
+    }
+}
+
+/// Type name without nested detail: element and field changes are reported
+/// at their own pointers. Optional types end in `?`.
+fn type_label(json_type: &JsonType) -> String {
+    match json_type {
+        JsonType::String => "string".to_string(),
+        JsonType::Number => "number".to_string(),
+        JsonType::Integer => "integer".to_string(),
+        JsonType::Boolean => "boolean".to_string(),
+        JsonType::Null => "null".to_string(),
+        JsonType::Array(_) => "array".to_string(),
+        JsonType::Object(_) => "object".to_string(),
+        JsonType::Any => "any".to_string(),
+        JsonType::Optional(inner) => format!("{}?", type_label(inner)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_schema_diff;
+
+    fn changes(left: &str, right: &str) -> Vec<String> {
+        json_schema_diff(left, right)
+            .unwrap()
+            .iter()
+            .map(|change| {
+                format!(
+                    "{:?} {} {} -> {}",
+                    change.kind,
+                    change.pointer,
+                    change.before_type.as_deref().unwrap_or("-"),
+                    change.after_type.as_deref().unwrap_or("-")
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_shape_changes_but_not_value_changes() {
+        let yesterday =
+            r#"{"id": 1, "name": "a", "tags": ["x"], "owner": {"login": "a", "site": "u"}}"#;
+        let today =
+            r#"{"id": "2", "name": "b", "tags": [1], "owner": {"login": "b", "admin": true}}"#;
+
+        assert_eq!(
+            changes(yesterday, today),
+            [
+                "TypeChanged /id integer -> string",
+                "Removed /owner/site string -> -",
+                "Added /owner/admin - -> boolean",
+                "TypeChanged /tags/* string -> integer",
+            ]
+        );
+        assert!(changes(
+            yesterday,
+            r#"{"id": 9, "name": "z", "tags": ["y", "z"], "owner": {"login": "q", "site": "v"}}"#
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn compares_arrays_by_merged_element_shape() {
+        let before = r#"[{"id": 1, "email": "a"}, {"id": 2, "email": "b"}]"#;
+        let after = r#"[{"id": 1, "email": "a", "plan": "pro"}, {"id": 2}]"#;
+
+        assert_eq!(
+            changes(before, after),
+            [
+                "TypeChanged /*/email string -> string?",
+                "Added /*/plan - -> string?",
+            ]
+        );
+    }
+}
@@ -0,0 +1,245 @@
+// Application settings
+//
+// The user's settings as one key → value map in `settings.json` under the
+// app config directory, shared by every window and read at startup, so the
+// backend can honor them before any page has loaded. Keys are the
+// frontend's setting names. The file carries a schema version and older
+// layouts are migrated when it is loaded.
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use super::store::{save_store, store_path};
+
+const SETTINGS_STORE: &str = "settings.json";
+/// Schema version written by this build; see `migrate`.
+const SETTINGS_VERSION: u64 = 1;
+
+/// Settings the backend reads itself.
+pub(crate) const IS_DARK_MODE_SETTING: &str = "isDarkMode";
+pub(crate) const FOLLOW_SYSTEM_THEME_SETTING: &str = "followSystemTheme";
+pub(crate) const RESTORE_WINDOW_GEOMETRY_SETTING: &str = "restoreWindowGeometry";
+/// Global shortcut action id → accelerator, empty when unbound.
+pub(crate) const GLOBAL_SHORTCUTS_SETTING: &str = "globalShortcuts";
+/// Lowest level written to the diagnostic log, e.g. `"debug"`.
+pub(crate) const LOG_LEVEL_SETTING: &str = "logLevel";
+/// Interface language, `"en"` or `"zh"`.
+pub(crate) const LANGUAGE_SETTING: &str = "language";
+/// Largest file, in bytes, opened in the editor rather than in preview mode.
+pub(crate) const MAX_EDITOR_FILE_BYTES_SETTING: &str = "maxEditorFileBytes";
+
+/// Serializes read-modify-write cycles on the settings file.
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct SettingsFile {
+    version: u64,
+    values: Map<String, Value>,
+}
+
+/// Every stored setting; keys without a value use the frontend's default
+#[tauri::command]
+pub fn get_settings(app: AppHandle) -> Result<Map<String, Value>, String> {
+    let path = store_path(&app, SETTINGS_STORE)?;
+    let _guard = SETTINGS_LOCK.lock().unwrap();
+    Ok(load_settings(&path).values)
+}
+
+/// One stored setting, `None` when it has its default
+#[tauri::command]
+pub fn get_setting(app: AppHandle, key: String) -> Result<Option<Value>, String> {
+    get_settings(app).map(|mut values| values.remove(&key))
+}
+
+/// Store one setting and send `settings-changed` to every window
+///
+/// A `null` value removes the setting, so its default applies again.
+#[tauri::command]
+pub fn set_setting(app: AppHandle, key: String, value: Value) -> Result<(), String> {
+    update_settings(&app, |values| {
+        if value.is_null() {
+            values.remove(&key);
+        } else {
+            values.insert(key, value);
+        }
+    })
+}
+
+/// Forget every setting and send `settings-changed` to every window
+#[tauri::command]
+pub fn reset_settings(app: AppHandle) -> Result<(), String> {
+    update_settings(&app, Map::clear)
+}
+
+/// A stored setting as `T`; `None` when it is missing or has another type.
+pub(crate) fn setting<T: DeserializeOwned>(app: &AppHandle, key: &str) -> Option<T> {
+    let path = store_path(app, SETTINGS_STORE).ok()?;
+    let _guard = SETTINGS_LOCK.lock().unwrap();
+    let value = load_settings(&path).values.remove(key)?;
+    serde_json::from_value(value).ok()
+}
+
+/// Change the stored settings and send all of them on `settings-changed`.
+pub(crate) fn update_settings(
+    app: &AppHandle,
+    change: impl FnOnce(&mut Map<String, Value>),
+) -> Result<(), String> {
+    let path = store_path(app, SETTINGS_STORE)?;
+    let values = {
+        let _guard = SETTINGS_LOCK.lock().unwrap();
+        update_settings_file(&path, change)?
+    };
+    let _ = app.emit("settings-changed", values);
+    Ok(())
+}
+
+/// Load, change and save the settings file at `path`. A file that can't be
+/// read as settings is moved to `settings.json.bak` first, so saving doesn't
+/// destroy what a partial write or a hand edit left behind.
+fn update_settings_file(
+    path: &Path,
+    change: impl FnOnce(&mut Map<String, Value>),
+) -> Result<Map<String, Value>, String> {
+    let mut settings = read_settings(path).unwrap_or_else(|reason| {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        log::warn!("Settings file is unreadable ({reason}); moving it to {backup:?}");
+        if let Err(e) = std::fs::rename(path, &backup) {
+            log::warn!("Failed to back up the settings file: {e}");
+        }
+        migrate(Value::Null)
+    });
+    change(&mut settings.values);
+    save_store(path, &settings)?;
+    Ok(settings.values)
+}
+
+/// A missing or unreadable file loads as no settings rather than failing.
+fn load_settings(path: &Path) -> SettingsFile {
+    read_settings(path).unwrap_or_else(|_| migrate(Value::Null))
+}
+
+/// The settings in `path`, with no settings when it is missing; an error
+/// when it exists but doesn't hold a JSON object.
+fn read_settings(path: &Path) -> Result<SettingsFile, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(migrate(Value::Null)),
+        Err(e) => return Err(e.to_string()),
+    };
+    match serde_json::from_str(&content).map_err(|e| e.to_string())? {
+        stored @ Value::Object(_) => Ok(migrate(stored)),
+        _ => Err("not a JSON object".to_string()),
+    }
+}
+
+/// Bring a stored file to `SETTINGS_VERSION`:
+///
+/// - 0: no `version` field; the values were the top-level object.
+///
+/// Files from a newer build are read as they are and keep their version.
+fn migrate(stored: Value) -> SettingsFile {
+    let Value::Object(mut object) = stored else {
+        return SettingsFile {
+            version: SETTINGS_VERSION,
+            values: Map::new(),
+        };
+    };
+    let version = object.get("version").and_then(Value::as_u64).unwrap_or(0);
+    let values = if version == 0 {
+        object
+    } else {
+        match object.remove("values") {
+            Some(Value::Object(values)) => values,
+            _ => Map::new(),
+        }
+    };
+    SettingsFile {
+        version: version.max(SETTINGS_VERSION),
+        values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_settings, migrate, update_settings_file, SettingsFile, SETTINGS_VERSION};
+    use serde_json::{json, Map};
+    use std::fs;
+
+    fn values(value: serde_json::Value) -> Map<String, serde_json::Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn migrates_unversioned_files() {
+        assert_eq!(
+            migrate(json!({ "tabSize": 4, "isDarkMode": true })),
+            SettingsFile {
+                version: SETTINGS_VERSION,
+                values: values(json!({ "tabSize": 4, "isDarkMode": true })),
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_current_and_newer_files() {
+        let current = migrate(json!({ "version": 1, "values": { "fontSize": 14 } }));
+        assert_eq!(current.values, values(json!({ "fontSize": 14 })));
+        let newer = migrate(json!({ "version": 7, "values": { "fontSize": 15 } }));
+        assert_eq!(newer.version, 7);
+        assert_eq!(newer.values, values(json!({ "fontSize": 15 })));
+    }
+
+    #[test]
+    fn unreadable_files_load_as_empty_settings() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        assert!(load_settings(&dir.join("missing.json")).values.is_empty());
+        fs::write(dir.join("broken.json"), "{\"version\": 1,").unwrap();
+        assert!(load_settings(&dir.join("broken.json")).values.is_empty());
+        fs::write(dir.join("list.json"), "[1, 2]").unwrap();
+        let file = load_settings(&dir.join("list.json"));
+        assert_eq!((file.version, file.values.len()), (SETTINGS_VERSION, 0));
+    }
+
+    #[test]
+    fn saving_over_a_corrupt_file_keeps_a_backup() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("settings.json");
+        let broken = "{\"version\": 1, \"values\": {\"tabSize\": 4";
+        fs::write(&path, broken).unwrap();
+
+        let stored = update_settings_file(&path, |values| {
+            values.insert("fontSize".into(), json!(14));
+        })
+        .unwrap();
+
+        assert_eq!(stored, values(json!({ "fontSize": 14 })));
+        assert_eq!(
+            fs::read_to_string(temp.path().join("settings.json.bak")).unwrap(),
+            broken
+        );
+        assert_eq!(load_settings(&path).values, stored);
+    }
+
+    #[test]
+    fn saving_over_a_valid_file_leaves_no_backup() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("settings.json");
+        fs::write(&path, r#"{"version": 1, "values": {"tabSize": 4}}"#).unwrap();
+
+        update_settings_file(&path, |values| {
+            values.insert("fontSize".into(), json!(14));
+        })
+        .unwrap();
+
+        assert_eq!(
+            load_settings(&path).values,
+            values(json!({ "tabSize": 4, "fontSize": 14 }))
+        );
+        assert!(!temp.path().join("settings.json.bak").exists());
+    }
+}
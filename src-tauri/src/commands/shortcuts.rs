@@ -1,146 +1,726 @@
-use std::{collections::HashMap, sync::Mutex};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+    sync::Mutex,
+};
 use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tauri_plugin_notification::NotificationExt;
 
-pub(crate) const SHOW_APP_SHORTCUT_ID: &str = "show_app";
-pub(crate) const FORMAT_CLIPBOARD_SHORTCUT_ID: &str = "format_clipboard";
-pub(crate) const DEFAULT_SHOW_APP_SHORTCUT: &str = "CommandOrControl+Shift+J";
-pub(crate) const DEFAULT_FORMAT_CLIPBOARD_SHORTCUT: &str = "CommandOrControl+Shift+V";
+use super::captures::capture_clipboard_to_scratch;
+use super::clipboard_history::{record_clipboard, record_clipboard_if_json};
+use super::json::{
+    format_json, json_escape, json_unescape, minify_json, validate_json, ValidationResult,
+};
+use super::logging::LogFailure;
+use super::quick_peek::{show_quick_peek, QuickPeek};
+use super::settings::{setting, update_settings, GLOBAL_SHORTCUTS_SETTING};
+use super::store::{save_shortcuts_enabled_preference, shortcuts_enabled_preference};
+use super::window::{is_pinned, target_window};
+use crate::app_state::emit_when_ready;
+use crate::error::{AppError, ErrorCode, Locale};
+
+const SHOW_APP_SHORTCUT_ID: &str = "show_app";
+const FORMAT_CLIPBOARD_SHORTCUT_ID: &str = "format_clipboard";
+
+/// What a global shortcut does when pressed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ShortcutAction {
+    ShowApp,
+    FormatClipboard,
+    FormatClipboardInPlace,
+    MinifyClipboard,
+    ValidateClipboard,
+    EscapeClipboard,
+    UnescapeClipboard,
+    QuickPeek,
+    CaptureClipboard,
+}
+
+impl ShortcutAction {
+    /// Whether the action works on the clipboard without showing a window, so
+    /// its outcome is reported with a system notification.
+    fn runs_in_background(self) -> bool {
+        !matches!(
+            self,
+            Self::ShowApp | Self::FormatClipboard | Self::QuickPeek
+        )
+    }
+}
+
+struct ActionSpec {
+    id: &'static str,
+    action: ShortcutAction,
+    description: &'static str,
+    /// Accelerator bound at startup; `None` leaves the action unbound.
+    default_key: Option<&'static str>,
+}
+
+/// Every bindable action, in display order, with the default keymap.
+const SHORTCUT_ACTIONS: &[ActionSpec] = &[
+    ActionSpec {
+        id: SHOW_APP_SHORTCUT_ID,
+        action: ShortcutAction::ShowApp,
+        description: "Show the JsonStudio window",
+        default_key: Some("CommandOrControl+Shift+J"),
+    },
+    ActionSpec {
+        id: FORMAT_CLIPBOARD_SHORTCUT_ID,
+        action: ShortcutAction::FormatClipboard,
+        description: "Open the clipboard JSON formatted in JsonStudio",
+        default_key: Some("CommandOrControl+Shift+V"),
+    },
+    ActionSpec {
+        id: "format_clipboard_in_place",
+        action: ShortcutAction::FormatClipboardInPlace,
+        description: "Format the clipboard JSON in place without showing the window",
+        default_key: None,
+    },
+    ActionSpec {
+        id: "minify_clipboard",
+        action: ShortcutAction::MinifyClipboard,
+        description: "Minify the clipboard JSON in place",
+        default_key: None,
+    },
+    ActionSpec {
+        id: "validate_clipboard",
+        action: ShortcutAction::ValidateClipboard,
+        description: "Validate the clipboard JSON and show the result",
+        default_key: None,
+    },
+    ActionSpec {
+        id: "escape_clipboard",
+        action: ShortcutAction::EscapeClipboard,
+        description: "Escape the clipboard text as a JSON string",
+        default_key: None,
+    },
+    ActionSpec {
+        id: "unescape_clipboard",
+        action: ShortcutAction::UnescapeClipboard,
+        description: "Unescape a JSON string in the clipboard",
+        default_key: None,
+    },
+    ActionSpec {
+        id: "quick_peek",
+        action: ShortcutAction::QuickPeek,
+        description: "Peek at the clipboard JSON in a popup near the cursor",
+        default_key: None,
+    },
+    ActionSpec {
+        id: "capture_clipboard_to_scratch",
+        action: ShortcutAction::CaptureClipboard,
+        description: "Append the clipboard JSON to today's scratch capture file",
+        default_key: None,
+    },
+];
+
+fn find_action(id: &str) -> Result<&'static ActionSpec, AppError> {
+    SHORTCUT_ACTIONS
+        .iter()
+        .find(|spec| spec.id == id)
+        .ok_or_else(|| ErrorCode::UnknownShortcut.with("id", id))
+}
+
+/// Sent on `shortcut-error` when the action behind a global shortcut fails.
+#[derive(Serialize, Clone)]
+struct ShortcutError {
+    action: &'static str,
+    /// Stable error code, see `ErrorCode`.
+    code: &'static str,
+    message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Binding {
+    /// As it was given, for display.
+    accelerator: String,
+    shortcut: Shortcut,
+}
+
+impl Binding {
+    fn parse(accelerator: &str) -> Result<Self, AppError> {
+        Ok(Self {
+            accelerator: accelerator.to_string(),
+            shortcut: parse_shortcut(accelerator)?,
+        })
+    }
+}
+
+/// Accelerators currently bound to each shortcut id; `None` when disabled.
+type Bindings = HashMap<String, Option<Binding>>;
 
 pub(crate) struct GlobalShortcutRegistry {
-    keys: Mutex<HashMap<String, String>>,
+    bindings: Mutex<Bindings>,
+    /// When false, bindings are kept but none is registered with the OS.
+    enabled: AtomicBool,
 }
 
 impl Default for GlobalShortcutRegistry {
     fn default() -> Self {
         Self {
-            keys: Mutex::new(HashMap::from([
-                (
-                    SHOW_APP_SHORTCUT_ID.to_string(),
-                    DEFAULT_SHOW_APP_SHORTCUT.to_string(),
-                ),
-                (
-                    FORMAT_CLIPBOARD_SHORTCUT_ID.to_string(),
-                    DEFAULT_FORMAT_CLIPBOARD_SHORTCUT.to_string(),
-                ),
-            ])),
+            bindings: Mutex::new(
+                SHORTCUT_ACTIONS
+                    .iter()
+                    .map(|spec| {
+                        let binding = spec.default_key.and_then(|key| Binding::parse(key).ok());
+                        (spec.id.to_string(), binding)
+                    })
+                    .collect(),
+            ),
+            enabled: AtomicBool::new(true),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ShortcutInfo {
+    pub id: String,
+    /// Empty when the shortcut is disabled.
+    pub accelerator: String,
+    pub enabled: bool,
+    pub description: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutStatus {
+    Ok,
+    ParseError,
+    /// Already bound to another action of this app.
+    ConflictInternal,
+    /// Taken by the OS or another application.
+    ConflictSystem,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ShortcutValidation {
+    pub status: ShortcutStatus,
+    pub message: Option<String>,
+    /// The action already using the accelerator, for `conflict_internal`.
+    pub conflicting_id: Option<String>,
+}
+
+impl ShortcutValidation {
+    fn failed(status: ShortcutStatus, message: String) -> Self {
+        Self {
+            status,
+            message: Some(message),
+            conflicting_id: None,
+        }
+    }
+}
+
+/// The registrations needed to move a shortcut id to a new binding.
+#[derive(Debug, PartialEq)]
+struct ShortcutChange {
+    unregister: Option<Shortcut>,
+    register: Option<Shortcut>,
+}
+
+fn plan_change(
+    bindings: &Bindings,
+    id: &str,
+    shortcut: Option<Shortcut>,
+) -> Result<ShortcutChange, AppError> {
+    let current = bindings
+        .get(id)
+        .ok_or_else(|| ErrorCode::UnknownShortcut.with("id", id))?
+        .as_ref()
+        .map(|binding| binding.shortcut);
+    if let Some(other) = shortcut.and_then(|shortcut| bound_to(bindings, shortcut, Some(id))) {
+        return Err(ErrorCode::ShortcutInUse.with("other", other));
+    }
+    Ok(ShortcutChange {
+        unregister: current.filter(|_| current != shortcut),
+        register: shortcut,
+    })
+}
+
+/// The id bound to `shortcut`, other than `except`.
+fn bound_to<'a>(
+    bindings: &'a Bindings,
+    shortcut: Shortcut,
+    except: Option<&str>,
+) -> Option<&'a str> {
+    bindings
+        .iter()
+        .find(|(id, binding)| {
+            Some(id.as_str()) != except
+                && binding
+                    .as_ref()
+                    .is_some_and(|binding| binding.shortcut == shortcut)
+        })
+        .map(|(id, _)| id.as_str())
+}
+
+/// Parse `key` and check it against this app's own bindings; the OS-level
+/// check needs the plugin and is left to `validate_shortcut`.
+fn check_shortcut(
+    bindings: &Bindings,
+    key: &str,
+    id: Option<&str>,
+) -> Result<Shortcut, ShortcutValidation> {
+    let shortcut = parse_shortcut(key)
+        .map_err(|error| ShortcutValidation::failed(ShortcutStatus::ParseError, error.message()))?;
+    match bound_to(bindings, shortcut, id) {
+        Some(other) => Err(ShortcutValidation {
+            status: ShortcutStatus::ConflictInternal,
+            message: Some(ErrorCode::ShortcutInUse.with("other", other).message()),
+            conflicting_id: Some(other.to_string()),
+        }),
+        None => Ok(shortcut),
+    }
+}
+
+fn shortcut_list(bindings: &Bindings) -> Vec<ShortcutInfo> {
+    SHORTCUT_ACTIONS
+        .iter()
+        .map(|spec| {
+            let binding = bindings.get(spec.id).and_then(Option::as_ref);
+            ShortcutInfo {
+                id: spec.id.to_string(),
+                accelerator: binding
+                    .map(|binding| binding.accelerator.clone())
+                    .unwrap_or_default(),
+                enabled: binding.is_some(),
+                description: spec.description.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn parse_shortcut(key: &str) -> Result<Shortcut, AppError> {
+    key.parse()
+        .map_err(|e| ErrorCode::InvalidShortcut.reason(format!("{e:?}")))
+}
+
+/// Register the default keymap with the user's saved changes at startup,
+/// unless shortcuts were disabled in the last session; failures are only
+/// logged, since another app may already own an accelerator.
+pub(crate) fn register_default_shortcuts(app: &AppHandle) {
+    let registry = app.state::<GlobalShortcutRegistry>();
+    let Ok(mut bindings) = lock_bindings(&registry) else {
+        return;
+    };
+    let saved: HashMap<String, String> = setting(app, GLOBAL_SHORTCUTS_SETTING).unwrap_or_default();
+    apply_saved_bindings(&mut bindings, saved);
+    if !shortcuts_enabled_preference(app) {
+        registry.enabled.store(false, Ordering::SeqCst);
+        return;
+    }
+    for (id, binding) in bindings.iter() {
+        let Some(binding) = binding else {
+            continue;
+        };
+        if let Err(error) = register_shortcut(app, id, binding.shortcut) {
+            log::warn!("Failed to register {id} shortcut: {error}");
         }
     }
 }
 
-pub(crate) fn register_global_shortcut(app: &AppHandle, id: &str, key: &str) -> Result<(), String> {
-    let shortcut: Shortcut = key
-        .parse()
-        .map_err(|e| format!("Invalid shortcut format: {:?}", e))?;
+/// Replace default bindings with saved ones, skipping unknown ids and
+/// accelerators that no longer parse.
+fn apply_saved_bindings(bindings: &mut Bindings, saved: HashMap<String, String>) {
+    for (id, key) in saved {
+        if !bindings.contains_key(&id) {
+            continue;
+        }
+        let binding = match key.trim() {
+            "" => None,
+            key => match Binding::parse(key) {
+                Ok(binding) => Some(binding),
+                Err(error) => {
+                    log::warn!("Ignoring saved {id} shortcut: {error}");
+                    continue;
+                }
+            },
+        };
+        bindings.insert(id, binding);
+    }
+}
 
-    match id {
-        SHOW_APP_SHORTCUT_ID => {
-            let app_handle = app.clone();
-            app.global_shortcut()
-                .on_shortcut(shortcut, move |_app, _shortcut, event| {
-                    if event.state != ShortcutState::Pressed {
-                        return;
-                    }
-                    let handle = app_handle.clone();
-                    tauri::async_runtime::spawn(async move {
-                        let _ = show_main_window(handle).await;
-                    });
-                })
-                .map_err(|e| format!("Failed to register shortcut: {}", e))
+/// Keep a changed binding in the settings, for the next launch.
+fn save_shortcut_setting(app: &AppHandle, id: &str, key: &str) {
+    let result = update_settings(app, |values| {
+        let saved = values
+            .entry(GLOBAL_SHORTCUTS_SETTING)
+            .or_insert_with(|| serde_json::Value::Object(Default::default()));
+        if !saved.is_object() {
+            *saved = serde_json::Value::Object(Default::default());
         }
-        FORMAT_CLIPBOARD_SHORTCUT_ID => {
-            let app_handle = app.clone();
-            app.global_shortcut()
-                .on_shortcut(shortcut, move |_app, _shortcut, event| {
-                    if event.state != ShortcutState::Pressed {
-                        return;
-                    }
-                    let handle = app_handle.clone();
-                    tauri::async_runtime::spawn(async move {
-                        let _ = format_clipboard_and_show(handle).await;
-                    });
-                })
-                .map_err(|e| format!("Failed to register shortcut: {}", e))
+        saved[id] = serde_json::Value::String(key.to_string());
+    });
+    if let Err(error) = result {
+        log::warn!("Failed to save {id} shortcut: {error}");
+    }
+}
+
+fn register_shortcut(app: &AppHandle, id: &str, shortcut: Shortcut) -> Result<(), AppError> {
+    let spec = find_action(id)?;
+    let app_handle = app.clone();
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            if event.state != ShortcutState::Pressed {
+                return;
+            }
+            let handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(error) = run_action(handle.clone(), spec.action).await {
+                    report_shortcut_error(&handle, spec, error);
+                }
+            });
+        })
+        .map_err(|e| ErrorCode::RegisterShortcut.reason(e))
+}
+
+async fn run_action(app: AppHandle, action: ShortcutAction) -> Result<(), AppError> {
+    match action {
+        ShortcutAction::ShowApp => show_main_window(app).await,
+        ShortcutAction::FormatClipboard => format_clipboard_and_show(app).await,
+        ShortcutAction::FormatClipboardInPlace => format_clipboard_in_place(app).await,
+        ShortcutAction::ValidateClipboard => validate_clipboard(&app),
+        ShortcutAction::MinifyClipboard => {
+            transform_clipboard(&app, ErrorCode::ClipboardMinified, |text| {
+                minify_json(text, None)
+            })
+        }
+        ShortcutAction::EscapeClipboard => {
+            transform_clipboard(&app, ErrorCode::ClipboardEscaped, |text| {
+                Ok(json_escape(text))
+            })
+        }
+        ShortcutAction::UnescapeClipboard => {
+            transform_clipboard(&app, ErrorCode::ClipboardUnescaped, json_unescape)
+        }
+        ShortcutAction::QuickPeek => {
+            let peek = app.state::<QuickPeek>();
+            Ok(show_quick_peek(app.clone(), peek).await?)
+        }
+        ShortcutAction::CaptureClipboard => {
+            Ok(capture_clipboard_to_scratch(app).await.map(drop)?)
         }
-        _ => Err("Unknown shortcut id".to_string()),
     }
 }
 
+/// Bind a shortcut id to a new accelerator, or disable it when `key` is empty
+///
+/// The accelerator the id is bound to now is unregistered first; if the new
+/// one can't be registered, the old binding is restored. While shortcuts are
+/// disabled the binding is only remembered, to be registered on re-enable.
+/// Either way it is saved in the settings for the next launch.
 #[tauri::command]
 pub async fn update_shortcut(
     app: AppHandle,
     registry: State<'_, GlobalShortcutRegistry>,
     id: String,
     key: String,
-) -> Result<(), String> {
-    let mut keys = registry
-        .keys
-        .lock()
-        .map_err(|_| "Global shortcut registry is unavailable".to_string())?;
-    let old_key = keys
-        .get(&id)
-        .cloned()
-        .ok_or_else(|| "Unknown shortcut id".to_string())?;
-    let shortcuts = app.global_shortcut();
-    if old_key == key && shortcuts.is_registered(key.as_str()) {
-        return Ok(());
-    }
+) -> Result<(), AppError> {
+    async {
+        let binding = match key.trim() {
+            "" => None,
+            key => Some(Binding::parse(key)?),
+        };
+        let mut bindings = lock_bindings(&registry)?;
+        let change = plan_change(&bindings, &id, binding.as_ref().map(|b| b.shortcut))?;
+        if !registry.enabled.load(Ordering::SeqCst) {
+            save_shortcut_setting(&app, &id, key.trim());
+            bindings.insert(id, binding);
+            return Ok(());
+        }
+        let shortcuts = app.global_shortcut();
+
+        if let Some(old) = change.unregister {
+            if shortcuts.is_registered(old) {
+                shortcuts
+                    .unregister(old)
+                    .map_err(|e| ErrorCode::UnregisterShortcut.reason(e))?;
+            }
+        }
+
+        if let Some(new) = change.register.filter(|new| !shortcuts.is_registered(*new)) {
+            if let Err(error) = register_shortcut(&app, &id, new) {
+                let Some(old) = change.unregister else {
+                    return Err(error);
+                };
+                return match register_shortcut(&app, &id, old) {
+                    Ok(()) => Err(error),
+                    Err(rollback_error) => Err(ErrorCode::RestoreShortcut
+                        .reason(error)
+                        .with("rollback", rollback_error.message())),
+                };
+            }
+        }
 
-    if shortcuts.is_registered(old_key.as_str()) {
-        shortcuts
-            .unregister(old_key.as_str())
-            .map_err(|e| format!("Failed to unregister shortcut: {}", e))?;
+        save_shortcut_setting(&app, &id, key.trim());
+        bindings.insert(id, binding);
+        Ok(())
     }
+    .await
+    .logged("update_shortcut")
+}
 
-    if let Err(error) = register_global_shortcut(&app, &id, &key) {
-        return match register_global_shortcut(&app, &id, &old_key) {
-            Ok(()) => Err(error),
-            Err(rollback_error) => Err(format!(
-                "{}; failed to restore previous shortcut: {}",
-                error, rollback_error
-            )),
+/// Turn every global shortcut off or back on, remembered across restarts
+///
+/// Disabling unregisters the accelerators but keeps them bound to their
+/// actions, so enabling again restores the user's keymap as it was.
+#[tauri::command]
+pub fn set_shortcuts_enabled(
+    app: AppHandle,
+    registry: State<'_, GlobalShortcutRegistry>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    toggle_shortcuts(&app, &registry, enabled).logged("set_shortcuts_enabled")
+}
+
+fn toggle_shortcuts(
+    app: &AppHandle,
+    registry: &GlobalShortcutRegistry,
+    enabled: bool,
+) -> Result<(), AppError> {
+    let bindings = lock_bindings(registry)?;
+    save_shortcuts_enabled_preference(app, enabled)?;
+    if registry.enabled.swap(enabled, Ordering::SeqCst) == enabled {
+        return Ok(());
+    }
+    let shortcuts = app.global_shortcut();
+    let mut failed = Vec::new();
+    for (id, binding) in bindings.iter() {
+        let Some(binding) = binding else {
+            continue;
+        };
+        let result = if enabled {
+            register_shortcut(app, id, binding.shortcut)
+        } else if shortcuts.is_registered(binding.shortcut) {
+            shortcuts
+                .unregister(binding.shortcut)
+                .map_err(|e| ErrorCode::UnregisterShortcut.reason(e))
+        } else {
+            Ok(())
         };
+        if let Err(error) = result {
+            failed.push(format!("{} ({})", binding.accelerator, error));
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        failed.sort();
+        Err(ErrorCode::ShortcutsNotChanged.with("shortcuts", failed.join(", ")))
     }
+}
 
-    keys.insert(id, key);
-    Ok(())
+/// Whether global shortcuts are currently enabled
+#[tauri::command]
+pub fn shortcuts_enabled(registry: State<'_, GlobalShortcutRegistry>) -> bool {
+    registry.enabled.load(Ordering::SeqCst)
+}
+
+/// Every bindable action with its current accelerator
+#[tauri::command]
+pub fn list_shortcuts(
+    registry: State<'_, GlobalShortcutRegistry>,
+) -> Result<Vec<ShortcutInfo>, AppError> {
+    Ok(shortcut_list(
+        &*lock_bindings(&registry).logged("list_shortcuts")?,
+    ))
+}
+
+/// Check whether `key` could be bound, before committing it with
+/// `update_shortcut`
+///
+/// `id` is the action being edited; its own current binding isn't reported
+/// as a conflict. Conflicts with other applications are found by briefly
+/// registering the accelerator.
+#[tauri::command]
+pub fn validate_shortcut(
+    app: AppHandle,
+    registry: State<'_, GlobalShortcutRegistry>,
+    key: String,
+    id: Option<String>,
+) -> Result<ShortcutValidation, AppError> {
+    let bindings = lock_bindings(&registry).logged("validate_shortcut")?;
+    let shortcut = match check_shortcut(&bindings, key.trim(), id.as_deref()) {
+        Ok(shortcut) => shortcut,
+        Err(validation) => return Ok(validation),
+    };
+    let shortcuts = app.global_shortcut();
+    if !shortcuts.is_registered(shortcut) {
+        if let Err(error) = shortcuts.register(shortcut) {
+            return Ok(ShortcutValidation::failed(
+                ShortcutStatus::ConflictSystem,
+                ErrorCode::ShortcutInUseElsewhere.reason(error).message(),
+            ));
+        }
+        let _ = shortcuts.unregister(shortcut);
+    }
+    Ok(ShortcutValidation {
+        status: ShortcutStatus::Ok,
+        message: None,
+        conflicting_id: None,
+    })
+}
+
+fn lock_bindings<'a>(
+    registry: &'a GlobalShortcutRegistry,
+) -> Result<std::sync::MutexGuard<'a, Bindings>, AppError> {
+    registry
+        .bindings
+        .lock()
+        .map_err(|_| ErrorCode::ShortcutRegistryUnavailable.into())
+}
+
+/// Bring the editor window focused last to the front
+#[tauri::command]
+pub async fn show_main_window(app: AppHandle) -> Result<(), AppError> {
+    front_window(&app).map(drop).logged("show_main_window")
 }
 
 #[tauri::command]
-pub async fn show_main_window(app: AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        ensure_window_in_front(&window)?;
+pub async fn format_clipboard_and_show(app: AppHandle) -> Result<(), AppError> {
+    async {
+        let clipboard_text = read_clipboard(&app)?;
+
+        // Show window first
+        let window = front_window(&app)?;
+
+        // Held until the page listens when the shortcut started the window.
+        emit_when_ready(
+            &app,
+            Some(&window),
+            "clipboard-content",
+            clipboard_text.clone(),
+        )?;
+        record_clipboard_if_json(&app, clipboard_text);
+
         Ok(())
-    } else {
-        Err("Main window not found".to_string())
     }
+    .await
+    .logged("format_clipboard_and_show")
 }
 
+/// Format the clipboard JSON and write it back without showing the window
 #[tauri::command]
-pub async fn format_clipboard_and_show(app: AppHandle) -> Result<(), String> {
-    // Get clipboard content
+pub async fn format_clipboard_in_place(app: AppHandle) -> Result<(), AppError> {
+    transform_clipboard(&app, ErrorCode::ClipboardFormatted, |text| {
+        let formatted = format_json(text, None, None, None, None, None)?;
+        record_clipboard(&app, text);
+        Ok(formatted)
+    })
+    .logged("format_clipboard_in_place")
+}
+
+/// Validate the clipboard and show the result in a system notification
+fn validate_clipboard(app: &AppHandle) -> Result<(), AppError> {
+    let result = validate_json(&read_clipboard(app)?);
+    notify(app, &validation_message(&result).message());
+    Ok(())
+}
+
+fn validation_message(result: &ValidationResult) -> AppError {
+    if result.valid {
+        return ErrorCode::ClipboardValid.into();
+    }
+    let reason = result.error_message.as_deref().unwrap_or("unknown error");
+    match (result.error_line, result.error_column) {
+        (Some(line), Some(column)) => ErrorCode::ClipboardInvalidAt
+            .reason(reason)
+            .with("line", line)
+            .with("column", column),
+        _ => ErrorCode::ClipboardInvalid.reason(reason),
+    }
+}
+
+/// Log a failed shortcut action and show the failure without raising any
+/// window: in the target editor window when it is open and the action is one
+/// that shows it, as a system notification otherwise.
+fn report_shortcut_error(app: &AppHandle, spec: &ActionSpec, error: AppError) {
+    log::error!(
+        "Shortcut action {} failed: {}",
+        spec.id,
+        error.message_in(Locale::En)
+    );
+    let window =
+        target_window(app).filter(|window| !spec.action.runs_in_background() && is_open(window));
+    let Some(window) = window else {
+        notify(app, &error.message());
+        return;
+    };
+    let result = window.emit_to(
+        window.label(),
+        "shortcut-error",
+        ShortcutError {
+            action: spec.id,
+            code: error.code.as_str(),
+            message: error.message(),
+        },
+    );
+    if let Err(error) = result {
+        log::warn!("Failed to report shortcut error: {error}");
+    }
+}
+
+/// Whether the user can see `window`: shown and not minimized.
+fn is_open(window: &WebviewWindow) -> bool {
+    window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(true)
+}
+
+/// Rewrite the clipboard in place without surfacing the window; success is
+/// reported with the `done` notification, failures are returned to the caller.
+fn transform_clipboard(
+    app: &AppHandle,
+    done: ErrorCode,
+    transform: impl FnOnce(&str) -> Result<String, AppError>,
+) -> Result<(), AppError> {
+    let text = read_clipboard(app)?;
+    let output = transform(&text)?;
+    app.clipboard()
+        .write_text(output)
+        .map_err(|e| ErrorCode::WriteClipboard.reason(e))?;
+    notify(app, &done.with("size", format_size(text.len())).message());
+    Ok(())
+}
+
+/// Show a system notification, for clipboard actions that run while the
+/// window may be hidden. Failures are only logged.
+pub(crate) fn notify(app: &AppHandle, message: &str) {
+    let result = app
+        .notification()
+        .builder()
+        .title("JsonStudio")
+        .body(message)
+        .show();
+    if let Err(error) = result {
+        log::warn!("Failed to show notification: {error}");
+    }
+}
+
+fn format_size(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    match bytes as f64 {
+        size if size < KB => format!("{bytes} B"),
+        size if size < KB * KB => format!("{:.1} KB", size / KB),
+        size => format!("{:.1} MB", size / (KB * KB)),
+    }
+}
+
+pub(crate) fn read_clipboard(app: &AppHandle) -> Result<String, AppError> {
     let clipboard_text = app
         .clipboard()
         .read_text()
-        .map_err(|e| format!("Failed to read clipboard: {}", e))?;
+        .map_err(|e| ErrorCode::ReadClipboard.reason(e))?;
 
     if clipboard_text.is_empty() {
-        return Err("Clipboard is empty".to_string());
+        return Err(ErrorCode::ClipboardEmpty.into());
     }
+    Ok(clipboard_text)
+}
 
-    // Show window first
-    let window = app
-        .get_webview_window("main")
-        .ok_or("Main window not found".to_string())?;
-
+fn front_window(app: &AppHandle) -> Result<WebviewWindow, AppError> {
+    let window = target_window(app).ok_or(ErrorCode::MainWindowNotFound)?;
     ensure_window_in_front(&window)?;
-
-    window
-        .emit("clipboard-content", clipboard_text)
-        .map_err(|e| e.to_string())?;
-
-    Ok(())
+    Ok(window)
 }
 
 fn ensure_window_in_front(window: &WebviewWindow) -> Result<(), String> {
@@ -161,14 +741,188 @@ fn ensure_window_in_front(window: &WebviewWindow) -> Result<(), String> {
         elevated = true;
     }
 
-    if elevated {
+    // A pinned window already stays on top; raising it must not unpin it.
+    if elevated && !is_pinned(window) {
         window.set_always_on_top(true).map_err(|e| e.to_string())?;
         let window_clone = window.clone();
         tauri::async_runtime::spawn(async move {
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            let _ = window_clone.set_always_on_top(false);
+            // Restore the user's choice, which may have changed meanwhile.
+            let _ = window_clone.set_always_on_top(is_pinned(&window_clone));
         });
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        apply_saved_bindings, check_shortcut, find_action, format_size, plan_change, shortcut_list,
+        validation_message, Binding, Bindings, GlobalShortcutRegistry, ShortcutChange,
+        ShortcutStatus, FORMAT_CLIPBOARD_SHORTCUT_ID, SHORTCUT_ACTIONS, SHOW_APP_SHORTCUT_ID,
+    };
+    use crate::commands::json::validate_json;
+    use crate::error::{ErrorCode, Locale};
+    use serde_json::json;
+    use std::collections::{HashMap, HashSet};
+    use tauri_plugin_global_shortcut::Shortcut;
+
+    fn default_bindings() -> Bindings {
+        GlobalShortcutRegistry::default()
+            .bindings
+            .into_inner()
+            .unwrap()
+    }
+
+    fn key(accelerator: &str) -> Option<Shortcut> {
+        Some(accelerator.parse().unwrap())
+    }
+
+    fn bound(bindings: &Bindings, id: &str) -> Option<Shortcut> {
+        bindings[id].as_ref().map(|binding| binding.shortcut)
+    }
+
+    /// Plan and apply a change the way `update_shortcut` does.
+    fn update(bindings: &mut Bindings, id: &str, accelerator: Option<&str>) -> ShortcutChange {
+        let binding = accelerator.map(|accelerator| Binding::parse(accelerator).unwrap());
+        let change = plan_change(bindings, id, binding.as_ref().map(|b| b.shortcut)).unwrap();
+        bindings.insert(id.to_string(), binding);
+        change
+    }
+
+    #[test]
+    fn unregisters_the_current_binding_on_every_change() {
+        let mut bindings = default_bindings();
+        let default = bound(&bindings, SHOW_APP_SHORTCUT_ID);
+
+        let first = update(&mut bindings, SHOW_APP_SHORTCUT_ID, Some("Alt+J"));
+        assert_eq!((first.unregister, first.register), (default, key("Alt+J")));
+
+        let second = update(&mut bindings, SHOW_APP_SHORTCUT_ID, Some("Alt+K"));
+        assert_eq!(
+            (second.unregister, second.register),
+            (key("Alt+J"), key("Alt+K"))
+        );
+
+        let disabled = update(&mut bindings, SHOW_APP_SHORTCUT_ID, None);
+        assert_eq!(
+            (disabled.unregister, disabled.register),
+            (key("Alt+K"), None)
+        );
+
+        let again = update(&mut bindings, SHOW_APP_SHORTCUT_ID, Some("Alt+K"));
+        assert_eq!((again.unregister, again.register), (None, key("Alt+K")));
+    }
+
+    #[test]
+    fn saved_bindings_replace_the_defaults() {
+        let mut bindings = default_bindings();
+        let saved = HashMap::from([
+            (SHOW_APP_SHORTCUT_ID.to_string(), "Alt+J".to_string()),
+            (FORMAT_CLIPBOARD_SHORTCUT_ID.to_string(), String::new()),
+            ("quick_peek".to_string(), "Not+A+Key".to_string()),
+            ("removed_action".to_string(), "Alt+R".to_string()),
+        ]);
+        apply_saved_bindings(&mut bindings, saved);
+        assert_eq!(bound(&bindings, SHOW_APP_SHORTCUT_ID), key("Alt+J"));
+        assert_eq!(bound(&bindings, FORMAT_CLIPBOARD_SHORTCUT_ID), None);
+        assert_eq!(bound(&bindings, "quick_peek"), None);
+        assert!(!bindings.contains_key("removed_action"));
+    }
+
+    #[test]
+    fn rejects_accelerators_bound_to_the_other_action() {
+        let bindings = default_bindings();
+        let taken = bound(&bindings, FORMAT_CLIPBOARD_SHORTCUT_ID);
+
+        let error = plan_change(&bindings, SHOW_APP_SHORTCUT_ID, taken).unwrap_err();
+        assert_eq!(error.code, ErrorCode::ShortcutInUse);
+        assert_eq!(error.param("other"), Some(&json!("format_clipboard")));
+        // Re-binding an id to its own accelerator only re-registers it.
+        assert_eq!(
+            plan_change(&bindings, FORMAT_CLIPBOARD_SHORTCUT_ID, taken).unwrap(),
+            ShortcutChange {
+                unregister: None,
+                register: taken,
+            }
+        );
+        assert!(plan_change(&bindings, "unknown", None).is_err());
+    }
+
+    #[test]
+    fn lists_and_checks_shortcuts() {
+        let mut bindings = default_bindings();
+        update(&mut bindings, SHOW_APP_SHORTCUT_ID, None);
+        let listed: Vec<_> = shortcut_list(&bindings)
+            .into_iter()
+            .map(|info| (info.id, info.accelerator, info.enabled))
+            .collect();
+        assert_eq!(
+            listed[..3],
+            [
+                (SHOW_APP_SHORTCUT_ID.to_string(), String::new(), false),
+                (
+                    FORMAT_CLIPBOARD_SHORTCUT_ID.to_string(),
+                    "CommandOrControl+Shift+V".to_string(),
+                    true
+                ),
+                (
+                    "format_clipboard_in_place".to_string(),
+                    String::new(),
+                    false
+                ),
+            ]
+        );
+
+        let status = |key: &str, id: Option<&str>| {
+            check_shortcut(&bindings, key, id).map_err(|validation| validation.status)
+        };
+        assert_eq!(status("Ctrl+Nope", None), Err(ShortcutStatus::ParseError));
+        assert_eq!(
+            status("CmdOrCtrl+Shift+V", None),
+            Err(ShortcutStatus::ConflictInternal)
+        );
+        assert!(status("CmdOrCtrl+Shift+V", Some(FORMAT_CLIPBOARD_SHORTCUT_ID)).is_ok());
+        assert!(status("Alt+Shift+J", None).is_ok());
+    }
+
+    #[test]
+    fn default_keymap_parses_and_ids_are_unique() {
+        for spec in SHORTCUT_ACTIONS {
+            assert_eq!(find_action(spec.id).unwrap().action, spec.action);
+            if let Some(key) = spec.default_key {
+                assert!(Binding::parse(key).is_ok(), "{key}");
+            }
+        }
+        let ids: HashSet<_> = SHORTCUT_ACTIONS.iter().map(|spec| spec.id).collect();
+        assert_eq!(ids.len(), SHORTCUT_ACTIONS.len());
+    }
+
+    #[test]
+    fn formats_sizes_for_clipboard_messages() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(4300), "4.2 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+        let done = ErrorCode::ClipboardMinified.with("size", format_size(4300));
+        assert_eq!(done.message_in(Locale::En), "Minified 4.2 KB of JSON");
+        assert_eq!(done.message_in(Locale::ZhCn), "已压缩 4.2 KB 的 JSON");
+    }
+
+    #[test]
+    fn describes_clipboard_validation_results() {
+        assert_eq!(
+            validation_message(&validate_json("[1]")).message_in(Locale::En),
+            "Clipboard contains valid JSON"
+        );
+        let invalid = validation_message(&validate_json("[1,"));
+        assert_eq!(invalid.code, ErrorCode::ClipboardInvalidAt);
+        assert_eq!(invalid.param("line"), Some(&1.into()));
+        assert!(invalid
+            .message_in(Locale::En)
+            .starts_with("Invalid JSON in clipboard at line 1, column "));
+        assert!(invalid
+            .message_in(Locale::ZhCn)
+            .starts_with("剪贴板中的 JSON 在第 1 行第 "));
+    }
+}
@@ -0,0 +1,250 @@
+// Source map command
+//
+// Formats a document exactly like `json_format` and records where every node
+// lands in the output, so the tree view can scroll the editor to a node.
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use super::json::parse_to_value;
+use crate::json_pointer::{child_pointer, index_pointer};
+use crate::json_writer::NumberLiterals;
+
+/// 1-based position; columns count UTF-16 code units like Monaco does.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SourceMapEntry {
+    pub pointer: String,
+    /// Position of the first character of the value.
+    pub start: Position,
+    /// Position just past the last character of the value.
+    pub end: Position,
+    /// Start and end of the quoted key, for object members.
+    pub key_start: Option<Position>,
+    pub key_end: Option<Position>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SourceMapResult {
+    /// Same text as `json_format` with the same indent.
+    pub content: String,
+    /// Entries in document order.
+    pub entries: Vec<SourceMapEntry>,
+}
+
+/// Format JSON and map each node's pointer to its position in the output
+///
+/// `indent` defaults to 2; 0 produces minified output. To keep the payload
+/// small, `max_depth` limits the map to nodes at most that many levels below
+/// the root, and `pointers` limits it to the listed nodes.
+#[tauri::command]
+pub fn json_source_map(
+    content: &str,
+    indent: Option<usize>,
+    max_depth: Option<usize>,
+    pointers: Option<Vec<String>>,
+) -> Result<SourceMapResult, String> {
+    let value = parse_to_value(content)?;
+    let mut writer = SourceMapWriter {
+        indent_unit: " ".repeat(indent.unwrap_or(2)),
+        literals: NumberLiterals::from_source(content),
+        max_depth,
+        pointers: pointers.map(|pointers| pointers.into_iter().collect()),
+        out: String::new(),
+        line: 1,
+        column: 1,
+        entries: Vec::new(),
+    };
+    writer.write_value(&value, "", 0, None);
+    Ok(SourceMapResult {
+        content: writer.out,
+        entries: writer.entries,
+    })
+}
+
+struct SourceMapWriter<'a> {
+    indent_unit: String,
+    literals: NumberLiterals<'a>,
+    max_depth: Option<usize>,
+    pointers: Option<HashSet<String>>,
+    out: String,
+    line: usize,
+    column: usize,
+    entries: Vec<SourceMapEntry>,
+}
+
+impl SourceMapWriter<'_> {
+    fn push(&mut self, text: &str) {
+        for c in text.chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += c.len_utf16();
+            }
+        }
+        self.out.push_str(text);
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    /// Start a new line at `depth`; minified output stays on one line.
+    fn newline(&mut self, depth: usize) {
+        if !self.indent_unit.is_empty() {
+            let text = format!("\n{}", self.indent_unit.repeat(depth));
+            self.push(&text);
+        }
+    }
+
+    fn is_mapped(&self, pointer: &str, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth <= max)
+            && self
+                .pointers
+                .as_ref()
+                .is_none_or(|pointers| pointers.contains(pointer))
+    }
+
+    fn write_value(
+        &mut self,
+        value: &Value,
+        pointer: &str,
+        depth: usize,
+        key: Option<(Position, Position)>,
+    ) {
+        // Reserve the entry so parents come before their children.
+        let entry = self.is_mapped(pointer, depth).then(|| {
+            let start = self.position();
+            self.entries.push(SourceMapEntry {
+                pointer: pointer.to_string(),
+                start,
+                end: start,
+                key_start: key.map(|(start, _)| start),
+                key_end: key.map(|(_, end)| end),
+            });
+            self.entries.len() - 1
+        });
+
+        match value {
+            Value::Array(items) if !items.is_empty() => {
+                self.push("[");
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        self.push(",");
+                    }
+                    self.newline(depth + 1);
+                    self.write_value(item, &index_pointer(pointer, index), depth + 1, None);
+                }
+                self.newline(depth);
+                self.push("]");
+            }
+            Value::Object(map) if !map.is_empty() => {
+                self.push("{");
+                for (index, (name, child)) in map.iter().enumerate() {
+                    if index > 0 {
+                        self.push(",");
+                    }
+                    self.newline(depth + 1);
+                    let key_start = self.position();
+                    self.push(&serde_json::to_string(name).unwrap_or_default());
+                    let key_end = self.position();
+                    self.push(if self.indent_unit.is_empty() {
+                        ":"
+                    } else {
+                        ": "
+                    });
+                    let child_pointer = child_pointer(pointer, name);
+                    self.write_value(child, &child_pointer, depth + 1, Some((key_start, key_end)));
+                }
+                self.newline(depth);
+                self.push("}");
+            }
+            Value::Number(number) => {
+                let spelled = self.literals.spell(&number.to_string());
+                self.push(&spelled);
+            }
+            scalar => self.push(&scalar.to_string()),
+        }
+
+        if let Some(index) = entry {
+            self.entries[index].end = self.position();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_source_map, Position};
+    use crate::commands::json::format_json;
+
+    fn at(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+
+    #[test]
+    fn matches_json_format_and_maps_every_node() {
+        let content = r#"{"name": "Zoë 😀", "tags": ["a", {}], "n": 1E3}"#;
+
+        let map = json_source_map(content, None, None, None).unwrap();
+
+        assert_eq!(
+            map.content,
+            format_json(content, None, None, None, None, None).unwrap()
+        );
+        let pointers: Vec<_> = map
+            .entries
+            .iter()
+            .map(|entry| entry.pointer.as_str())
+            .collect();
+        assert_eq!(pointers, ["", "/name", "/tags", "/tags/0", "/tags/1", "/n"]);
+
+        let root = &map.entries[0];
+        assert_eq!((root.start, root.end), (at(1, 1), at(8, 2)));
+        let name = &map.entries[1];
+        assert_eq!(
+            (name.key_start, name.key_end),
+            (Some(at(2, 3)), Some(at(2, 9)))
+        );
+        // The emoji is two UTF-16 code units.
+        assert_eq!((name.start, name.end), (at(2, 11), at(2, 19)));
+        let tags_1 = &map.entries[4];
+        assert_eq!(
+            (tags_1.start, tags_1.end, tags_1.key_start),
+            (at(5, 5), at(5, 7), None)
+        );
+        let n = &map.entries[5];
+        assert_eq!((n.start, n.end), (at(7, 8), at(7, 11)));
+    }
+
+    #[test]
+    fn limits_the_map_by_depth_or_pointer_list() {
+        let content = r#"{"a": {"b": [1, 2]}, "c": 3}"#;
+
+        let shallow = json_source_map(content, Some(0), Some(1), None).unwrap();
+        assert_eq!(shallow.content, r#"{"a":{"b":[1,2]},"c":3}"#);
+        let pointers: Vec<_> = shallow
+            .entries
+            .iter()
+            .map(|entry| entry.pointer.as_str())
+            .collect();
+        assert_eq!(pointers, ["", "/a", "/c"]);
+        assert_eq!(
+            (shallow.entries[2].start, shallow.entries[2].end),
+            (at(1, 22), at(1, 23))
+        );
+
+        let picked =
+            json_source_map(content, None, None, Some(vec!["/a/b/1".to_string()])).unwrap();
+        assert_eq!(picked.entries.len(), 1);
+        assert_eq!(picked.entries[0].start, at(5, 7));
+    }
+}
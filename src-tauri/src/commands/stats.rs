@@ -0,0 +1,491 @@
+// Document statistics command
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use serde_json::ser::CompactFormatter;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+use tauri::State;
+
+use crate::commands::document::DocumentStore;
+use crate::commands::json::{parse_to_value, validate_json, ValidationResult};
+use crate::commands::parse_cache::ParseCache;
+use crate::json_pointer::escape_token;
+use crate::json_writer::{quoted_len, serialized_len, NumberLiterals, RawNumbers, NUMBER_TOKEN};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JsonStats {
+    pub valid: bool,
+    /// Object members across the whole document.
+    pub key_count: usize,
+    /// Deepest container nesting; a scalar document has depth 0.
+    pub depth: usize,
+    pub byte_size: usize,
+    /// Lines of the raw text, counting a trailing empty line like editors do.
+    pub line_count: usize,
+    /// Unicode scalar values in the raw text.
+    pub char_count: usize,
+    /// Size of the `json_minify` output; absent for invalid documents.
+    pub minified_byte_size: Option<usize>,
+    /// `"JSON"` or `"JSON5"`, empty for invalid documents.
+    pub format_type: String,
+    pub error_info: Option<ValidationResult>,
+    /// Shape of the document; absent for invalid documents.
+    pub breakdown: Option<StatsBreakdown>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct StatsBreakdown {
+    pub objects: usize,
+    pub arrays: usize,
+    pub strings: usize,
+    pub numbers: usize,
+    pub booleans: usize,
+    pub nulls: usize,
+    /// Elements across all arrays.
+    pub array_elements: usize,
+    pub longest_array: Option<PointerMetric>,
+    /// Measured in characters.
+    pub longest_string: Option<PointerMetric>,
+    /// First node (in document order) at the greatest nesting level.
+    pub deepest_pointer: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct PointerMetric {
+    pub pointer: String,
+    pub length: usize,
+}
+
+/// Compute statistics for a JSON or JSON5 document
+#[tauri::command]
+pub fn json_stats(cache: State<'_, ParseCache>, content: &str) -> JsonStats {
+    cache.stats(content)
+}
+
+/// `json_stats` without the cache
+pub(crate) fn collect_stats(content: &str) -> JsonStats {
+    if let Some(stats) = streamed_stats(content) {
+        return stats;
+    }
+    let (value, format_type) = match serde_json::from_str::<Value>(content) {
+        Ok(value) => (value, "JSON"),
+        Err(_) => match parse_to_value(content) {
+            Ok(value) => (value, "JSON5"),
+            Err(_) => {
+                return JsonStats {
+                    valid: false,
+                    key_count: 0,
+                    depth: 0,
+                    byte_size: content.len(),
+                    line_count: content.matches('\n').count() + 1,
+                    char_count: content.chars().count(),
+                    minified_byte_size: None,
+                    format_type: String::new(),
+                    error_info: Some(validate_json(content)),
+                    breakdown: None,
+                }
+            }
+        },
+    };
+
+    value_stats(content, &value, format_type)
+}
+
+/// `json_stats` for a document opened with `open_document`
+#[tauri::command]
+pub fn json_stats_document(
+    doc_id: String,
+    store: State<'_, DocumentStore>,
+) -> Result<JsonStats, String> {
+    store.read(&doc_id, |document| {
+        let format_type = if document.strict { "JSON" } else { "JSON5" };
+        value_stats(&document.content, &document.value, format_type)
+    })
+}
+
+fn value_stats(content: &str, value: &Value, format_type: &str) -> JsonStats {
+    let mut walker = StatsWalker::default();
+    walker.visit(value);
+    let minified = serialized_len(value, RawNumbers::new(CompactFormatter, content));
+    valid_stats(content, walker, minified, format_type)
+}
+
+/// Statistics of strict JSON gathered while it is parsed, without building a
+/// `Value`: besides the current path, only the keys of the open objects are
+/// kept.
+///
+/// `None` when `content` isn't strict JSON, or when an object repeats a key:
+/// the `Value` keeps only the last one, so its figures differ.
+fn streamed_stats(content: &str) -> Option<JsonStats> {
+    let mut stream = StatsStream {
+        walker: StatsWalker::default(),
+        literals: NumberLiterals::from_source(content),
+        minified: 0,
+    };
+    let mut deserializer = serde_json::Deserializer::from_str(content);
+    StatsVisitor(&mut stream)
+        .deserialize(&mut deserializer)
+        .ok()?;
+    deserializer.end().ok()?;
+    Some(valid_stats(content, stream.walker, stream.minified, "JSON"))
+}
+
+fn valid_stats(
+    content: &str,
+    walker: StatsWalker,
+    minified: usize,
+    format_type: &str,
+) -> JsonStats {
+    JsonStats {
+        valid: true,
+        key_count: walker.key_count,
+        depth: walker.depth,
+        byte_size: content.len(),
+        line_count: content.matches('\n').count() + 1,
+        char_count: content.chars().count(),
+        minified_byte_size: Some(minified),
+        format_type: format_type.to_string(),
+        error_info: None,
+        breakdown: Some(walker.breakdown),
+    }
+}
+
+/// Collects every statistic in one walk, from a `Value` or from the parser.
+/// Pointers are only built when a new maximum is found, from the escaped
+/// tokens of the current path.
+#[derive(Default)]
+struct StatsWalker {
+    key_count: usize,
+    depth: usize,
+    breakdown: StatsBreakdown,
+    path: Vec<String>,
+    deepest_level: usize,
+    /// Nodes started so far.
+    nodes: usize,
+    /// Where the longest array started, counted in nodes.
+    longest_array_start: usize,
+}
+
+impl StatsWalker {
+    fn pointer(&self) -> String {
+        self.path.iter().map(|token| format!("/{token}")).collect()
+    }
+
+    /// A node starts at the current path; returns its place in document order.
+    fn begin_node(&mut self) -> usize {
+        self.nodes += 1;
+        if self.path.len() > self.deepest_level {
+            self.deepest_level = self.path.len();
+            self.breakdown.deepest_pointer = self.pointer();
+        }
+        self.nodes
+    }
+
+    fn string(&mut self, text: &str) {
+        self.breakdown.strings += 1;
+        let length = text.chars().count();
+        if is_longer(&self.breakdown.longest_string, length) {
+            self.breakdown.longest_string = Some(PointerMetric {
+                pointer: self.pointer(),
+                length,
+            });
+        }
+    }
+
+    fn enter_container(&mut self) {
+        self.depth = self.depth.max(self.path.len() + 1);
+    }
+
+    /// The array that started as node `start` ends with `length` elements.
+    /// Of equally long arrays the one that starts first wins, though inner
+    /// arrays end before the outer ones.
+    fn end_array(&mut self, start: usize, length: usize) {
+        self.breakdown.arrays += 1;
+        self.breakdown.array_elements += length;
+        let longest = match &self.breakdown.longest_array {
+            Some(metric) if metric.length == length => start < self.longest_array_start,
+            current => is_longer(current, length),
+        };
+        if longest {
+            self.breakdown.longest_array = Some(PointerMetric {
+                pointer: self.pointer(),
+                length,
+            });
+            self.longest_array_start = start;
+        }
+    }
+
+    fn end_object(&mut self, length: usize) {
+        self.breakdown.objects += 1;
+        self.key_count += length;
+    }
+
+    fn visit(&mut self, value: &Value) {
+        let start = self.begin_node();
+        match value {
+            Value::Null => self.breakdown.nulls += 1,
+            Value::Bool(_) => self.breakdown.booleans += 1,
+            Value::Number(_) => self.breakdown.numbers += 1,
+            Value::String(text) => self.string(text),
+            Value::Array(items) => {
+                self.enter_container();
+                for (index, item) in items.iter().enumerate() {
+                    self.path.push(index.to_string());
+                    self.visit(item);
+                    self.path.pop();
+                }
+                self.end_array(start, items.len());
+            }
+            Value::Object(map) => {
+                self.enter_container();
+                for (key, child) in map {
+                    self.path.push(escape_token(key));
+                    self.visit(child);
+                    self.path.pop();
+                }
+                self.end_object(map.len());
+            }
+        }
+    }
+}
+
+fn is_longer(current: &Option<PointerMetric>, length: usize) -> bool {
+    current.as_ref().is_none_or(|metric| length > metric.length)
+}
+
+struct StatsStream<'a> {
+    walker: StatsWalker,
+    /// Source spellings of numbers, which the minified size counts.
+    literals: NumberLiterals<'a>,
+    /// Bytes of the `json_minify` output so far.
+    minified: usize,
+}
+
+/// Feeds each value to the walker as the parser reads it.
+struct StatsVisitor<'s, 'a>(&'s mut StatsStream<'a>);
+
+impl<'a> StatsVisitor<'_, 'a> {
+    fn reborrow(&mut self) -> StatsVisitor<'_, 'a> {
+        StatsVisitor(&mut *self.0)
+    }
+
+    fn number(self, text: &str) {
+        self.0.walker.breakdown.numbers += 1;
+        self.0.minified += self.0.literals.spell(text).len();
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for StatsVisitor<'_, '_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        self.0.walker.begin_node();
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for StatsVisitor<'_, '_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        self.0.walker.breakdown.nulls += 1;
+        self.0.minified += "null".len();
+        Ok(())
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<(), E> {
+        self.0.walker.breakdown.booleans += 1;
+        self.0.minified += if value { "true".len() } else { "false".len() };
+        Ok(())
+    }
+
+    // Numbers that fit 64 bits come as integers, the others as a map under
+    // `NUMBER_TOKEN`.
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<(), E> {
+        self.number(&value.to_string());
+        Ok(())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<(), E> {
+        self.number(&value.to_string());
+        Ok(())
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<(), E> {
+        self.0.walker.string(value);
+        self.0.minified += quoted_len(value);
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<(), A::Error> {
+        // `begin_node` already counted this array.
+        let start = self.0.walker.nodes;
+        self.0.walker.enter_container();
+        let mut length: usize = 0;
+        loop {
+            self.0.walker.path.push(length.to_string());
+            let more = seq.next_element_seed(self.reborrow())?.is_some();
+            self.0.walker.path.pop();
+            if !more {
+                break;
+            }
+            length += 1;
+        }
+        // Brackets and commas
+        self.0.minified += 2 + length.saturating_sub(1);
+        self.0.walker.end_array(start, length);
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        let mut next = map.next_key::<String>()?;
+        if next.as_deref() == Some(NUMBER_TOKEN) {
+            let number: String = map.next_value()?;
+            self.number(&number);
+            return Ok(());
+        }
+        self.0.walker.enter_container();
+        let mut seen = HashSet::new();
+        while let Some(key) = next {
+            self.0.walker.path.push(escape_token(&key));
+            // The key and its colon
+            self.0.minified += quoted_len(&key) + 1;
+            if !seen.insert(key) {
+                return Err(de::Error::custom("duplicate key"));
+            }
+            map.next_value_seed(self.reborrow())?;
+            self.0.walker.path.pop();
+            next = map.next_key()?;
+        }
+        // Braces and commas
+        self.0.minified += 2 + seen.len().saturating_sub(1);
+        self.0.walker.end_object(seen.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_stats, streamed_stats, value_stats, PointerMetric};
+    use serde_json::Value;
+
+    fn stats_from_value(content: &str) -> Value {
+        let value: Value = serde_json::from_str(content).unwrap();
+        serde_json::to_value(value_stats(content, &value, "JSON")).unwrap()
+    }
+
+    #[test]
+    fn counts_value_types_and_container_metrics() {
+        let stats = collect_stats(
+            r#"{"users": [{"name": "Ada", "tags": ["x", "y", "z"]}, {"name": null, "bio": "longest text"}], "ok": true, "a/b": [1, 2.5]}"#,
+        );
+
+        assert!(stats.valid);
+        assert_eq!(stats.format_type, "JSON");
+        assert_eq!(stats.key_count, 7);
+        assert_eq!(stats.depth, 4);
+        let breakdown = stats.breakdown.unwrap();
+        assert_eq!(
+            (
+                breakdown.objects,
+                breakdown.arrays,
+                breakdown.strings,
+                breakdown.numbers,
+                breakdown.booleans,
+                breakdown.nulls,
+            ),
+            (3, 3, 5, 2, 1, 1)
+        );
+        assert_eq!(breakdown.array_elements, 7);
+        assert_eq!(
+            breakdown.longest_array,
+            Some(PointerMetric {
+                pointer: "/users/0/tags".to_string(),
+                length: 3
+            })
+        );
+        assert_eq!(
+            breakdown.longest_string,
+            Some(PointerMetric {
+                pointer: "/users/1/bio".to_string(),
+                length: 12
+            })
+        );
+        assert_eq!(breakdown.deepest_pointer, "/users/0/tags/0");
+    }
+
+    #[test]
+    fn reports_json5_and_invalid_documents() {
+        let json5 = collect_stats("{a: 'é', b: [],}");
+        assert_eq!(json5.format_type, "JSON5");
+        assert_eq!(json5.byte_size, 17);
+        let breakdown = json5.breakdown.unwrap();
+        assert_eq!(breakdown.longest_string.unwrap().length, 1);
+        assert_eq!(breakdown.longest_array.unwrap().pointer, "/b");
+
+        let scalar = collect_stats("42");
+        assert_eq!(scalar.depth, 0);
+        assert_eq!(scalar.breakdown.unwrap().deepest_pointer, "");
+
+        let invalid = collect_stats(r#"{"a": }"#);
+        assert!(!invalid.valid);
+        assert!(invalid.breakdown.is_none());
+        assert_eq!(invalid.error_info.unwrap().error_line, Some(1));
+    }
+
+    #[test]
+    fn counts_lines_chars_and_minified_size() {
+        let content = "{\n  \"name\": \"Zoë\",\n  \"n\": 1E3\n}\n";
+
+        let stats = collect_stats(content);
+
+        assert_eq!(stats.line_count, 5);
+        assert_eq!(stats.char_count, content.len() - 1);
+        assert_eq!(
+            stats.minified_byte_size,
+            Some(r#"{"name":"Zoë","n":1E3}"#.len())
+        );
+
+        let invalid = collect_stats("[1,\n 2,\n é");
+        assert_eq!((invalid.line_count, invalid.char_count), (3, 10));
+        assert_eq!(invalid.minified_byte_size, None);
+    }
+
+    #[test]
+    fn streamed_stats_match_the_value_walk() {
+        let corpus = [
+            "0",
+            r#""""#,
+            "[]",
+            "{}",
+            r#"[[1, 2], [3, 4], {"k": [5, 6]}]"#,
+            r#"[[], [[]], [[[]]], {"a": {"b": {"c": null}}}]"#,
+            r#"{"a/b~c": ["x\"y", "é\n", "😀"], "": [true, false]}"#,
+            r#"[1E3, -0, 0.10, 1e-7, 123456789012345678901234567890, -9223372036854775808]"#,
+            r#"{"z": {"y": [[1, 2, 3], [4, 5, 6, 7]], "x": "\u0001\t"}, "w": [1, 2, 3, 4]}"#,
+            " {\n  \"spaced\" : [ 1 , 2 ] \n} \n",
+        ];
+        for content in corpus {
+            let streamed = streamed_stats(content).unwrap();
+            assert_eq!(
+                serde_json::to_value(streamed).unwrap(),
+                stats_from_value(content),
+                "{content}"
+            );
+        }
+
+        // The `Value` keeps the last of repeated keys; only it can count those.
+        let repeated = r#"{"a": [1, 2, 3], "a": "x"}"#;
+        assert!(streamed_stats(repeated).is_none());
+        assert_eq!(
+            serde_json::to_value(collect_stats(repeated)).unwrap(),
+            stats_from_value(repeated)
+        );
+        assert!(streamed_stats("{a: 1}").is_none());
+    }
+}
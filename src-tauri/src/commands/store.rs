@@ -0,0 +1,380 @@
+// Persistence commands
+//
+// Small JSON files under the app config directory for state that outlives a
+// session, starting with the recent files list.
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+const RECENT_FILES_STORE: &str = "recent_files.json";
+const MAX_RECENT_FILES: usize = 20;
+const LAST_DIRECTORY_STORE: &str = "last_directory.json";
+const SHORTCUTS_STORE: &str = "shortcuts.json";
+const WINDOW_THEME_STORE: &str = "window_theme.json";
+const ZOOM_STORE: &str = "zoom.json";
+const ADVANCED_STORE: &str = "advanced.json";
+const MINI_MODE_STORE: &str = "mini_mode.json";
+
+/// Serializes read-modify-write cycles on the store files.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+pub(crate) fn store_path(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(name))
+        .map_err(|e| format!("Failed to resolve config directory: {}", e))
+}
+
+/// A missing or unreadable store starts out empty rather than failing.
+pub(crate) fn load_store<T: DeserializeOwned + Default>(path: &Path) -> T {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Write through a temp file so a crash never leaves a truncated store.
+pub(crate) fn save_store<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content =
+        serde_json::to_string_pretty(value).map_err(|e| format!("JSON formatting error: {}", e))?;
+    write_atomic(path, content.as_bytes()).map_err(|e| format!("Failed to save store: {}", e))
+}
+
+/// Write to a sibling temp file and rename it into place.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    std::fs::write(&temp, bytes)?;
+    std::fs::rename(&temp, path)
+}
+
+/// Load a store, change it and write it back, holding the store lock throughout.
+pub(crate) fn update_store<T, R>(
+    app: &AppHandle,
+    name: &str,
+    change: impl FnOnce(&mut T) -> R,
+) -> Result<R, String>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    let path = store_path(app, name)?;
+    let _guard = STORE_LOCK.lock().unwrap();
+    let mut value = load_store(&path);
+    let result = change(&mut value);
+    save_store(&path, &value)?;
+    Ok(result)
+}
+
+/// Directory of the last file dialog, if it still exists.
+pub(crate) fn last_directory(app: &AppHandle) -> Option<PathBuf> {
+    let path = store_path(app, LAST_DIRECTORY_STORE).ok()?;
+    let dir: Option<PathBuf> = {
+        let _guard = STORE_LOCK.lock().unwrap();
+        load_store(&path)
+    };
+    dir.filter(|dir| dir.is_dir())
+}
+
+/// Remember `dir` as the starting point of the next file dialog. Like
+/// `record_recent_file`, failures are only logged.
+pub(crate) fn remember_directory(app: &AppHandle, dir: &Path) {
+    if let Err(error) = update_store(app, LAST_DIRECTORY_STORE, |last: &mut Option<PathBuf>| {
+        *last = Some(dir.to_path_buf())
+    }) {
+        log::warn!("Failed to remember directory: {error}");
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ShortcutPreferences {
+    /// Stored inverted so a missing store means enabled.
+    disabled: bool,
+}
+
+/// Whether global shortcuts were left enabled in the last session.
+pub(crate) fn shortcuts_enabled_preference(app: &AppHandle) -> bool {
+    let Ok(path) = store_path(app, SHORTCUTS_STORE) else {
+        return true;
+    };
+    let _guard = STORE_LOCK.lock().unwrap();
+    !load_store::<ShortcutPreferences>(&path).disabled
+}
+
+pub(crate) fn save_shortcuts_enabled_preference(
+    app: &AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    update_store(
+        app,
+        SHORTCUTS_STORE,
+        |preferences: &mut ShortcutPreferences| preferences.disabled = !enabled,
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct WindowThemePreference {
+    dark: Option<bool>,
+}
+
+/// The native theme last applied from the frontend, so new windows can match
+/// it before the page loads. `None` until the frontend has set one, or while
+/// it follows the system theme.
+pub(crate) fn window_theme_preference(app: &AppHandle) -> Option<bool> {
+    let path = store_path(app, WINDOW_THEME_STORE).ok()?;
+    let _guard = STORE_LOCK.lock().unwrap();
+    load_store::<WindowThemePreference>(&path).dark
+}
+
+pub(crate) fn save_window_theme_preference(
+    app: &AppHandle,
+    is_dark: Option<bool>,
+) -> Result<(), String> {
+    update_store(
+        app,
+        WINDOW_THEME_STORE,
+        |preference: &mut WindowThemePreference| preference.dark = is_dark,
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ZoomPreference {
+    factor: Option<f64>,
+}
+
+/// The webview zoom factor last chosen, `None` for the default 100%.
+pub(crate) fn zoom_preference(app: &AppHandle) -> Option<f64> {
+    let path = store_path(app, ZOOM_STORE).ok()?;
+    let _guard = STORE_LOCK.lock().unwrap();
+    load_store::<ZoomPreference>(&path).factor
+}
+
+pub(crate) fn save_zoom_preference(app: &AppHandle, factor: f64) -> Result<(), String> {
+    update_store(app, ZOOM_STORE, |preference: &mut ZoomPreference| {
+        preference.factor = Some(factor)
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct MiniModePreference {
+    /// Logical size the mini mode window had when it was last left.
+    size: Option<(f64, f64)>,
+}
+
+/// The logical size mini mode was last used at, `None` until it was left once.
+pub(crate) fn mini_mode_size(app: &AppHandle) -> Option<(f64, f64)> {
+    let path = store_path(app, MINI_MODE_STORE).ok()?;
+    let _guard = STORE_LOCK.lock().unwrap();
+    load_store::<MiniModePreference>(&path).size
+}
+
+pub(crate) fn save_mini_mode_size(app: &AppHandle, size: (f64, f64)) -> Result<(), String> {
+    update_store(app, MINI_MODE_STORE, |preference: &mut MiniModePreference| {
+        preference.size = Some(size)
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct AdvancedSettings {
+    enable_devtools: bool,
+}
+
+/// Whether the user opted into devtools in release builds.
+pub(crate) fn devtools_preference(app: &AppHandle) -> bool {
+    let Ok(path) = store_path(app, ADVANCED_STORE) else {
+        return false;
+    };
+    let _guard = STORE_LOCK.lock().unwrap();
+    load_store::<AdvancedSettings>(&path).enable_devtools
+}
+
+pub(crate) fn save_devtools_preference(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    update_store(app, ADVANCED_STORE, |settings: &mut AdvancedSettings| {
+        settings.enable_devtools = enabled
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct RecentEntry {
+    path: String,
+    /// Milliseconds since the Unix epoch.
+    last_opened: i64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RecentFile {
+    pub path: String,
+    pub file_name: String,
+    pub last_opened: i64,
+    /// Current size in bytes; `None` when the file is gone.
+    pub size: Option<u64>,
+    pub still_exists: bool,
+}
+
+/// Record `path` as just opened. Failures are logged, never surfaced, so
+/// they can't break the open itself.
+pub(crate) fn record_recent_file(app: &AppHandle, path: &str) {
+    let now = chrono::Utc::now().timestamp_millis();
+    match update_store(app, RECENT_FILES_STORE, |entries| {
+        push_recent(entries, path, now)
+    }) {
+        Ok(()) => recent_files_changed(app),
+        Err(error) => log::warn!("Failed to record recent file: {error}"),
+    }
+}
+
+/// Tell listeners such as the macOS Open Recent menu that the list changed.
+fn recent_files_changed(app: &AppHandle) {
+    let _ = app.emit("recent-files-changed", ());
+}
+
+/// Add a file to the recent files list
+#[tauri::command]
+pub fn add_recent_file(app: AppHandle, path: String) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    update_store(&app, RECENT_FILES_STORE, |entries| {
+        push_recent(entries, &path, now)
+    })?;
+    recent_files_changed(&app);
+    Ok(())
+}
+
+/// Recently opened files, most recent first
+#[tauri::command]
+pub fn get_recent_files(app: AppHandle) -> Result<Vec<RecentFile>, String> {
+    let path = store_path(&app, RECENT_FILES_STORE)?;
+    let entries: Vec<RecentEntry> = {
+        let _guard = STORE_LOCK.lock().unwrap();
+        load_store(&path)
+    };
+    Ok(entries.iter().map(recent_file).collect())
+}
+
+/// Remove one file from the recent files list; returns whether it was listed
+#[tauri::command]
+pub fn remove_recent_file(app: AppHandle, path: String) -> Result<bool, String> {
+    let key = canonical_path(&path);
+    let removed = update_store(
+        &app,
+        RECENT_FILES_STORE,
+        |entries: &mut Vec<RecentEntry>| {
+            let before = entries.len();
+            entries.retain(|entry| canonical_path(&entry.path) != key);
+            entries.len() != before
+        },
+    )?;
+    if removed {
+        recent_files_changed(&app);
+    }
+    Ok(removed)
+}
+
+/// Forget every recent file
+#[tauri::command]
+pub fn clear_recent_files(app: AppHandle) -> Result<(), String> {
+    update_store(
+        &app,
+        RECENT_FILES_STORE,
+        |entries: &mut Vec<RecentEntry>| entries.clear(),
+    )?;
+    recent_files_changed(&app);
+    Ok(())
+}
+
+/// Move `path` to the front, dropping older entries for the same file.
+fn push_recent(entries: &mut Vec<RecentEntry>, path: &str, now: i64) {
+    let key = canonical_path(path);
+    entries.retain(|entry| canonical_path(&entry.path) != key);
+    entries.insert(
+        0,
+        RecentEntry {
+            path: key,
+            last_opened: now,
+        },
+    );
+    entries.truncate(MAX_RECENT_FILES);
+}
+
+/// Files that no longer exist keep their spelling, so they still dedupe.
+fn canonical_path(path: &str) -> String {
+    std::fs::canonicalize(path)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+fn recent_file(entry: &RecentEntry) -> RecentFile {
+    let metadata = std::fs::metadata(&entry.path)
+        .ok()
+        .filter(|metadata| metadata.is_file());
+    RecentFile {
+        path: entry.path.clone(),
+        file_name: Path::new(&entry.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| entry.path.clone()),
+        last_opened: entry.last_opened,
+        size: metadata.as_ref().map(|metadata| metadata.len()),
+        still_exists: metadata.is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_store, push_recent, recent_file, save_store, RecentEntry, MAX_RECENT_FILES};
+    use std::fs;
+
+    #[test]
+    fn keeps_recent_files_deduped_capped_and_marks_missing_ones() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let file = dir.join("data.json");
+        fs::write(&file, "{}").unwrap();
+        let file = file.to_string_lossy().into_owned();
+        let aliased = dir
+            .join(".")
+            .join("data.json")
+            .to_string_lossy()
+            .into_owned();
+        let missing = dir.join("gone.json").to_string_lossy().into_owned();
+
+        let mut entries = Vec::new();
+        push_recent(&mut entries, &file, 1);
+        push_recent(&mut entries, &missing, 2);
+        push_recent(&mut entries, &aliased, 3);
+        let listed: Vec<_> = entries.iter().map(recent_file).collect();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(
+            (
+                listed[0].file_name.as_str(),
+                listed[0].size,
+                listed[0].last_opened
+            ),
+            ("data.json", Some(2), 3)
+        );
+        assert!(!listed[1].still_exists);
+
+        for n in 0..MAX_RECENT_FILES {
+            push_recent(&mut entries, &format!("/missing/{n}.json"), 10);
+        }
+        assert_eq!(entries.len(), MAX_RECENT_FILES);
+    }
+
+    #[test]
+    fn round_trips_stores_and_treats_missing_ones_as_empty() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("nested").join("recent_files.json");
+        assert_eq!(load_store::<Vec<RecentEntry>>(&path), Vec::new());
+
+        let entries = vec![RecentEntry {
+            path: "/a.json".to_string(),
+            last_opened: 5,
+        }];
+        save_store(&path, &entries).unwrap();
+        assert_eq!(load_store::<Vec<RecentEntry>>(&path), entries);
+    }
+}
@@ -0,0 +1,227 @@
+// Background tasks
+//
+// Heavy commands have `_async` variants that return a task id at once and do
+// their work on the blocking thread pool, so a huge document doesn't hold up
+// the command and can be stopped. Between its steps a task checks whether it
+// was cancelled and sends `task-progress`; it ends with `task-completed` or
+// `task-failed` on the window that started it. `cancel_task` reports the
+// task as failed right away; the work stops at its next step and whatever it
+// still produces is dropped.
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+
+use super::crash::catch_panic;
+use super::progress::{percent, ProgressGuard};
+
+pub(crate) const TASK_CANCELLED: &str = "Task cancelled";
+
+#[derive(Default)]
+pub struct TaskManager {
+    tasks: Mutex<HashMap<String, Arc<TaskState>>>,
+    next_id: AtomicU64,
+}
+
+struct TaskState {
+    kind: &'static str,
+    window: String,
+    started_at: u64,
+    percent: AtomicU64,
+    cancelled: AtomicBool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TaskInfo {
+    pub task_id: String,
+    pub kind: String,
+    /// Label of the window that receives the task's events.
+    pub window: String,
+    /// Milliseconds since the Unix epoch.
+    pub started_at: u64,
+    pub percent: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct TaskProgress<'a> {
+    task_id: &'a str,
+    kind: &'a str,
+    percent: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct TaskCompleted<'a> {
+    task_id: &'a str,
+    kind: &'a str,
+    result: Value,
+}
+
+#[derive(Serialize, Clone)]
+struct TaskFailed<'a> {
+    task_id: &'a str,
+    kind: &'a str,
+    error: &'a str,
+    cancelled: bool,
+}
+
+/// Handed to a task's work to report its steps.
+pub(crate) struct TaskContext {
+    task_id: String,
+    state: Arc<TaskState>,
+    window: Window,
+    progress: ProgressGuard,
+}
+
+impl TaskContext {
+    /// Report that `done` of `total` steps are finished. Fails with
+    /// `TASK_CANCELLED` once the task was cancelled, so work can stop with `?`.
+    pub(crate) fn step(&self, done: u64, total: u64) -> Result<(), String> {
+        if self.state.cancelled.load(Ordering::Relaxed) {
+            return Err(TASK_CANCELLED.to_string());
+        }
+        let percent = percent(done, total);
+        if self.state.percent.swap(percent, Ordering::Relaxed) != percent {
+            let _ = self.window.emit_to(
+                &self.state.window,
+                "task-progress",
+                TaskProgress {
+                    task_id: &self.task_id,
+                    kind: self.state.kind,
+                    percent,
+                },
+            );
+        }
+        self.progress.update(done, total);
+        Ok(())
+    }
+
+    fn finish(self, result: Result<Value, String>) {
+        let app = self.window.app_handle();
+        let tasks = app.state::<TaskManager>();
+        // A cancelled task was already reported by `cancel_task`.
+        if tasks.tasks.lock().unwrap().remove(&self.task_id).is_none() {
+            return;
+        }
+        let (task_id, kind) = (self.task_id.as_str(), self.state.kind);
+        let emitted = match result {
+            Ok(result) => app.emit_to(
+                &self.state.window,
+                "task-completed",
+                TaskCompleted {
+                    task_id,
+                    kind,
+                    result,
+                },
+            ),
+            Err(error) => app.emit_to(
+                &self.state.window,
+                "task-failed",
+                TaskFailed {
+                    task_id,
+                    kind,
+                    error: &error,
+                    cancelled: false,
+                },
+            ),
+        };
+        if let Err(error) = emitted {
+            log::warn!("Failed to report task {task_id}: {error}");
+        }
+    }
+}
+
+/// Run `work` on the blocking thread pool as a task of `kind`; returns its id.
+///
+/// The result arrives on `task-completed`, an error or a panic on
+/// `task-failed`. Callers should listen before invoking the command, since a
+/// small task can finish before its id is returned.
+pub(crate) fn spawn_task<T, F>(window: Window, kind: &'static str, work: F) -> String
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce(&TaskContext) -> Result<T, String> + Send + 'static,
+{
+    let manager = window.state::<TaskManager>();
+    let task_id = format!(
+        "task-{}",
+        manager.next_id.fetch_add(1, Ordering::Relaxed) + 1
+    );
+    let state = Arc::new(TaskState {
+        kind,
+        window: window.label().to_string(),
+        started_at: now_millis(),
+        percent: AtomicU64::new(0),
+        cancelled: AtomicBool::new(false),
+    });
+    manager
+        .tasks
+        .lock()
+        .unwrap()
+        .insert(task_id.clone(), state.clone());
+
+    let context = TaskContext {
+        task_id: task_id.clone(),
+        state,
+        progress: ProgressGuard::start(window.clone()),
+        window,
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        // A panic fails the task instead of taking the app down.
+        let result = catch_panic(|| work(&context)).and_then(|result| {
+            serde_json::to_value(result).map_err(|e| format!("Task result error: {e}"))
+        });
+        context.finish(result);
+    });
+    task_id
+}
+
+/// Stop a background task; returns whether it was still running
+///
+/// `task-failed` with `cancelled` set is sent at once.
+#[tauri::command]
+pub fn cancel_task(app: AppHandle, task_id: String, tasks: State<'_, TaskManager>) -> bool {
+    let Some(state) = tasks.tasks.lock().unwrap().remove(&task_id) else {
+        return false;
+    };
+    state.cancelled.store(true, Ordering::Relaxed);
+    let _ = app.emit_to(
+        &state.window,
+        "task-failed",
+        TaskFailed {
+            task_id: &task_id,
+            kind: state.kind,
+            error: TASK_CANCELLED,
+            cancelled: true,
+        },
+    );
+    true
+}
+
+/// Running background tasks, oldest first
+#[tauri::command]
+pub fn list_tasks(tasks: State<'_, TaskManager>) -> Vec<TaskInfo> {
+    let mut list: Vec<TaskInfo> = tasks
+        .tasks
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(task_id, state)| TaskInfo {
+            task_id: task_id.clone(),
+            kind: state.kind.to_string(),
+            window: state.window.clone(),
+            started_at: state.started_at,
+            percent: state.percent.load(Ordering::Relaxed),
+        })
+        .collect();
+    list.sort_by_key(|task| task.started_at);
+    list
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
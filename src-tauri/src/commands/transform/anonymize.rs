@@ -0,0 +1,255 @@
+use chrono::{DateTime, NaiveDate, SecondsFormat, TimeZone, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use regex::Regex;
+use serde_json::{Number, Value};
+use std::collections::HashMap;
+
+use crate::json_value::canonical_number;
+
+/// Replaces string and number values with random look-alikes while keeping
+/// keys, structure, booleans and nulls. Equal inputs map to equal outputs so
+/// IDs that reference each other still match after anonymization.
+pub(crate) struct Anonymizer {
+    rng: StdRng,
+    strings: HashMap<String, String>,
+    numbers: HashMap<String, Value>,
+    uuid: Regex,
+    email: Regex,
+    url: Regex,
+}
+
+impl Anonymizer {
+    pub(crate) fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            rng,
+            strings: HashMap::new(),
+            numbers: HashMap::new(),
+            uuid: Regex::new(
+                r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+            )
+            .expect("valid UUID regex"),
+            email: Regex::new(r"^[^@\s]+@[^@\s]+\.[A-Za-z]{2,}$").expect("valid email regex"),
+            url: Regex::new(r"^[A-Za-z][A-Za-z0-9+.-]*://").expect("valid URL regex"),
+        }
+    }
+
+    /// Anonymize `value` in place, returning how many values were replaced.
+    pub(crate) fn anonymize(&mut self, value: &mut Value) -> usize {
+        match value {
+            Value::Object(map) => map.values_mut().map(|child| self.anonymize(child)).sum(),
+            Value::Array(items) => items.iter_mut().map(|item| self.anonymize(item)).sum(),
+            Value::String(text) => {
+                *text = self.fake_string(text);
+                1
+            }
+            Value::Number(number) => {
+                *value = self.fake_number(number);
+                1
+            }
+            Value::Bool(_) | Value::Null => 0,
+        }
+    }
+
+    fn fake_string(&mut self, original: &str) -> String {
+        if let Some(existing) = self.strings.get(original) {
+            return existing.clone();
+        }
+        let fake = if self.uuid.is_match(original) {
+            // Keep the version and variant nibbles so the result is still a valid UUID.
+            self.scramble(original, |index| index == 14 || index == 19, true)
+        } else if self.email.is_match(original) {
+            let tld_start = original.rfind('.').unwrap_or(original.len());
+            self.scramble(original, |index| index >= tld_start, false)
+        } else if let Some(scheme) = self.url.find(original) {
+            let host_end = original[scheme.end()..]
+                .find(['/', '?', '#', ':'])
+                .map_or(original.len(), |offset| scheme.end() + offset);
+            let tld_start = original[scheme.end()..host_end]
+                .rfind('.')
+                .map_or(host_end, |offset| scheme.end() + offset);
+            self.scramble(
+                original,
+                |index| index < scheme.end() || (tld_start..host_end).contains(&index),
+                false,
+            )
+        } else if let Some(date) = self.fake_date(original) {
+            date
+        } else {
+            self.scramble(original, |_| false, false)
+        };
+        self.strings.insert(original.to_string(), fake.clone());
+        fake
+    }
+
+    /// Replace letters and digits with random ones of the same class, leaving
+    /// punctuation and the byte positions for which `keep` is true untouched.
+    fn scramble(&mut self, text: &str, keep: impl Fn(usize) -> bool, hex: bool) -> String {
+        text.char_indices()
+            .map(|(index, c)| {
+                if keep(index) {
+                    return c;
+                }
+                match c {
+                    _ if hex && c.is_ascii_hexdigit() => {
+                        let digit = self.rng.gen_range(0..16u32);
+                        let fake = char::from_digit(digit, 16).unwrap_or('0');
+                        if c.is_ascii_uppercase() {
+                            fake.to_ascii_uppercase()
+                        } else {
+                            fake
+                        }
+                    }
+                    '0'..='9' => char::from(b'0' + self.rng.gen_range(0..10u8)),
+                    'A'..='Z' => char::from(b'A' + self.rng.gen_range(0..26u8)),
+                    _ if c.is_alphabetic() => char::from(b'a' + self.rng.gen_range(0..26u8)),
+                    other => other,
+                }
+            })
+            .collect()
+    }
+
+    /// Dates and RFC 3339 timestamps become other valid values in the same format.
+    fn fake_date(&mut self, original: &str) -> Option<String> {
+        let seconds = self.rng.gen_range(946_684_800..1_893_456_000i64); // 2000-01-01..2030-01-01
+        if NaiveDate::parse_from_str(original, "%Y-%m-%d").is_ok() {
+            let date = DateTime::<Utc>::from_timestamp(seconds, 0)?;
+            return Some(date.format("%Y-%m-%d").to_string());
+        }
+        let parsed = DateTime::parse_from_rfc3339(original).ok()?;
+        let fraction_digits = original.split_once('.').map_or(0, |(_, rest)| {
+            rest.chars().take_while(char::is_ascii_digit).count()
+        });
+        let format = match fraction_digits {
+            0 => SecondsFormat::Secs,
+            1..=3 => SecondsFormat::Millis,
+            4..=6 => SecondsFormat::Micros,
+            _ => SecondsFormat::Nanos,
+        };
+        let nanos = if fraction_digits == 0 {
+            0
+        } else {
+            self.rng.gen_range(0..1_000_000_000u32)
+        };
+        let fake = parsed.offset().timestamp_opt(seconds, nanos).single()?;
+        Some(fake.to_rfc3339_opts(format, original.ends_with('Z')))
+    }
+
+    fn fake_number(&mut self, original: &Number) -> Value {
+        let key = canonical_number(original);
+        if let Some(existing) = self.numbers.get(&key) {
+            return existing.clone();
+        }
+        let fake = if let Some(unsigned) = original.as_u64() {
+            Value::from(self.same_digit_count(unsigned))
+        } else if let Some(signed) = original.as_i64() {
+            Value::from(-(self.same_digit_count(signed.unsigned_abs()) as i64))
+        } else {
+            self.fake_float(original)
+        };
+        self.numbers.insert(key, fake.clone());
+        fake
+    }
+
+    fn same_digit_count(&mut self, original: u64) -> u64 {
+        // Capped at 18 digits so the result (and its negation) always fits.
+        let digits = original.to_string().len().min(18) as u32;
+        if digits == 1 {
+            return self.rng.gen_range(0..10);
+        }
+        self.rng.gen_range(10u64.pow(digits - 1)..10u64.pow(digits))
+    }
+
+    /// A float with the same sign, order of magnitude and decimal places.
+    fn fake_float(&mut self, original: &Number) -> Value {
+        let value = original.as_f64().unwrap_or(0.0);
+        if value == 0.0 || !value.is_finite() {
+            return Value::Number(original.clone());
+        }
+        let text = original.to_string();
+        let decimals = text
+            .split_once('.')
+            .map_or(0, |(_, fraction)| {
+                fraction.chars().take_while(char::is_ascii_digit).count()
+            })
+            .min(15) as i32;
+        let exponent = value.abs().log10().floor();
+        let low = 10f64.powf(exponent);
+        let scale = 10f64.powi(decimals);
+        let fake = (self.rng.gen_range(low..low * 10.0) * scale).round() / scale;
+        let signed = if value < 0.0 { -fake } else { fake };
+        Number::from_f64(signed).map_or(Value::Number(original.clone()), Value::Number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Anonymizer;
+    use chrono::DateTime;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_structure_and_maps_equal_values_consistently() {
+        let mut value = json!({
+            "users": [{"id": "u-123", "active": true, "age": 42}, {"id": "u-123", "nick": null}],
+            "owner": "u-123"
+        });
+
+        let replaced = Anonymizer::new(Some(7)).anonymize(&mut value);
+
+        assert_eq!(replaced, 4);
+        let id = value["owner"].as_str().unwrap();
+        assert_ne!(id, "u-123");
+        assert_eq!(id.len(), 5);
+        assert_eq!(&id[1..2], "-");
+        assert_eq!(value["users"][0]["id"], value["owner"]);
+        assert_eq!(value["users"][1]["id"], value["owner"]);
+        assert_eq!(value["users"][0]["active"], true);
+        assert!(value["users"][1]["nick"].is_null());
+        let age = value["users"][0]["age"].as_u64().unwrap();
+        assert!((10..100).contains(&age));
+    }
+
+    #[test]
+    fn preserves_detected_formats() {
+        let mut value = json!([
+            "jane.doe@example.com",
+            "550e8400-e29b-41d4-a716-446655440000",
+            "https://api.example.org/v1/users?id=7",
+            "2024-02-29",
+            "2024-02-29T12:30:00.123+02:00",
+            -12.75
+        ]);
+
+        Anonymizer::new(Some(1)).anonymize(&mut value);
+
+        let email = value[0].as_str().unwrap();
+        assert!(email.ends_with(".com") && email.contains('@'));
+        let uuid = value[1].as_str().unwrap();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        let url = value[2].as_str().unwrap();
+        assert!(url.starts_with("https://") && url.contains(".org/"));
+        assert!(chrono::NaiveDate::parse_from_str(value[3].as_str().unwrap(), "%Y-%m-%d").is_ok());
+        let timestamp = value[4].as_str().unwrap();
+        assert!(DateTime::parse_from_rfc3339(timestamp).is_ok());
+        assert!(timestamp.ends_with("+02:00"));
+        let number = value[5].as_f64().unwrap();
+        assert!((-100.0..=-10.0).contains(&number));
+    }
+
+    #[test]
+    fn seeds_make_output_reproducible() {
+        let original = json!({"name": "Ada Lovelace", "score": 3.5});
+        let (mut a, mut b) = (original.clone(), original);
+
+        Anonymizer::new(Some(99)).anonymize(&mut a);
+        Anonymizer::new(Some(99)).anonymize(&mut b);
+
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,124 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CleanOptions {
+    pub remove_nulls: bool,
+    pub remove_empty_strings: bool,
+    pub remove_empty_arrays: bool,
+    pub remove_empty_objects: bool,
+    /// Also remove containers that only became empty because their children were cleaned.
+    pub recursive_collapse: bool,
+    /// Drop matching array elements too. Off by default because it shifts indexes.
+    pub remove_array_items: bool,
+}
+
+/// Clean `value` in place and return how many nodes were removed.
+/// The root itself is never removed, even if it ends up matching.
+pub(crate) fn clean_value(value: &mut Value, options: &CleanOptions) -> usize {
+    let mut removed = 0;
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, child| {
+                let was_empty = is_empty_container(child);
+                removed += clean_value(child, options);
+                let drop = should_remove(child, was_empty, options);
+                if drop {
+                    removed += 1;
+                }
+                !drop
+            });
+        }
+        Value::Array(items) => {
+            items.retain_mut(|child| {
+                let was_empty = is_empty_container(child);
+                removed += clean_value(child, options);
+                let drop = options.remove_array_items && should_remove(child, was_empty, options);
+                if drop {
+                    removed += 1;
+                }
+                !drop
+            });
+        }
+        _ => {}
+    }
+    removed
+}
+
+fn is_empty_container(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+fn should_remove(value: &Value, was_empty: bool, options: &CleanOptions) -> bool {
+    // Without recursive collapse, only containers that were empty in the source qualify.
+    let collapsible = was_empty || options.recursive_collapse;
+    match value {
+        Value::Null => options.remove_nulls,
+        Value::String(s) => options.remove_empty_strings && s.is_empty(),
+        Value::Array(items) => options.remove_empty_arrays && collapsible && items.is_empty(),
+        Value::Object(map) => options.remove_empty_objects && collapsible && map.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clean_value, CleanOptions};
+    use serde_json::json;
+
+    fn all_options() -> CleanOptions {
+        CleanOptions {
+            remove_nulls: true,
+            remove_empty_strings: true,
+            remove_empty_arrays: true,
+            remove_empty_objects: true,
+            recursive_collapse: false,
+            remove_array_items: false,
+        }
+    }
+
+    #[test]
+    fn removes_matching_fields_but_keeps_array_items_by_default() {
+        let mut value = json!({"a": null, "b": "", "c": [], "d": {}, "e": [null, ""], "f": 0});
+
+        let removed = clean_value(&mut value, &all_options());
+
+        assert_eq!(value, json!({"e": [null, ""], "f": 0}));
+        assert_eq!(removed, 4);
+    }
+
+    #[test]
+    fn recursive_collapse_removes_objects_emptied_by_cleaning() {
+        let input = json!({"outer": {"inner": {"a": null}}, "keep": 1});
+
+        let mut shallow = input.clone();
+        assert_eq!(clean_value(&mut shallow, &all_options()), 1);
+        assert_eq!(shallow, json!({"outer": {"inner": {}}, "keep": 1}));
+
+        let mut collapsed = input;
+        let options = CleanOptions {
+            recursive_collapse: true,
+            ..all_options()
+        };
+        assert_eq!(clean_value(&mut collapsed, &options), 3);
+        assert_eq!(collapsed, json!({"keep": 1}));
+    }
+
+    #[test]
+    fn removes_array_items_only_when_requested() {
+        let mut value = json!([1, null, "", {"a": null}, []]);
+        let options = CleanOptions {
+            remove_array_items: true,
+            recursive_collapse: true,
+            ..all_options()
+        };
+
+        assert_eq!(clean_value(&mut value, &options), 5);
+        assert_eq!(value, json!([1]));
+    }
+}
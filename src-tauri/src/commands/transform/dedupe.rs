@@ -0,0 +1,72 @@
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::json_pointer::lookup_field_path;
+use crate::json_value::{canonical_key, value_type_name};
+
+/// Remove duplicate elements from `target`, keeping the first occurrence.
+///
+/// Elements are compared by deep equality, or by the value at `key` (a
+/// dot-separated field path) when given. Elements without that field are
+/// never treated as duplicates.
+pub(crate) fn dedupe_array(target: &mut Value, key: Option<&str>) -> Result<usize, String> {
+    let Value::Array(items) = target else {
+        return Err(format!(
+            "Expected an array at the target pointer, found {}",
+            value_type_name(target)
+        ));
+    };
+
+    let before = items.len();
+    let mut seen = HashSet::new();
+    items.retain(|item| {
+        let compared = match key {
+            Some(path) => lookup_field_path(item, path),
+            None => Some(item),
+        };
+        match compared {
+            Some(value) => seen.insert(canonical_key(value)),
+            None => true,
+        }
+    });
+    Ok(before - items.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dedupe_array;
+    use serde_json::json;
+
+    #[test]
+    fn dedupes_by_deep_equality_with_json_number_semantics() {
+        let mut value = json!([1, 1.0, "1", {"a": 1, "b": 2}, {"b": 2, "a": 1}]);
+
+        assert_eq!(dedupe_array(&mut value, None).unwrap(), 2);
+        assert_eq!(value, json!([1, "1", {"a": 1, "b": 2}]));
+    }
+
+    #[test]
+    fn dedupes_by_key_path_keeping_first() {
+        let mut value = json!([
+            {"user": {"id": 1}, "v": "first"},
+            {"user": {"id": 2}},
+            {"user": {"id": 1}, "v": "second"},
+            {"other": true},
+            {"other": true}
+        ]);
+
+        assert_eq!(dedupe_array(&mut value, Some("user.id")).unwrap(), 1);
+        assert_eq!(value.as_array().unwrap().len(), 4);
+        assert_eq!(value[0]["v"], "first");
+    }
+
+    #[test]
+    fn rejects_non_array_targets_with_the_actual_type() {
+        let mut value = json!({"a": 1});
+
+        assert_eq!(
+            dedupe_array(&mut value, None).unwrap_err(),
+            "Expected an array at the target pointer, found object"
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+use crate::json_pointer::lookup_field_path;
+use crate::json_value::value_type_name;
+
+#[derive(Serialize, Debug)]
+pub struct FilterWarning {
+    /// Index of the element in the unfiltered array.
+    pub index: usize,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+    Matches,
+}
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Eq => "==",
+            Op::Ne => "!=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Contains => "contains",
+            Op::Matches => "=~",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum Operand {
+    Value(Value),
+    Pattern(Regex),
+}
+
+/// A parsed filter such as `status == "active" && (price > 100 || tags
+/// contains "beta")`. Fields are dot-separated paths into each element;
+/// comparisons on a missing field are false.
+#[derive(Debug)]
+pub(crate) enum Predicate {
+    Or(Vec<Predicate>),
+    And(Vec<Predicate>),
+    Not(Box<Predicate>),
+    Compare {
+        field: String,
+        op: Op,
+        operand: Operand,
+    },
+}
+
+impl Predicate {
+    pub(crate) fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            chars: source.chars().collect(),
+            pos: 0,
+        };
+        let predicate = parser.parse_or()?;
+        parser.skip_whitespace();
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("unexpected input"));
+        }
+        Ok(predicate)
+    }
+
+    /// Evaluate against one element. Type mismatches count as "no match" and
+    /// are reported through `warn` instead of failing the whole filter.
+    pub(crate) fn eval(&self, element: &Value, warn: &mut dyn FnMut(String)) -> bool {
+        match self {
+            Predicate::Or(parts) => parts.iter().any(|part| part.eval(element, warn)),
+            Predicate::And(parts) => parts.iter().all(|part| part.eval(element, warn)),
+            Predicate::Not(inner) => !inner.eval(element, warn),
+            Predicate::Compare { field, op, operand } => {
+                let Some(actual) = lookup_field_path(element, field) else {
+                    return false;
+                };
+                match compare(actual, *op, operand) {
+                    Ok(result) => result,
+                    Err(()) => {
+                        warn(format!(
+                            "cannot apply {} to {} field \"{field}\"",
+                            op.symbol(),
+                            value_type_name(actual)
+                        ));
+                        false
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn compare(actual: &Value, op: Op, operand: &Operand) -> Result<bool, ()> {
+    let expected = match operand {
+        Operand::Pattern(pattern) => {
+            return actual.as_str().map(|text| pattern.is_match(text)).ok_or(());
+        }
+        Operand::Value(expected) => expected,
+    };
+    match op {
+        Op::Eq => Ok(loosely_equal(actual, expected)),
+        Op::Ne => Ok(!loosely_equal(actual, expected)),
+        Op::Contains => match (actual, expected) {
+            (Value::Array(items), _) => Ok(items.iter().any(|item| loosely_equal(item, expected))),
+            (Value::String(text), Value::String(needle)) => Ok(text.contains(needle.as_str())),
+            _ => Err(()),
+        },
+        Op::Gt | Op::Ge | Op::Lt | Op::Le => {
+            let (Some(a), Some(b)) = (actual.as_f64(), expected.as_f64()) else {
+                return Err(());
+            };
+            let ordering = a.partial_cmp(&b).ok_or(())?;
+            Ok(match op {
+                Op::Gt => ordering == Ordering::Greater,
+                Op::Ge => ordering != Ordering::Less,
+                Op::Lt => ordering == Ordering::Less,
+                _ => ordering != Ordering::Greater,
+            })
+        }
+        Op::Matches => Err(()),
+    }
+}
+
+/// Equality where `1` and `1.0` match.
+fn loosely_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        _ => a == b,
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> String {
+        format!("Invalid predicate at position {}: {message}", self.pos)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let matches = token
+            .chars()
+            .enumerate()
+            .all(|(offset, c)| self.chars.get(self.pos + offset) == Some(&c));
+        if matches {
+            self.pos += token.chars().count();
+        }
+        matches
+    }
+
+    fn parse_or(&mut self) -> Result<Predicate, String> {
+        let mut parts = vec![self.parse_and()?];
+        while self.eat("||") {
+            parts.push(self.parse_and()?);
+        }
+        Ok(if parts.len() == 1 {
+            parts.remove(0)
+        } else {
+            Predicate::Or(parts)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Predicate, String> {
+        let mut parts = vec![self.parse_term()?];
+        while self.eat("&&") {
+            parts.push(self.parse_term()?);
+        }
+        Ok(if parts.len() == 1 {
+            parts.remove(0)
+        } else {
+            Predicate::And(parts)
+        })
+    }
+
+    fn parse_term(&mut self) -> Result<Predicate, String> {
+        if self.eat("(") {
+            let inner = self.parse_or()?;
+            if !self.eat(")") {
+                return Err(self.error("expected ')'"));
+            }
+            return Ok(inner);
+        }
+        if self.eat("!") {
+            return Ok(Predicate::Not(Box::new(self.parse_term()?)));
+        }
+
+        let field = self.read_while(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '$' | '-'));
+        if field.is_empty() {
+            return Err(self.error("expected a field name"));
+        }
+        let op = self.parse_op()?;
+        let operand = if op == Op::Matches {
+            Operand::Pattern(self.parse_regex()?)
+        } else {
+            Operand::Value(self.parse_literal()?)
+        };
+        Ok(Predicate::Compare { field, op, operand })
+    }
+
+    fn parse_op(&mut self) -> Result<Op, String> {
+        // Two-character operators first so `>=` isn't read as `>`.
+        for (token, op) in [
+            ("==", Op::Eq),
+            ("!=", Op::Ne),
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            ("=~", Op::Matches),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+            ("contains", Op::Contains),
+        ] {
+            if self.eat(token) {
+                return Ok(op);
+            }
+        }
+        Err(self.error("expected an operator (==, !=, >, >=, <, <=, contains, =~)"))
+    }
+
+    fn parse_literal(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        if self.chars.get(self.pos) == Some(&'"') {
+            self.pos += 1;
+            while let Some(&c) = self.chars.get(self.pos) {
+                self.pos += 1;
+                match c {
+                    '\\' => self.pos += 1,
+                    '"' => break,
+                    _ => {}
+                }
+            }
+        } else {
+            self.read_while(|c| !c.is_whitespace() && !matches!(c, ')' | '&' | '|'));
+        }
+        let text: String = self.chars[start..self.pos.min(self.chars.len())]
+            .iter()
+            .collect();
+        match serde_json::from_str::<Value>(&text) {
+            Ok(value @ (Value::String(_) | Value::Number(_) | Value::Bool(_) | Value::Null)) => {
+                Ok(value)
+            }
+            _ => {
+                self.pos = start;
+                Err(self.error("expected a string, number, true, false or null"))
+            }
+        }
+    }
+
+    fn parse_regex(&mut self) -> Result<Regex, String> {
+        if !self.eat("/") {
+            return Err(self.error("expected a /regex/"));
+        }
+        let mut pattern = String::new();
+        loop {
+            match self.chars.get(self.pos) {
+                None => return Err(self.error("unterminated regex")),
+                Some('/') => break,
+                Some('\\') if self.chars.get(self.pos + 1) == Some(&'/') => {
+                    pattern.push('/');
+                    self.pos += 2;
+                }
+                Some(&c) => {
+                    pattern.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        self.pos += 1;
+        let flags = self.read_while(|c| c.is_ascii_alphabetic());
+        RegexBuilder::new(&pattern)
+            .case_insensitive(flags.contains('i'))
+            .build()
+            .map_err(|error| format!("Invalid regex /{pattern}/: {error}"))
+    }
+
+    fn read_while(&mut self, keep: impl Fn(char) -> bool) -> String {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|&c| keep(c)) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Predicate;
+    use serde_json::{json, Value};
+
+    fn matching(predicate: &str, items: &Value) -> (Vec<usize>, Vec<String>) {
+        let predicate = Predicate::parse(predicate).unwrap();
+        let mut warnings = Vec::new();
+        let kept = items
+            .as_array()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| predicate.eval(item, &mut |w| warnings.push(w)))
+            .map(|(index, _)| index)
+            .collect();
+        (kept, warnings)
+    }
+
+    #[test]
+    fn evaluates_comparisons_with_boolean_operators() {
+        let items = json!([
+            {"status": "active", "price": 150, "tags": ["beta"], "name": "foobar"},
+            {"status": "active", "price": 50, "tags": [], "name": "Foo"},
+            {"status": "inactive", "price": 500, "tags": ["beta"], "name": "bar"}
+        ]);
+
+        assert_eq!(matching(r#"status == "active""#, &items).0, [0, 1]);
+        assert_eq!(
+            matching("price > 100 && tags contains \"beta\"", &items).0,
+            [0, 2]
+        );
+        assert_eq!(matching("name =~ /^foo/i || price <= 50", &items).0, [0, 1]);
+        assert_eq!(
+            matching(
+                r#"!(status != "active") && (price >= 150 || name == "x")"#,
+                &items
+            )
+            .0,
+            [0]
+        );
+    }
+
+    #[test]
+    fn type_mismatches_warn_instead_of_failing() {
+        let items = json!([{"price": "cheap"}, {"price": 10}, {}]);
+
+        let (kept, warnings) = matching("price > 5", &items);
+
+        assert_eq!(kept, [1]);
+        assert_eq!(warnings, ["cannot apply > to string field \"price\""]);
+    }
+
+    #[test]
+    fn rejects_malformed_predicates() {
+        assert!(Predicate::parse("price >").is_err());
+        assert!(Predicate::parse("(a == 1").is_err());
+        assert!(Predicate::parse("a ~ 1").is_err());
+        assert!(Predicate::parse("a =~ /[/").is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use serde_json::{Map, Value};
+
+use crate::json_pointer::lookup_field_path;
+use crate::json_value::value_type_name;
+
+pub(crate) const DEFAULT_UNGROUPED_KEY: &str = "__ungrouped__";
+
+/// Replace the array in `target` with an object mapping each stringified
+/// value of `key` (a dot-separated path) to the matching elements, or to
+/// their count with `counts_only`. Groups appear in first-seen order;
+/// elements without the key go under `ungrouped_key`. Returns the group count.
+pub(crate) fn group_array(
+    target: &mut Value,
+    key: &str,
+    counts_only: bool,
+    ungrouped_key: &str,
+) -> Result<usize, String> {
+    let Value::Array(items) = target else {
+        return Err(format!(
+            "Expected an array at the target pointer, found {}",
+            value_type_name(target)
+        ));
+    };
+
+    let mut groups: Map<String, Value> = Map::new();
+    for item in std::mem::take(items) {
+        let group = match lookup_field_path(&item, key) {
+            Some(Value::String(text)) => text.clone(),
+            Some(other) => other.to_string(),
+            None => ungrouped_key.to_string(),
+        };
+        let entry = groups.entry(group).or_insert_with(|| {
+            if counts_only {
+                Value::from(0)
+            } else {
+                Value::Array(Vec::new())
+            }
+        });
+        match entry {
+            Value::Array(members) => members.push(item),
+            count => *count = Value::from(count.as_u64().unwrap_or(0) + 1),
+        }
+    }
+
+    let group_count = groups.len();
+    *target = Value::Object(groups);
+    Ok(group_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_array, DEFAULT_UNGROUPED_KEY};
+    use serde_json::json;
+
+    #[test]
+    fn groups_by_stringified_key_in_first_seen_order() {
+        let mut value = json!([
+            {"status": "open", "id": 1},
+            {"status": 2, "id": 2},
+            {"id": 3},
+            {"status": "open", "id": 4}
+        ]);
+
+        assert_eq!(
+            group_array(&mut value, "status", false, DEFAULT_UNGROUPED_KEY).unwrap(),
+            3
+        );
+        assert_eq!(
+            value,
+            json!({
+                "open": [{"status": "open", "id": 1}, {"status": "open", "id": 4}],
+                "2": [{"status": 2, "id": 2}],
+                "__ungrouped__": [{"id": 3}]
+            })
+        );
+    }
+
+    #[test]
+    fn counts_groups_by_nested_key() {
+        let mut value = json!([
+            {"user": {"role": "admin"}},
+            {"user": {"role": "dev"}},
+            {"user": {"role": "dev"}},
+            {"user": {}}
+        ]);
+
+        group_array(&mut value, "user.role", true, "none").unwrap();
+
+        assert_eq!(value, json!({"admin": 1, "dev": 2, "none": 1}));
+    }
+}
@@ -0,0 +1,137 @@
+use heck::{ToKebabCase, ToLowerCamelCase, ToSnakeCase, ToUpperCamelCase};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy)]
+pub(crate) enum KeyCase {
+    Camel,
+    Snake,
+    Kebab,
+    Pascal,
+}
+
+impl KeyCase {
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "camel" | "camelcase" => Ok(Self::Camel),
+            "snake" | "snake_case" => Ok(Self::Snake),
+            "kebab" | "kebab-case" => Ok(Self::Kebab),
+            "pascal" | "pascalcase" => Ok(Self::Pascal),
+            _ => Err(format!("Unsupported key case: {name}")),
+        }
+    }
+
+    /// heck splits on existing separators, case changes, and acronym boundaries,
+    /// so `HTTPStatus` becomes `http_status` and `version2Name` keeps its digit.
+    pub(crate) fn apply(self, key: &str) -> String {
+        match self {
+            Self::Camel => key.to_lower_camel_case(),
+            Self::Snake => key.to_snake_case(),
+            Self::Kebab => key.to_kebab_case(),
+            Self::Pascal => key.to_upper_camel_case(),
+        }
+    }
+}
+
+/// Rename object keys in place and return how many keys changed.
+///
+/// Arrays are traversed transparently, so `deep = false` converts the keys of a
+/// root object or of each object directly inside a root array. Fails without
+/// touching the document when two sibling keys would collapse into one.
+pub(crate) fn convert_keys(value: &mut Value, case: KeyCase, deep: bool) -> Result<usize, String> {
+    let mut converted = value.clone();
+    let mut collisions = Vec::new();
+    let renamed = convert_value(&mut converted, case, deep, &mut collisions);
+    if !collisions.is_empty() {
+        return Err(format!(
+            "Key conversion would create duplicate keys: {}",
+            collisions.join("; ")
+        ));
+    }
+    *value = converted;
+    Ok(renamed)
+}
+
+fn convert_value(
+    value: &mut Value,
+    case: KeyCase,
+    deep: bool,
+    collisions: &mut Vec<String>,
+) -> usize {
+    match value {
+        Value::Array(items) => items
+            .iter_mut()
+            .map(|item| convert_value(item, case, deep, collisions))
+            .sum(),
+        Value::Object(map) => {
+            let mut renamed = 0;
+            let mut converted = Map::with_capacity(map.len());
+            let mut sources: HashMap<String, String> = HashMap::new();
+            for (key, mut child) in std::mem::take(map) {
+                if deep {
+                    renamed += convert_value(&mut child, case, deep, collisions);
+                }
+                let new_key = case.apply(&key);
+                if let Some(previous) = sources.get(&new_key) {
+                    collisions.push(format!("{previous}, {key} -> {new_key}"));
+                    continue;
+                }
+                if new_key != key {
+                    renamed += 1;
+                }
+                sources.insert(new_key.clone(), key);
+                converted.insert(new_key, child);
+            }
+            *map = converted;
+            renamed
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_keys, KeyCase};
+    use serde_json::json;
+
+    #[test]
+    fn converts_keys_between_cases() {
+        assert_eq!(KeyCase::Snake.apply("HTTPStatus"), "http_status");
+        assert_eq!(KeyCase::Snake.apply("version2Name"), "version2_name");
+        assert_eq!(KeyCase::Camel.apply("user_id"), "userId");
+        assert_eq!(KeyCase::Kebab.apply("createdAt"), "created-at");
+        assert_eq!(KeyCase::Pascal.apply("first-name"), "FirstName");
+    }
+
+    #[test]
+    fn deep_conversion_renames_nested_keys_but_not_values() {
+        let mut value = json!({"userName": "keepThisValue", "items": [{"itemId": 1}]});
+
+        let renamed = convert_keys(&mut value, KeyCase::Snake, true).unwrap();
+
+        assert_eq!(
+            value,
+            json!({"user_name": "keepThisValue", "items": [{"item_id": 1}]})
+        );
+        assert_eq!(renamed, 2);
+    }
+
+    #[test]
+    fn shallow_conversion_keeps_nested_keys() {
+        let mut value = json!({"outerKey": {"innerKey": 1}});
+
+        convert_keys(&mut value, KeyCase::Snake, false).unwrap();
+
+        assert_eq!(value, json!({"outer_key": {"innerKey": 1}}));
+    }
+
+    #[test]
+    fn reports_collisions_without_modifying_the_document() {
+        let mut value = json!({"userId": 1, "user_id": 2});
+
+        let error = convert_keys(&mut value, KeyCase::Snake, true).unwrap_err();
+
+        assert!(error.contains("userId, user_id"));
+        assert_eq!(value, json!({"userId": 1, "user_id": 2}));
+    }
+}
@@ -0,0 +1,387 @@
+// JSON transform commands
+//
+// Each transform parses the input with the same JSON → JSON5 fallback chain as
+// `json_format`, rewrites the `Value` in its own submodule, and returns the
+// pretty-printed document together with a short summary of what changed.
+
+mod anonymize;
+mod clean;
+mod dedupe;
+mod filter;
+mod group;
+mod keys;
+mod nested;
+mod pick;
+mod redact;
+mod timestamps;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::json::parse_to_value;
+use crate::json_pointer::resolve_mut;
+use crate::json_value::value_type_name;
+use anonymize::Anonymizer;
+use clean::{clean_value, CleanOptions};
+use dedupe::dedupe_array;
+use filter::{FilterWarning, Predicate};
+use group::{group_array, DEFAULT_UNGROUPED_KEY};
+use keys::convert_keys;
+pub(crate) use keys::KeyCase;
+use nested::{collapse_nested, expand_nested};
+use pick::{KeyPicker, PickMode};
+use redact::Redactor;
+use timestamps::{convert_timestamps, Direction, TimestampAnnotation, TimestampDetector};
+
+#[derive(Serialize)]
+pub struct CleanResult {
+    pub content: String,
+    pub removed_count: usize,
+}
+
+/// Remove null, empty-string, and empty-container fields.
+#[tauri::command]
+pub fn json_clean(content: &str, options: CleanOptions) -> Result<CleanResult, String> {
+    let mut value = parse_to_value(content)?;
+    let removed_count = clean_value(&mut value, &options);
+    Ok(CleanResult {
+        content: to_pretty_json(&value)?,
+        removed_count,
+    })
+}
+
+#[derive(Serialize)]
+pub struct ConvertKeysResult {
+    pub content: String,
+    pub renamed_count: usize,
+}
+
+/// Convert object keys to camelCase, snake_case, kebab-case, or PascalCase.
+#[tauri::command]
+pub fn json_convert_keys(
+    content: &str,
+    target_case: String,
+    deep: bool,
+) -> Result<ConvertKeysResult, String> {
+    let case = KeyCase::parse(&target_case)?;
+    let mut value = parse_to_value(content)?;
+    let renamed_count = convert_keys(&mut value, case, deep)?;
+    Ok(ConvertKeysResult {
+        content: to_pretty_json(&value)?,
+        renamed_count,
+    })
+}
+
+#[derive(Serialize)]
+pub struct RedactResult {
+    pub content: String,
+    pub redacted_pointers: Vec<String>,
+}
+
+/// Replace values whose key matches any of the given regexes.
+/// Matching is case-insensitive unless `case_sensitive` is set.
+#[tauri::command]
+pub fn json_redact(
+    content: &str,
+    key_patterns: Vec<String>,
+    replacement: Option<String>,
+    case_sensitive: Option<bool>,
+    preserve_types: Option<bool>,
+) -> Result<RedactResult, String> {
+    let redactor = Redactor::new(
+        &key_patterns,
+        replacement,
+        case_sensitive.unwrap_or(false),
+        preserve_types.unwrap_or(false),
+    )?;
+    let mut value = parse_to_value(content)?;
+    let redacted_pointers = redactor.redact(&mut value);
+    Ok(RedactResult {
+        content: to_pretty_json(&value)?,
+        redacted_pointers,
+    })
+}
+
+#[derive(Serialize)]
+pub struct DedupeResult {
+    pub content: String,
+    pub removed_count: usize,
+}
+
+/// Remove duplicate elements from the array at `array_pointer`.
+#[tauri::command]
+pub fn json_dedupe_array(
+    content: &str,
+    array_pointer: String,
+    key: Option<String>,
+) -> Result<DedupeResult, String> {
+    let mut value = parse_to_value(content)?;
+    let target = resolve_mut(&mut value, &array_pointer)?;
+    let removed_count = dedupe_array(target, key.as_deref().filter(|k| !k.is_empty()))?;
+    Ok(DedupeResult {
+        content: to_pretty_json(&value)?,
+        removed_count,
+    })
+}
+
+#[derive(Serialize)]
+pub struct FilterResult {
+    pub content: String,
+    pub kept_count: usize,
+    pub removed_count: usize,
+    pub warnings: Vec<FilterWarning>,
+}
+
+/// Filter the array at `array_pointer` in place with a predicate such as
+/// `status == "active" && price > 100`. With `matches_only`, the content is
+/// just the array of matching elements instead of the whole document.
+#[tauri::command]
+pub fn json_filter_array(
+    content: &str,
+    array_pointer: String,
+    predicate: String,
+    matches_only: Option<bool>,
+) -> Result<FilterResult, String> {
+    let predicate = Predicate::parse(&predicate)?;
+    let mut value = parse_to_value(content)?;
+    let target = resolve_mut(&mut value, &array_pointer)?;
+    let Value::Array(items) = target else {
+        return Err(format!(
+            "Expected an array at the target pointer, found {}",
+            value_type_name(target)
+        ));
+    };
+
+    let mut warnings = Vec::new();
+    let before = items.len();
+    let mut index = 0;
+    items.retain(|item| {
+        let keep = predicate.eval(item, &mut |message| {
+            warnings.push(FilterWarning { index, message })
+        });
+        index += 1;
+        keep
+    });
+    let kept_count = items.len();
+
+    let content = if matches_only.unwrap_or(false) {
+        to_pretty_json(target)?
+    } else {
+        to_pretty_json(&value)?
+    };
+    Ok(FilterResult {
+        content,
+        kept_count,
+        removed_count: before - kept_count,
+        warnings,
+    })
+}
+
+#[derive(Serialize)]
+pub struct AnonymizeResult {
+    pub content: String,
+    pub anonymized_count: usize,
+}
+
+/// Replace strings and numbers with random look-alikes for sharing. Emails,
+/// UUIDs, URLs and dates keep a valid format; equal values stay equal. A
+/// `seed` makes the output reproducible.
+#[tauri::command]
+pub fn json_anonymize(content: &str, seed: Option<u64>) -> Result<AnonymizeResult, String> {
+    let mut value = parse_to_value(content)?;
+    let anonymized_count = Anonymizer::new(seed).anonymize(&mut value);
+    Ok(AnonymizeResult {
+        content: to_pretty_json(&value)?,
+        anonymized_count,
+    })
+}
+
+#[derive(Serialize)]
+pub struct GroupByResult {
+    pub content: String,
+    pub group_count: usize,
+}
+
+/// Turn the array at `array_pointer` into an object of groups keyed by the
+/// value at `key`. Elements without the key go under `ungrouped_key`
+/// (`__ungrouped__` by default).
+#[tauri::command]
+pub fn json_group_by(
+    content: &str,
+    array_pointer: String,
+    key: String,
+    counts_only: Option<bool>,
+    ungrouped_key: Option<String>,
+) -> Result<GroupByResult, String> {
+    let mut value = parse_to_value(content)?;
+    let target = resolve_mut(&mut value, &array_pointer)?;
+    let group_count = group_array(
+        target,
+        &key,
+        counts_only.unwrap_or(false),
+        ungrouped_key.as_deref().unwrap_or(DEFAULT_UNGROUPED_KEY),
+    )?;
+    Ok(GroupByResult {
+        content: to_pretty_json(&value)?,
+        group_count,
+    })
+}
+
+#[derive(Serialize)]
+pub struct PickResult {
+    pub content: String,
+    pub affected_count: usize,
+}
+
+/// Keep (`pick`) or remove (`omit`) object keys matching glob patterns such
+/// as `*_id` or `meta.*`. Without `deep`, only paths from the root (or from
+/// each element of a root array) are matched.
+#[tauri::command]
+pub fn json_pick(
+    content: &str,
+    keys: Vec<String>,
+    mode: String,
+    deep: bool,
+) -> Result<PickResult, String> {
+    let picker = KeyPicker::new(&keys, PickMode::parse(&mode)?, deep)?;
+    let mut value = parse_to_value(content)?;
+    let affected_count = picker.apply(&mut value);
+    Ok(PickResult {
+        content: to_pretty_json(&value)?,
+        affected_count,
+    })
+}
+
+#[derive(Serialize)]
+pub struct ExpandNestedResult {
+    pub content: String,
+    pub expanded_pointers: Vec<String>,
+}
+
+/// Parse string values that contain JSON objects or arrays in place, up to
+/// `max_depth` levels of nesting (unlimited when omitted).
+#[tauri::command]
+pub fn json_expand_nested(
+    content: &str,
+    max_depth: Option<usize>,
+) -> Result<ExpandNestedResult, String> {
+    let mut value = parse_to_value(content)?;
+    let expanded_pointers = expand_nested(&mut value, max_depth);
+    Ok(ExpandNestedResult {
+        content: to_pretty_json(&value)?,
+        expanded_pointers,
+    })
+}
+
+#[derive(Serialize)]
+pub struct CollapseNestedResult {
+    pub content: String,
+    pub collapsed_count: usize,
+}
+
+/// Turn the values at `pointers` back into JSON strings. Passing the pointers
+/// returned by `json_expand_nested` reverses the expansion.
+#[tauri::command]
+pub fn json_collapse_nested(
+    content: &str,
+    pointers: Vec<String>,
+) -> Result<CollapseNestedResult, String> {
+    let mut value = parse_to_value(content)?;
+    let collapsed_count = collapse_nested(&mut value, &pointers)?;
+    Ok(CollapseNestedResult {
+        content: to_pretty_json(&value)?,
+        collapsed_count,
+    })
+}
+
+#[derive(Serialize)]
+pub struct ConvertTimestampsResult {
+    pub content: String,
+    pub converted_pointers: Vec<String>,
+}
+
+/// Convert epoch seconds/milliseconds to ISO 8601 strings (`to_iso`) or ISO
+/// strings back to epoch numbers (`to_epoch`).
+#[tauri::command]
+pub fn json_convert_timestamps(
+    content: &str,
+    direction: String,
+    keys_hint: Option<Vec<String>>,
+) -> Result<ConvertTimestampsResult, String> {
+    let direction = Direction::parse(&direction)?;
+    let mut value = parse_to_value(content)?;
+    let converted_pointers =
+        convert_timestamps(&mut value, direction, &TimestampDetector::new(keys_hint));
+    Ok(ConvertTimestampsResult {
+        content: to_pretty_json(&value)?,
+        converted_pointers,
+    })
+}
+
+/// List detected timestamps with both representations, without modifying
+/// the document.
+#[tauri::command]
+pub fn json_annotate_timestamps(
+    content: &str,
+    keys_hint: Option<Vec<String>>,
+) -> Result<Vec<TimestampAnnotation>, String> {
+    let value = parse_to_value(content)?;
+    Ok(TimestampDetector::new(keys_hint).annotate(&value))
+}
+
+fn to_pretty_json(value: &Value) -> Result<String, String> {
+    serde_json::to_string_pretty(value)
+        .map_err(|error| format!("JSON serialization failed: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_clean, json_convert_keys, json_pick, json_redact, CleanOptions};
+    use serde_json::Value;
+
+    const UNSORTED: &str =
+        r#"{"zeta": 1, "alpha": {"mike": null, "bravo": 2, "yankee": 3}, "kilo": "x"}"#;
+
+    /// Top-level keys followed by the keys of the `alpha` object, in output order.
+    fn key_order(content: &str) -> Vec<String> {
+        let value: Value = serde_json::from_str(content).unwrap();
+        let top = value.as_object().unwrap();
+        let nested = top["alpha"].as_object().unwrap();
+        top.keys().chain(nested.keys()).cloned().collect()
+    }
+
+    #[test]
+    fn transforms_keep_source_key_order() {
+        let cleaned = json_clean(
+            UNSORTED,
+            CleanOptions {
+                remove_nulls: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            key_order(&cleaned.content),
+            ["zeta", "alpha", "kilo", "bravo", "yankee"]
+        );
+
+        let converted = json_convert_keys(UNSORTED, "snake_case".to_string(), true).unwrap();
+        assert_eq!(
+            key_order(&converted.content),
+            ["zeta", "alpha", "kilo", "mike", "bravo", "yankee"]
+        );
+
+        let redacted = json_redact(UNSORTED, vec!["^kilo$".to_string()], None, None, None).unwrap();
+        assert_eq!(
+            key_order(&redacted.content),
+            ["zeta", "alpha", "kilo", "mike", "bravo", "yankee"]
+        );
+
+        let picked =
+            json_pick(UNSORTED, vec!["mike".to_string()], "omit".to_string(), true).unwrap();
+        assert_eq!(
+            key_order(&picked.content),
+            ["zeta", "alpha", "kilo", "bravo", "yankee"]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use serde_json::Value;
+
+use crate::json_pointer::{child_pointer, index_pointer, resolve_mut};
+
+/// Replace string values that hold a JSON object or array with the parsed
+/// value, looking inside expanded values again up to `max_depth` levels.
+/// Returns the expanded pointers, outermost first.
+pub(crate) fn expand_nested(value: &mut Value, max_depth: Option<usize>) -> Vec<String> {
+    let mut expanded = Vec::new();
+    expand_at(value, "", 0, max_depth, &mut expanded);
+    expanded
+}
+
+fn expand_at(
+    value: &mut Value,
+    pointer: &str,
+    level: usize,
+    max_depth: Option<usize>,
+    expanded: &mut Vec<String>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                expand_at(
+                    child,
+                    &child_pointer(pointer, key),
+                    level,
+                    max_depth,
+                    expanded,
+                );
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                expand_at(
+                    item,
+                    &index_pointer(pointer, index),
+                    level,
+                    max_depth,
+                    expanded,
+                );
+            }
+        }
+        Value::String(text) => {
+            if max_depth.is_some_and(|max| level >= max) {
+                return;
+            }
+            // Only containers count: "42" or "true" stay strings.
+            let trimmed = text.trim_start();
+            if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+                return;
+            }
+            if let Ok(parsed) = serde_json::from_str::<Value>(text) {
+                *value = parsed;
+                expanded.push(pointer.to_string());
+                expand_at(value, pointer, level + 1, max_depth, expanded);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Re-stringify the values at `pointers`, innermost first, undoing
+/// `expand_nested` when given the pointers it returned.
+pub(crate) fn collapse_nested(value: &mut Value, pointers: &[String]) -> Result<usize, String> {
+    for pointer in pointers.iter().rev() {
+        let target = resolve_mut(value, pointer)?;
+        *target = Value::String(target.to_string());
+    }
+    Ok(pointers.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collapse_nested, expand_nested};
+    use serde_json::json;
+
+    #[test]
+    fn expands_multiple_levels_and_collapses_back() {
+        let inner = json!({"id": 7}).to_string();
+        let middle = json!({"payload": inner, "tags": "[1,2]"}).to_string();
+        let original = json!({"body": middle, "count": "42", "flag": "true", "text": "{oops"});
+        let mut value = original.clone();
+
+        let pointers = expand_nested(&mut value, None);
+
+        assert_eq!(
+            value,
+            json!({
+                "body": {"payload": {"id": 7}, "tags": [1, 2]},
+                "count": "42",
+                "flag": "true",
+                "text": "{oops"
+            })
+        );
+        assert_eq!(pointers, ["/body", "/body/payload", "/body/tags"]);
+
+        collapse_nested(&mut value, &pointers).unwrap();
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn stops_at_max_depth() {
+        let inner = json!({"id": 7}).to_string();
+        let mut value = json!({"body": json!({"payload": inner}).to_string()});
+
+        let pointers = expand_nested(&mut value, Some(1));
+
+        assert_eq!(pointers, ["/body"]);
+        assert!(value["body"]["payload"].is_string());
+    }
+}
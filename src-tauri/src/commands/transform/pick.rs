@@ -0,0 +1,223 @@
+use regex::Regex;
+use serde_json::{Map, Value};
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum PickMode {
+    Pick,
+    Omit,
+}
+
+impl PickMode {
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "pick" => Ok(Self::Pick),
+            "omit" => Ok(Self::Omit),
+            _ => Err(format!("Unsupported pick mode: {name}")),
+        }
+    }
+}
+
+/// Keeps or removes object keys by glob pattern. A pattern is a dot-separated
+/// key path whose segments may use `*` and `?`, e.g. `*_id` or `meta.*`.
+/// Array indexes are not part of paths, so every element of an array is
+/// processed the same way.
+///
+/// Shallow patterns match the path from the root; deep patterns match the
+/// end of the path at any depth.
+pub(crate) struct KeyPicker {
+    patterns: Vec<Vec<Regex>>,
+    mode: PickMode,
+    deep: bool,
+}
+
+impl KeyPicker {
+    pub(crate) fn new(keys: &[String], mode: PickMode, deep: bool) -> Result<Self, String> {
+        if keys.is_empty() {
+            return Err("At least one key pattern is required".to_string());
+        }
+        let patterns = keys
+            .iter()
+            .map(|pattern| pattern.split('.').map(glob_segment).collect())
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            patterns,
+            mode,
+            deep,
+        })
+    }
+
+    /// Apply the picker to `value`, returning how many keys were removed.
+    pub(crate) fn apply(&self, value: &mut Value) -> usize {
+        self.visit(value, &mut Vec::new())
+    }
+
+    fn visit(&self, value: &mut Value, path: &mut Vec<String>) -> usize {
+        match value {
+            Value::Object(map) => self.visit_object(map, path),
+            Value::Array(items) => items.iter_mut().map(|item| self.visit(item, path)).sum(),
+            _ => 0,
+        }
+    }
+
+    fn visit_object(&self, map: &mut Map<String, Value>, path: &mut Vec<String>) -> usize {
+        let mut removed = 0;
+        let mut keep = Vec::with_capacity(map.len());
+        for (key, child) in map.iter_mut() {
+            path.push(key.clone());
+            let matched = self.matches(path);
+            let kept = match self.mode {
+                PickMode::Omit if matched => false,
+                PickMode::Omit => {
+                    if self.may_match_below(path) {
+                        removed += self.visit(child, path);
+                    }
+                    true
+                }
+                PickMode::Pick if matched => true,
+                // Unmatched containers survive when something inside was picked.
+                PickMode::Pick if self.may_match_below(path) && is_container(child) => {
+                    removed += self.visit(child, path);
+                    has_keys(child)
+                }
+                PickMode::Pick => false,
+            };
+            path.pop();
+            keep.push(kept);
+        }
+        let mut flags = keep.into_iter();
+        map.retain(|_, child| {
+            let kept = flags.next().unwrap_or(true);
+            if !kept {
+                removed += 1 + count_keys(child);
+            }
+            kept
+        });
+        removed
+    }
+
+    fn matches(&self, path: &[String]) -> bool {
+        self.patterns.iter().any(|pattern| {
+            if self.deep {
+                path.len() >= pattern.len()
+                    && segments_match(pattern, &path[path.len() - pattern.len()..])
+            } else {
+                path.len() == pattern.len() && segments_match(pattern, path)
+            }
+        })
+    }
+
+    /// Whether a key under `path` could still match some pattern.
+    fn may_match_below(&self, path: &[String]) -> bool {
+        self.deep
+            || self.patterns.iter().any(|pattern| {
+                pattern.len() > path.len() && segments_match(&pattern[..path.len()], path)
+            })
+    }
+}
+
+fn segments_match(pattern: &[Regex], path: &[String]) -> bool {
+    pattern
+        .iter()
+        .zip(path)
+        .all(|(segment, key)| segment.is_match(key))
+}
+
+fn glob_segment(segment: &str) -> Result<Regex, String> {
+    let mut regex = String::from("^");
+    for c in segment.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            other => regex.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).map_err(|error| format!("Invalid key pattern: {error}"))
+}
+
+fn is_container(value: &Value) -> bool {
+    value.is_object() || value.is_array()
+}
+
+fn has_keys(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => items.iter().any(has_keys),
+        _ => false,
+    }
+}
+
+/// Keys inside a removed value, so the count reflects everything dropped.
+fn count_keys(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => map.len() + map.values().map(count_keys).sum::<usize>(),
+        Value::Array(items) => items.iter().map(count_keys).sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyPicker, PickMode};
+    use serde_json::{json, Value};
+
+    fn pick(mut value: Value, keys: &[&str], mode: PickMode, deep: bool) -> (Value, usize) {
+        let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+        let removed = KeyPicker::new(&keys, mode, deep).unwrap().apply(&mut value);
+        (value, removed)
+    }
+
+    #[test]
+    fn omits_glob_matches_and_keeps_order() {
+        let (value, removed) = pick(
+            json!({"z": 1, "user_id": 2, "a": {"org_id": 3, "b": 4}, "meta": {"x": 1, "y": 2}}),
+            &["*_id", "meta.*"],
+            PickMode::Omit,
+            false,
+        );
+
+        assert_eq!(
+            value,
+            json!({"z": 1, "a": {"org_id": 3, "b": 4}, "meta": {}})
+        );
+        assert_eq!(removed, 3);
+        assert_eq!(
+            value.as_object().unwrap().keys().collect::<Vec<_>>(),
+            ["z", "a", "meta"]
+        );
+    }
+
+    #[test]
+    fn deep_omit_processes_every_object_including_array_elements() {
+        let (value, removed) = pick(
+            json!([{"id": 1, "tags": [{"id": 2, "name": "x"}]}, {"id": 3}]),
+            &["id"],
+            PickMode::Omit,
+            true,
+        );
+
+        assert_eq!(value, json!([{"tags": [{"name": "x"}]}, {}]));
+        assert_eq!(removed, 3);
+    }
+
+    #[test]
+    fn picks_keys_and_the_containers_leading_to_them() {
+        let (value, removed) = pick(
+            json!({"id": 1, "name": "a", "meta": {"created": 1, "owner": {"id": 9}}, "extra": {"k": 1}}),
+            &["id", "meta.owner"],
+            PickMode::Pick,
+            false,
+        );
+
+        assert_eq!(value, json!({"id": 1, "meta": {"owner": {"id": 9}}}));
+        assert_eq!(removed, 4);
+
+        let (deep, _) = pick(
+            json!([{"id": 1, "x": 0, "tags": ["a"], "child": {"id": 2, "y": 0}}]),
+            &["id"],
+            PickMode::Pick,
+            true,
+        );
+        assert_eq!(deep, json!([{"id": 1, "child": {"id": 2}}]));
+    }
+}
@@ -0,0 +1,153 @@
+use regex::{RegexSet, RegexSetBuilder};
+use serde_json::Value;
+
+use crate::json_pointer::{child_pointer, index_pointer};
+
+pub(crate) const DEFAULT_REDACTION: &str = "***";
+
+pub(crate) struct Redactor {
+    patterns: RegexSet,
+    replacement: String,
+    preserve_types: bool,
+}
+
+impl Redactor {
+    pub(crate) fn new(
+        key_patterns: &[String],
+        replacement: Option<String>,
+        case_sensitive: bool,
+        preserve_types: bool,
+    ) -> Result<Self, String> {
+        if key_patterns.is_empty() {
+            return Err("At least one key pattern is required".to_string());
+        }
+        let patterns = RegexSetBuilder::new(key_patterns)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map_err(|error| format!("Invalid key pattern: {error}"))?;
+        Ok(Self {
+            patterns,
+            replacement: replacement.unwrap_or_else(|| DEFAULT_REDACTION.to_string()),
+            preserve_types,
+        })
+    }
+
+    /// Redact every value whose key matches, returning the pointers that changed.
+    pub(crate) fn redact(&self, value: &mut Value) -> Vec<String> {
+        let mut redacted = Vec::new();
+        self.walk(value, "", &mut redacted);
+        redacted
+    }
+
+    fn walk(&self, value: &mut Value, pointer: &str, redacted: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    let child_path = child_pointer(pointer, key);
+                    if self.patterns.is_match(key) {
+                        self.redact_all(child, &child_path, redacted);
+                    } else {
+                        self.walk(child, &child_path, redacted);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter_mut().enumerate() {
+                    self.walk(item, &index_pointer(pointer, index), redacted);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Matched containers keep their shape; every scalar leaf inside is redacted.
+    fn redact_all(&self, value: &mut Value, pointer: &str, redacted: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    self.redact_all(child, &child_pointer(pointer, key), redacted);
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter_mut().enumerate() {
+                    self.redact_all(item, &index_pointer(pointer, index), redacted);
+                }
+            }
+            Value::Null => {}
+            Value::Number(_) if self.preserve_types => {
+                *value = Value::from(0);
+                redacted.push(pointer.to_string());
+            }
+            Value::Bool(_) if self.preserve_types => {
+                *value = Value::Bool(false);
+                redacted.push(pointer.to_string());
+            }
+            _ => {
+                *value = Value::String(self.replacement.clone());
+                redacted.push(pointer.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Redactor;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_matching_keys_at_any_depth() {
+        let redactor = Redactor::new(
+            &["token".to_string(), "^email$".to_string()],
+            None,
+            false,
+            false,
+        )
+        .unwrap();
+        let mut value = json!({
+            "accessToken": "abc",
+            "users": [{"Email": "a@b.c", "name": "A"}, {"email": null}],
+            "auth": {"refresh_token": 42}
+        });
+
+        let redacted = redactor.redact(&mut value);
+
+        assert_eq!(
+            value,
+            json!({
+                "accessToken": "***",
+                "users": [{"Email": "***", "name": "A"}, {"email": null}],
+                "auth": {"refresh_token": "***"}
+            })
+        );
+        assert_eq!(
+            redacted,
+            vec!["/accessToken", "/users/0/Email", "/auth/refresh_token"]
+        );
+    }
+
+    #[test]
+    fn preserves_types_and_container_shape_when_requested() {
+        let redactor = Redactor::new(
+            &["secret".to_string()],
+            Some("[hidden]".to_string()),
+            true,
+            true,
+        )
+        .unwrap();
+        let mut value = json!({"secret": {"pin": 1234, "enabled": true, "hint": "x"}, "Secret": 1});
+
+        let redacted = redactor.redact(&mut value);
+
+        assert_eq!(
+            value,
+            json!({"secret": {"pin": 0, "enabled": false, "hint": "[hidden]"}, "Secret": 1})
+        );
+        assert_eq!(redacted.len(), 3);
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(Redactor::new(&["(".to_string()], None, false, false).is_err());
+    }
+}
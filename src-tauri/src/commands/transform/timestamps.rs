@@ -0,0 +1,230 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::json_pointer::{child_pointer, index_pointer};
+
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum EpochUnit {
+    Seconds,
+    Milliseconds,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum StoredAs {
+    Epoch,
+    Iso,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TimestampAnnotation {
+    pub pointer: String,
+    pub epoch: i64,
+    pub unit: EpochUnit,
+    pub iso: String,
+    /// How the value is currently written in the document.
+    pub stored_as: StoredAs,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Direction {
+    ToIso,
+    ToEpoch,
+}
+
+impl Direction {
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "to_iso" => Ok(Self::ToIso),
+            "to_epoch" => Ok(Self::ToEpoch),
+            _ => Err(format!("Unsupported timestamp direction: {name}")),
+        }
+    }
+}
+
+/// Finds epoch numbers and RFC 3339 strings. Numbers count when they have 10
+/// (seconds) or 13 (milliseconds) digits, or sit under a timestamp-like key
+/// (`*_at`, `time`, `timestamp`, or one of the hints).
+pub(crate) struct TimestampDetector {
+    hints: Vec<String>,
+}
+
+impl TimestampDetector {
+    pub(crate) fn new(keys_hint: Option<Vec<String>>) -> Self {
+        Self {
+            hints: keys_hint
+                .unwrap_or_default()
+                .into_iter()
+                .map(|hint| hint.to_lowercase())
+                .collect(),
+        }
+    }
+
+    fn is_timestamp_key(&self, key: &str) -> bool {
+        let key = key.to_lowercase();
+        key.ends_with("_at") || key == "time" || key == "timestamp" || self.hints.contains(&key)
+    }
+
+    pub(crate) fn annotate(&self, value: &Value) -> Vec<TimestampAnnotation> {
+        let mut found = Vec::new();
+        self.visit(value, None, "", &mut found);
+        found
+    }
+
+    fn visit(
+        &self,
+        value: &Value,
+        key: Option<&str>,
+        pointer: &str,
+        found: &mut Vec<TimestampAnnotation>,
+    ) {
+        match value {
+            Value::Object(map) => {
+                for (child_key, child) in map {
+                    self.visit(
+                        child,
+                        Some(child_key),
+                        &child_pointer(pointer, child_key),
+                        found,
+                    );
+                }
+            }
+            Value::Array(items) => {
+                // Elements of a `timestamps: [...]` array inherit the key.
+                for (index, item) in items.iter().enumerate() {
+                    self.visit(item, key, &index_pointer(pointer, index), found);
+                }
+            }
+            Value::Number(number) => {
+                let hinted = key.is_some_and(|key| self.is_timestamp_key(key));
+                if let Some((epoch, unit, iso)) =
+                    number.as_i64().and_then(|n| epoch_to_iso(n, hinted))
+                {
+                    found.push(TimestampAnnotation {
+                        pointer: pointer.to_string(),
+                        epoch,
+                        unit,
+                        iso,
+                        stored_as: StoredAs::Epoch,
+                    });
+                }
+            }
+            Value::String(text) => {
+                if let Ok(parsed) = DateTime::parse_from_rfc3339(text) {
+                    let (epoch, unit) = if parsed.timestamp_subsec_nanos() == 0 {
+                        (parsed.timestamp(), EpochUnit::Seconds)
+                    } else {
+                        (parsed.timestamp_millis(), EpochUnit::Milliseconds)
+                    };
+                    found.push(TimestampAnnotation {
+                        pointer: pointer.to_string(),
+                        epoch,
+                        unit,
+                        iso: text.clone(),
+                        stored_as: StoredAs::Iso,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn epoch_to_iso(epoch: i64, hinted: bool) -> Option<(i64, EpochUnit, String)> {
+    if epoch <= 0 {
+        return None;
+    }
+    let unit = match epoch.to_string().len() {
+        10 => EpochUnit::Seconds,
+        13 => EpochUnit::Milliseconds,
+        _ if hinted && epoch >= 100_000_000_000 => EpochUnit::Milliseconds,
+        _ if hinted => EpochUnit::Seconds,
+        _ => return None,
+    };
+    let (time, format) = match unit {
+        EpochUnit::Seconds => (
+            DateTime::<Utc>::from_timestamp(epoch, 0)?,
+            SecondsFormat::Secs,
+        ),
+        EpochUnit::Milliseconds => (
+            DateTime::<Utc>::from_timestamp_millis(epoch)?,
+            SecondsFormat::Millis,
+        ),
+    };
+    Some((epoch, unit, time.to_rfc3339_opts(format, true)))
+}
+
+/// Rewrite detected timestamps in `direction`, returning the changed pointers.
+/// ISO strings with fractional seconds become epoch milliseconds, others
+/// epoch seconds, so converting back and forth is lossless.
+pub(crate) fn convert_timestamps(
+    value: &mut Value,
+    direction: Direction,
+    detector: &TimestampDetector,
+) -> Vec<String> {
+    let wanted = match direction {
+        Direction::ToIso => StoredAs::Epoch,
+        Direction::ToEpoch => StoredAs::Iso,
+    };
+    let mut converted = Vec::new();
+    for found in detector.annotate(value) {
+        if found.stored_as != wanted {
+            continue;
+        }
+        if let Some(target) = value.pointer_mut(&found.pointer) {
+            *target = match direction {
+                Direction::ToIso => Value::String(found.iso),
+                Direction::ToEpoch => Value::from(found.epoch),
+            };
+            converted.push(found.pointer);
+        }
+    }
+    converted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_timestamps, Direction, EpochUnit, StoredAs, TimestampDetector};
+    use serde_json::json;
+
+    #[test]
+    fn detects_epochs_by_digits_and_by_key() {
+        let value = json!({
+            "created": 1700000000,
+            "ms": 1700000000123i64,
+            "updated_at": 86400,
+            "expiry": 86400,
+            "count": 42,
+            "seen": "2023-11-14T22:13:20Z"
+        });
+
+        let found = TimestampDetector::new(Some(vec!["Expiry".to_string()])).annotate(&value);
+
+        let pointers: Vec<_> = found.iter().map(|f| f.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            ["/created", "/ms", "/updated_at", "/expiry", "/seen"]
+        );
+        assert_eq!(found[0].iso, "2023-11-14T22:13:20Z");
+        assert_eq!(found[1].unit, EpochUnit::Milliseconds);
+        assert_eq!(found[1].iso, "2023-11-14T22:13:20.123Z");
+        assert_eq!(found[4].stored_as, StoredAs::Iso);
+        assert_eq!(found[4].epoch, 1700000000);
+    }
+
+    #[test]
+    fn converts_to_iso_and_back_losslessly() {
+        let original = json!({"at": [1700000000, 1700000000123i64], "name": "x"});
+        let detector = TimestampDetector::new(None);
+        let mut value = original.clone();
+
+        let to_iso = convert_timestamps(&mut value, Direction::ToIso, &detector);
+        assert_eq!(to_iso, ["/at/0", "/at/1"]);
+        assert_eq!(value["at"][1], "2023-11-14T22:13:20.123Z");
+
+        convert_timestamps(&mut value, Direction::ToEpoch, &detector);
+        assert_eq!(value, original);
+    }
+}
@@ -0,0 +1,411 @@
+// Multi-error JSON validation command
+//
+// Unlike json_validate, which stops at the first problem, this walks the
+// token stream with a tolerant parser: after an error it skips ahead to the
+// next `,`, `}` or `]` at the same nesting level and keeps going. The input
+// accepted as valid matches json_validate (JSON plus the JSON5 extras).
+// Containers nested past serde_json's limit are reported and skipped rather
+// than recursed into, so deep input can't overflow the stack.
+use serde::Serialize;
+use std::iter::Peekable;
+use tauri::Window;
+
+use super::tasks::spawn_task;
+use crate::json_lexer::{Lexer, Token, TokenKind};
+use crate::json_simd::SERDE_MAX_DEPTH;
+
+#[derive(Serialize, Debug)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// Set on every error after the first: once the parser has had to guess
+    /// how to resynchronize, later positions may be knock-on effects.
+    pub best_effort: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ValidateAllResult {
+    pub valid: bool,
+    pub errors: Vec<SyntaxError>,
+    /// Whether validation stopped after reaching `max_errors`.
+    pub truncated: bool,
+}
+
+/// Validate JSON and report up to `max_errors` syntax errors in one pass
+#[tauri::command]
+pub fn json_validate_all(content: &str, max_errors: usize) -> ValidateAllResult {
+    let mut checker = Checker {
+        tokens: Lexer::new(content).peekable(),
+        errors: Vec::new(),
+        max_errors: max_errors.max(1),
+        end: end_position(content),
+        depth: 0,
+    };
+    let truncated = checker.check_document().is_err();
+    ValidateAllResult {
+        valid: checker.errors.is_empty(),
+        errors: checker.errors,
+        truncated,
+    }
+}
+
+/// `json_validate_all` as a background task; returns its id (see `spawn_task`)
+#[tauri::command]
+pub fn json_validate_all_async(window: Window, content: String, max_errors: usize) -> String {
+    spawn_task(window, "validate", move |task| {
+        task.step(0, 1)?;
+        Ok(json_validate_all(&content, max_errors))
+    })
+}
+
+/// Returned once `max_errors` is reached to unwind the parser.
+struct Stop;
+
+struct Checker<'a> {
+    tokens: Peekable<Lexer<'a>>,
+    errors: Vec<SyntaxError>,
+    max_errors: usize,
+    end: (usize, usize),
+    /// Containers currently open.
+    depth: usize,
+}
+
+impl Checker<'_> {
+    fn peek_kind(&mut self) -> Option<&TokenKind> {
+        self.tokens.peek().map(|token| &token.kind)
+    }
+
+    fn report(&mut self, position: (usize, usize), message: String) -> Result<(), Stop> {
+        if self.errors.len() >= self.max_errors {
+            return Err(Stop);
+        }
+        self.errors.push(SyntaxError {
+            line: position.0,
+            column: position.1,
+            message,
+            best_effort: !self.errors.is_empty(),
+        });
+        Ok(())
+    }
+
+    /// Report at the next token, or at the end of the input.
+    fn report_here(&mut self, message: String) -> Result<(), Stop> {
+        let position = self
+            .tokens
+            .peek()
+            .map_or(self.end, |token| (token.line, token.column));
+        self.report(position, message)
+    }
+
+    fn check_document(&mut self) -> Result<(), Stop> {
+        if self.tokens.peek().is_none() {
+            return self.report(self.end, "Document is empty".to_string());
+        }
+        self.check_value()?;
+        if let Some(token) = self.tokens.peek() {
+            let message = format!("Unexpected {} after the document", describe(&token.kind));
+            self.report_here(message)?;
+        }
+        Ok(())
+    }
+
+    fn check_value(&mut self) -> Result<(), Stop> {
+        let Some(kind) = self.peek_kind().cloned() else {
+            return self.report(
+                self.end,
+                "Unexpected end of input, expected a value".to_string(),
+            );
+        };
+        match kind {
+            TokenKind::BeginObject | TokenKind::BeginArray => {
+                let open = self.tokens.next().expect("peeked token");
+                if self.depth == SERDE_MAX_DEPTH {
+                    let message = format!("Nesting is deeper than {SERDE_MAX_DEPTH} levels");
+                    self.report((open.line, open.column), message)?;
+                    self.skip_container();
+                    return Ok(());
+                }
+                self.depth += 1;
+                let result = self.check_container(open);
+                self.depth -= 1;
+                result
+            }
+            TokenKind::String(_) => {
+                self.tokens.next();
+                Ok(())
+            }
+            TokenKind::Number(text) => {
+                let token = self.tokens.next().expect("peeked token");
+                if is_valid_number(&text) {
+                    Ok(())
+                } else {
+                    self.report(
+                        (token.line, token.column),
+                        format!("Invalid number '{text}'"),
+                    )
+                }
+            }
+            TokenKind::Word(word) => {
+                let token = self.tokens.next().expect("peeked token");
+                if matches!(
+                    word.as_str(),
+                    "true" | "false" | "null" | "NaN" | "Infinity"
+                ) {
+                    Ok(())
+                } else {
+                    let message = format!("Unexpected word '{word}', expected a value");
+                    self.report((token.line, token.column), message)
+                }
+            }
+            // Leave separators and closers for the enclosing container.
+            TokenKind::Comma | TokenKind::EndObject | TokenKind::EndArray => {
+                self.report_here(format!("Expected a value, found {}", describe(&kind)))
+            }
+            TokenKind::Colon | TokenKind::Unexpected(_) => {
+                self.report_here(format!("Expected a value, found {}", describe(&kind)))?;
+                self.tokens.next();
+                Ok(())
+            }
+        }
+    }
+
+    fn check_container(&mut self, open: Token) -> Result<(), Stop> {
+        let is_object = open.kind == TokenKind::BeginObject;
+        let (closer, name) = if is_object {
+            (TokenKind::EndObject, "object")
+        } else {
+            (TokenKind::EndArray, "array")
+        };
+        loop {
+            match self.peek_kind() {
+                Some(kind) if *kind == closer => {
+                    self.tokens.next();
+                    return Ok(());
+                }
+                Some(TokenKind::EndObject | TokenKind::EndArray) | None => {
+                    return self.report_unclosed(&open, name);
+                }
+                _ => {}
+            }
+
+            if is_object && !self.check_member_key()? {
+                if self.resync(&closer) {
+                    continue;
+                }
+                return Ok(());
+            }
+            self.check_value()?;
+
+            match self.peek_kind() {
+                Some(TokenKind::Comma) => {
+                    self.tokens.next();
+                }
+                // Closers are handled at the top of the loop.
+                Some(TokenKind::EndObject | TokenKind::EndArray) | None => {}
+                // Looks like the start of the next entry: assume a missing comma.
+                Some(
+                    TokenKind::String(_)
+                    | TokenKind::Word(_)
+                    | TokenKind::Number(_)
+                    | TokenKind::BeginObject
+                    | TokenKind::BeginArray,
+                ) => {
+                    self.report_here(format!("Expected ',' between {name} entries"))?;
+                }
+                Some(kind) => {
+                    let message =
+                        format!("Expected ',' or end of {name}, found {}", describe(kind));
+                    self.report_here(message)?;
+                    if !self.resync(&closer) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check `key:`. Returns false when no key could be read at all.
+    fn check_member_key(&mut self) -> Result<bool, Stop> {
+        match self.peek_kind() {
+            Some(TokenKind::String(_) | TokenKind::Word(_)) => {
+                self.tokens.next();
+            }
+            Some(kind) => {
+                let message = format!("Expected an object key, found {}", describe(kind));
+                self.report_here(message)?;
+                return Ok(false);
+            }
+            None => return Ok(true),
+        }
+        if self.peek_kind() == Some(&TokenKind::Colon) {
+            self.tokens.next();
+        } else {
+            self.report_here("Expected ':' after object key".to_string())?;
+        }
+        Ok(true)
+    }
+
+    /// Skip to the next `,` or closer at the current nesting level. Returns
+    /// true when positioned to read another entry of the current container.
+    fn resync(&mut self, closer: &TokenKind) -> bool {
+        let mut depth = 0usize;
+        while let Some(kind) = self.peek_kind() {
+            match kind {
+                TokenKind::BeginObject | TokenKind::BeginArray => depth += 1,
+                TokenKind::EndObject | TokenKind::EndArray if depth == 0 => {
+                    return kind == closer;
+                }
+                TokenKind::EndObject | TokenKind::EndArray => depth -= 1,
+                TokenKind::Comma if depth == 0 => {
+                    self.tokens.next();
+                    return true;
+                }
+                _ => {}
+            }
+            self.tokens.next();
+        }
+        false
+    }
+
+    /// Skip the rest of a container whose opener was just consumed.
+    fn skip_container(&mut self) {
+        let mut depth = 1usize;
+        for token in self.tokens.by_ref() {
+            match token.kind {
+                TokenKind::BeginObject | TokenKind::BeginArray => depth += 1,
+                TokenKind::EndObject | TokenKind::EndArray => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn report_unclosed(&mut self, open: &Token, name: &str) -> Result<(), Stop> {
+        let found = self
+            .peek_kind()
+            .map_or_else(|| "end of input".to_string(), describe);
+        let message = format!(
+            "Unclosed {name} opened at line {} column {}, found {found}",
+            open.line, open.column
+        );
+        self.report_here(message)
+    }
+}
+
+fn describe(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::BeginObject => "'{'".to_string(),
+        TokenKind::EndObject => "'}'".to_string(),
+        TokenKind::BeginArray => "'['".to_string(),
+        TokenKind::EndArray => "']'".to_string(),
+        TokenKind::Colon => "':'".to_string(),
+        TokenKind::Comma => "','".to_string(),
+        TokenKind::String(_) => "a string".to_string(),
+        TokenKind::Number(text) => format!("number '{text}'"),
+        TokenKind::Word(word) => format!("'{word}'"),
+        TokenKind::Unexpected(c) => format!("'{c}'"),
+    }
+}
+
+/// JSON numbers plus the JSON5 forms: hex, leading/trailing dot, leading `+`.
+fn is_valid_number(text: &str) -> bool {
+    serde_json::from_str::<serde_json::Number>(text).is_ok()
+        || json5::from_str::<serde_json::Value>(text).is_ok_and(|value| value.is_number())
+}
+
+fn end_position(content: &str) -> (usize, usize) {
+    let line = content.matches('\n').count() + 1;
+    let last_line = content.rsplit('\n').next().unwrap_or("");
+    (line, last_line.chars().count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_validate_all;
+    use crate::json_simd::SERDE_MAX_DEPTH;
+
+    fn errors(content: &str, max_errors: usize) -> Vec<String> {
+        json_validate_all(content, max_errors)
+            .errors
+            .iter()
+            .map(|error| format!("{}:{} {}", error.line, error.column, error.message))
+            .collect()
+    }
+
+    #[test]
+    fn reports_independent_errors_in_one_pass() {
+        let content =
+            "{\n  \"a\": 1\n  \"b\": tru,\n  \"c\": [1, 2 3],\n  \"d\": {\"x\" 1},\n  \"e\": 01\n}";
+
+        let result = json_validate_all(content, 10);
+
+        assert!(!result.valid);
+        assert!(!result.truncated);
+        assert!(!result.errors[0].best_effort);
+        assert!(result.errors[1..].iter().all(|error| error.best_effort));
+        assert_eq!(
+            errors(content, 10),
+            [
+                "3:3 Expected ',' between object entries",
+                "3:8 Unexpected word 'tru', expected a value",
+                "4:14 Expected ',' between array entries",
+                "5:13 Expected ':' after object key",
+                "6:8 Invalid number '01'",
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_max_errors_and_reports_unclosed_containers() {
+        let content = "[1 2 3 4";
+
+        let result = json_validate_all(content, 2);
+
+        assert!(result.truncated);
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(
+            errors(content, 10),
+            [
+                "1:4 Expected ',' between array entries",
+                "1:6 Expected ',' between array entries",
+                "1:8 Expected ',' between array entries",
+                "1:9 Unclosed array opened at line 1 column 1, found end of input",
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_valid_json_and_json5() {
+        assert!(json_validate_all(r#"{"a": [1, {"b": null}]}"#, 5).valid);
+        assert!(json_validate_all("{a: 'x', b: [0x1F, .5,], // note\n}", 5).valid);
+        assert_eq!(errors("", 5), ["1:1 Document is empty"]);
+        assert_eq!(
+            errors("{\"a\": 1} }", 5),
+            ["1:10 Unexpected '}' after the document"]
+        );
+    }
+
+    #[test]
+    fn reports_nesting_past_the_serde_limit_without_recursing() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+        assert!(json_validate_all(&nested(SERDE_MAX_DEPTH), 5).valid);
+        assert_eq!(
+            errors(&nested(SERDE_MAX_DEPTH + 1), 5),
+            [format!(
+                "1:{} Nesting is deeper than {SERDE_MAX_DEPTH} levels",
+                SERDE_MAX_DEPTH + 1
+            )]
+        );
+        let deep = format!("{{\"a\": {}, \"b\": tru}}", nested(100_000));
+        assert_eq!(
+            errors(&deep, 5)[1..],
+            ["1:200014 Unexpected word 'tru', expected a value"]
+        );
+    }
+}
@@ -1,21 +1,375 @@
 // Window-related commands
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tauri::window::Effect;
+use tauri::{
+    AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, PhysicalSize, State, WebviewWindow,
+    WebviewWindowBuilder,
+};
+
+use super::settings::{
+    setting, FOLLOW_SYSTEM_THEME_SETTING, IS_DARK_MODE_SETTING, RESTORE_WINDOW_GEOMETRY_SETTING,
+};
+use super::store::{
+    devtools_preference, mini_mode_size, save_devtools_preference, save_mini_mode_size,
+    save_window_theme_preference, save_zoom_preference, window_theme_preference, zoom_preference,
+};
+use crate::app_state::forget_window;
+use crate::window_bounds::{self, cancel_window_geometry_save, schedule_window_geometry_save};
+
+pub(crate) const MAIN_WINDOW_LABEL: &str = "main";
+/// Set to `1` to allow devtools in a release build.
+const DEVTOOLS_ENV: &str = "JSONSTUDIO_DEVTOOLS";
+/// Webview zoom factors outside this range make the UI unusable.
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 3.0;
+/// Extra editor windows are labelled `editor-1`, `editor-2`, ...
+const EDITOR_WINDOW_PREFIX: &str = "editor-";
+/// Logical size of a window entering mini mode for the first time, and the
+/// smallest it can be resized to there.
+const MINI_MODE_SIZE: (f64, f64) = (420.0, 320.0);
+const MINI_MODE_MIN_SIZE: (f64, f64) = (280.0, 160.0);
+
+/// Labels of the windows the user pinned on top.
+#[derive(Default)]
+pub struct PinnedWindows(Mutex<HashSet<String>>);
+
+/// Whether the user pinned `window` on top, as opposed to a temporary raise.
+pub(crate) fn is_pinned(window: &tauri::WebviewWindow) -> bool {
+    window
+        .state::<PinnedWindows>()
+        .0
+        .lock()
+        .unwrap()
+        .contains(window.label())
+}
+
+/// Geometry a window had before entering mini mode, restored when it leaves.
+struct SavedGeometry {
+    /// Position and size of the window when neither maximized nor fullscreen.
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    maximized: bool,
+    fullscreen: bool,
+    decorated: bool,
+}
+
+/// Windows in mini mode, by label.
+#[derive(Default)]
+pub struct MiniModeWindows(Mutex<HashMap<String, SavedGeometry>>);
+
+fn is_mini_mode(app: &AppHandle, label: &str) -> bool {
+    app.state::<MiniModeWindows>()
+        .0
+        .lock()
+        .unwrap()
+        .contains_key(label)
+}
+
+/// Vibrancy material of each window that has one, kept so theme changes can
+/// put it back.
+#[derive(Default)]
+pub struct WindowVibrancy(Mutex<HashMap<String, Effect>>);
+
+/// Editor windows opened with `new_window`, next to `main`.
+#[derive(Default)]
+pub struct EditorWindows {
+    next_id: AtomicUsize,
+    /// Content a new window opens with, taken once by its frontend.
+    initial_content: Mutex<HashMap<String, String>>,
+    /// The editor window focused most recently.
+    last_focused: Mutex<Option<String>>,
+}
+
+/// Whether `label` is an editor window, as opposed to e.g. the quick peek.
+pub(crate) fn is_editor_window(label: &str) -> bool {
+    label == MAIN_WINDOW_LABEL || label.starts_with(EDITOR_WINDOW_PREFIX)
+}
+
+/// The editor window that shortcuts and opened files go to: the one focused
+/// last, else `main`, else any other.
+pub(crate) fn target_window(app: &AppHandle) -> Option<WebviewWindow> {
+    let windows = app.webview_windows();
+    let last_focused = app
+        .state::<EditorWindows>()
+        .last_focused
+        .lock()
+        .unwrap()
+        .clone();
+    let labels: Vec<&str> = windows.keys().map(String::as_str).collect();
+    pick_target(&labels, last_focused.as_deref()).and_then(|label| windows.get(label).cloned())
+}
+
+fn pick_target<'a>(labels: &[&'a str], last_focused: Option<&str>) -> Option<&'a str> {
+    let mut editors: Vec<&str> = labels
+        .iter()
+        .copied()
+        .filter(|label| is_editor_window(label))
+        .collect();
+    editors.sort();
+    editors
+        .iter()
+        .find(|label| Some(**label) == last_focused)
+        .or_else(|| editors.iter().find(|label| **label == MAIN_WINDOW_LABEL))
+        .or_else(|| editors.first())
+        .copied()
+}
+
+/// Track focus for `target_window`, pass system theme changes on, and quit once the last editor window is
+/// gone so a hidden helper window can't keep the app running.
+pub(crate) fn handle_window_event(app: &AppHandle, label: &str, event: &tauri::WindowEvent) {
+    if !is_editor_window(label) {
+        return;
+    }
+    let windows = app.state::<EditorWindows>();
+    match event {
+        // The mini mode size is remembered on its own.
+        tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)
+            if !is_mini_mode(app, label) =>
+        {
+            schedule_window_geometry_save(app);
+        }
+        tauri::WindowEvent::CloseRequested { .. } => {
+            // Closing in mini mode must not leave the strip as the saved geometry.
+            if let Some(window) = app.get_webview_window(label) {
+                if let Err(error) = leave_mini_mode(&window) {
+                    log::warn!("Failed to leave mini mode: {error}");
+                }
+            }
+        }
+        tauri::WindowEvent::ThemeChanged(theme) => follow_system_theme(app, label, theme),
+        tauri::WindowEvent::Focused(true) => {
+            *windows.last_focused.lock().unwrap() = Some(label.to_string());
+        }
+        tauri::WindowEvent::Destroyed => {
+            windows.initial_content.lock().unwrap().remove(label);
+            forget_window(app, label);
+            app.state::<MiniModeWindows>()
+                .0
+                .lock()
+                .unwrap()
+                .remove(label);
+            app.state::<WindowVibrancy>()
+                .0
+                .lock()
+                .unwrap()
+                .remove(label);
+            let remaining = app
+                .webview_windows()
+                .into_keys()
+                .any(|other| other != label && is_editor_window(&other));
+            if !remaining {
+                app.exit(0);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Open another editor window, optionally with a document in its first tab
+///
+/// Returns the new window's label. Extra windows start with their own tabs
+/// and don't persist them; only `main` restores its session.
+#[tauri::command]
+pub fn new_window(
+    app: AppHandle,
+    windows: State<'_, EditorWindows>,
+    initial_content: Option<String>,
+    title: Option<String>,
+) -> Result<String, String> {
+    let id = windows.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+    let label = format!("{EDITOR_WINDOW_PREFIX}{id}");
+    let mut config = main_window_config(&app)?;
+    config.label = label.clone();
+    if let Some(title) = title {
+        config.title = title;
+    }
+    if let Some(content) = initial_content {
+        windows
+            .initial_content
+            .lock()
+            .unwrap()
+            .insert(label.clone(), content);
+    }
+    build_editor_window(&app, &config)?;
+    Ok(label)
+}
+
+fn main_window_config(app: &AppHandle) -> Result<tauri::utils::config::WindowConfig, String> {
+    app.config()
+        .app
+        .windows
+        .iter()
+        .find(|config| config.label == MAIN_WINDOW_LABEL)
+        .cloned()
+        .ok_or_else(|| "Main window configuration not found".to_string())
+}
+
+fn build_editor_window(
+    app: &AppHandle,
+    config: &tauri::utils::config::WindowConfig,
+) -> Result<WebviewWindow, String> {
+    let window = WebviewWindowBuilder::from_config(app, config)
+        .and_then(|builder| builder.devtools(devtools_allowed(app)).build())
+        .map_err(|e| format!("Failed to open window: {}", e))?;
+    apply_window_preferences(&window);
+    #[cfg(target_os = "macos")]
+    if let Ok(ns_window) = window.ns_window() {
+        apply_macos_transparent_chrome(ns_window);
+    }
+    Ok(window)
+}
+
+/// Create the main window in setup rather than from the config, since
+/// whether it gets devtools depends on the user's settings.
+pub(crate) fn create_main_window(app: &AppHandle) -> Result<(), String> {
+    let window = build_editor_window(app, &main_window_config(app)?)?;
+    // The window state plugin restored the last geometry while building it.
+    if setting::<bool>(app, RESTORE_WINDOW_GEOMETRY_SETTING) == Some(false) {
+        window_bounds::reset_window_geometry(&window)?;
+    }
+    Ok(())
+}
+
+/// Devtools are always available in debug builds; release builds need
+/// `JSONSTUDIO_DEVTOOLS=1` or the `advanced.enable_devtools` setting.
+pub(crate) fn devtools_allowed(app: &AppHandle) -> bool {
+    cfg!(debug_assertions)
+        || std::env::var(DEVTOOLS_ENV).is_ok_and(|value| value == "1")
+        || devtools_preference(app)
+}
+
+/// The content this window was opened with by `new_window`, once
+#[tauri::command]
+pub fn take_window_initial_content(
+    window: WebviewWindow,
+    windows: State<'_, EditorWindows>,
+) -> Option<String> {
+    windows
+        .initial_content
+        .lock()
+        .unwrap()
+        .remove(window.label())
+}
 
 fn transparent_window_background() -> tauri::window::Color {
     tauri::window::Color(0, 0, 0, 0)
 }
 
-/// Set the native window theme while leaving custom chrome transparency intact.
-#[tauri::command]
-pub fn set_window_theme(window: tauri::Window, is_dark: bool) -> Result<(), String> {
-    let theme = if is_dark {
+fn native_theme(is_dark: bool) -> tauri::Theme {
+    if is_dark {
         tauri::Theme::Dark
     } else {
         tauri::Theme::Light
-    };
-    window.set_theme(Some(theme)).map_err(|e| e.to_string())?;
+    }
+}
+
+/// The theme in the settings: `Some(None)` when it follows the system, `None`
+/// when the settings don't have one.
+fn theme_setting(app: &AppHandle) -> Option<Option<bool>> {
+    if setting::<bool>(app, FOLLOW_SYSTEM_THEME_SETTING) == Some(true) {
+        return Some(None);
+    }
+    setting::<bool>(app, IS_DARK_MODE_SETTING).map(Some)
+}
+
+/// Give a new window the theme from the settings, else the one the frontend
+/// last applied, else the system one, before its page loads and sets it, so
+/// the first paint matches.
+fn apply_saved_window_theme(window: &WebviewWindow) {
+    let app = window.app_handle();
+    let saved = theme_setting(app).unwrap_or_else(|| window_theme_preference(app));
+    if let Some(is_dark) = saved {
+        if let Err(error) = window.set_theme(Some(native_theme(is_dark))) {
+            log::warn!("Failed to apply window theme: {error}");
+        }
+    }
+    #[cfg(windows)]
+    {
+        let is_dark = saved.unwrap_or_else(|| matches!(window.theme(), Ok(tauri::Theme::Dark)));
+        if let Ok(hwnd) = window.hwnd() {
+            set_windows_dark_title_bar(hwnd.0, is_dark);
+        }
+    }
+}
+
+/// Give a new editor window the saved theme and zoom.
+fn apply_window_preferences(window: &WebviewWindow) {
+    apply_saved_window_theme(window);
+    if let Some(factor) = zoom_preference(window.app_handle()) {
+        if let Err(error) = window.set_zoom(clamp_zoom(factor)) {
+            log::warn!("Failed to apply zoom: {error}");
+        }
+    }
+}
+
+fn theme_name(theme: &tauri::Theme) -> &'static str {
+    match theme {
+        tauri::Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+/// Match the title bar to a new system theme unless the user picked one, and
+/// tell the window's frontend, which follows it when set to "system".
+fn follow_system_theme(app: &AppHandle, label: &str, theme: &tauri::Theme) {
+    #[cfg(windows)]
+    if window_theme_preference(app).is_none() {
+        if let Some(hwnd) = app
+            .get_webview_window(label)
+            .and_then(|window| window.hwnd().ok())
+        {
+            set_windows_dark_title_bar(hwnd.0, matches!(theme, tauri::Theme::Dark));
+        }
+    }
+    #[cfg(target_os = "macos")]
+    if window_theme_preference(app).is_none() {
+        // The appearance follows the system by itself; the titlebar relayout
+        // still moves the traffic lights.
+        if let Some(ns_window) = app
+            .get_webview_window(label)
+            .and_then(|window| window.ns_window().ok())
+        {
+            apply_macos_transparent_chrome(ns_window);
+        }
+    }
+    let _ = app.emit_to(label, "system-theme-changed", theme_name(theme));
+}
+
+/// The theme the OS gives windows that follow it, `"dark"` or `"light"`
+#[tauri::command]
+pub fn get_system_theme(window: WebviewWindow) -> Result<&'static str, String> {
+    window
+        .theme()
+        .map(|theme| theme_name(&theme))
+        .map_err(|e| e.to_string())
+}
+
+/// Set the native window theme while leaving custom chrome transparency intact.
+///
+/// With `follow_system` the window is left to follow the OS theme instead,
+/// and `is_dark` only describes the theme it currently has.
+#[tauri::command]
+pub fn set_window_theme(
+    window: tauri::Window,
+    is_dark: bool,
+    follow_system: Option<bool>,
+) -> Result<(), String> {
+    let follow_system = follow_system.unwrap_or(false);
+    let chosen = (!follow_system).then_some(is_dark);
+    window
+        .set_theme(chosen.map(native_theme))
+        .map_err(|e| e.to_string())?;
     window
         .set_background_color(Some(transparent_window_background()))
         .map_err(|e| e.to_string())?;
+    if let Err(error) = save_window_theme_preference(window.app_handle(), chosen) {
+        log::warn!("Failed to save window theme: {error}");
+    }
+
+    #[cfg(windows)]
+    set_windows_dark_title_bar(window.hwnd().map_err(|e| e.to_string())?.0, is_dark);
 
     #[cfg(target_os = "macos")]
     {
@@ -26,28 +380,140 @@ pub fn set_window_theme(window: tauri::Window, is_dark: bool) -> Result<(), Stri
         let ns_window = window.ns_window().map_err(|e| e.to_string())? as id;
 
         unsafe {
-            let appearance_name = if is_dark {
-                // Dark mode
-                NSString::alloc(nil).init_str("NSAppearanceNameDarkAqua")
+            let appearance: id = if follow_system {
+                // No appearance of its own: the window follows the system
+                nil
             } else {
-                // Light mode
-                NSString::alloc(nil).init_str("NSAppearanceNameAqua")
-            };
+                let appearance_name = if is_dark {
+                    // Dark mode
+                    NSString::alloc(nil).init_str("NSAppearanceNameDarkAqua")
+                } else {
+                    // Light mode
+                    NSString::alloc(nil).init_str("NSAppearanceNameAqua")
+                };
 
-            // Get NSAppearance class
-            let appearance_class = objc::class!(NSAppearance);
-            let appearance: id = msg_send![appearance_class, appearanceNamed: appearance_name];
+                // Get NSAppearance class
+                let appearance_class = objc::class!(NSAppearance);
+                msg_send![appearance_class, appearanceNamed: appearance_name]
+            };
             let _: () = msg_send![ns_window, setAppearance: appearance];
         }
 
         // Changing the appearance relayouts the native titlebar. Position the
         // traffic lights after that relayout so a theme switch cannot move them.
         apply_macos_transparent_chrome(ns_window as *mut std::ffi::c_void);
+        restore_macos_vibrancy(&window)?;
     }
 
     Ok(())
 }
 
+fn vibrancy_material(name: &str) -> Result<Effect, String> {
+    match name {
+        "sidebar" => Ok(Effect::Sidebar),
+        "hud" => Ok(Effect::HudWindow),
+        "under-window" => Ok(Effect::UnderWindowBackground),
+        "under-page" => Ok(Effect::UnderPageBackground),
+        "window" => Ok(Effect::WindowBackground),
+        "content" => Ok(Effect::ContentBackground),
+        "header" => Ok(Effect::HeaderView),
+        "titlebar" => Ok(Effect::Titlebar),
+        "menu" => Ok(Effect::Menu),
+        "popover" => Ok(Effect::Popover),
+        "sheet" => Ok(Effect::Sheet),
+        "tooltip" => Ok(Effect::Tooltip),
+        _ => Err(format!("Unsupported vibrancy material: {name}")),
+    }
+}
+
+/// Show a frosted-glass `material` (`sidebar`, `hud`, `under-window`, ...)
+/// behind the window content, or remove it with `None`
+///
+/// Vibrancy is macOS only; elsewhere this does nothing.
+#[tauri::command]
+pub fn set_window_vibrancy(window: WebviewWindow, material: Option<String>) -> Result<(), String> {
+    let effect = material.as_deref().map(vibrancy_material).transpose()?;
+    #[cfg(target_os = "macos")]
+    {
+        // The effect view sits behind the webview, which has to let it through.
+        window
+            .set_background_color(Some(transparent_window_background()))
+            .map_err(|e| e.to_string())?;
+        let vibrancy = window.state::<WindowVibrancy>();
+        let mut materials = vibrancy.0.lock().unwrap();
+        match effect {
+            Some(effect) => materials.insert(window.label().to_string(), effect),
+            None => materials.remove(window.label()),
+        };
+        drop(materials);
+        apply_macos_vibrancy(&window.as_ref().window(), effect)?;
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = (window, effect);
+    Ok(())
+}
+
+/// Put back the vibrancy a theme switch may have reset.
+#[cfg(target_os = "macos")]
+fn restore_macos_vibrancy(window: &tauri::Window) -> Result<(), String> {
+    let effect = window
+        .state::<WindowVibrancy>()
+        .0
+        .lock()
+        .unwrap()
+        .get(window.label())
+        .copied();
+    match effect {
+        Some(effect) => apply_macos_vibrancy(window, Some(effect)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn apply_macos_vibrancy(window: &tauri::Window, effect: Option<Effect>) -> Result<(), String> {
+    use tauri::window::{EffectState, EffectsBuilder};
+
+    let effects = effect.map(|effect| {
+        EffectsBuilder::new()
+            .effect(effect)
+            .state(EffectState::FollowsWindowActiveState)
+            .build()
+    });
+    window.set_effects(effects).map_err(|e| e.to_string())
+}
+
+/// Switch the DWM title bar between light and dark. Windows versions
+/// without dark title bars reject both attributes and keep the light one.
+#[cfg(windows)]
+fn set_windows_dark_title_bar(hwnd: *mut std::ffi::c_void, is_dark: bool) {
+    use windows::core::BOOL;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Dwm::{
+        DwmSetWindowAttribute, DWMWA_USE_IMMERSIVE_DARK_MODE, DWMWINDOWATTRIBUTE,
+    };
+
+    // Windows 10 builds before 20H1 used the undocumented attribute 19.
+    const DWMWA_USE_IMMERSIVE_DARK_MODE_BEFORE_20H1: DWMWINDOWATTRIBUTE = DWMWINDOWATTRIBUTE(19);
+
+    let value = BOOL::from(is_dark);
+    for attribute in [
+        DWMWA_USE_IMMERSIVE_DARK_MODE,
+        DWMWA_USE_IMMERSIVE_DARK_MODE_BEFORE_20H1,
+    ] {
+        let applied = unsafe {
+            DwmSetWindowAttribute(
+                HWND(hwnd),
+                attribute,
+                &value as *const BOOL as *const std::ffi::c_void,
+                std::mem::size_of::<BOOL>() as u32,
+            )
+        };
+        if applied.is_ok() {
+            return;
+        }
+    }
+}
+
 #[cfg(target_os = "macos")]
 pub fn apply_macos_transparent_chrome(ns_window: *mut std::ffi::c_void) {
     use cocoa::appkit::{
@@ -86,6 +552,29 @@ pub fn apply_macos_transparent_chrome(ns_window: *mut std::ffi::c_void) {
     }
 }
 
+/// Hide the close, minimize and zoom buttons, e.g. in mini mode.
+#[cfg(target_os = "macos")]
+fn set_macos_traffic_lights_hidden(ns_window: *mut std::ffi::c_void, hidden: bool) {
+    use cocoa::appkit::{NSWindow, NSWindowButton};
+    use cocoa::base::{id, nil, NO, YES};
+    use objc::{msg_send, sel, sel_impl};
+
+    let ns_window = ns_window as id;
+    let hidden = if hidden { YES } else { NO };
+    unsafe {
+        for kind in [
+            NSWindowButton::NSWindowCloseButton,
+            NSWindowButton::NSWindowMiniaturizeButton,
+            NSWindowButton::NSWindowZoomButton,
+        ] {
+            let button = ns_window.standardWindowButton_(kind);
+            if button != nil {
+                let _: () = msg_send![button, setHidden: hidden];
+            }
+        }
+    }
+}
+
 #[cfg(target_os = "macos")]
 pub fn reposition_macos_traffic_lights(ns_window: *mut std::ffi::c_void) {
     unsafe {
@@ -150,11 +639,241 @@ pub fn restart_app(app: tauri::AppHandle) {
     app.restart();
 }
 
-/// Open developer tools
+/// Pin the window above other applications, or unpin it
 #[tauri::command]
-pub fn open_devtools(_window: tauri::WebviewWindow) {
-    #[cfg(debug_assertions)]
-    {
-        let _ = _window.open_devtools();
+pub fn set_always_on_top(
+    window: tauri::WebviewWindow,
+    pins: State<'_, PinnedWindows>,
+    pinned: bool,
+) -> Result<(), String> {
+    window
+        .set_always_on_top(pinned)
+        .map_err(|e| e.to_string())?;
+    let mut pinned_labels = pins.0.lock().unwrap();
+    if pinned {
+        pinned_labels.insert(window.label().to_string());
+    } else {
+        pinned_labels.remove(window.label());
+    }
+    Ok(())
+}
+
+/// Restore the window's default size and position, for a window that was
+/// saved somewhere awkward
+#[tauri::command]
+pub fn reset_window_geometry(window: tauri::WebviewWindow) -> Result<(), String> {
+    window_bounds::reset_window_geometry(&window)
+        .map_err(|e| format!("Failed to reset window geometry: {}", e))
+}
+
+/// Shrink the window to a compact strip without title bar controls, pinned
+/// on top, or give it back the geometry it had before
+///
+/// Emits `mini-mode-changed` with the new state to the window, whose
+/// frontend switches to the condensed layout.
+#[tauri::command]
+pub fn set_mini_mode(window: WebviewWindow, enabled: bool) -> Result<(), String> {
+    let changed = if enabled {
+        enter_mini_mode(&window)
+    } else {
+        leave_mini_mode(&window)
+    }
+    .map_err(|e| format!("Failed to switch mini mode: {}", e))?;
+    if changed {
+        let _ = window.emit_to(window.label(), "mini-mode-changed", enabled);
+    }
+    Ok(())
+}
+
+/// Whether the window is in mini mode, for a frontend that reloads
+#[tauri::command]
+pub fn is_mini_mode_enabled(window: WebviewWindow) -> bool {
+    is_mini_mode(window.app_handle(), window.label())
+}
+
+/// Returns false when the window already was in mini mode.
+fn enter_mini_mode(window: &WebviewWindow) -> tauri::Result<bool> {
+    if is_mini_mode(window.app_handle(), window.label()) {
+        return Ok(false);
+    }
+    let maximized = window.is_maximized()?;
+    let fullscreen = window.is_fullscreen()?;
+    if fullscreen {
+        window.set_fullscreen(false)?;
+    }
+    if maximized {
+        window.unmaximize()?;
+    }
+    let saved = SavedGeometry {
+        position: window.outer_position()?,
+        size: window.inner_size()?,
+        maximized,
+        fullscreen,
+        decorated: window.is_decorated()?,
+    };
+    // Registered before resizing, so the moves below aren't saved as the
+    // window's geometry.
+    window
+        .state::<MiniModeWindows>()
+        .0
+        .lock()
+        .unwrap()
+        .insert(window.label().to_string(), saved);
+    cancel_window_geometry_save();
+
+    let (width, height) = mini_mode_size(window.app_handle()).unwrap_or(MINI_MODE_SIZE);
+    window.set_decorations(false)?;
+    #[cfg(target_os = "macos")]
+    if let Ok(ns_window) = window.ns_window() {
+        set_macos_traffic_lights_hidden(ns_window, true);
+    }
+    window.set_min_size(Some(LogicalSize::new(
+        MINI_MODE_MIN_SIZE.0,
+        MINI_MODE_MIN_SIZE.1,
+    )))?;
+    window.set_size(LogicalSize::new(
+        width.max(MINI_MODE_MIN_SIZE.0),
+        height.max(MINI_MODE_MIN_SIZE.1),
+    ))?;
+    window.set_always_on_top(true)?;
+    Ok(true)
+}
+
+/// Returns false when the window wasn't in mini mode.
+fn leave_mini_mode(window: &WebviewWindow) -> tauri::Result<bool> {
+    let app = window.app_handle();
+    let saved = app
+        .state::<MiniModeWindows>()
+        .0
+        .lock()
+        .unwrap()
+        .remove(window.label());
+    let Some(saved) = saved else {
+        return Ok(false);
+    };
+
+    let size = window
+        .inner_size()?
+        .to_logical::<f64>(window.scale_factor()?);
+    if let Err(error) = save_mini_mode_size(app, (size.width, size.height)) {
+        log::warn!("Failed to save mini mode size: {error}");
+    }
+    window.set_always_on_top(is_pinned(window))?;
+    let min_size = main_window_config(app)
+        .ok()
+        .and_then(|config| Some(LogicalSize::new(config.min_width?, config.min_height?)));
+    window.set_min_size(min_size)?;
+    window.set_decorations(saved.decorated)?;
+    #[cfg(target_os = "macos")]
+    if let Ok(ns_window) = window.ns_window() {
+        set_macos_traffic_lights_hidden(ns_window, false);
+        apply_macos_transparent_chrome(ns_window);
+    }
+    window.set_size(saved.size)?;
+    window.set_position(saved.position)?;
+    if saved.maximized {
+        window.maximize()?;
+    }
+    if saved.fullscreen {
+        window.set_fullscreen(true)?;
+    }
+    schedule_window_geometry_save(app);
+    Ok(true)
+}
+
+/// Zoom the whole window UI, clamped to 50%–300%, and remember the factor
+/// for new windows and the next launch
+///
+/// Returns the factor applied.
+#[tauri::command]
+pub fn set_zoom(window: WebviewWindow, factor: f64) -> Result<f64, String> {
+    let factor = clamp_zoom(factor);
+    window.set_zoom(factor).map_err(|e| e.to_string())?;
+    save_zoom_preference(window.app_handle(), factor)?;
+    Ok(factor)
+}
+
+/// The remembered zoom factor, 1.0 until one is set
+#[tauri::command]
+pub fn get_zoom(app: AppHandle) -> f64 {
+    zoom_preference(&app).map_or(1.0, clamp_zoom)
+}
+
+fn clamp_zoom(factor: f64) -> f64 {
+    if factor.is_finite() {
+        factor.clamp(MIN_ZOOM, MAX_ZOOM)
+    } else {
+        1.0
+    }
+}
+
+/// Whether the user pinned the window on top
+#[tauri::command]
+pub fn is_always_on_top(window: tauri::WebviewWindow) -> bool {
+    is_pinned(&window)
+}
+
+/// Open developer tools, where they are enabled
+#[tauri::command]
+pub fn open_devtools(app: AppHandle, window: tauri::WebviewWindow) -> Result<(), String> {
+    if !devtools_allowed(&app) {
+        return Err(format!(
+            "devtools disabled; set {DEVTOOLS_ENV}=1 or enable developer tools in Settings, then restart"
+        ));
+    }
+    window.open_devtools();
+    Ok(())
+}
+
+/// Allow devtools in release builds from the next launch on
+#[tauri::command]
+pub fn set_devtools_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    save_devtools_preference(&app, enabled)
+}
+
+/// Whether the user opted into devtools in release builds
+#[tauri::command]
+pub fn get_devtools_enabled(app: AppHandle) -> bool {
+    devtools_preference(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clamp_zoom, pick_target, vibrancy_material};
+    use tauri::window::Effect;
+
+    #[test]
+    fn targets_the_last_focused_editor_window() {
+        let labels = ["quick-peek", "editor-2", "main", "editor-1"];
+        assert_eq!(pick_target(&labels, Some("editor-2")), Some("editor-2"));
+        assert_eq!(pick_target(&labels, None), Some("main"));
+        assert_eq!(pick_target(&labels, Some("quick-peek")), Some("main"));
+        assert_eq!(
+            pick_target(&["quick-peek", "editor-3"], Some("main")),
+            Some("editor-3")
+        );
+        assert_eq!(pick_target(&["quick-peek"], None), None);
+    }
+
+    #[test]
+    fn parses_vibrancy_materials() {
+        assert_eq!(vibrancy_material("sidebar").unwrap(), Effect::Sidebar);
+        assert_eq!(vibrancy_material("hud").unwrap(), Effect::HudWindow);
+        assert_eq!(
+            vibrancy_material("under-window").unwrap(),
+            Effect::UnderWindowBackground
+        );
+        assert_eq!(
+            vibrancy_material("mica").unwrap_err(),
+            "Unsupported vibrancy material: mica"
+        );
+    }
+
+    #[test]
+    fn clamps_zoom_to_a_usable_range() {
+        assert_eq!(clamp_zoom(1.25), 1.25);
+        assert_eq!(clamp_zoom(0.1), 0.5);
+        assert_eq!(clamp_zoom(10.0), 3.0);
+        assert_eq!(clamp_zoom(f64::NAN), 1.0);
     }
 }
@@ -0,0 +1,204 @@
+// jsonstudio:// links
+//
+// `jsonstudio://open?url=https://…/thing.json` fetches a remote document and
+// `jsonstudio://format?data=<base64url>` carries a small one inline. Either
+// way the content goes to the editor on `open-deeplink`, queued until the
+// page listens when the link launched the app. A link that can't be used is
+// reported on `deeplink-error` instead, so a bad link never stops startup.
+use base64::alphabet::URL_SAFE;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use serde::Serialize;
+use tauri::{AppHandle, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::app_state::{emit_when_ready, focus_main_window};
+use crate::commands::window::target_window;
+use crate::error::{AppError, ErrorCode};
+use crate::http;
+
+/// Largest inline `format` document, decoded.
+const MAX_INLINE_BYTES: usize = 1024 * 1024;
+/// Largest document fetched for `open`.
+const MAX_REMOTE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// base64url with or without `=` padding.
+const BASE64_URL: GeneralPurpose = GeneralPurpose::new(
+    &URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+#[derive(Debug, PartialEq)]
+enum DeepLinkAction {
+    Open(Url),
+    Format(String),
+}
+
+/// Sent on `open-deeplink`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DeepLinkContent {
+    /// `open` or `format`.
+    pub action: &'static str,
+    /// The fetched URL for `open`.
+    pub url: Option<String>,
+    pub content: String,
+}
+
+/// Handle links that launched the app and those that arrive later.
+pub(crate) fn setup(app: &AppHandle) {
+    // Installed builds register the scheme; an unpacked AppImage or a dev
+    // build on Windows has to do it at runtime.
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(error) = app.deep_link().register_all() {
+        log::warn!("Failed to register jsonstudio:// links: {error}");
+    }
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for link in event.urls() {
+            handle_link(&handle, link);
+        }
+    });
+    match app.deep_link().get_current() {
+        Ok(links) => {
+            for link in links.unwrap_or_default() {
+                handle_link(app, link);
+            }
+        }
+        Err(error) => log::warn!("Failed to read the launch link: {error}"),
+    }
+}
+
+fn handle_link(app: &AppHandle, link: Url) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = parse_link(&link).and_then(resolve);
+        if let Err(error) = &result {
+            log::warn!("Rejected link {}: {error}", link.host_str().unwrap_or(""));
+        }
+        deliver(&app, result);
+    });
+}
+
+fn parse_link(link: &Url) -> Result<DeepLinkAction, AppError> {
+    let param = |name: &'static str| {
+        link.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| ErrorCode::DeepLinkMissingParam.with("param", name))
+    };
+    match link.host_str().unwrap_or_default() {
+        "open" => {
+            let url = param("url")?;
+            let url = Url::parse(&url).map_err(|e| ErrorCode::InvalidDeepLink.reason(e))?;
+            if !http::is_fetchable(&url) {
+                return Err(ErrorCode::UnsupportedUrl.with("url", url.as_str()));
+            }
+            Ok(DeepLinkAction::Open(url))
+        }
+        "format" => {
+            let data = param("data")?;
+            // Four base64 characters carry three bytes.
+            if data.len() > MAX_INLINE_BYTES.div_ceil(3) * 4 {
+                return Err(ErrorCode::DocumentTooLarge.with("limit", MAX_INLINE_BYTES));
+            }
+            let bytes = BASE64_URL
+                .decode(data.trim_end())
+                .map_err(|e| ErrorCode::InvalidDeepLink.reason(e))?;
+            if bytes.len() > MAX_INLINE_BYTES {
+                return Err(ErrorCode::DocumentTooLarge.with("limit", MAX_INLINE_BYTES));
+            }
+            let content =
+                String::from_utf8(bytes).map_err(|e| ErrorCode::InvalidDeepLink.reason(e))?;
+            Ok(DeepLinkAction::Format(content))
+        }
+        action => Err(ErrorCode::UnknownDeepLinkAction.with("action", action)),
+    }
+}
+
+fn resolve(action: DeepLinkAction) -> Result<DeepLinkContent, AppError> {
+    Ok(match action {
+        DeepLinkAction::Open(url) => DeepLinkContent {
+            action: "open",
+            content: http::fetch_text(&url, MAX_REMOTE_BYTES)?,
+            url: Some(url.into()),
+        },
+        DeepLinkAction::Format(content) => DeepLinkContent {
+            action: "format",
+            url: None,
+            content,
+        },
+    })
+}
+
+fn deliver(app: &AppHandle, result: Result<DeepLinkContent, AppError>) {
+    focus_main_window(app);
+    let window = target_window(app);
+    let emitted = match result {
+        Ok(content) => emit_when_ready(app, window.as_ref(), "open-deeplink", content),
+        Err(error) => emit_when_ready(app, window.as_ref(), "deeplink-error", error),
+    };
+    if let Err(error) = emitted {
+        log::warn!("Failed to deliver link: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_link, DeepLinkAction, MAX_INLINE_BYTES};
+    use crate::error::ErrorCode;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+    use tauri::Url;
+
+    fn parse(link: &str) -> Result<DeepLinkAction, ErrorCode> {
+        parse_link(&Url::parse(link).unwrap()).map_err(|error| error.code)
+    }
+
+    #[test]
+    fn parses_open_and_format_links() {
+        assert_eq!(
+            parse("jsonstudio://open?url=https://api.example.com/thing.json"),
+            Ok(DeepLinkAction::Open(
+                Url::parse("https://api.example.com/thing.json").unwrap()
+            ))
+        );
+        let data = URL_SAFE_NO_PAD.encode(r#"{"a":"é?"}"#);
+        assert_eq!(
+            parse(&format!("jsonstudio://format?data={data}")),
+            Ok(DeepLinkAction::Format(r#"{"a":"é?"}"#.to_string()))
+        );
+        assert_eq!(
+            parse("jsonstudio://format?data=eyJhIjoxfQ=="),
+            Ok(DeepLinkAction::Format(r#"{"a":1}"#.to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        assert_eq!(
+            parse("jsonstudio://delete?path=/"),
+            Err(ErrorCode::UnknownDeepLinkAction)
+        );
+        assert_eq!(
+            parse("jsonstudio://open"),
+            Err(ErrorCode::DeepLinkMissingParam)
+        );
+        assert_eq!(
+            parse("jsonstudio://open?url=file:///etc/passwd"),
+            Err(ErrorCode::UnsupportedUrl)
+        );
+        assert_eq!(
+            parse("jsonstudio://format?data=not*base64"),
+            Err(ErrorCode::InvalidDeepLink)
+        );
+        assert_eq!(
+            parse(&format!(
+                "jsonstudio://format?data={}",
+                URL_SAFE_NO_PAD.encode(vec![b' '; MAX_INLINE_BYTES + 3])
+            )),
+            Err(ErrorCode::DocumentTooLarge)
+        );
+    }
+}
@@ -0,0 +1,548 @@
+// Structured command errors
+//
+// An error has a stable `code` and the `params` that go into its message, so
+// the page can react to a specific failure (a parse error's line and column,
+// the file that couldn't be read) instead of matching on English text. The
+// message itself comes from a small catalog in the locale set by
+// `set_locale`, which also covers errors shown outside the webview, such as
+// those of global shortcut actions. Commands that still return a `String`
+// get the localized message through `From<AppError> for String`.
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+use tauri::AppHandle;
+
+use crate::commands::settings::{setting, LANGUAGE_SETTING};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    ZhCn,
+}
+
+impl Locale {
+    /// `zh`, `zh-CN`, `zh-Hans` and the like are Chinese; anything else is English.
+    pub fn parse(locale: &str) -> Self {
+        if locale.to_ascii_lowercase().starts_with("zh") {
+            Self::ZhCn
+        } else {
+            Self::En
+        }
+    }
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(0);
+
+fn current_locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::ZhCn,
+        _ => Locale::En,
+    }
+}
+
+fn store_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+/// Use the saved interface language for backend messages until the page
+/// calls `set_locale`.
+pub(crate) fn init_locale(app: &AppHandle) {
+    if let Some(language) = setting::<String>(app, LANGUAGE_SETTING) {
+        store_locale(Locale::parse(&language));
+    }
+}
+
+/// Language of backend error messages (`en` or `zh`/`zh-CN`)
+#[tauri::command]
+pub fn set_locale(locale: String) {
+    store_locale(Locale::parse(&locale));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// An error that has no code of its own; its text is the `message` param.
+    Other,
+    Parse,
+    Formatting,
+    Minify,
+    Unescape,
+    UnsupportedIndent,
+    UnsupportedLanguage,
+    ReadFile,
+    SaveFile,
+    WriteFile,
+    CreateFile,
+    CreateDirectory,
+    RenameFile,
+    RevealInFolder,
+    FileNameHasPath,
+    NoParentDirectory,
+    FileExists,
+    DirectoryMissing,
+    Decompress,
+    Compress,
+    ReadCancelled,
+    TaskFailed,
+    SelectTwoFiles,
+    UnsupportedExportFormat,
+    NdjsonNeedsArray,
+    JoinFailed,
+    JoinTooLarge,
+    UnsupportedSplitMode,
+    ChunkSizeTooSmall,
+    InvalidJsonArray,
+    UnknownShortcut,
+    ShortcutInUse,
+    ShortcutInUseElsewhere,
+    InvalidShortcut,
+    RegisterShortcut,
+    UnregisterShortcut,
+    RestoreShortcut,
+    ShortcutsNotChanged,
+    ShortcutRegistryUnavailable,
+    ReadClipboard,
+    WriteClipboard,
+    ClipboardEmpty,
+    /// Notification of a clipboard check that passed, not an error.
+    ClipboardValid,
+    ClipboardInvalid,
+    ClipboardInvalidAt,
+    /// Notifications of clipboard text rewritten in place, not errors.
+    ClipboardFormatted,
+    ClipboardMinified,
+    ClipboardEscaped,
+    ClipboardUnescaped,
+    MainWindowNotFound,
+    UnknownDeepLinkAction,
+    DeepLinkMissingParam,
+    InvalidDeepLink,
+    UnsupportedUrl,
+    FetchFailed,
+    DocumentTooLarge,
+    InvalidNdjsonLine,
+    FileTooLargeForEditing,
+    ResultExpired,
+}
+
+impl ErrorCode {
+    /// The code sent to the page, and the message in English and Chinese.
+    /// `{name}` stands for the param of that name.
+    fn entry(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Self::Other => ("other", "{message}", "{message}"),
+            Self::Parse => (
+                "parse",
+                "JSON/JSON5 parsing error: {reason}",
+                "JSON/JSON5 解析错误：{reason}",
+            ),
+            Self::Formatting => (
+                "formatting",
+                "JSON formatting error: {reason}",
+                "JSON 格式化错误：{reason}",
+            ),
+            Self::Minify => (
+                "minify",
+                "JSON minification error: {reason}",
+                "JSON 压缩错误：{reason}",
+            ),
+            Self::Unescape => ("unescape", "Unescape failed: {reason}", "去除转义失败：{reason}"),
+            Self::UnsupportedIndent => (
+                "unsupported_indent",
+                "Unsupported indent character: {indent}",
+                "不支持的缩进字符：{indent}",
+            ),
+            Self::UnsupportedLanguage => (
+                "unsupported_language",
+                "Unsupported language: {language}",
+                "不支持的语言：{language}",
+            ),
+            Self::ReadFile => ("read_file", "Failed to read file: {reason}", "读取文件失败：{reason}"),
+            Self::SaveFile => ("save_file", "Failed to save file: {reason}", "保存文件失败：{reason}"),
+            Self::WriteFile => (
+                "write_file",
+                "Failed to write file: {reason}",
+                "写入文件失败：{reason}",
+            ),
+            Self::CreateFile => (
+                "create_file",
+                "Failed to create file: {reason}",
+                "创建文件失败：{reason}",
+            ),
+            Self::CreateDirectory => (
+                "create_directory",
+                "Failed to create output directory: {reason}",
+                "创建输出目录失败：{reason}",
+            ),
+            Self::RenameFile => (
+                "rename_file",
+                "Failed to rename file: {reason}",
+                "重命名文件失败：{reason}",
+            ),
+            Self::RevealInFolder => (
+                "reveal_in_folder",
+                "Failed to reveal in folder: {reason}",
+                "无法在文件夹中显示：{reason}",
+            ),
+            Self::FileNameHasPath => (
+                "file_name_has_path",
+                "File name must not contain a path",
+                "文件名不能包含路径",
+            ),
+            Self::NoParentDirectory => (
+                "no_parent_directory",
+                "File path has no parent directory",
+                "文件路径没有上级目录",
+            ),
+            Self::FileExists => (
+                "file_exists",
+                "A file with that name already exists",
+                "已存在同名文件",
+            ),
+            Self::DirectoryMissing => (
+                "directory_missing",
+                "Directory does not exist",
+                "目录不存在",
+            ),
+            Self::Decompress => (
+                "decompress",
+                "Failed to decompress gzip file: {reason}",
+                "解压 gzip 文件失败：{reason}",
+            ),
+            Self::Compress => (
+                "compress",
+                "Failed to compress file: {reason}",
+                "压缩文件失败：{reason}",
+            ),
+            Self::ReadCancelled => ("read_cancelled", "File read cancelled", "已取消读取文件"),
+            Self::TaskFailed => ("task_failed", "Task failed: {reason}", "任务失败：{reason}"),
+            Self::SelectTwoFiles => (
+                "select_two_files",
+                "Select exactly two files to compare",
+                "请选择两个文件进行比较",
+            ),
+            Self::UnsupportedExportFormat => (
+                "unsupported_export_format",
+                "Unsupported export format: {format}",
+                "不支持的导出格式：{format}",
+            ),
+            Self::NdjsonNeedsArray => (
+                "ndjson_needs_array",
+                "NDJSON export requires a JSON array at the root",
+                "导出 NDJSON 需要根节点为 JSON 数组",
+            ),
+            Self::JoinFailed => (
+                "join_failed",
+                "Failed to join {path}: {reason}",
+                "无法合并 {path}：{reason}",
+            ),
+            Self::JoinTooLarge => (
+                "join_too_large",
+                "Joined document is {size} bytes; choose an output file for results over {limit} bytes",
+                "合并后的文档有 {size} 字节；超过 {limit} 字节的结果请选择输出文件",
+            ),
+            Self::UnsupportedSplitMode => (
+                "unsupported_split_mode",
+                "Unsupported split mode: {mode}",
+                "不支持的拆分方式：{mode}",
+            ),
+            Self::ChunkSizeTooSmall => (
+                "chunk_size_too_small",
+                "Chunk size must be at least 1",
+                "分块大小至少为 1",
+            ),
+            Self::InvalidJsonArray => (
+                "invalid_json_array",
+                "Invalid JSON array: {reason}",
+                "无效的 JSON 数组：{reason}",
+            ),
+            Self::UnknownShortcut => ("unknown_shortcut", "Unknown shortcut id", "未知的快捷键"),
+            Self::ShortcutInUse => (
+                "shortcut_in_use",
+                "This shortcut is already used by {other}",
+                "该快捷键已被 {other} 使用",
+            ),
+            Self::ShortcutInUseElsewhere => (
+                "shortcut_in_use_elsewhere",
+                "This shortcut is in use by the system or another app: {reason}",
+                "该快捷键已被系统或其他应用占用：{reason}",
+            ),
+            Self::InvalidShortcut => (
+                "invalid_shortcut",
+                "Invalid shortcut format: {reason}",
+                "快捷键格式无效：{reason}",
+            ),
+            Self::RegisterShortcut => (
+                "register_shortcut",
+                "Failed to register shortcut: {reason}",
+                "注册快捷键失败：{reason}",
+            ),
+            Self::UnregisterShortcut => (
+                "unregister_shortcut",
+                "Failed to unregister shortcut: {reason}",
+                "注销快捷键失败：{reason}",
+            ),
+            Self::RestoreShortcut => (
+                "restore_shortcut",
+                "{reason}; failed to restore previous shortcut: {rollback}",
+                "{reason}；恢复原快捷键失败：{rollback}",
+            ),
+            Self::ShortcutsNotChanged => (
+                "shortcuts_not_changed",
+                "Some shortcuts could not be changed: {shortcuts}",
+                "部分快捷键无法更改：{shortcuts}",
+            ),
+            Self::ShortcutRegistryUnavailable => (
+                "shortcut_registry_unavailable",
+                "Global shortcut registry is unavailable",
+                "全局快捷键不可用",
+            ),
+            Self::ReadClipboard => (
+                "read_clipboard",
+                "Failed to read clipboard: {reason}",
+                "读取剪贴板失败：{reason}",
+            ),
+            Self::WriteClipboard => (
+                "write_clipboard",
+                "Failed to write clipboard: {reason}",
+                "写入剪贴板失败：{reason}",
+            ),
+            Self::ClipboardEmpty => ("clipboard_empty", "Clipboard is empty", "剪贴板为空"),
+            Self::ClipboardValid => (
+                "clipboard_valid",
+                "Clipboard contains valid JSON",
+                "剪贴板内容是有效的 JSON",
+            ),
+            Self::ClipboardInvalid => (
+                "clipboard_invalid",
+                "Invalid JSON in clipboard: {reason}",
+                "剪贴板中的 JSON 无效：{reason}",
+            ),
+            Self::ClipboardInvalidAt => (
+                "clipboard_invalid_at",
+                "Invalid JSON in clipboard at line {line}, column {column}: {reason}",
+                "剪贴板中的 JSON 在第 {line} 行第 {column} 列无效：{reason}",
+            ),
+            Self::ClipboardFormatted => (
+                "clipboard_formatted",
+                "Formatted {size} of JSON",
+                "已格式化 {size} 的 JSON",
+            ),
+            Self::ClipboardMinified => (
+                "clipboard_minified",
+                "Minified {size} of JSON",
+                "已压缩 {size} 的 JSON",
+            ),
+            Self::ClipboardEscaped => (
+                "clipboard_escaped",
+                "Escaped {size} of JSON",
+                "已转义 {size} 的 JSON",
+            ),
+            Self::ClipboardUnescaped => (
+                "clipboard_unescaped",
+                "Unescaped {size} of JSON",
+                "已对 {size} 的 JSON 去除转义",
+            ),
+            Self::MainWindowNotFound => (
+                "main_window_not_found",
+                "Main window not found",
+                "未找到主窗口",
+            ),
+            Self::UnknownDeepLinkAction => (
+                "unknown_deep_link_action",
+                "Unknown link action: {action}",
+                "未知的链接操作：{action}",
+            ),
+            Self::DeepLinkMissingParam => (
+                "deep_link_missing_param",
+                "The link has no {param} parameter",
+                "链接缺少 {param} 参数",
+            ),
+            Self::InvalidDeepLink => ("invalid_deep_link", "Invalid link: {reason}", "无效的链接：{reason}"),
+            Self::UnsupportedUrl => (
+                "unsupported_url",
+                "Only http and https URLs can be opened: {url}",
+                "只能打开 http 和 https 地址：{url}",
+            ),
+            Self::FetchFailed => (
+                "fetch_failed",
+                "Failed to fetch {url}: {reason}",
+                "无法获取 {url}：{reason}",
+            ),
+            Self::DocumentTooLarge => (
+                "document_too_large",
+                "The document is larger than {limit} bytes",
+                "文档超过 {limit} 字节",
+            ),
+            Self::InvalidNdjsonLine => (
+                "invalid_ndjson_line",
+                "Line {line} is not valid JSON: {reason}",
+                "第 {line} 行不是有效的 JSON：{reason}",
+            ),
+            Self::FileTooLargeForEditing => (
+                "file_too_large_for_editing",
+                "The file is {size} bytes, more than the {limit} the editor opens; use preview or query mode",
+                "文件大小为 {size} 字节，超过编辑器可打开的 {limit} 字节；请使用预览或查询模式",
+            ),
+            Self::ResultExpired => (
+                "result_expired",
+                "Result {result_id} is no longer available; run the command again",
+                "结果 {result_id} 已失效；请重新运行命令",
+            ),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        self.entry().0
+    }
+
+    fn template(self, locale: Locale) -> &'static str {
+        let (_, en, zh) = self.entry();
+        match locale {
+            Locale::En => en,
+            Locale::ZhCn => zh,
+        }
+    }
+
+    /// This error with the underlying cause as its `reason`.
+    pub fn reason(self, reason: impl fmt::Display) -> AppError {
+        AppError::from(self).with("reason", reason.to_string())
+    }
+
+    pub fn with(self, name: &'static str, value: impl Into<Value>) -> AppError {
+        AppError::from(self).with(name, value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub params: Vec<(&'static str, Value)>,
+}
+
+impl AppError {
+    pub fn with(mut self, name: &'static str, value: impl Into<Value>) -> Self {
+        self.params.push((name, value.into()));
+        self
+    }
+
+    pub fn param(&self, name: &str) -> Option<&Value> {
+        self.params
+            .iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn message_in(&self, locale: Locale) -> String {
+        self.params.iter().fold(
+            self.code.template(locale).to_string(),
+            |message, (name, value)| {
+                let text = match value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                message.replace(&format!("{{{name}}}"), &text)
+            },
+        )
+    }
+
+    pub fn message(&self) -> String {
+        self.message_in(current_locale())
+    }
+}
+
+impl From<ErrorCode> for AppError {
+    fn from(code: ErrorCode) -> Self {
+        Self {
+            code,
+            params: Vec::new(),
+        }
+    }
+}
+
+/// Errors from code that still reports plain strings.
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        ErrorCode::Other.with("message", message)
+    }
+}
+
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.message()
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// `{ "code": …, "params": { … }, "message": … }`, the message localized.
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let params: Map<String, Value> = self
+            .params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("code", self.code.as_str())?;
+        map.serialize_entry("params", &params)?;
+        map.serialize_entry("message", &self.message())?;
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppError, ErrorCode, Locale};
+    use serde_json::json;
+
+    #[test]
+    fn renders_messages_in_each_locale() {
+        let error = ErrorCode::ReadFile
+            .reason("denied")
+            .with("path", "/tmp/a.json");
+
+        assert_eq!(error.message_in(Locale::En), "Failed to read file: denied");
+        assert_eq!(error.message_in(Locale::ZhCn), "读取文件失败：denied");
+        assert_eq!(
+            ErrorCode::JoinTooLarge
+                .with("size", 30)
+                .with("limit", 20)
+                .message_in(Locale::En),
+            "Joined document is 30 bytes; choose an output file for results over 20 bytes"
+        );
+    }
+
+    #[test]
+    fn serializes_code_params_and_message() {
+        let error = ErrorCode::Parse
+            .reason("expected value")
+            .with("line", 3)
+            .with("column", 7);
+
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "parse",
+                "params": { "reason": "expected value", "line": 3, "column": 7 },
+                "message": "JSON/JSON5 parsing error: expected value",
+            })
+        );
+    }
+
+    #[test]
+    fn keeps_plain_string_errors_as_they_are() {
+        let error = AppError::from("Something broke".to_string());
+
+        assert_eq!(error.code, ErrorCode::Other);
+        assert_eq!(String::from(error), "Something broke");
+        assert_eq!(Locale::parse("zh-Hans"), Locale::ZhCn);
+        assert_eq!(Locale::parse("en-US"), Locale::En);
+    }
+}
@@ -0,0 +1,50 @@
+// Fetching remote documents
+//
+// Only GET over http(s), with a timeout and a size limit so a slow or huge
+// response can't hang or exhaust the app. The body is decoded like an opened
+// file: UTF-8 or UTF-16, without its BOM.
+use std::time::Duration;
+
+use crate::error::{AppError, ErrorCode};
+use crate::text_encoding;
+
+/// Time allowed for the whole request, body included.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Whether `url` is one `fetch_text` accepts.
+pub(crate) fn is_fetchable(url: &tauri::Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+}
+
+/// GET `url` and decode the body as text. Blocks, so call it off the async
+/// runtime.
+pub(crate) fn fetch_text(url: &tauri::Url, max_bytes: u64) -> Result<String, AppError> {
+    let fetch_error = |reason: String| {
+        ErrorCode::FetchFailed
+            .reason(reason)
+            .with("url", url.as_str())
+    };
+    if !is_fetchable(url) {
+        return Err(ErrorCode::UnsupportedUrl.with("url", url.as_str()));
+    }
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(FETCH_TIMEOUT))
+        .build()
+        .into();
+    let mut response = agent
+        .get(url.as_str())
+        .call()
+        .map_err(|e| fetch_error(e.to_string()))?;
+    let bytes = response
+        .body_mut()
+        .with_config()
+        .limit(max_bytes)
+        .read_to_vec()
+        .map_err(|e| match e {
+            ureq::Error::BodyExceedsLimit(_) => {
+                ErrorCode::DocumentTooLarge.with("limit", max_bytes)
+            }
+            e => fetch_error(e.to_string()),
+        })?;
+    Ok(text_encoding::decode(&bytes).map_err(fetch_error)?.content)
+}
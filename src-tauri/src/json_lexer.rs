@@ -0,0 +1,229 @@
+// Position-aware JSON tokenizer shared by the validation commands.
+//
+// The lexer never fails: it accepts the JSON5 extras the parsers accept
+// (comments, single quotes, bare words) and reports anything else as an
+// `Unexpected` token so callers can decide how strict to be.
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TokenKind {
+    BeginObject,
+    EndObject,
+    BeginArray,
+    EndArray,
+    Colon,
+    Comma,
+    /// Decoded string contents.
+    String(String),
+    Number(String),
+    /// Bare identifiers: `true`, `false`, `null` and unquoted JSON5 keys.
+    Word(String),
+    Unexpected(char),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Token {
+    pub kind: TokenKind,
+    /// 1-based position of the first character.
+    pub line: usize,
+    pub column: usize,
+    /// Byte range of the token in the source.
+    pub start: usize,
+    pub end: usize,
+}
+
+pub(crate) struct Lexer<'a> {
+    source: &'a str,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub(crate) fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            pos: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.source[self.pos..].chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_trivia(&mut self) {
+        while let Some(c) = self.peek() {
+            match (c, self.peek_second()) {
+                _ if c.is_whitespace() || c == '\u{feff}' => {
+                    self.bump();
+                }
+                ('/', Some('/')) => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.bump();
+                    }
+                }
+                ('/', Some('*')) => {
+                    self.bump();
+                    self.bump();
+                    while self.peek().is_some() && !self.source[self.pos..].starts_with("*/") {
+                        self.bump();
+                    }
+                    self.bump();
+                    self.bump();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn read_string(&mut self, quote: char) -> String {
+        let mut text = String::new();
+        while let Some(c) = self.bump() {
+            match c {
+                _ if c == quote => break,
+                '\\' => match self.bump() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some('r') => text.push('\r'),
+                    Some('b') => text.push('\u{08}'),
+                    Some('f') => text.push('\u{0C}'),
+                    Some('u') => text.push(self.read_unicode_escape()),
+                    Some(other) => text.push(other),
+                    None => break,
+                },
+                c => text.push(c),
+            }
+        }
+        text
+    }
+
+    /// Decode the hex digits after `\u`, combining surrogate pairs.
+    fn read_unicode_escape(&mut self) -> char {
+        let high = self.read_hex4();
+        if (0xD800..0xDC00).contains(&high) && self.source[self.pos..].starts_with("\\u") {
+            self.bump();
+            self.bump();
+            let low = self.read_hex4();
+            let combined = 0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+            return char::from_u32(combined).unwrap_or(char::REPLACEMENT_CHARACTER);
+        }
+        char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+
+    fn read_hex4(&mut self) -> u32 {
+        let mut value = 0;
+        for _ in 0..4 {
+            match self.peek().and_then(|c| c.to_digit(16)) {
+                Some(digit) => {
+                    self.bump();
+                    value = value * 16 + digit;
+                }
+                None => break,
+            }
+        }
+        value
+    }
+
+    fn read_while(&mut self, keep: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(&keep) {
+            self.bump();
+        }
+        self.source[start..self.pos].to_string()
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        self.skip_trivia();
+        let (line, column, start) = (self.line, self.column, self.pos);
+        let c = self.peek()?;
+        let kind = match c {
+            '{' | '}' | '[' | ']' | ':' | ',' => {
+                self.bump();
+                match c {
+                    '{' => TokenKind::BeginObject,
+                    '}' => TokenKind::EndObject,
+                    '[' => TokenKind::BeginArray,
+                    ']' => TokenKind::EndArray,
+                    ':' => TokenKind::Colon,
+                    _ => TokenKind::Comma,
+                }
+            }
+            '"' | '\'' => {
+                self.bump();
+                TokenKind::String(self.read_string(c))
+            }
+            '-' | '+' | '.' | '0'..='9' => TokenKind::Number(
+                self.read_while(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-')),
+            ),
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                TokenKind::Word(self.read_while(|c| c.is_alphanumeric() || c == '_' || c == '$'))
+            }
+            c => {
+                self.bump();
+                TokenKind::Unexpected(c)
+            }
+        };
+        Some(Token {
+            kind,
+            line,
+            column,
+            start,
+            end: self.pos,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lexer, TokenKind};
+
+    #[test]
+    fn tokenizes_with_positions_and_decoded_strings() {
+        let tokens: Vec<_> =
+            Lexer::new("{\n  // note\n  \"a\\u00e9\": [1.5e3, true, 'x'] @\n}").collect();
+
+        let kinds: Vec<_> = tokens.iter().map(|token| token.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::BeginObject,
+                TokenKind::String("aé".to_string()),
+                TokenKind::Colon,
+                TokenKind::BeginArray,
+                TokenKind::Number("1.5e3".to_string()),
+                TokenKind::Comma,
+                TokenKind::Word("true".to_string()),
+                TokenKind::Comma,
+                TokenKind::String("x".to_string()),
+                TokenKind::EndArray,
+                TokenKind::Unexpected('@'),
+                TokenKind::EndObject,
+            ]
+        );
+        assert_eq!((tokens[1].line, tokens[1].column), (3, 3));
+        assert_eq!((tokens[10].line, tokens[10].column), (3, 33));
+        assert_eq!((tokens[11].line, tokens[11].column), (4, 1));
+        assert_eq!((tokens[1].start, tokens[1].end), (14, 23));
+    }
+}
@@ -0,0 +1,178 @@
+// RFC 6901 JSON Pointer helpers shared by the JSON commands.
+
+use serde_json::Value;
+
+use crate::json_value::value_type_name;
+
+/// Escape a single reference token (`~` → `~0`, `/` → `~1`).
+pub(crate) fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Pointer of the member `key` inside the object at `parent`.
+pub(crate) fn child_pointer(parent: &str, key: &str) -> String {
+    format!("{parent}/{}", escape_token(key))
+}
+
+/// Pointer of the element `index` inside the array at `parent`.
+pub(crate) fn index_pointer(parent: &str, index: usize) -> String {
+    format!("{parent}/{index}")
+}
+
+/// Split a pointer into unescaped reference tokens. `""` is the whole document.
+pub(crate) fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(format!(
+            "Invalid JSON pointer \"{pointer}\": must be empty or start with '/'"
+        ));
+    };
+    Ok(rest
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+/// Parse an array reference token. Leading zeros and `-` are rejected because
+/// they never name an existing element.
+pub(crate) fn parse_index(token: &str, len: usize, pointer: &str, parent: &str) -> Result<usize, String> {
+    let is_canonical =
+        token == "0" || (!token.starts_with('0') && token.bytes().all(|b| b.is_ascii_digit()));
+    let index = token
+        .parse::<usize>()
+        .ok()
+        .filter(|_| is_canonical && !token.is_empty())
+        .ok_or_else(|| {
+            format!(
+                "Pointer \"{pointer}\" does not resolve: \"{token}\" is not a valid index for the array at \"{parent}\""
+            )
+        })?;
+    if index >= len {
+        return Err(format!(
+            "Pointer \"{pointer}\" does not resolve: index {index} is out of bounds for the array at \"{parent}\" (length {len})"
+        ));
+    }
+    Ok(index)
+}
+
+pub(crate) fn missing_key(pointer: &str, token: &str, parent: &str) -> String {
+    format!(
+        "Pointer \"{pointer}\" does not resolve: no key \"{token}\" in the object at \"{parent}\""
+    )
+}
+
+pub(crate) fn not_a_container(pointer: &str, value: &Value, parent: &str) -> String {
+    format!(
+        "Pointer \"{pointer}\" does not resolve: cannot descend into {} at \"{parent}\"",
+        value_type_name(value)
+    )
+}
+
+/// Resolve `pointer` against `value`.
+pub(crate) fn resolve<'a>(value: &'a Value, pointer: &str) -> Result<&'a Value, String> {
+    let mut current = value;
+    let mut walked = String::new();
+    for token in parse_pointer(pointer)? {
+        current = match current {
+            Value::Object(map) => map
+                .get(&token)
+                .ok_or_else(|| missing_key(pointer, &token, &walked))?,
+            Value::Array(items) => &items[parse_index(&token, items.len(), pointer, &walked)?],
+            other => return Err(not_a_container(pointer, other, &walked)),
+        };
+        walked = child_pointer(&walked, &token);
+    }
+    Ok(current)
+}
+
+/// Resolve `pointer` against `value` for in-place edits.
+pub(crate) fn resolve_mut<'a>(
+    value: &'a mut Value,
+    pointer: &str,
+) -> Result<&'a mut Value, String> {
+    let mut current = value;
+    let mut walked = String::new();
+    for token in parse_pointer(pointer)? {
+        current = match current {
+            Value::Object(map) => map
+                .get_mut(&token)
+                .ok_or_else(|| missing_key(pointer, &token, &walked))?,
+            Value::Array(items) => {
+                let index = parse_index(&token, items.len(), pointer, &walked)?;
+                &mut items[index]
+            }
+            other => return Err(not_a_container(pointer, other, &walked)),
+        };
+        walked = child_pointer(&walked, &token);
+    }
+    Ok(current)
+}
+
+/// Look up a dot-separated field path such as `metrics.latency_ms`.
+/// Numeric segments index into arrays.
+pub(crate) fn lookup_field_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.is_empty() {
+        return Some(value);
+    }
+    path.split('.')
+        .try_fold(value, |current, segment| match current {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{child_pointer, index_pointer, lookup_field_path, resolve, resolve_mut};
+    use serde_json::json;
+
+    #[test]
+    fn builds_escaped_pointers() {
+        assert_eq!(child_pointer("", "a/b~c"), "/a~1b~0c");
+        assert_eq!(index_pointer("/items", 3), "/items/3");
+    }
+
+    #[test]
+    fn resolves_pointers_with_escapes_and_indexes() {
+        let mut value = json!({"a/b": {"c~d": [10, 20]}});
+
+        assert_eq!(resolve_mut(&mut value, "/a~1b/c~0d/1").unwrap(), &json!(20));
+        assert_eq!(resolve(&value, "").unwrap(), &value);
+        assert_eq!(resolve(&value, "/a~1b/c~0d").unwrap(), &json!([10, 20]));
+        *resolve_mut(&mut value, "/a~1b/c~0d/0").unwrap() = json!(11);
+        assert_eq!(value, json!({"a/b": {"c~d": [11, 20]}}));
+    }
+
+    #[test]
+    fn reports_where_resolution_stopped() {
+        let mut value = json!({"items": [1, 2], "name": "x"});
+
+        assert_eq!(
+            resolve_mut(&mut value, "/items/5").unwrap_err(),
+            "Pointer \"/items/5\" does not resolve: index 5 is out of bounds for the array at \"/items\" (length 2)"
+        );
+        assert!(resolve_mut(&mut value, "/items/01").is_err());
+        assert!(resolve_mut(&mut value, "/missing")
+            .unwrap_err()
+            .contains("no key \"missing\""));
+        assert!(resolve_mut(&mut value, "/name/x")
+            .unwrap_err()
+            .contains("cannot descend into string"));
+        assert!(resolve_mut(&mut value, "items").is_err());
+    }
+
+    #[test]
+    fn looks_up_dotted_field_paths() {
+        let value = json!({"metrics": {"latency_ms": 12}, "tags": ["a", "b"]});
+
+        assert_eq!(
+            lookup_field_path(&value, "metrics.latency_ms"),
+            Some(&json!(12))
+        );
+        assert_eq!(lookup_field_path(&value, "tags.1"), Some(&json!("b")));
+        assert_eq!(lookup_field_path(&value, "metrics.missing"), None);
+    }
+}
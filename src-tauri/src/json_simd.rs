@@ -0,0 +1,91 @@
+// SIMD validation of strict JSON
+//
+// simd-json checks a large document several times faster than serde_json
+// but says nothing about where an error is, so it only answers "valid".
+// Anything it rejects goes through the usual parsers, which find the error
+// position or accept the document as JSON5. Where simd-json is more lenient
+// than serde_json — nesting past serde_json's recursion limit and escaped
+// high surrogates without their low half — it doesn't answer either, so the
+// outcome is always the one the parsers would give.
+use simd_json::{Node, Tape};
+
+/// Deepest nesting serde_json parses; one more level fails with "recursion
+/// limit exceeded".
+pub(crate) const SERDE_MAX_DEPTH: usize = 127;
+
+/// Whether `content` is JSON that serde_json is sure to accept. `false` only
+/// means the parsers have to decide.
+pub(crate) fn accepts(content: &str) -> bool {
+    // simd-json parses in place, unescaping strings into the buffer.
+    let mut bytes = content.as_bytes().to_vec();
+    match simd_json::to_tape(&mut bytes) {
+        Ok(tape) => within_serde_depth(&tape) && !has_lone_surrogate_escape(content.as_bytes()),
+        Err(_) => false,
+    }
+}
+
+fn within_serde_depth(tape: &Tape) -> bool {
+    // Index of the last node of each open container.
+    let mut ends: Vec<usize> = Vec::new();
+    for (index, node) in tape.0.iter().enumerate() {
+        while ends.last().is_some_and(|&end| end < index) {
+            ends.pop();
+        }
+        if let Node::Array { count, .. } | Node::Object { count, .. } = node {
+            ends.push(index + count);
+            if ends.len() > SERDE_MAX_DEPTH {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Whether a `\u` escape in already validated JSON is a high surrogate that
+/// isn't followed by an escaped low one.
+fn has_lone_surrogate_escape(bytes: &[u8]) -> bool {
+    let escape_at = |at: usize| {
+        let hex = bytes.get(at..at + 6)?.strip_prefix(b"\\u")?;
+        u16::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()
+    };
+    let mut at = 0;
+    while let Some(offset) = bytes[at..].iter().position(|&byte| byte == b'\\') {
+        at += offset;
+        match escape_at(at) {
+            Some(0xD800..=0xDBFF) => {
+                if !matches!(escape_at(at + 6), Some(0xDC00..=0xDFFF)) {
+                    return true;
+                }
+                at += 12;
+            }
+            Some(_) => at += 6,
+            // `\"`, `\\` and the other two-character escapes
+            None => at += 2,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{accepts, SERDE_MAX_DEPTH};
+
+    fn nested(depth: usize, open: &str, close: &str) -> String {
+        format!("{}1{}", open.repeat(depth), close.repeat(depth))
+    }
+
+    #[test]
+    fn accepts_plain_json_up_to_the_serde_depth() {
+        assert!(accepts(r#"{"a":[1,2.5,-0,"é\n😀",null,true]}"#));
+        assert!(accepts(&nested(SERDE_MAX_DEPTH, "[", "]")));
+        assert!(!accepts(&nested(SERDE_MAX_DEPTH + 1, "[", "]")));
+        let parse = |text: &str| serde_json::from_str::<serde_json::Value>(text).is_ok();
+        assert!(parse(&nested(SERDE_MAX_DEPTH, "[", "]")));
+        assert!(!parse(&nested(SERDE_MAX_DEPTH + 1, "[", "]")));
+        assert!(accepts(&nested(SERDE_MAX_DEPTH / 3, r#"[{"k":[0,"#, "]}]")));
+        assert!(!accepts(r#"["\ud800"]"#));
+        assert!(accepts(r#"["\\ud800"]"#));
+        assert!(accepts(r#"["\ud83d\ude00"]"#));
+        assert!(!accepts("1e400"));
+    }
+}
@@ -0,0 +1,161 @@
+// Helpers for inspecting and comparing `serde_json::Value`s.
+
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde_json::{Number, Value};
+
+/// JSON type name used in error messages and results.
+pub(crate) fn value_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Render a number so that values equal under JSON number semantics
+/// (`1`, `1.0`, `1e0`) produce the same text.
+pub(crate) fn canonical_number(number: &Number) -> String {
+    if let Some(i) = number.as_i64() {
+        return i.to_string();
+    }
+    if let Some(u) = number.as_u64() {
+        return u.to_string();
+    }
+    // Integers beyond 64 bits are kept exactly rather than rounded through f64.
+    let text = number.to_string();
+    let digits = text.strip_prefix('-').unwrap_or(&text);
+    if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+        return text;
+    }
+    match number.as_f64() {
+        Some(f) if f.is_finite() && f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 => {
+            (f as i64).to_string()
+        }
+        Some(f) => f.to_string(),
+        None => number.to_string(),
+    }
+}
+
+/// Serializes a `Value` with numbers as plain integers or floats.
+///
+/// With `arbitrary_precision`, `Value` serializes numbers as a private
+/// string wrapper only serde_json understands, so output to other formats
+/// (YAML, TOML) has to go through this.
+pub(crate) struct PlainNumbers<'a>(pub &'a Value);
+
+impl Serialize for PlainNumbers<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(flag) => serializer.serialize_bool(*flag),
+            Value::Number(number) => {
+                if let Some(i) = number.as_i64() {
+                    serializer.serialize_i64(i)
+                } else if let Some(u) = number.as_u64() {
+                    serializer.serialize_u64(u)
+                } else {
+                    serializer.serialize_f64(number.as_f64().unwrap_or(f64::NAN))
+                }
+            }
+            Value::String(text) => serializer.serialize_str(text),
+            Value::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(&PlainNumbers(item))?;
+                }
+                seq.end()
+            }
+            Value::Object(map) => {
+                let mut out = serializer.serialize_map(Some(map.len()))?;
+                for (key, child) in map {
+                    out.serialize_entry(key, &PlainNumbers(child))?;
+                }
+                out.end()
+            }
+        }
+    }
+}
+
+/// Compact JSON rendering of `value`, cut to `max_chars` characters with an
+/// ellipsis for display in result lists.
+pub(crate) fn value_preview(value: &Value, max_chars: usize) -> String {
+    let text = value.to_string();
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text,
+    }
+}
+
+/// Order-insensitive, number-normalized rendering of `value`, suitable as a
+/// hash key for deep-equality checks.
+pub(crate) fn canonical_key(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&canonical_number(n)),
+        Value::String(s) => out.push_str(&serde_json::to_string(s).unwrap_or_default()),
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (index, (key, item)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(key).unwrap_or_default());
+                out.push(':');
+                write_canonical(item, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical_key, canonical_number, value_preview};
+    use serde_json::json;
+
+    #[test]
+    fn canonical_keys_ignore_key_order_and_number_spelling() {
+        assert_eq!(
+            canonical_key(&json!({"a": 1, "b": [1.0, "x"]})),
+            canonical_key(&json!({"b": [1, "x"], "a": 1.0}))
+        );
+        assert_ne!(canonical_key(&json!(1)), canonical_key(&json!("1")));
+    }
+
+    #[test]
+    fn previews_are_cut_on_character_boundaries() {
+        assert_eq!(value_preview(&json!({"a": 1}), 20), r#"{"a":1}"#);
+        assert_eq!(value_preview(&json!("héllo wörld"), 4), "\"hél…");
+    }
+
+    #[test]
+    fn canonical_numbers_keep_integers_beyond_64_bits() {
+        let big = |text: &str| canonical_number(&serde_json::from_str(text).unwrap());
+
+        assert_eq!(big("123456789012345678901"), "123456789012345678901");
+        assert_ne!(big("123456789012345678901"), big("123456789012345678902"));
+        assert_eq!(big("1e3"), big("1000"));
+    }
+}
@@ -0,0 +1,701 @@
+// Serialization helpers for formatted JSON output.
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Serialize;
+use serde_json::ser::Formatter;
+use serde_json::{Number, Value};
+use std::collections::{HashSet, VecDeque};
+use std::{fmt, io};
+
+use crate::error::{AppError, ErrorCode};
+use crate::json_lexer::{Lexer, TokenKind};
+
+/// Map key serde_json (with `arbitrary_precision`) hands a number to a
+/// visitor under, with the number's text as the value.
+pub(crate) const NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+/// How far ahead to look for the source literal of a number. Literals only
+/// go out of step when duplicate keys dropped a value.
+const LITERAL_LOOKAHEAD: usize = 64;
+
+/// Serialize `value` with a serde_json formatter.
+pub(crate) fn write_value<F: Formatter>(value: &Value, formatter: F) -> Result<String, AppError> {
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    value
+        .serialize(&mut serializer)
+        .map_err(|e| ErrorCode::Formatting.reason(e))?;
+    String::from_utf8(out).map_err(|e| ErrorCode::Formatting.reason(e))
+}
+
+/// Write strict JSON with a serde_json formatter straight from the parser,
+/// without building a `Value`. The output is what `write_value` gives for the
+/// parsed value.
+///
+/// `None` when `content` isn't strict JSON, or when an object repeats a key:
+/// the `Value` keeps only the last one, so the caller has to format that.
+pub(crate) fn transcode<F: Formatter>(content: &str, mut formatter: F) -> Option<String> {
+    // Formatting mostly adds whitespace, so this usually fits.
+    let mut out = Vec::with_capacity(content.len() + content.len() / 4);
+    let mut deserializer = serde_json::Deserializer::from_str(content);
+    Transcoder {
+        out: &mut out,
+        formatter: &mut formatter,
+    }
+    .deserialize(&mut deserializer)
+    .ok()?;
+    deserializer.end().ok()?;
+    String::from_utf8(out).ok()
+}
+
+/// Writes each value as the parser reads it.
+struct Transcoder<'a, F> {
+    out: &'a mut Vec<u8>,
+    formatter: &'a mut F,
+}
+
+impl<F> Transcoder<'_, F> {
+    fn reborrow(&mut self) -> Transcoder<'_, F> {
+        Transcoder {
+            out: self.out,
+            formatter: self.formatter,
+        }
+    }
+
+    /// A quoted, escaped string; formatters only change how strings are
+    /// escaped when they override the string methods, which ours don't.
+    fn write_str<E: de::Error>(&mut self, text: &str) -> Result<(), E> {
+        serde_json::to_writer(&mut *self.out, text).map_err(E::custom)
+    }
+}
+
+fn written<E: de::Error>(result: io::Result<()>) -> Result<(), E> {
+    result.map_err(E::custom)
+}
+
+impl<'de, F: Formatter> DeserializeSeed<'de> for Transcoder<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, F: Formatter> Visitor<'de> for Transcoder<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        written(self.formatter.write_null(self.out))
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<(), E> {
+        written(self.formatter.write_bool(self.out, value))
+    }
+
+    // Numbers that fit 64 bits come as integers, as text for the formatter
+    // like a `Value` number; the others as a map under `NUMBER_TOKEN`.
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<(), E> {
+        written(self.formatter.write_number_str(self.out, &value.to_string()))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<(), E> {
+        written(self.formatter.write_number_str(self.out, &value.to_string()))
+    }
+
+    fn visit_str<E: de::Error>(mut self, value: &str) -> Result<(), E> {
+        self.write_str(value)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<(), A::Error> {
+        written(self.formatter.begin_array(self.out))?;
+        let mut first = true;
+        while seq
+            .next_element_seed(Element {
+                inner: self.reborrow(),
+                first,
+            })?
+            .is_some()
+        {
+            first = false;
+        }
+        written(self.formatter.end_array(self.out))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        let Some(mut key) = map.next_key::<String>()? else {
+            written(self.formatter.begin_object(self.out))?;
+            return written(self.formatter.end_object(self.out));
+        };
+        if key == NUMBER_TOKEN {
+            let number: String = map.next_value()?;
+            return written(self.formatter.write_number_str(self.out, &number));
+        }
+        written(self.formatter.begin_object(self.out))?;
+        let mut seen = HashSet::new();
+        let mut first = true;
+        loop {
+            written(self.formatter.begin_object_key(self.out, first))?;
+            self.write_str(&key)?;
+            written(self.formatter.end_object_key(self.out))?;
+            if !seen.insert(key) {
+                return Err(de::Error::custom("duplicate key"));
+            }
+            written(self.formatter.begin_object_value(self.out))?;
+            map.next_value_seed(self.reborrow())?;
+            written(self.formatter.end_object_value(self.out))?;
+            first = false;
+            match map.next_key()? {
+                Some(next) => key = next,
+                None => break,
+            }
+        }
+        written(self.formatter.end_object(self.out))
+    }
+}
+
+/// An array element, with the formatter told where it starts and ends.
+struct Element<'a, F> {
+    inner: Transcoder<'a, F>,
+    first: bool,
+}
+
+impl<'de, F: Formatter> DeserializeSeed<'de> for Element<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(mut self, deserializer: D) -> Result<(), D::Error> {
+        let inner = &mut self.inner;
+        written(inner.formatter.begin_array_value(inner.out, self.first))?;
+        inner.reborrow().deserialize(deserializer)?;
+        written(inner.formatter.end_array_value(inner.out))
+    }
+}
+
+/// Length in bytes of the serialized `value`, without building the string.
+pub(crate) fn serialized_len<F: Formatter>(value: &Value, formatter: F) -> usize {
+    let mut counter = ByteCounter(0);
+    let mut serializer = serde_json::Serializer::with_formatter(&mut counter, formatter);
+    // Writing to a counter cannot fail, and Value always serializes.
+    let _ = value.serialize(&mut serializer);
+    counter.0
+}
+
+/// Length in bytes of `text` quoted and escaped like serde_json writes it.
+pub(crate) fn quoted_len(text: &str) -> usize {
+    let mut counter = ByteCounter(0);
+    let _ = serde_json::to_writer(&mut counter, text);
+    counter.0
+}
+
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Number literals of a source document, in document order, read from the
+/// source as they are needed.
+///
+/// serde_json keeps number digits verbatim but rewrites exponents (`1E3`
+/// becomes `1e+3`), so each number being written is matched against the next
+/// source literals and replaced by the original spelling.
+pub(crate) struct NumberLiterals<'a> {
+    source: Lexer<'a>,
+    /// Literals read ahead that haven't been written yet.
+    literals: VecDeque<String>,
+}
+
+impl<'a> NumberLiterals<'a> {
+    pub(crate) fn from_source(source: &'a str) -> Self {
+        Self {
+            source: Lexer::new(source),
+            literals: VecDeque::new(),
+        }
+    }
+
+    /// The source spelling of the next number, whose serde_json text is `value`.
+    pub(crate) fn spell(&mut self, value: &str) -> String {
+        while self.literals.len() < LITERAL_LOOKAHEAD {
+            let next = self.source.find_map(|token| match token.kind {
+                TokenKind::Number(text) => Some(text),
+                _ => None,
+            });
+            match next {
+                Some(literal) => self.literals.push_back(literal),
+                None => break,
+            }
+        }
+        let found = self
+            .literals
+            .iter()
+            .position(|literal| serde_spelling(literal) == value);
+        match found {
+            Some(index) => self
+                .literals
+                .drain(..=index)
+                .next_back()
+                .unwrap_or_default(),
+            None => value.to_string(),
+        }
+    }
+}
+
+/// The spelling serde_json uses for a literal: lowercase `e`, an explicit
+/// exponent sign, and `0` for the integer `-0`.
+fn serde_spelling(literal: &str) -> String {
+    if literal == "-0" {
+        return "0".to_string();
+    }
+    match literal.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) if exponent.starts_with(['+', '-']) => {
+            format!("{mantissa}e{exponent}")
+        }
+        Some((mantissa, exponent)) => format!("{mantissa}e+{exponent}"),
+        None => literal.to_string(),
+    }
+}
+
+/// Formatter that writes numbers exactly as they were spelled in the source.
+pub(crate) struct RawNumbers<'a, F> {
+    inner: F,
+    literals: NumberLiterals<'a>,
+}
+
+impl<'a, F> RawNumbers<'a, F> {
+    pub(crate) fn new(inner: F, source: &'a str) -> Self {
+        Self {
+            inner,
+            literals: NumberLiterals::from_source(source),
+        }
+    }
+}
+
+impl<F: Formatter> Formatter for RawNumbers<'_, F> {
+    fn write_number_str<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: &str,
+    ) -> io::Result<()> {
+        writer.write_all(self.literals.spell(value).as_bytes())
+    }
+
+    fn begin_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.begin_array(writer)
+    }
+
+    fn end_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.end_array(writer)
+    }
+
+    fn begin_array_value<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.inner.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.end_array_value(writer)
+    }
+
+    fn begin_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.begin_object(writer)
+    }
+
+    fn end_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.end_object(writer)
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.inner.begin_object_key(writer, first)
+    }
+
+    fn end_object_key<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.end_object_key(writer)
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.begin_object_value(writer)
+    }
+
+    fn end_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.end_object_value(writer)
+    }
+}
+
+/// Pretty-printer that keeps an array or object on one line when its
+/// single-line rendering fits within `width` columns (like Prettier's
+/// printWidth). Everything inside an inlined container is inlined too.
+pub(crate) struct WidthWriter<'a> {
+    indent_unit: &'a str,
+    width: usize,
+    literals: NumberLiterals<'a>,
+    out: String,
+}
+
+impl<'a> WidthWriter<'a> {
+    pub(crate) fn new(indent_unit: &'a str, width: usize, source: &'a str) -> Self {
+        Self {
+            indent_unit,
+            width,
+            literals: NumberLiterals::from_source(source),
+            out: String::new(),
+        }
+    }
+
+    pub(crate) fn write(mut self, value: &Value) -> String {
+        self.write_value(value, 0, 0, 0);
+        self.out
+    }
+
+    /// Write `value` starting at `column`, followed by `trailing` characters
+    /// (a comma) that must also fit on the line.
+    fn write_value(&mut self, value: &Value, depth: usize, column: usize, trailing: usize) {
+        let budget = self.width.saturating_sub(column + trailing);
+        let breakable = match value {
+            Value::Array(items) => !items.is_empty(),
+            Value::Object(map) => !map.is_empty(),
+            _ => false,
+        };
+        if !breakable || inline_len(value, budget).is_some() {
+            self.write_inline(value);
+            return;
+        }
+
+        let child_indent = self.indent_unit.repeat(depth + 1);
+        let child_column = (depth + 1) * self.indent_unit.chars().count();
+        match value {
+            Value::Array(items) => {
+                self.out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    let last = index + 1 == items.len();
+                    self.out.push('\n');
+                    self.out.push_str(&child_indent);
+                    self.write_value(item, depth + 1, child_column, usize::from(!last));
+                    if !last {
+                        self.out.push(',');
+                    }
+                }
+                self.close(depth, ']');
+            }
+            Value::Object(map) => {
+                self.out.push('{');
+                for (index, (key, child)) in map.iter().enumerate() {
+                    let last = index + 1 == map.len();
+                    let key = quoted(key);
+                    self.out.push('\n');
+                    self.out.push_str(&child_indent);
+                    self.out.push_str(&key);
+                    self.out.push_str(": ");
+                    let column = child_column + key.chars().count() + 2;
+                    self.write_value(child, depth + 1, column, usize::from(!last));
+                    if !last {
+                        self.out.push(',');
+                    }
+                }
+                self.close(depth, '}');
+            }
+            _ => unreachable!("only containers are broken across lines"),
+        }
+    }
+
+    fn close(&mut self, depth: usize, closer: char) {
+        self.out.push('\n');
+        self.out.push_str(&self.indent_unit.repeat(depth));
+        self.out.push(closer);
+    }
+
+    fn write_inline(&mut self, value: &Value) {
+        match value {
+            Value::Number(number) => {
+                let spelled = self.literals.spell(&number.to_string());
+                self.out.push_str(&spelled);
+            }
+            Value::Array(items) => {
+                self.out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        self.out.push_str(", ");
+                    }
+                    self.write_inline(item);
+                }
+                self.out.push(']');
+            }
+            Value::Object(map) => {
+                self.out.push('{');
+                for (index, (key, child)) in map.iter().enumerate() {
+                    if index > 0 {
+                        self.out.push_str(", ");
+                    }
+                    self.out.push_str(&quoted(key));
+                    self.out.push_str(": ");
+                    self.write_inline(child);
+                }
+                self.out.push('}');
+            }
+            scalar => self.out.push_str(&scalar.to_string()),
+        }
+    }
+}
+
+fn quoted(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+/// Length of the single-line rendering of `value`, or `None` as soon as it
+/// exceeds `budget` so huge subtrees aren't measured in full.
+fn inline_len(value: &Value, budget: usize) -> Option<usize> {
+    let len = match value {
+        Value::Null => 4,
+        Value::Bool(flag) => 4 + usize::from(!flag),
+        Value::Number(number) => number.to_string().len(),
+        Value::String(text) => quoted(text).chars().count(),
+        Value::Array(items) => {
+            let mut len = 2 + items.len().saturating_sub(1) * 2;
+            for item in items {
+                len += inline_len(item, budget.checked_sub(len)?)?;
+            }
+            len
+        }
+        Value::Object(map) => {
+            let mut len = 2 + map.len().saturating_sub(1) * 2;
+            for (key, child) in map {
+                len += quoted(key).chars().count() + 2;
+                len += inline_len(child, budget.checked_sub(len)?)?;
+            }
+            len
+        }
+    };
+    (len <= budget).then_some(len)
+}
+
+/// Rewrite every number in its shortest form: integers stay exact, other
+/// numbers go through f64 (`1e3` becomes `1000.0`, `2.50` becomes `2.5`).
+pub(crate) fn normalize_numbers(value: &mut Value) {
+    match value {
+        Value::Number(number) => {
+            let normalized = if let Some(i) = number.as_i64() {
+                Some(Number::from(i))
+            } else if let Some(u) = number.as_u64() {
+                Some(Number::from(u))
+            } else if number.is_f64() {
+                number.as_f64().and_then(Number::from_f64)
+            } else {
+                None
+            };
+            if let Some(normalized) = normalized {
+                *number = normalized;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize_numbers),
+        Value::Object(map) => map.values_mut().for_each(normalize_numbers),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        normalize_numbers, serialized_len, transcode, write_value, RawNumbers, WidthWriter,
+    };
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use serde_json::ser::{CompactFormatter, PrettyFormatter};
+    use serde_json::Value;
+
+    const NUMBERS: &[&str] = &[
+        "0",
+        "-0",
+        "7",
+        "1E3",
+        "1e+3",
+        "2.50",
+        "-0.0",
+        "6.02e+23",
+        "1e-7",
+        "1.5E-300",
+        "12345678901234567890123",
+        "-9223372036854775809",
+    ];
+    const STRINGS: &[&str] = &[
+        "",
+        "plain",
+        "Zoë",
+        "😀",
+        r"\u00e9",
+        r"\ud83d\ude00",
+        r"line\nbreak",
+        r"\/",
+        r#"\"q\""#,
+        r"tab\t\u0001",
+        r"\\",
+    ];
+
+    /// A random strict JSON document with unique keys, in random layout.
+    fn random_document(rng: &mut StdRng, depth: usize) -> String {
+        let space = |rng: &mut StdRng| [" ", "", "\n  ", "\t"][rng.gen_range(0..4)];
+        let choice = if depth >= 5 {
+            rng.gen_range(0..5)
+        } else {
+            rng.gen_range(0..7)
+        };
+        match choice {
+            0 => ["true", "false", "null"][rng.gen_range(0..3)].to_string(),
+            1 | 2 => NUMBERS[rng.gen_range(0..NUMBERS.len())].to_string(),
+            3 | 4 => format!("\"{}\"", STRINGS[rng.gen_range(0..STRINGS.len())]),
+            5 => {
+                let items: Vec<String> = (0..rng.gen_range(0..4))
+                    .map(|_| format!("{}{}", space(rng), random_document(rng, depth + 1)))
+                    .collect();
+                format!("[{}{}]", items.join(","), space(rng))
+            }
+            _ => {
+                let members: Vec<String> = (0..rng.gen_range(0..4))
+                    .map(|index| {
+                        let key = STRINGS[rng.gen_range(0..STRINGS.len())];
+                        let value = random_document(rng, depth + 1);
+                        format!("{}\"{key}{index}\"{}:{}", space(rng), space(rng), value)
+                    })
+                    .collect();
+                format!("{{{}}}", members.join(","))
+            }
+        }
+    }
+
+    #[test]
+    fn transcoding_matches_writing_the_parsed_value() {
+        let mut rng = StdRng::seed_from_u64(0x15_0397);
+        for _ in 0..300 {
+            let source = random_document(&mut rng, 0);
+            let value: Value = serde_json::from_str(&source).unwrap();
+
+            for indent in ["  ", "\t"] {
+                assert_eq!(
+                    transcode(
+                        &source,
+                        RawNumbers::new(PrettyFormatter::with_indent(indent.as_bytes()), &source)
+                    ),
+                    Some(
+                        write_value(
+                            &value,
+                            RawNumbers::new(
+                                PrettyFormatter::with_indent(indent.as_bytes()),
+                                &source
+                            )
+                        )
+                        .unwrap()
+                    ),
+                    "{source}"
+                );
+            }
+            assert_eq!(
+                transcode(&source, RawNumbers::new(CompactFormatter, &source)),
+                Some(write_value(&value, RawNumbers::new(CompactFormatter, &source)).unwrap())
+            );
+            assert_eq!(
+                transcode(&source, CompactFormatter),
+                Some(serde_json::to_string(&value).unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn transcoding_leaves_other_documents_to_the_value() {
+        for source in [
+            r#"{"a": 1, "b": 2, "a": 3}"#,
+            "{a: 1}",
+            "[1,]",
+            "[1] x",
+            r#""\ud800""#,
+            &format!("{}1{}", "[".repeat(129), "]".repeat(129)),
+        ] {
+            assert_eq!(transcode(source, CompactFormatter), None, "{source}");
+        }
+        assert_eq!(
+            transcode(r#"{"a": {"a": [{"a": 1}]}}"#, CompactFormatter).as_deref(),
+            Some(r#"{"a":{"a":[{"a":1}]}}"#)
+        );
+    }
+
+    #[test]
+    fn raw_numbers_keep_source_spelling() {
+        let source = r#"[1E3, 2.50, -0, 1.0, 6.02e+23, 1e-7, {"a": 12345678901234567890123}]"#;
+        let value: Value = serde_json::from_str(source).unwrap();
+
+        let compact = write_value(&value, RawNumbers::new(CompactFormatter, source)).unwrap();
+        assert_eq!(
+            compact,
+            r#"[1E3,2.50,-0,1.0,6.02e+23,1e-7,{"a":12345678901234567890123}]"#
+        );
+
+        let pretty = write_value(&value, RawNumbers::new(PrettyFormatter::new(), source)).unwrap();
+        assert!(pretty.contains("\n  1E3,\n  2.50,"));
+    }
+
+    #[test]
+    fn normalizing_rewrites_numbers_through_f64() {
+        let mut value: Value =
+            serde_json::from_str("[1e3, 2.50, -0, 7, 12345678901234567890123]").unwrap();
+
+        normalize_numbers(&mut value);
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            "[1000.0,2.5,0,7,12345678901234567890123]"
+        );
+    }
+
+    #[test]
+    fn width_writer_inlines_containers_that_fit() {
+        let source = r#"{"type": "LineString", "coordinates": [[102.0, 0.5], [103.0, 1.0E1]], "properties": {"name": "a fairly long feature name", "tags": ["x", "y"]}}"#;
+        let value: Value = serde_json::from_str(source).unwrap();
+
+        assert_eq!(
+            WidthWriter::new("  ", 60, source).write(&value),
+            concat!(
+                "{\n",
+                "  \"type\": \"LineString\",\n",
+                "  \"coordinates\": [[102.0, 0.5], [103.0, 1.0E1]],\n",
+                "  \"properties\": {\n",
+                "    \"name\": \"a fairly long feature name\",\n",
+                "    \"tags\": [\"x\", \"y\"]\n",
+                "  }\n",
+                "}"
+            )
+        );
+        assert_eq!(
+            WidthWriter::new("  ", 200, source)
+                .write(&value)
+                .lines()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn serialized_len_matches_written_output() {
+        let source = r#"{"name": "Zoë", "values": [1E3, 2.50, null]}"#;
+        let value: Value = serde_json::from_str(source).unwrap();
+
+        let written = write_value(&value, RawNumbers::new(CompactFormatter, source)).unwrap();
+        assert_eq!(
+            serialized_len(&value, RawNumbers::new(CompactFormatter, source)),
+            written.len()
+        );
+    }
+}
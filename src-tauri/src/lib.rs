@@ -1,33 +1,107 @@
 mod app_state;
+mod cli;
 mod commands;
+mod deep_link;
+mod error;
+mod http;
+mod json_lexer;
+mod json_pointer;
+mod json_simd;
+mod json_value;
+mod json_writer;
+#[cfg(target_os = "macos")]
+mod macos_app_menu;
 #[cfg(target_os = "macos")]
 mod macos_menu_view;
 #[cfg(target_os = "macos")]
 mod macos_window;
+mod text_encoding;
 mod window_bounds;
 
 use app_state::{
-    collect_json_file_args, focus_main_window, get_pending_files, queue_or_emit_open_files,
+    collect_json_file_args, focus_main_window, frontend_ready, queue_or_emit_open_files,
+    PendingEvents,
+};
+use commands::archive::{zip_list_entries, zip_read_entry};
+use commands::breadcrumb::json_path_at_offset;
+use commands::canonical::{json_canonicalize, json_hash};
+use commands::captures::{capture_clipboard_to_scratch, get_scratch_captures};
+use commands::clipboard_history::{
+    clear_clipboard_history, get_clipboard_history, get_clipboard_history_item, ClipboardHistory,
 };
 use commands::codegen::{code_to_json, json_to_code};
+use commands::crash::{list_crash_reports, read_crash_report};
 use commands::convert::{
     csv_to_json, json_to_csv, json_to_toml, json_to_xml, json_to_yaml, toml_to_json, xml_to_json,
     yaml_to_json,
 };
+use commands::diff::{json_diff, json_diff_async, json_equivalent};
+use commands::document::{
+    close_document, json_tree_children, open_document, update_document, DocumentStore,
+};
+use commands::drafts::{delete_draft, list_drafts, load_draft, save_draft};
 use commands::export_image::export_json_image;
+use commands::extract::{extract_json_from_file, extract_json_from_text};
 use commands::file::{
-    create_untitled_json, get_file_name, is_json_file, open_file_dialog, open_folder_dialog,
-    read_file, read_json_dir, rename_file, save_binary_file_dialog, save_file, save_file_dialog,
-    show_in_folder,
+    cancel_file_read, compare_files, create_untitled_json, detect_json_content, export_file_dialog,
+    get_file_name, is_json_file, join_json_files, list_json_files, open_compare_dialog,
+    open_file_dialog, open_files_dialog, open_folder_dialog, read_file, read_file_preview,
+    read_file_streamed, read_files, read_json_dir, rename_file, save_binary_file_dialog, save_file,
+    save_file_dialog, show_in_folder, split_json_file, FileReads,
 };
 use commands::file_watcher::{unwatch_all_files, unwatch_file, watch_file, FileWatcherState};
-use commands::json::{json_escape, json_format, json_minify, json_unescape};
+use commands::history::{list_file_history, read_file_history};
+use commands::incremental::json_validate_incremental;
+use commands::json::{
+    escape_for_language, json_escape, json_escape_levels, json_format, json_format_async,
+    json_format_document, json_minify, json_minify_async, json_minify_document, json_unescape,
+    json_unescape_levels, json_validate,
+};
+use commands::lint::json_lint;
+use commands::logging::{get_log_file_path, get_recent_logs, set_log_level};
+use commands::mapped::{
+    close_mapped_file, json_extract_mapped, json_stats_mapped, json_validate_mapped,
+    open_mapped_file, MappedFiles,
+};
+use commands::merge::{json_merge, json_merge_patch, json_merge_patch_generate};
+use commands::parse_cache::ParseCache;
+use commands::patch::{json_patch_apply, json_patch_generate};
+use commands::progress::set_progress;
+use commands::settings::{get_setting, get_settings, reset_settings, set_setting};
+use commands::query::{
+    json_aggregate, json_aggregate_document, json_extract, json_extract_document,
+    json_find_duplicates, json_find_duplicates_document, json_find_key, json_find_key_document,
+    json_histogram, json_histogram_document, json_sample, json_sample_document,
+};
+use commands::quick_peek::{get_quick_peek_content, show_quick_peek, QuickPeek};
+use commands::repair::json_repair;
+use commands::report::export_report;
+use commands::results::{read_result_chunk, release_result, ResultStore};
+use commands::schema_diff::{json_schema_diff, json_schema_diff_async};
 use commands::shortcuts::{
-    format_clipboard_and_show, register_global_shortcut, show_main_window, update_shortcut,
-    GlobalShortcutRegistry, DEFAULT_FORMAT_CLIPBOARD_SHORTCUT, DEFAULT_SHOW_APP_SHORTCUT,
-    FORMAT_CLIPBOARD_SHORTCUT_ID, SHOW_APP_SHORTCUT_ID,
+    format_clipboard_and_show, format_clipboard_in_place, list_shortcuts,
+    register_default_shortcuts, set_shortcuts_enabled, shortcuts_enabled, show_main_window,
+    update_shortcut, validate_shortcut, GlobalShortcutRegistry,
 };
-use commands::window::{desktop_platform, open_devtools, quit_app, restart_app, set_window_theme};
+use commands::source_map::json_source_map;
+use commands::stats::{json_stats, json_stats_document};
+use commands::ndjson::{ndjson_format, ndjson_stats, ndjson_to_array, ndjson_validate};
+use commands::store::{add_recent_file, clear_recent_files, get_recent_files, remove_recent_file};
+use commands::transform::{
+    json_annotate_timestamps, json_anonymize, json_clean, json_collapse_nested, json_convert_keys,
+    json_convert_timestamps, json_dedupe_array, json_expand_nested, json_filter_array, json_group_by,
+    json_pick, json_redact,
+};
+use commands::tasks::{cancel_task, list_tasks, TaskManager};
+use commands::validate::{json_validate_all, json_validate_all_async};
+use commands::window::{
+    create_main_window, desktop_platform, get_devtools_enabled, get_system_theme, get_zoom,
+    handle_window_event, is_always_on_top, is_mini_mode_enabled, new_window, open_devtools,
+    quit_app, reset_window_geometry, restart_app, set_always_on_top, set_devtools_enabled,
+    set_mini_mode, set_window_theme, set_window_vibrancy, set_zoom, take_window_initial_content,
+    EditorWindows, MiniModeWindows, PinnedWindows, WindowVibrancy,
+};
+use error::set_locale;
 use window_bounds::schedule_main_window_bounds_clamp;
 
 #[tauri::command]
@@ -42,8 +116,27 @@ fn set_app_menu_language(app: tauri::AppHandle, language: String) -> Result<(),
     }
 }
 
+/// Enable the document items of the native menu while a document is open
+#[tauri::command]
+fn set_menu_document_open(app: tauri::AppHandle, open: bool) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    return macos_app_menu::set_document_open(&app, open);
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (app, open);
+        Ok(())
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+    commands::crash::install_panic_hook();
+
     let builder = tauri::Builder::default()
         .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
             let paths = collect_json_file_args(&args, &cwd);
@@ -53,12 +146,19 @@ pub fn run() {
                 queue_or_emit_open_files(app, paths);
             }
         }))
-        .plugin(tauri_plugin_window_state::Builder::default().build())
+        .plugin(
+            tauri_plugin_window_state::Builder::default()
+                // Quick peek positions itself next to the cursor each time.
+                .with_denylist(&["quick-peek"])
+                .build(),
+        )
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_fs::init());
+        .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init());
 
     #[cfg(desktop)]
     let builder = builder.plugin(tauri_plugin_updater::Builder::new().build());
@@ -66,8 +166,25 @@ pub fn run() {
     let app = builder
         .manage(FileWatcherState::new())
         .manage(GlobalShortcutRegistry::default())
+        .manage(DocumentStore::default())
+        .manage(FileReads::default())
+        .manage(MappedFiles::default())
+        .manage(ResultStore::default())
+        .manage(ParseCache::default())
+        .manage(TaskManager::default())
+        .manage(ClipboardHistory::default())
+        .manage(QuickPeek::default())
+        .manage(PinnedWindows::default())
+        .manage(MiniModeWindows::default())
+        .manage(WindowVibrancy::default())
+        .manage(EditorWindows::default())
+        .manage(PendingEvents::default())
         .setup(|app| {
             let app_handle = app.handle().clone();
+            commands::logging::init(&app_handle);
+            commands::crash::init(&app_handle);
+            error::init_locale(&app_handle);
+            create_main_window(&app_handle)?;
             schedule_main_window_bounds_clamp(&app_handle);
             #[cfg(target_os = "macos")]
             macos_window::setup(&app_handle)?;
@@ -80,42 +197,151 @@ pub fn run() {
                     .unwrap_or_default();
                 queue_or_emit_open_files(&app_handle, collect_json_file_args(&args, &cwd));
             }
-            if let Err(error) = register_global_shortcut(
-                &app_handle,
-                SHOW_APP_SHORTCUT_ID,
-                DEFAULT_SHOW_APP_SHORTCUT,
-            ) {
-                eprintln!("Failed to register show app shortcut: {error}");
-            }
-            if let Err(error) = register_global_shortcut(
-                &app_handle,
-                FORMAT_CLIPBOARD_SHORTCUT_ID,
-                DEFAULT_FORMAT_CLIPBOARD_SHORTCUT,
-            ) {
-                eprintln!("Failed to register format clipboard shortcut: {error}");
-            }
+            register_default_shortcuts(&app_handle);
+            deep_link::setup(&app_handle);
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(commands::logging::instrument(tauri::generate_handler![
+            set_locale,
             json_format,
+            json_format_document,
+            json_format_async,
             json_minify,
+            json_minify_document,
+            json_minify_async,
+            json_validate,
+            json_validate_all,
+            json_validate_all_async,
+            cancel_task,
+            list_tasks,
+            json_source_map,
+            json_path_at_offset,
+            open_document,
+            update_document,
+            close_document,
+            json_tree_children,
+            json_stats,
+            json_stats_document,
+            json_validate_incremental,
+            open_mapped_file,
+            close_mapped_file,
+            json_validate_mapped,
+            json_stats_mapped,
+            json_extract_mapped,
+            read_result_chunk,
+            release_result,
+            ndjson_validate,
+            ndjson_format,
+            ndjson_to_array,
+            ndjson_stats,
+            json_lint,
             json_escape,
             json_unescape,
+            json_escape_levels,
+            json_unescape_levels,
+            escape_for_language,
+            json_repair,
+            extract_json_from_text,
+            extract_json_from_file,
+            json_clean,
+            json_convert_keys,
+            json_redact,
+            json_anonymize,
+            json_dedupe_array,
+            json_group_by,
+            json_filter_array,
+            json_pick,
+            json_expand_nested,
+            json_collapse_nested,
+            json_convert_timestamps,
+            json_annotate_timestamps,
+            json_merge,
+            json_merge_patch,
+            json_merge_patch_generate,
+            json_patch_apply,
+            json_patch_generate,
+            json_diff,
+            json_diff_async,
+            json_equivalent,
+            json_schema_diff,
+            json_schema_diff_async,
+            json_canonicalize,
+            json_hash,
+            json_sample,
+            json_sample_document,
+            json_extract,
+            json_extract_document,
+            json_find_key,
+            json_find_key_document,
+            json_aggregate,
+            json_aggregate_document,
+            json_find_duplicates,
+            json_find_duplicates_document,
+            json_histogram,
+            json_histogram_document,
             set_window_theme,
+            set_window_vibrancy,
+            get_system_theme,
+            set_zoom,
+            get_zoom,
             desktop_platform,
             open_devtools,
+            set_devtools_enabled,
+            get_devtools_enabled,
+            set_always_on_top,
+            is_always_on_top,
+            reset_window_geometry,
+            set_mini_mode,
+            is_mini_mode_enabled,
+            set_progress,
+            get_settings,
+            get_setting,
+            set_setting,
+            reset_settings,
+            get_recent_logs,
+            list_crash_reports,
+            read_crash_report,
+            get_log_file_path,
+            set_log_level,
+            new_window,
+            take_window_initial_content,
             show_main_window,
             format_clipboard_and_show,
+            format_clipboard_in_place,
+            get_clipboard_history,
+            get_clipboard_history_item,
+            clear_clipboard_history,
             update_shortcut,
+            list_shortcuts,
+            set_shortcuts_enabled,
+            shortcuts_enabled,
+            show_quick_peek,
+            get_quick_peek_content,
+            capture_clipboard_to_scratch,
+            get_scratch_captures,
+            validate_shortcut,
             open_file_dialog,
+            open_files_dialog,
+            open_compare_dialog,
+            compare_files,
             save_file,
+            list_file_history,
+            read_file_history,
             save_file_dialog,
             save_binary_file_dialog,
+            export_file_dialog,
+            export_report,
             open_folder_dialog,
             read_json_dir,
+            list_json_files,
             create_untitled_json,
             read_file,
+            read_files,
+            read_file_streamed,
+            read_file_preview,
+            cancel_file_read,
             is_json_file,
+            detect_json_content,
             get_file_name,
             rename_file,
             watch_file,
@@ -132,16 +358,32 @@ pub fn run() {
             json_to_code,
             code_to_json,
             export_json_image,
-            get_pending_files,
+            frontend_ready,
             show_in_folder,
+            add_recent_file,
+            get_recent_files,
+            remove_recent_file,
+            clear_recent_files,
+            save_draft,
+            list_drafts,
+            load_draft,
+            delete_draft,
+            split_json_file,
+            join_json_files,
+            zip_list_entries,
+            zip_read_entry,
             quit_app,
             restart_app,
-            set_app_menu_language
-        ])
+            set_app_menu_language,
+            set_menu_document_open
+        ]))
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
 
     app.run(|app_handle, event| {
+        if let tauri::RunEvent::WindowEvent { label, event, .. } = &event {
+            handle_window_event(app_handle, label, event);
+        }
         #[cfg(target_os = "macos")]
         macos_window::handle_run_event(app_handle, &event);
     });
@@ -0,0 +1,243 @@
+// File, JSON and Help menus of the macOS menu bar
+//
+// Opening files happens here. Everything that needs the open document is
+// sent to the focused editor window as a `menu-action` event and runs there
+// like the matching shortcut. Those items are disabled while no document is
+// open, and Open Recent is rebuilt whenever the recent files store changes.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::menu::{MenuItem, MenuItemBuilder, Submenu, SubmenuBuilder, HELP_SUBMENU_ID};
+use tauri::{AppHandle, Emitter, Listener, Manager, Wry};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::app_state::{focus_main_window, queue_or_emit_open_files};
+use crate::commands::file::{dialog_extensions, file_dialog, remember_parent};
+use crate::commands::store::{clear_recent_files, get_recent_files};
+use crate::commands::window::target_window;
+
+const OPEN_FILE_ID: &str = "menu_open_file";
+const CLEAR_RECENT_ID: &str = "menu_clear_recent";
+/// Recent file items carry their path after this prefix.
+const OPEN_RECENT_PREFIX: &str = "menu_open_recent:";
+/// Document items carry the frontend shortcut action after this prefix.
+const ACTION_PREFIX: &str = "menu_action:";
+const WEBSITE_ID: &str = "menu_help_website";
+const CHANGELOG_ID: &str = "menu_help_changelog";
+const WEBSITE_URL: &str = "https://jsonstudio.js.org/";
+const CHANGELOG_URL: &str = "https://jsonstudio.js.org/changelog";
+
+/// Menu items that change with the open document and the recent files.
+#[derive(Default)]
+pub(crate) struct AppMenuState {
+    document_items: Mutex<Vec<MenuItem<Wry>>>,
+    open_recent: Mutex<Option<Submenu<Wry>>>,
+    language: Mutex<String>,
+    has_document: AtomicBool,
+}
+
+fn text(language: &str, key: &str) -> &'static str {
+    match (language, key) {
+        ("en", "file") => "File",
+        (_, "file") => "文件",
+        ("en", "open") => "Open...",
+        (_, "open") => "打开...",
+        ("en", "open_recent") => "Open Recent",
+        (_, "open_recent") => "打开最近使用",
+        ("en", "clear_recent") => "Clear Menu",
+        (_, "clear_recent") => "清除菜单",
+        ("en", "save") => "Save",
+        (_, "save") => "保存",
+        ("en", "save_as") => "Save As...",
+        (_, "save_as") => "另存为...",
+        ("en", "export") => "Export Image...",
+        (_, "export") => "导出图片...",
+        ("en", "format") => "Format",
+        (_, "format") => "格式化",
+        ("en", "minify") => "Minify",
+        (_, "minify") => "压缩",
+        ("en", "validate") => "Validate",
+        (_, "validate") => "校验",
+        ("en", "compare") => "Compare...",
+        (_, "compare") => "对比...",
+        ("en", "help") => "Help",
+        (_, "help") => "帮助",
+        ("en", "website") => "Json Studio Website",
+        (_, "website") => "Json Studio 官网",
+        ("en", "changelog") => "Release Notes",
+        _ => "更新日志",
+    }
+}
+
+fn document_item(
+    app: &AppHandle,
+    language: &str,
+    key: &str,
+    action: &str,
+    accelerator: Option<&str>,
+) -> tauri::Result<MenuItem<Wry>> {
+    let state = app.state::<AppMenuState>();
+    let mut builder =
+        MenuItemBuilder::with_id(format!("{ACTION_PREFIX}{action}"), text(language, key))
+            .enabled(state.has_document.load(Ordering::SeqCst));
+    if let Some(accelerator) = accelerator {
+        builder = builder.accelerator(accelerator);
+    }
+    let item = builder.build(app)?;
+    state.document_items.lock().unwrap().push(item.clone());
+    Ok(item)
+}
+
+/// The File, JSON and Help submenus for `language`, replacing the items
+/// tracked from the previous build.
+pub(crate) fn build_menus(app: &AppHandle, language: &str) -> tauri::Result<[Submenu<Wry>; 3]> {
+    let state = app.state::<AppMenuState>();
+    state.document_items.lock().unwrap().clear();
+    *state.language.lock().unwrap() = language.to_owned();
+
+    let open = MenuItemBuilder::with_id(OPEN_FILE_ID, text(language, "open"))
+        .accelerator("CmdOrCtrl+O")
+        .build(app)?;
+    let open_recent = SubmenuBuilder::new(app, text(language, "open_recent")).build()?;
+    fill_open_recent(app, &open_recent, language)?;
+    *state.open_recent.lock().unwrap() = Some(open_recent.clone());
+    let file_menu = SubmenuBuilder::new(app, text(language, "file"))
+        .item(&open)
+        .item(&open_recent)
+        .separator()
+        .item(&document_item(
+            app,
+            language,
+            "save",
+            "save_file",
+            Some("CmdOrCtrl+S"),
+        )?)
+        .item(&document_item(
+            app,
+            language,
+            "save_as",
+            "save_as",
+            Some("CmdOrCtrl+Shift+S"),
+        )?)
+        .separator()
+        .item(&document_item(
+            app,
+            language,
+            "export",
+            "export_image",
+            None,
+        )?)
+        .build()?;
+
+    let json_menu = SubmenuBuilder::new(app, "JSON")
+        .item(&document_item(app, language, "format", "format", None)?)
+        .item(&document_item(app, language, "minify", "minify", None)?)
+        .item(&document_item(app, language, "validate", "validate", None)?)
+        .separator()
+        .item(&document_item(app, language, "compare", "compare", None)?)
+        .build()?;
+
+    let help_menu = SubmenuBuilder::with_id(app, HELP_SUBMENU_ID, text(language, "help"))
+        .text(WEBSITE_ID, text(language, "website"))
+        .text(CHANGELOG_ID, text(language, "changelog"))
+        .build()?;
+
+    Ok([file_menu, json_menu, help_menu])
+}
+
+fn fill_open_recent(app: &AppHandle, menu: &Submenu<Wry>, language: &str) -> tauri::Result<()> {
+    while menu.remove_at(0)?.is_some() {}
+    let recent = get_recent_files(app.clone()).unwrap_or_default();
+    for file in &recent {
+        let item =
+            MenuItemBuilder::with_id(format!("{OPEN_RECENT_PREFIX}{}", file.path), &file.path)
+                .enabled(file.still_exists)
+                .build(app)?;
+        menu.append(&item)?;
+    }
+    if !recent.is_empty() {
+        menu.append(&tauri::menu::PredefinedMenuItem::separator(app)?)?;
+    }
+    let clear = MenuItemBuilder::with_id(CLEAR_RECENT_ID, text(language, "clear_recent"))
+        .enabled(!recent.is_empty())
+        .build(app)?;
+    menu.append(&clear)
+}
+
+/// Enable the document items while a document is open in the focused window.
+pub(crate) fn set_document_open(app: &AppHandle, open: bool) -> Result<(), String> {
+    let state = app.state::<AppMenuState>();
+    state.has_document.store(open, Ordering::SeqCst);
+    for item in state.document_items.lock().unwrap().iter() {
+        item.set_enabled(open).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn refresh_open_recent(app: &AppHandle) {
+    let state = app.state::<AppMenuState>();
+    let menu = state.open_recent.lock().unwrap().clone();
+    let language = state.language.lock().unwrap().clone();
+    if let Some(menu) = menu {
+        if let Err(error) = fill_open_recent(app, &menu, &language) {
+            log::warn!("Failed to update Open Recent: {error}");
+        }
+    }
+}
+
+/// Track the menu state and keep Open Recent in step with the recent files
+/// store. Runs before the menu is first built.
+pub(crate) fn setup(app: &AppHandle) {
+    app.manage(AppMenuState::default());
+    let app_handle = app.clone();
+    app.listen_any("recent-files-changed", move |_| {
+        refresh_open_recent(&app_handle)
+    });
+}
+
+fn open_files(app: &AppHandle) {
+    let app_handle = app.clone();
+    file_dialog(app)
+        .add_filter("JSON Files", &dialog_extensions())
+        .add_filter("All Files", &["*"])
+        .pick_files(move |paths| {
+            let paths: Vec<String> = paths
+                .unwrap_or_default()
+                .into_iter()
+                .map(|path| path.to_string())
+                .collect();
+            if let Some(first) = paths.first() {
+                remember_parent(&app_handle, first);
+            }
+            queue_or_emit_open_files(&app_handle, paths);
+        });
+}
+
+/// Handle a click on one of these menus; returns false for other items.
+pub(crate) fn handle_menu_event(app: &AppHandle, id: &str) -> bool {
+    if let Some(action) = id.strip_prefix(ACTION_PREFIX) {
+        focus_main_window(app);
+        if let Some(window) = target_window(app) {
+            let _ = app.emit_to(window.label(), "menu-action", action);
+        }
+    } else if let Some(path) = id.strip_prefix(OPEN_RECENT_PREFIX) {
+        queue_or_emit_open_files(app, vec![path.to_string()]);
+    } else if id == OPEN_FILE_ID {
+        open_files(app);
+    } else if id == CLEAR_RECENT_ID {
+        if let Err(error) = clear_recent_files(app.clone()) {
+            log::warn!("Failed to clear recent files: {error}");
+        }
+    } else if id == WEBSITE_ID || id == CHANGELOG_ID {
+        let url = if id == WEBSITE_ID {
+            WEBSITE_URL
+        } else {
+            CHANGELOG_URL
+        };
+        if let Err(error) = app.opener().open_url(url, None::<&str>) {
+            log::warn!("Failed to open {url}: {error}");
+        }
+    } else {
+        return false;
+    }
+    true
+}
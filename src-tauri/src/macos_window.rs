@@ -1,5 +1,6 @@
-use crate::app_state::{focus_main_window, queue_or_emit_open_files};
+use crate::app_state::{focus_main_window, is_openable_json_file, queue_or_emit_open_files};
 use crate::commands::window::{apply_macos_transparent_chrome, reposition_macos_traffic_lights};
+use crate::macos_app_menu;
 use crate::macos_menu_view::make_window_position_menu_item_view;
 use crate::window_bounds::restored_window_axis;
 use tauri::menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder, WINDOW_SUBMENU_ID};
@@ -331,9 +332,17 @@ fn set_menu(app: &tauri::AppHandle, language: &str) -> tauri::Result<()> {
         .maximize()
         .fullscreen()
         .build()?;
+    let [file_menu, json_menu, help_menu] = macos_app_menu::build_menus(app, language)?;
     app.set_menu(
         MenuBuilder::new(app)
-            .items(&[&app_menu, &edit_menu, &window_menu])
+            .items(&[
+                &app_menu,
+                &file_menu,
+                &edit_menu,
+                &json_menu,
+                &window_menu,
+                &help_menu,
+            ])
             .build()?,
     )?;
     schedule_menu_views(app.clone(), language.to_owned(), 4);
@@ -348,6 +357,7 @@ pub(crate) fn setup(app: &tauri::AppHandle) -> tauri::Result<()> {
     if let Some(window) = app.get_webview_window("main") {
         apply_macos_transparent_chrome(window.ns_window()?);
     }
+    macos_app_menu::setup(app);
     set_menu(app, "zh")?;
     install_event_tap(app);
     app.on_menu_event(|app, event| match event.id().0.as_str() {
@@ -378,7 +388,9 @@ pub(crate) fn setup(app: &tauri::AppHandle) -> tauri::Result<()> {
         "window_move_top" => position_main_window(app, Placement::Top),
         "window_move_bottom" => position_main_window(app, Placement::Bottom),
         "window_move_center" => position_main_window(app, Placement::Center),
-        _ => {}
+        id => {
+            macos_app_menu::handle_menu_event(app, id);
+        }
     });
     Ok(())
 }
@@ -394,6 +406,7 @@ pub(crate) fn handle_run_event(app: &tauri::AppHandle, event: &tauri::RunEvent)
                         .flatten()
                         .map(|path| path.to_string_lossy().into_owned())
                 })
+                .filter(|path| is_openable_json_file(path))
                 .collect();
             queue_or_emit_open_files(app, paths);
         }
@@ -0,0 +1,360 @@
+// Text encodings for opened and saved files
+//
+// JSON files arrive as UTF-8 or UTF-16, with or without a byte order mark,
+// and with LF or CRLF line endings. Older files from Windows and East Asian
+// systems are in a legacy encoding such as GBK or Shift-JIS; these are
+// recognised with chardetng and transcoded with encoding_rs. Decoding strips
+// the BOM so validators see the document itself, and reports what was found
+// so saving can write the same bytes back.
+use chardetng::EncodingDetector;
+use encoding_rs::Encoding;
+use serde::{Serialize, Serializer};
+use std::borrow::Cow;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Serialized as its lowercase name: `utf-8`, `utf-16le`, `utf-16be`, or the
+/// WHATWG name of a legacy encoding such as `gbk` or `shift_jis`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// A single- or multi-byte encoding other than UTF-8 and UTF-16.
+    Legacy(&'static Encoding),
+}
+
+impl TextEncoding {
+    /// Accepts the names `name()` returns and any other WHATWG label.
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "utf8" => return Ok(Self::Utf8),
+            "utf16le" => return Ok(Self::Utf16Le),
+            "utf16be" => return Ok(Self::Utf16Be),
+            _ => {}
+        }
+        match Encoding::for_label_no_replacement(name.as_bytes()) {
+            Some(encoding) => Ok(Self::from_encoding(encoding)),
+            None => Err(format!("Unsupported encoding: {name}")),
+        }
+    }
+
+    fn from_encoding(encoding: &'static Encoding) -> Self {
+        if encoding == encoding_rs::UTF_8 {
+            Self::Utf8
+        } else if encoding == encoding_rs::UTF_16LE {
+            Self::Utf16Le
+        } else if encoding == encoding_rs::UTF_16BE {
+            Self::Utf16Be
+        } else {
+            Self::Legacy(encoding)
+        }
+    }
+
+    pub(crate) fn name(self) -> String {
+        match self {
+            Self::Utf8 => "utf-8".to_string(),
+            Self::Utf16Le => "utf-16le".to_string(),
+            Self::Utf16Be => "utf-16be".to_string(),
+            Self::Legacy(encoding) => encoding.name().to_ascii_lowercase(),
+        }
+    }
+}
+
+impl Serialize for TextEncoding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    /// Both kinds appear.
+    Mixed,
+}
+
+/// Line endings to write on save.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum LineEndingMode {
+    Lf,
+    Crlf,
+    /// Keep each line's ending as it is in the content.
+    Preserve,
+}
+
+impl LineEndingMode {
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "lf" => Ok(Self::Lf),
+            "crlf" => Ok(Self::Crlf),
+            "preserve" => Ok(Self::Preserve),
+            _ => Err(format!("Unsupported line ending: {name}")),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct DecodedText {
+    pub content: String,
+    pub encoding: TextEncoding,
+    pub has_bom: bool,
+    /// `lf` for files without any line break.
+    pub line_ending: LineEnding,
+    pub final_newline: bool,
+}
+
+/// Decode file bytes, honouring a BOM when present. Without one, UTF-16 is
+/// recognised by the NUL half of the first code unit, since a JSON document
+/// starts with an ASCII character, and bytes that aren't valid UTF-8 are
+/// decoded in the legacy encoding chardetng guesses for them.
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedText, String> {
+    let (encoding, has_bom, body) = if let Some(body) = bytes.strip_prefix(UTF8_BOM) {
+        (TextEncoding::Utf8, true, body)
+    } else if let Some(body) = bytes.strip_prefix(UTF16LE_BOM) {
+        (TextEncoding::Utf16Le, true, body)
+    } else if let Some(body) = bytes.strip_prefix(UTF16BE_BOM) {
+        (TextEncoding::Utf16Be, true, body)
+    } else {
+        (sniff(bytes), false, bytes)
+    };
+
+    let (content, encoding) = match encoding {
+        TextEncoding::Utf8 => match std::str::from_utf8(body) {
+            Ok(text) => (text.to_string(), encoding),
+            Err(_) if has_bom => return Err("File is not valid UTF-8".to_string()),
+            Err(_) => decode_detected(body)?,
+        },
+        TextEncoding::Utf16Le => (decode_utf16(body, u16::from_le_bytes)?, encoding),
+        TextEncoding::Utf16Be => (decode_utf16(body, u16::from_be_bytes)?, encoding),
+        TextEncoding::Legacy(legacy) => (decode_legacy(body, legacy)?, encoding),
+    };
+    Ok(DecodedText {
+        line_ending: detect_line_ending(&content),
+        final_newline: content.ends_with('\n'),
+        content,
+        encoding,
+        has_bom,
+    })
+}
+
+/// The text of UTF-8 file bytes without their BOM; `None` for UTF-16.
+pub(crate) fn utf8_body(bytes: &[u8]) -> Option<&[u8]> {
+    if let Some(body) = bytes.strip_prefix(UTF8_BOM) {
+        return Some(body);
+    }
+    if bytes.starts_with(UTF16LE_BOM) || bytes.starts_with(UTF16BE_BOM) {
+        return None;
+    }
+    (sniff(bytes) == TextEncoding::Utf8).then_some(bytes)
+}
+
+pub(crate) fn detect_line_ending(content: &str) -> LineEnding {
+    let newlines = content.matches('\n').count();
+    let crlf = content.matches("\r\n").count();
+    match crlf {
+        0 => LineEnding::Lf,
+        _ if crlf == newlines => LineEnding::Crlf,
+        _ => LineEnding::Mixed,
+    }
+}
+
+/// Convert line endings for saving, then add or remove the final newline
+/// when `final_newline` asks for it.
+pub(crate) fn normalize_lines(
+    content: &str,
+    mode: LineEndingMode,
+    final_newline: Option<bool>,
+) -> Cow<'_, str> {
+    let mut text = match mode {
+        LineEndingMode::Preserve => Cow::Borrowed(content),
+        LineEndingMode::Lf if content.contains("\r\n") => Cow::Owned(content.replace("\r\n", "\n")),
+        LineEndingMode::Lf => Cow::Borrowed(content),
+        LineEndingMode::Crlf => Cow::Owned(content.replace("\r\n", "\n").replace('\n', "\r\n")),
+    };
+    match final_newline {
+        Some(true) if !text.ends_with('\n') => {
+            let newline = match mode {
+                LineEndingMode::Crlf => "\r\n",
+                LineEndingMode::Preserve if detect_line_ending(&text) == LineEnding::Crlf => "\r\n",
+                _ => "\n",
+            };
+            text.to_mut().push_str(newline);
+        }
+        Some(false) => {
+            let trimmed = text.trim_end_matches(['\r', '\n']).len();
+            if trimmed < text.len() {
+                text.to_mut().truncate(trimmed);
+            }
+        }
+        _ => {}
+    }
+    text
+}
+
+/// Encode text for saving, optionally with a BOM. Legacy encodings have no
+/// BOM, and fail on characters they can't represent.
+pub(crate) fn encode(content: &str, encoding: TextEncoding, bom: bool) -> Result<Vec<u8>, String> {
+    Ok(match encoding {
+        TextEncoding::Utf8 => {
+            let mut bytes = if bom { UTF8_BOM.to_vec() } else { Vec::new() };
+            bytes.extend_from_slice(content.as_bytes());
+            bytes
+        }
+        TextEncoding::Utf16Le => {
+            encode_utf16(content, bom.then_some(UTF16LE_BOM), u16::to_le_bytes)
+        }
+        TextEncoding::Utf16Be => {
+            encode_utf16(content, bom.then_some(UTF16BE_BOM), u16::to_be_bytes)
+        }
+        TextEncoding::Legacy(legacy) => {
+            let (bytes, _, unmappable) = legacy.encode(content);
+            if unmappable {
+                return Err(format!(
+                    "The text has characters {} can't represent; save it as UTF-8",
+                    legacy.name()
+                ));
+            }
+            bytes.into_owned()
+        }
+    })
+}
+
+fn sniff(bytes: &[u8]) -> TextEncoding {
+    match bytes {
+        [first, 0, ..] if *first != 0 => TextEncoding::Utf16Le,
+        [0, second, ..] if *second != 0 => TextEncoding::Utf16Be,
+        _ => TextEncoding::Utf8,
+    }
+}
+
+/// Decode bytes that aren't UTF-8 in the encoding they most likely use.
+fn decode_detected(body: &[u8]) -> Result<(String, TextEncoding), String> {
+    let mut detector = EncodingDetector::new();
+    detector.feed(body, true);
+    let guess = detector.guess(None, false);
+    let content = decode_legacy(body, guess).map_err(|_| {
+        "File is not valid UTF-8 and its encoding couldn't be recognised; save it as UTF-8 \
+         and try again"
+            .to_string()
+    })?;
+    Ok((content, TextEncoding::from_encoding(guess)))
+}
+
+fn decode_legacy(body: &[u8], encoding: &'static Encoding) -> Result<String, String> {
+    encoding
+        .decode_without_bom_handling_and_without_replacement(body)
+        .map(Cow::into_owned)
+        .ok_or_else(|| format!("File is not valid {}", encoding.name()))
+}
+
+fn decode_utf16(body: &[u8], unit: fn([u8; 2]) -> u16) -> Result<String, String> {
+    if !body.len().is_multiple_of(2) {
+        return Err("File is not valid UTF-16: odd number of bytes".to_string());
+    }
+    let units = body.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|e| format!("File is not valid UTF-16: {}", e))
+}
+
+fn encode_utf16(content: &str, bom: Option<&[u8]>, bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+    let mut out = bom.map(<[u8]>::to_vec).unwrap_or_default();
+    for unit in content.encode_utf16() {
+        out.extend_from_slice(&bytes(unit));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode, detect_line_ending, encode, normalize_lines, LineEnding, LineEndingMode,
+        TextEncoding,
+    };
+
+    #[test]
+    fn strips_boms_and_round_trips_each_encoding() {
+        let text = "{\"name\": \"Zoë 😀\"}";
+        for encoding in [
+            TextEncoding::Utf8,
+            TextEncoding::Utf16Le,
+            TextEncoding::Utf16Be,
+        ] {
+            for bom in [true, false] {
+                let decoded = decode(&encode(text, encoding, bom).unwrap()).unwrap();
+                assert_eq!(decoded.content, text);
+                assert_eq!((decoded.encoding, decoded.has_bom), (encoding, bom));
+            }
+        }
+    }
+
+    #[test]
+    fn detects_and_round_trips_legacy_encodings() {
+        for (text, encoding, name) in [
+            (
+                r#"{"城市": "北京", "描述": "这是一个中文编码的测试文件"}"#,
+                encoding_rs::GBK,
+                "gbk",
+            ),
+            (
+                r#"{"都市": "東京", "説明": "これは日本語のテストファイルです"}"#,
+                encoding_rs::SHIFT_JIS,
+                "shift_jis",
+            ),
+        ] {
+            let (bytes, _, _) = encoding.encode(text);
+            let decoded = decode(&bytes).unwrap();
+            assert_eq!(decoded.content, text);
+            assert_eq!(decoded.encoding, TextEncoding::Legacy(encoding));
+            assert!(!decoded.has_bom);
+            assert_eq!(serde_json::to_value(decoded.encoding).unwrap(), name);
+            assert_eq!(TextEncoding::parse(name), Ok(decoded.encoding));
+            assert_eq!(
+                encode(&decoded.content, decoded.encoding, true).unwrap(),
+                &*bytes
+            );
+        }
+
+        assert!(encode("😀", TextEncoding::parse("gbk").unwrap(), false).is_err());
+        assert!(TextEncoding::parse("not-an-encoding").is_err());
+        // Odd UTF-16 and broken UTF-8 after a BOM are still rejected.
+        assert!(decode(&[0xFF, 0xFE, b'{']).is_err());
+        assert!(decode(&[0xEF, 0xBB, 0xBF, b'"', 0xD6, 0xD0, b'"']).is_err());
+    }
+
+    #[test]
+    fn detects_and_converts_line_endings() {
+        assert_eq!(detect_line_ending("{}"), LineEnding::Lf);
+        assert_eq!(detect_line_ending("{\r\n}\r\n"), LineEnding::Crlf);
+        assert_eq!(detect_line_ending("{\r\n}\n"), LineEnding::Mixed);
+
+        let mixed = "{\r\n\"a\": 1\n}";
+        assert_eq!(
+            normalize_lines(mixed, LineEndingMode::Lf, None),
+            "{\n\"a\": 1\n}"
+        );
+        assert_eq!(
+            normalize_lines(mixed, LineEndingMode::Crlf, Some(true)),
+            "{\r\n\"a\": 1\r\n}\r\n"
+        );
+        assert_eq!(
+            normalize_lines(mixed, LineEndingMode::Preserve, None),
+            mixed
+        );
+        assert_eq!(
+            normalize_lines("{}\r\n\r\n", LineEndingMode::Preserve, Some(false)),
+            "{}"
+        );
+
+        let decoded = decode(b"{\r\n}\r\n").unwrap();
+        assert_eq!(
+            (decoded.line_ending, decoded.final_newline),
+            (LineEnding::Crlf, true)
+        );
+    }
+}
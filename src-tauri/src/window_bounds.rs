@@ -1,6 +1,18 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use tauri::{LogicalSize, Manager, PhysicalPosition, WebviewWindow};
+use tauri_plugin_window_state::{AppHandleExt, StateFlags};
 
 const WINDOW_SCREEN_MARGIN: u32 = 48;
+const DEFAULT_WINDOW_WIDTH: f64 = 1440.0;
+const DEFAULT_WINDOW_HEIGHT: f64 = 900.0;
+const MIN_WINDOW_WIDTH: f64 = 960.0;
+const MIN_WINDOW_HEIGHT: f64 = 640.0;
+/// Quiet time after the last move or resize before geometry is written.
+const GEOMETRY_SAVE_DELAY_MS: u64 = 500;
+
+/// Bumped on every move or resize; a pending save only runs if it is still
+/// the latest one.
+static GEOMETRY_GENERATION: AtomicU64 = AtomicU64::new(0);
 const RESTORED_WINDOW_MAX_SCREEN_RATIO: f64 = 0.9;
 
 fn clamp_axis(value: i32, min: i32, max: i32) -> i32 {
@@ -51,14 +63,14 @@ fn clamp_main_window_to_visible_area(window: &WebviewWindow) -> tauri::Result<()
         width: restored_window_axis(
             current_logical_size.width,
             work_area_logical_size.width,
-            1440.0,
-            960.0,
+            DEFAULT_WINDOW_WIDTH,
+            MIN_WINDOW_WIDTH,
         ),
         height: restored_window_axis(
             current_logical_size.height,
             work_area_logical_size.height,
-            900.0,
-            640.0,
+            DEFAULT_WINDOW_HEIGHT,
+            MIN_WINDOW_HEIGHT,
         ),
     };
     let clamped_size = clamped_logical_size.to_physical::<u32>(scale_factor);
@@ -92,6 +104,52 @@ pub(crate) fn schedule_main_window_bounds_clamp(app: &tauri::AppHandle) {
     });
 }
 
+/// Write window geometry once moving or resizing settles, so a crash or
+/// forced quit doesn't lose it. Closing and exiting still save right away.
+pub(crate) fn schedule_window_geometry_save(app: &tauri::AppHandle) {
+    let generation = GEOMETRY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(GEOMETRY_SAVE_DELAY_MS)).await;
+        if GEOMETRY_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Err(error) = app_handle.save_window_state(StateFlags::all()) {
+            log::warn!("Failed to save window geometry: {error}");
+        }
+    });
+}
+
+/// Drop a geometry save that is still waiting, e.g. before a window takes a
+/// temporary size that shouldn't be remembered.
+pub(crate) fn cancel_window_geometry_save() {
+    GEOMETRY_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Put the window back to its default size, centered on its current monitor,
+/// and forget the saved geometry.
+pub(crate) fn reset_window_geometry(window: &WebviewWindow) -> Result<(), String> {
+    let reset = || -> tauri::Result<()> {
+        if window.is_fullscreen()? {
+            window.set_fullscreen(false)?;
+        }
+        if window.is_maximized()? {
+            window.unmaximize()?;
+        }
+        window.set_size(LogicalSize::new(
+            DEFAULT_WINDOW_WIDTH,
+            DEFAULT_WINDOW_HEIGHT,
+        ))?;
+        clamp_main_window_to_visible_area(window)?;
+        window.center()
+    };
+    reset().map_err(|e| e.to_string())?;
+    window
+        .app_handle()
+        .save_window_state(StateFlags::all())
+        .map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::{max_window_axis, restored_window_axis};
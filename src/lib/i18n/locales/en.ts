@@ -27,6 +27,8 @@ export default {
   'toolbar.unescapeTooltip': 'Unescape JSON string',
   'toolbar.minifyEscape': 'Min+Esc',
   'toolbar.minifyEscapeTooltip': 'Minify and escape JSON',
+  'toolbar.cancelTask': 'Cancel ({percent}%)',
+  'toolbar.cancelTaskTooltip': 'Stop the running background operation',
   'toolbar.convertToStandard': 'Convert JSONC/JSON5 to standard JSON',
   'toolbar.convertToStandardLabel': 'To JSON',
   'toolbar.noContentConvertToStandard': 'No content to convert',
@@ -75,9 +77,10 @@ export default {
   'settings.close': 'Close settings',
   'settings.appearance': 'Appearance',
   'settings.theme': 'Theme',
-  'settings.themeHint': 'Switch between light and dark appearance',
+  'settings.themeHint': 'Light, dark, or follow the system appearance',
   'settings.light': 'Light',
   'settings.dark': 'Dark',
+  'settings.systemTheme': 'System',
   'settings.colorScheme': 'Color Scheme',
   'settings.darkColorSchemeHint': 'Choose a dark mode color scheme for the editor',
   'settings.lightColorSchemeHint': 'Choose a light mode color scheme for the editor',
@@ -100,6 +103,13 @@ export default {
   'settings.application': 'Application',
   'settings.currentVersion': 'Current Version',
   'settings.versionUnknown': 'Unknown version',
+  'settings.windowGeometry': 'Window Size',
+  'settings.windowGeometryHint': 'Move the window back to its default size and position',
+  'settings.restoreWindowGeometry': 'Restore Window Size',
+  'settings.restoreWindowGeometryHint': 'Reopen with the last size, position and monitor',
+  'settings.resetWindowGeometry': 'Reset',
+  'settings.devtools': 'Developer Tools',
+  'settings.devtoolsHint': 'Allow opening developer tools with Ctrl/Cmd+Shift+I for troubleshooting. Takes effect after restart',
   'settings.autoUpdate': 'Auto Update',
   'settings.updateReady': 'Check GitHub Releases for a new version',
   'settings.updateChecking': 'Checking for updates...',
@@ -126,13 +136,34 @@ export default {
 
   'settings.shortcuts': 'Shortcuts',
   'settings.shortcutsGlobal': 'Global Shortcuts',
+  'settings.globalShortcutsEnabled': 'Enable Global Shortcuts',
+  'settings.globalShortcutsEnabledHint': 'Turn off while screen sharing or gaming; your key bindings are kept',
   'settings.shortcutsApp': 'App Shortcuts',
   'settings.showApp': 'Show App',
   'settings.showAppDesc': 'Bring Json Studio to front',
   'settings.formatClipboard': 'Format Clipboard',
   'settings.formatClipboardDesc': 'Format JSON in clipboard and display',
+  'settings.formatClipboardInPlace': 'Format Clipboard In Place',
+  'settings.formatClipboardInPlaceDesc': 'Format JSON in clipboard without showing the window',
+  'settings.minifyClipboard': 'Minify Clipboard',
+  'settings.minifyClipboardDesc': 'Minify JSON in clipboard in place',
+  'settings.validateClipboard': 'Validate Clipboard',
+  'settings.validateClipboardDesc': 'Validate JSON in clipboard and show the result',
+  'settings.escapeClipboard': 'Escape Clipboard',
+  'settings.escapeClipboardDesc': 'Escape clipboard text as a JSON string',
+  'settings.unescapeClipboard': 'Unescape Clipboard',
+  'settings.unescapeClipboardDesc': 'Unescape a JSON string in clipboard',
+  'settings.quickPeek': 'Quick Peek',
+  'settings.quickPeekDesc': 'Peek at clipboard JSON in a popup near the cursor',
+  'settings.captureClipboard': 'Capture Clipboard',
+  'settings.captureClipboardDesc': "Append clipboard JSON to today's scratch file",
   'settings.newFile': 'New File',
   'settings.newFileDesc': 'Create a new tab',
+  'settings.newWindow': 'New Window',
+  'settings.newWindowDesc': 'Open another editor window',
+  'settings.toggleMiniMode': 'Mini Mode',
+  'settings.toggleMiniModeDesc': 'Shrink the window to a compact strip on top of other windows',
+  'miniMode.exit': 'Leave mini mode',
   'settings.openFile': 'Open File',
   'settings.openFileDesc': 'Open a local file',
   'settings.saveFile': 'Save File',
@@ -245,6 +276,9 @@ export default {
   'toast.failedToSave': 'Failed to save file',
   'toast.clipboardFormatted': 'Clipboard content formatted',
   'toast.clipboardPastedRaw': 'Clipboard content pasted (invalid JSON)',
+  'toast.deepLinkOpened': 'Opened JSON from link',
+  'toast.deepLinkFailed': 'Could not open link: {message}',
+  'toast.crashReportFailed': 'Could not read the crash report',
   'toast.pinnedToTop': 'Pinned to top',
   'toast.unpinnedFromTop': 'Unpinned from top',
   'toast.json5Detected': 'JSON5 format detected, auto-parsed',
@@ -329,4 +363,10 @@ export default {
   'gridView.invalidEdit': 'Invalid value',
   'gridView.duplicateKeysReadOnlyTitle': 'Duplicate keys detected',
   'gridView.duplicateKeysReadOnly': 'An object contains the same key more than once. To avoid updating the wrong entry, Grid editing is disabled until you resolve the duplicates.',
+
+  // Crash reports
+  'crash.title': 'JsonStudio crashed last time',
+  'crash.message': 'The app closed unexpectedly: {message}. View the crash report?',
+  'crash.view': 'View Report',
+  'crash.dismiss': 'Dismiss',
 } as const;
@@ -27,6 +27,8 @@ export default {
   'toolbar.unescapeTooltip': '反转义字符串恢复标准 JSON',
   'toolbar.minifyEscape': '压缩转义',
   'toolbar.minifyEscapeTooltip': '压缩并转义 JSON 代码',
+  'toolbar.cancelTask': '取消 ({percent}%)',
+  'toolbar.cancelTaskTooltip': '停止正在后台运行的操作',
   'toolbar.convertToStandard': '将 JSONC/JSON5 转为标准 JSON',
   'toolbar.convertToStandardLabel': '转 JSON',
   'toolbar.noContentConvertToStandard': '没有可转换的内容',
@@ -75,9 +77,10 @@ export default {
   'settings.close': '关闭设置',
   'settings.appearance': '外观',
   'settings.theme': '主题',
-  'settings.themeHint': '切换亮色和暗色外观',
+  'settings.themeHint': '亮色、暗色或跟随系统外观',
   'settings.light': '亮色',
   'settings.dark': '暗色',
+  'settings.systemTheme': '跟随系统',
   'settings.colorScheme': '配色方案',
   'settings.darkColorSchemeHint': '选择编辑器暗色模式配色方案',
   'settings.lightColorSchemeHint': '选择编辑器亮色模式配色方案',
@@ -100,6 +103,13 @@ export default {
   'settings.application': '应用',
   'settings.currentVersion': '当前版本',
   'settings.versionUnknown': '未知版本',
+  'settings.windowGeometry': '窗口大小',
+  'settings.windowGeometryHint': '将窗口恢复为默认大小和位置',
+  'settings.restoreWindowGeometry': '恢复窗口大小',
+  'settings.restoreWindowGeometryHint': '启动时恢复上次的窗口大小、位置和所在显示器',
+  'settings.resetWindowGeometry': '重置',
+  'settings.devtools': '开发者工具',
+  'settings.devtoolsHint': '允许使用 Ctrl/Cmd+Shift+I 打开开发者工具以排查问题，重启后生效',
   'settings.autoUpdate': '自动更新',
   'settings.updateReady': '可手动检查 GitHub Release 中的新版本',
   'settings.updateChecking': '正在检查更新...',
@@ -126,13 +136,34 @@ export default {
 
   'settings.shortcuts': '快捷键',
   'settings.shortcutsGlobal': '全局快捷键',
+  'settings.globalShortcutsEnabled': '启用全局快捷键',
+  'settings.globalShortcutsEnabledHint': '屏幕共享或游戏时可关闭，快捷键设置会保留',
   'settings.shortcutsApp': '应用快捷键',
   'settings.showApp': '显示应用',
   'settings.showAppDesc': '将 Json Studio 置于前台',
   'settings.formatClipboard': '格式化剪贴板',
   'settings.formatClipboardDesc': '格式化剪贴板中的内容并显示',
+  'settings.formatClipboardInPlace': '就地格式化剪贴板',
+  'settings.formatClipboardInPlaceDesc': '格式化剪贴板中的 JSON，不显示窗口',
+  'settings.minifyClipboard': '压缩剪贴板',
+  'settings.minifyClipboardDesc': '就地压缩剪贴板中的 JSON',
+  'settings.validateClipboard': '校验剪贴板',
+  'settings.validateClipboardDesc': '校验剪贴板中的 JSON 并显示结果',
+  'settings.escapeClipboard': '转义剪贴板',
+  'settings.escapeClipboardDesc': '将剪贴板文本转义为 JSON 字符串',
+  'settings.unescapeClipboard': '反转义剪贴板',
+  'settings.unescapeClipboardDesc': '反转义剪贴板中的 JSON 字符串',
+  'settings.quickPeek': '快速预览',
+  'settings.quickPeekDesc': '在光标附近的弹窗中预览剪贴板 JSON',
+  'settings.captureClipboard': '收集剪贴板',
+  'settings.captureClipboardDesc': '将剪贴板 JSON 追加到今天的收集文件',
   'settings.newFile': '新建文件',
   'settings.newFileDesc': '创建新标签页',
+  'settings.newWindow': '新建窗口',
+  'settings.newWindowDesc': '打开另一个编辑器窗口',
+  'settings.toggleMiniMode': '迷你模式',
+  'settings.toggleMiniModeDesc': '将窗口缩小为置顶的紧凑窗口',
+  'miniMode.exit': '退出迷你模式',
   'settings.openFile': '打开文件',
   'settings.openFileDesc': '打开本地文件',
   'settings.saveFile': '保存文件',
@@ -245,6 +276,9 @@ export default {
   'toast.failedToSave': '保存文件失败',
   'toast.clipboardFormatted': '剪贴板内容已格式化',
   'toast.clipboardPastedRaw': '剪贴板内容已粘贴（无效 JSON）',
+  'toast.deepLinkOpened': '已从链接打开 JSON',
+  'toast.deepLinkFailed': '无法打开链接：{message}',
+  'toast.crashReportFailed': '无法读取崩溃报告',
   'toast.pinnedToTop': '已置顶',
   'toast.unpinnedFromTop': '已取消置顶',
   'toast.json5Detected': '检测到 JSON5 格式，已自动解析',
@@ -329,4 +363,10 @@ export default {
   'gridView.invalidEdit': '值格式无效',
   'gridView.duplicateKeysReadOnlyTitle': '检测到重复 key',
   'gridView.duplicateKeysReadOnly': '同一个对象中出现了多个相同 key。为避免改错同名字段，Grid 编辑已暂时禁用，请先处理重复 key。',
+
+  // Crash reports
+  'crash.title': 'JsonStudio 上次意外退出',
+  'crash.message': '应用意外关闭：{message}。要查看崩溃报告吗？',
+  'crash.view': '查看报告',
+  'crash.dismiss': '忽略',
 } as const;
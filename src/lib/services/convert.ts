@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { receiveText, type StoredResult } from './results';
 import { formatMarkdownTable, formatProperties, parseMarkdownTable, parseProperties } from './convertExtraFormats.js';
 
 export type ConvertFormat = 'yaml' | 'xml' | 'toml' | 'csv' | 'properties' | 'ini' | 'markdown';
@@ -16,13 +17,13 @@ export const CONVERT_FORMATS: { id: ConvertFormat; label: string; lang: string }
 export async function convertJson(content: string, format: ConvertFormat): Promise<string> {
   switch (format) {
     case 'yaml':
-      return await invoke<string>('json_to_yaml', { content });
+      return await receiveText(await invoke<string | StoredResult>('json_to_yaml', { content }));
     case 'xml':
-      return await invoke<string>('json_to_xml', { content });
+      return await receiveText(await invoke<string | StoredResult>('json_to_xml', { content }));
     case 'toml':
-      return await invoke<string>('json_to_toml', { content });
+      return await receiveText(await invoke<string | StoredResult>('json_to_toml', { content }));
     case 'csv':
-      return await invoke<string>('json_to_csv', { content });
+      return await receiveText(await invoke<string | StoredResult>('json_to_csv', { content }));
     case 'properties':
       return formatProperties(JSON.parse(content));
     case 'ini':
@@ -35,13 +36,13 @@ export async function convertJson(content: string, format: ConvertFormat): Promi
 export async function convertToJson(content: string, format: ConvertFormat): Promise<string> {
   switch (format) {
     case 'yaml':
-      return await invoke<string>('yaml_to_json', { content });
+      return await receiveText(await invoke<string | StoredResult>('yaml_to_json', { content }));
     case 'xml':
-      return await invoke<string>('xml_to_json', { content });
+      return await receiveText(await invoke<string | StoredResult>('xml_to_json', { content }));
     case 'toml':
-      return await invoke<string>('toml_to_json', { content });
+      return await receiveText(await invoke<string | StoredResult>('toml_to_json', { content }));
     case 'csv':
-      return await invoke<string>('csv_to_json', { content });
+      return await receiveText(await invoke<string | StoredResult>('csv_to_json', { content }));
     case 'properties':
       return JSON.stringify(parseProperties(content), null, 2);
     case 'ini':
@@ -0,0 +1,36 @@
+// Crash reports - written by the backend when the app panics, offered on the next launch
+import { invoke } from '@tauri-apps/api/core';
+
+const LAST_SEEN_KEY = 'last-seen-crash-report';
+
+export interface CrashReportInfo {
+  id: string;
+  /** Milliseconds since the Unix epoch */
+  created_at: number;
+  /** First line of the panic message */
+  message: string;
+}
+
+/**
+ * Crash reports from earlier sessions, newest first
+ */
+export async function listCrashReports(): Promise<CrashReportInfo[]> {
+  return await invoke<CrashReportInfo[]>('list_crash_reports');
+}
+
+/**
+ * Full text of a crash report
+ */
+export async function readCrashReport(id: string): Promise<string> {
+  return await invoke<string>('read_crash_report', { id });
+}
+
+/**
+ * The newest report if it hasn't been offered yet; it counts as offered from now on
+ */
+export async function takeUnseenCrashReport(): Promise<CrashReportInfo | null> {
+  const [latest] = await listCrashReports();
+  if (!latest || localStorage.getItem(LAST_SEEN_KEY) === latest.id) return null;
+  localStorage.setItem(LAST_SEEN_KEY, latest.id);
+  return latest;
+}
@@ -0,0 +1,22 @@
+// jsonstudio:// links - content resolved by the backend for `open` and `format` links
+
+/** Payload of `open-deeplink` */
+export interface DeepLinkContent {
+  action: 'open' | 'format';
+  /** The fetched URL, for `open` links */
+  url: string | null;
+  content: string;
+}
+
+/**
+ * Tab name for linked content: the file name at the end of a fetched URL
+ */
+export function deepLinkTabName(link: DeepLinkContent): string | null {
+  if (!link.url) return null;
+  try {
+    const name = new URL(link.url).pathname.split('/').pop() || '';
+    return decodeURIComponent(name) || null;
+  } catch {
+    return null;
+  }
+}
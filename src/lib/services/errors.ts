@@ -0,0 +1,30 @@
+// Backend errors - commands fail with a structured error carrying a localized message
+
+/**
+ * Error returned by commands that report structured errors; `message` is in
+ * the locale last passed to `set_locale`
+ */
+export interface BackendError {
+  /** Stable identifier such as `parse` or `read_file` */
+  code: string;
+  /** Values in the message, e.g. `line` and `column` for `parse` */
+  params: Record<string, unknown>;
+  message: string;
+}
+
+export function isBackendError(error: unknown): error is BackendError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as BackendError).code === 'string' &&
+    typeof (error as BackendError).message === 'string'
+  );
+}
+
+/**
+ * Text to show for a failed command, whichever form its error takes
+ */
+export function errorMessage(error: unknown): string {
+  if (isBackendError(error) || error instanceof Error) return error.message;
+  return String(error);
+}
@@ -1,20 +1,85 @@
 // File operation service - communicates with Rust backend
 import { invoke } from '@tauri-apps/api/core';
+import type { JsonStats, ValidationResult } from './json';
+import { receiveDecodedText, type DeliveredText } from './results';
+
+/** Legacy encodings use their WHATWG name, e.g. `gbk` or `shift_jis` */
+export type TextEncoding = 'utf-8' | 'utf-16le' | 'utf-16be' | (string & {});
+
+/** File text with the BOM stripped, plus what is needed to save it back unchanged */
+export interface DecodedText {
+  content: string;
+  encoding: TextEncoding;
+  has_bom: boolean;
+  line_ending: 'lf' | 'crlf' | 'mixed';
+  final_newline: boolean;
+}
 
 /**
  * Open file using file picker dialog
- * @returns Tuple of [filePath, content] or null if cancelled
+ * @returns Tuple of [filePath, text] or null if cancelled
  */
-export async function openFileDialog(): Promise<[string, string] | null> {
-  const result = await invoke<[string, string] | null>('open_file_dialog');
-  return result;
+export async function openFileDialog(): Promise<[string, DecodedText] | null> {
+  const result = await invoke<[string, DeliveredText] | null>('open_file_dialog');
+  if (!result) return null;
+  return [result[0], await receiveDecodedText(result[1])];
+}
+
+export interface FileFailure {
+  path: string;
+  /** Error code, e.g. `file_too_large_for_editing` */
+  code: string;
+  error: string;
+}
+
+export interface ReadFilesResult<Text = DecodedText> {
+  files: [string, Text][];
+  failures: FileFailure[];
+}
+
+/**
+ * Open several files using a multi-select file picker
+ */
+export async function openFilesDialog(): Promise<ReadFilesResult> {
+  return await receiveFiles(await invoke<ReadFilesResult<DeliveredText>>('open_files_dialog'));
+}
+
+async function receiveFiles({ files, failures }: ReadFilesResult<DeliveredText>): Promise<ReadFilesResult> {
+  const received: [string, DecodedText][] = [];
+  for (const [path, text] of files) received.push([path, await receiveDecodedText(text)]);
+  return { files: received, failures };
+}
+
+export type LineEndingMode = 'lf' | 'crlf' | 'preserve';
+
+export interface SaveOptions {
+  encoding?: TextEncoding;
+  bom?: boolean;
+  /** Defaults to whether the path ends in .gz */
+  compress?: boolean;
+  /** Defaults to preserve */
+  lineEnding?: LineEndingMode;
+  /** Add or strip the trailing newline; left as-is when unset */
+  finalNewline?: boolean;
 }
 
 /**
- * Save content to existing file path
+ * Save options that write text back the way it was read from disk
  */
-export async function saveFile(path: string, content: string): Promise<void> {
-  await invoke('save_file', { path, content });
+export function saveOptionsFor(text: DecodedText): SaveOptions {
+  return {
+    encoding: text.encoding,
+    bom: text.has_bom,
+    lineEnding: text.line_ending === 'mixed' ? 'preserve' : text.line_ending,
+    finalNewline: text.final_newline,
+  };
+}
+
+/**
+ * Save content to existing file path; defaults to UTF-8 without a BOM
+ */
+export async function saveFile(path: string, content: string, options: SaveOptions = {}): Promise<void> {
+  await invoke('save_file', { path, content, options });
 }
 
 export async function renameFile(path: string, newFileName: string): Promise<string> {
@@ -22,11 +87,17 @@ export async function renameFile(path: string, newFileName: string): Promise<str
 }
 
 /**
- * Save content to new file using save dialog
+ * Save content to new file using save dialog; `suggestedName` (the opened
+ * file's name or path) proposes `<name>.json` in place of `defaultFileName`
  * @returns File path or null if cancelled
  */
-export async function saveFileDialog(content: string, defaultFileName: string): Promise<string | null> {
-  const result = await invoke<string | null>('save_file_dialog', { content, defaultFileName });
+export async function saveFileDialog(
+  content: string,
+  defaultFileName: string,
+  suggestedName: string | null = null,
+  options: SaveOptions = {},
+): Promise<string | null> {
+  const result = await invoke<string | null>('save_file_dialog', { content, defaultFileName, suggestedName, options });
   return result;
 }
 
@@ -45,8 +116,190 @@ export async function saveBinaryFileDialog(
 /**
  * Read file content by path (for drag & drop)
  */
-export async function readFile(path: string): Promise<string> {
-  return await invoke<string>('read_file', { path });
+export async function readFile(path: string): Promise<DecodedText> {
+  return await receiveDecodedText(await invoke<DeliveredText>('read_file', { path }));
+}
+
+/** A file mapped by the backend instead of read, see `openMappedFile` */
+export interface MappedFileInfo {
+  handle: string;
+  /** Leading part of the file */
+  preview: string;
+  /** Whether `preview` is shorter than the file */
+  truncated: boolean;
+  total_bytes: number;
+}
+
+/**
+ * Map a UTF-8 file too large for the editor; the handle works with the `*Mapped` functions
+ */
+export async function openMappedFile(path: string): Promise<MappedFileInfo> {
+  return await invoke<MappedFileInfo>('open_mapped_file', { path });
+}
+
+/**
+ * Release a mapped file
+ */
+export async function closeMappedFile(handle: string): Promise<boolean> {
+  return await invoke<boolean>('close_mapped_file', { handle });
+}
+
+export interface ExtractResult {
+  content: string;
+  value_type: string;
+  /** Members or items directly inside the value; 0 for scalars */
+  child_count: number;
+  byte_size: number;
+}
+
+export async function validateMapped(handle: string): Promise<ValidationResult> {
+  return await invoke<ValidationResult>('json_validate_mapped', { handle });
+}
+
+export async function getStatsMapped(handle: string): Promise<JsonStats> {
+  return await invoke<JsonStats>('json_stats_mapped', { handle });
+}
+
+/**
+ * Extract the value at a JSON Pointer without loading the whole file
+ */
+export async function extractMapped(handle: string, pointer: string, indent?: number): Promise<ExtractResult> {
+  return await invoke<ExtractResult>('json_extract_mapped', { handle, pointer, indent });
+}
+
+export interface RecentFile {
+  path: string;
+  file_name: string;
+  /** Milliseconds since the Unix epoch */
+  last_opened: number;
+  size: number | null;
+  still_exists: boolean;
+}
+
+/**
+ * Recently opened files, most recent first
+ */
+export async function getRecentFiles(): Promise<RecentFile[]> {
+  return await invoke<RecentFile[]>('get_recent_files');
+}
+
+export async function addRecentFile(path: string): Promise<void> {
+  await invoke('add_recent_file', { path });
+}
+
+export async function removeRecentFile(path: string): Promise<boolean> {
+  return await invoke<boolean>('remove_recent_file', { path });
+}
+
+export async function clearRecentFiles(): Promise<void> {
+  await invoke('clear_recent_files');
+}
+
+/**
+ * Read several files in one round-trip (for dropping multiple files)
+ */
+export async function readFiles(paths: string[]): Promise<ReadFilesResult> {
+  return await receiveFiles(await invoke<ReadFilesResult<DeliveredText>>('read_files', { paths }));
+}
+
+export interface SnapshotInfo {
+  id: string;
+  /** Milliseconds since the Unix epoch */
+  created: number;
+  size: number;
+}
+
+/**
+ * Earlier saved versions of a file, newest first
+ */
+export async function listFileHistory(path: string): Promise<SnapshotInfo[]> {
+  return await invoke<SnapshotInfo[]>('list_file_history', { path });
+}
+
+/**
+ * Read one earlier version of a file, e.g. to restore it or diff against it
+ */
+export async function readFileHistory(path: string, snapshotId: string): Promise<DecodedText> {
+  return await invoke<DecodedText>('read_file_history', { path, snapshotId });
+}
+
+export interface ClipboardHistoryEntry {
+  id: number;
+  created: number;
+  size: number;
+  preview: string;
+}
+
+/**
+ * JSON that went through the clipboard shortcuts, newest first.
+ * Changes are also sent on the `clipboard-history-updated` event.
+ */
+export async function getClipboardHistory(): Promise<ClipboardHistoryEntry[]> {
+  return await invoke<ClipboardHistoryEntry[]>('get_clipboard_history');
+}
+
+/**
+ * Full content of one clipboard history entry
+ */
+export async function getClipboardHistoryItem(id: number): Promise<string> {
+  return await invoke<string>('get_clipboard_history_item', { id });
+}
+
+export async function clearClipboardHistory(): Promise<void> {
+  await invoke('clear_clipboard_history');
+}
+
+export interface ScratchCaptures {
+  date: string;
+  path: string | null;
+  count: number;
+}
+
+/**
+ * The scratch capture file of a day (YYYY-MM-DD, today by default), to open it in a tab
+ */
+export async function getScratchCaptures(date: string | null = null): Promise<ScratchCaptures> {
+  return await invoke<ScratchCaptures>('get_scratch_captures', { date });
+}
+
+export interface ZipEntry {
+  name: string;
+  size: number;
+  compressed_size: number;
+  is_json: boolean;
+}
+
+/**
+ * List the files inside a ZIP archive without extracting it
+ */
+export async function zipListEntries(path: string): Promise<ZipEntry[]> {
+  return await invoke<ZipEntry[]>('zip_list_entries', { path });
+}
+
+/**
+ * Read one file from a ZIP archive
+ */
+export async function zipReadEntry(path: string, entry: string): Promise<DecodedText> {
+  return await invoke<DecodedText>('zip_read_entry', { path, entry });
+}
+
+export interface JsonFileEntry {
+  path: string;
+  name: string;
+  size: number;
+  /** Milliseconds since the Unix epoch */
+  modified: number | null;
+}
+
+/**
+ * List JSON files in a folder, stopping after maxEntries
+ */
+export async function listJsonFiles(
+  dir: string,
+  recursive: boolean,
+  maxEntries: number,
+): Promise<{ entries: JsonFileEntry[]; truncated: boolean }> {
+  return await invoke('list_json_files', { dir, recursive, maxEntries });
 }
 
 /**
@@ -3,25 +3,30 @@ import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 
 export interface FileChangeEvent {
   path: string;
+  /** Milliseconds since the Unix epoch; null once the file is deleted */
+  mtime: number | null;
+  exists: boolean;
 }
 
+type FileChangeCallback = (path: string, event: FileChangeEvent) => void;
+
 class FileWatcherService {
   private listeners: Map<string, UnlistenFn> = new Map();
-  private callbacks: Map<string, ((path: string) => void)[]> = new Map();
+  private callbacks: Map<string, FileChangeCallback[]> = new Map();
   private globalListener: UnlistenFn | null = null;
 
   async init() {
     // Listen for file-changed events from Rust
-    this.globalListener = await listen<string>('file-changed', (event) => {
-      const path = event.payload;
+    this.globalListener = await listen<FileChangeEvent>('file-changed', (event) => {
+      const path = event.payload.path;
       const callbacks = this.callbacks.get(path);
       if (callbacks) {
-        callbacks.forEach(callback => callback(path));
+        callbacks.forEach(callback => callback(path, event.payload));
       }
     });
   }
 
-  async watchFile(path: string, callback: (path: string) => void): Promise<void> {
+  async watchFile(path: string, callback: FileChangeCallback): Promise<void> {
     // Add callback
     if (!this.callbacks.has(path)) {
       this.callbacks.set(path, []);
@@ -40,7 +45,7 @@ class FileWatcherService {
     }
   }
 
-  async unwatchFile(path: string, callback?: (path: string) => void): Promise<void> {
+  async unwatchFile(path: string, callback?: FileChangeCallback): Promise<void> {
     if (callback) {
       // Remove specific callback
       const callbacks = this.callbacks.get(path);
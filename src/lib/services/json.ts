@@ -1,12 +1,22 @@
 // JSON processing service - communicates with Rust backend
 import { invoke } from '@tauri-apps/api/core';
+import { receiveText, type StoredResult } from './results';
 
 // Validation result type
+export interface ValidationWarning {
+  kind: 'duplicate_key';
+  message: string;
+  line: number;
+  column: number;
+  pointer: string;
+}
+
 export interface ValidationResult {
   valid: boolean;
   error_message: string | null;
   error_line: number | null;
   error_column: number | null;
+  warnings: ValidationWarning[];
 }
 
 // JSON statistics type
@@ -15,15 +25,38 @@ export interface JsonStats {
   key_count: number;
   depth: number;
   byte_size: number;
+  line_count?: number;
+  char_count?: number;
+  minified_byte_size?: number | null;
   format_type: string;  // "JSON" or "JSON5"
   error_info: ValidationResult | null;
+  breakdown?: JsonStatsBreakdown | null;
+}
+
+export interface PointerMetric {
+  pointer: string;
+  length: number;
+}
+
+// Per-type counts and container metrics reported by json_stats
+export interface JsonStatsBreakdown {
+  objects: number;
+  arrays: number;
+  strings: number;
+  numbers: number;
+  booleans: number;
+  nulls: number;
+  array_elements: number;
+  longest_array: PointerMetric | null;
+  longest_string: PointerMetric | null;
+  deepest_pointer: string;
 }
 
 /**
  * Format JSON string
  */
 export async function formatJson(content: string, indent: number = 2): Promise<string> {
-  return await invoke<string>('json_format', { content, indent });
+  return await receiveText(await invoke<string | StoredResult>('json_format', { content, indent }));
 }
 
 /**
@@ -0,0 +1,26 @@
+// Diagnostic log - read and configure the backend log file
+import { invoke } from '@tauri-apps/api/core';
+
+export type LogLevel = 'off' | 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+/**
+ * The last `lines` lines of the log, oldest first; each is a JSON object with
+ * `time`, `level`, `target` and `message`
+ */
+export async function getRecentLogs(lines: number = 200): Promise<string[]> {
+  return await invoke<string[]>('get_recent_logs', { lines });
+}
+
+/**
+ * Path of the current log file, for attaching to a bug report
+ */
+export async function getLogFilePath(): Promise<string> {
+  return await invoke<string>('get_log_file_path');
+}
+
+/**
+ * Set the lowest level written to the log; remembered across launches
+ */
+export async function setLogLevel(level: LogLevel): Promise<void> {
+  await invoke('set_log_level', { level });
+}
@@ -0,0 +1,62 @@
+// NDJSON (JSON Lines) service - one JSON document per line, processed by the backend
+import { invoke } from '@tauri-apps/api/core';
+
+export interface NdjsonLineError {
+  line: number;
+  /** Column within the line */
+  column: number;
+  message: string;
+}
+
+export interface NdjsonValidation {
+  valid: boolean;
+  /** Non-blank lines */
+  records: number;
+  errors: NdjsonLineError[];
+  /** Whether checking stopped after reaching `maxErrors` */
+  truncated: boolean;
+}
+
+export interface LineMetric {
+  line: number;
+  length: number;
+}
+
+export interface NdjsonStats {
+  records: number;
+  invalid_records: number;
+  first_invalid_line: number | null;
+  key_count: number;
+  depth: number;
+  deepest_record: LineMetric | null;
+  /** Measured in bytes */
+  largest_record: LineMetric | null;
+}
+
+/**
+ * Check each line and report up to `maxErrors` invalid ones
+ */
+export async function validateNdjson(content: string, maxErrors: number = 100): Promise<NdjsonValidation> {
+  return await invoke<NdjsonValidation>('ndjson_validate', { content, maxErrors });
+}
+
+/**
+ * Rewrite each line minified, one record per line
+ */
+export async function formatNdjson(content: string): Promise<string> {
+  return await invoke<string>('ndjson_format', { content });
+}
+
+/**
+ * Collect the lines into a JSON array, one element per line
+ */
+export async function ndjsonToArray(content: string, indent: number = 2): Promise<string> {
+  return await invoke<string>('ndjson_to_array', { content, indent });
+}
+
+/**
+ * Statistics over the lines, each measured like `json_stats`
+ */
+export async function getNdjsonStats(content: string): Promise<NdjsonStats> {
+  return await invoke<NdjsonStats>('ndjson_stats', { content });
+}
@@ -0,0 +1,61 @@
+// Large command results - text the backend keeps instead of sending it in one message
+import { invoke } from '@tauri-apps/api/core';
+import type { DecodedText } from './file';
+
+/** Bytes requested per `read_result_chunk` call */
+const CHUNK_BYTES = 4 * 1024 * 1024;
+
+export interface StoredResult {
+  result_id: string;
+  /** Length of the text in bytes */
+  size: number;
+  /** Leading part of the text */
+  preview: string;
+}
+
+interface ResultChunk {
+  text: string;
+  next_offset: number;
+  done: boolean;
+}
+
+/** A file's text; `content` is empty and `stored` set when the text was too large to send at once */
+export interface DeliveredText extends DecodedText {
+  stored?: StoredResult;
+}
+
+export function isStoredResult(value: unknown): value is StoredResult {
+  return typeof value === 'object' && value !== null && typeof (value as StoredResult).result_id === 'string';
+}
+
+/**
+ * The text of a command result, pulling it in chunks when the backend stored it
+ */
+export async function receiveText(result: string | StoredResult): Promise<string> {
+  if (!isStoredResult(result)) return result;
+  const parts: string[] = [];
+  let offset = 0;
+  try {
+    for (;;) {
+      const chunk = await invoke<ResultChunk>('read_result_chunk', {
+        resultId: result.result_id,
+        offset,
+        len: CHUNK_BYTES,
+      });
+      parts.push(chunk.text);
+      offset = chunk.next_offset;
+      if (chunk.done) return parts.join('');
+    }
+  } catch (e) {
+    await invoke<boolean>('release_result', { resultId: result.result_id }).catch(() => false);
+    throw e;
+  }
+}
+
+/**
+ * A file's text with its content in place
+ */
+export async function receiveDecodedText({ stored, ...text }: DeliveredText): Promise<DecodedText> {
+  if (stored) text.content = await receiveText(stored);
+  return text;
+}
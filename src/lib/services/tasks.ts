@@ -0,0 +1,127 @@
+// Background tasks - heavy commands run off the command thread and can be cancelled
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * Inputs above this size go through the `_async` command variants
+ */
+export const BACKGROUND_TASK_THRESHOLD = 8 * 1024 * 1024;
+
+export interface TaskInfo {
+  task_id: string;
+  kind: string;
+  window: string;
+  started_at: number;
+  percent: number;
+}
+
+interface TaskProgressEvent {
+  task_id: string;
+  kind: string;
+  percent: number;
+}
+
+interface TaskCompletedEvent<T> {
+  task_id: string;
+  kind: string;
+  result: T;
+}
+
+interface TaskFailedEvent {
+  task_id: string;
+  kind: string;
+  error: string;
+  cancelled: boolean;
+}
+
+export class TaskCancelledError extends Error {
+  constructor() {
+    super('Task cancelled');
+    this.name = 'TaskCancelledError';
+  }
+}
+
+export interface BackgroundTask<T> {
+  /** Resolves with the result; rejects with `TaskCancelledError` when cancelled */
+  result: Promise<T>;
+  cancel: () => Promise<void>;
+}
+
+/**
+ * Start an `_async` command and follow it through its task events
+ *
+ * Listeners are registered before the command runs, because a small task can
+ * finish before its id comes back.
+ */
+export function runBackgroundTask<T>(
+  command: string,
+  args: Record<string, unknown>,
+  onProgress?: (percent: number) => void
+): BackgroundTask<T> {
+  let taskId: string | null = null;
+  let cancelRequested = false;
+
+  const result = (async () => {
+    const { getCurrentWebviewWindow } = await import('@tauri-apps/api/webviewWindow');
+    const appWindow = getCurrentWebviewWindow();
+    const unlisteners: Array<() => void> = [];
+    // Events that arrive before the id is known are matched once it is.
+    const early: Array<() => boolean> = [];
+    try {
+      return await new Promise<T>((resolve, reject) => {
+        const handle = (id: string, settle: () => void) => {
+          if (taskId === null) {
+            early.push(() => {
+              if (id !== taskId) return false;
+              settle();
+              return true;
+            });
+          } else if (id === taskId) {
+            settle();
+          }
+        };
+        const subscribe = async () => {
+          unlisteners.push(
+            await appWindow.listen<TaskProgressEvent>('task-progress', (event) => {
+              if (event.payload.task_id === taskId) onProgress?.(event.payload.percent);
+            }),
+            await appWindow.listen<TaskCompletedEvent<T>>('task-completed', (event) => {
+              handle(event.payload.task_id, () => resolve(event.payload.result));
+            }),
+            await appWindow.listen<TaskFailedEvent>('task-failed', (event) => {
+              const { task_id, error, cancelled } = event.payload;
+              handle(task_id, () => reject(cancelled ? new TaskCancelledError() : new Error(error)));
+            })
+          );
+          taskId = await invoke<string>(command, args);
+          early.find((settle) => settle());
+          if (cancelRequested) await cancelTask(taskId);
+        };
+        subscribe().catch(reject);
+      });
+    } finally {
+      unlisteners.forEach((unlisten) => unlisten());
+    }
+  })();
+
+  return {
+    result,
+    cancel: async () => {
+      cancelRequested = true;
+      if (taskId !== null) await cancelTask(taskId);
+    },
+  };
+}
+
+/**
+ * Stop a background task; its result promise rejects with `TaskCancelledError`
+ */
+export async function cancelTask(taskId: string): Promise<boolean> {
+  return await invoke<boolean>('cancel_task', { taskId });
+}
+
+/**
+ * Background tasks that are still running, oldest first
+ */
+export async function listTasks(): Promise<TaskInfo[]> {
+  return await invoke<TaskInfo[]>('list_tasks');
+}
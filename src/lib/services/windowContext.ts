@@ -0,0 +1,124 @@
+import { getCurrentWindow } from '@tauri-apps/api/window';
+
+/**
+ * Label of the window this page runs in; 'main' outside Tauri
+ */
+export function currentWindowLabel(): string {
+  try {
+    return getCurrentWindow().label;
+  } catch {
+    return 'main';
+  }
+}
+
+/**
+ * Only the main window restores and persists its tabs; extra editor windows
+ * are session-only so they never overwrite the main window's state.
+ */
+export function isMainWindow(): boolean {
+  return currentWindowLabel() === 'main';
+}
+
+/**
+ * Open another editor window, optionally with a document in its first tab
+ */
+export async function openNewWindow(
+  initialContent: string | null = null,
+  title: string | null = null
+): Promise<string> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return await invoke<string>('new_window', { initialContent, title });
+}
+
+/**
+ * The content this window was opened with, if any; returned only once
+ */
+export async function takeWindowInitialContent(): Promise<string | null> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return await invoke<string | null>('take_window_initial_content');
+}
+
+/**
+ * Put this window back to its default size, centered on its monitor
+ */
+export async function resetWindowGeometry(): Promise<void> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('reset_window_geometry');
+}
+
+/**
+ * Zoom this window's whole UI; returns the factor applied after clamping
+ */
+export async function setZoom(factor: number): Promise<number> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return await invoke<number>('set_zoom', { factor });
+}
+
+/**
+ * The remembered zoom factor, 1 until one is set
+ */
+export async function getZoom(): Promise<number> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return await invoke<number>('get_zoom');
+}
+
+/**
+ * Whether devtools are allowed in release builds, from the next launch on
+ */
+export async function getDevtoolsEnabled(): Promise<boolean> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return await invoke<boolean>('get_devtools_enabled');
+}
+
+export async function setDevtoolsEnabled(enabled: boolean): Promise<void> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('set_devtools_enabled', { enabled });
+}
+
+export type ProgressState = 'none' | 'indeterminate' | 'normal' | 'error';
+
+/**
+ * Show progress on this window's taskbar button or dock icon; `value` is a
+ * percentage for `normal` and `error`
+ */
+export async function setProgress(state: ProgressState, value?: number): Promise<void> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('set_progress', { state, value: value ?? null });
+}
+
+/**
+ * Shrink this window to a compact always-on-top strip, or restore it; the
+ * window receives `mini-mode-changed` when the mode changes
+ */
+export async function setMiniMode(enabled: boolean): Promise<void> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('set_mini_mode', { enabled });
+}
+
+export async function isMiniModeEnabled(): Promise<boolean> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return await invoke<boolean>('is_mini_mode_enabled');
+}
+
+export type VibrancyMaterial =
+  | 'sidebar'
+  | 'hud'
+  | 'under-window'
+  | 'under-page'
+  | 'window'
+  | 'content'
+  | 'header'
+  | 'titlebar'
+  | 'menu'
+  | 'popover'
+  | 'sheet'
+  | 'tooltip';
+
+/**
+ * Show a frosted-glass material behind this window's content on macOS, or
+ * remove it with `null`; does nothing on other platforms
+ */
+export async function setWindowVibrancy(material: VibrancyMaterial | null): Promise<void> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('set_window_vibrancy', { material });
+}
@@ -1,3 +1,5 @@
+import { isMainWindow } from '$lib/services/windowContext';
+
 const DB_NAME = 'jsonstudio_documents';
 const DB_VERSION = 1;
 const STORE_NAME = 'documents';
@@ -16,8 +18,11 @@ const documents = new Map<string, string>();
 const pendingWrites = new Map<string, PendingWrite>();
 let dbPromise: Promise<IDBDatabase | null> | null = null;
 
+// Extra editor windows keep documents in memory only, like their tabs.
+const PERSIST_DOCUMENTS = isMainWindow();
+
 function canUseIndexedDb() {
-  return typeof indexedDB !== 'undefined';
+  return PERSIST_DOCUMENTS && typeof indexedDB !== 'undefined';
 }
 
 function openDatabase(): Promise<IDBDatabase | null> {
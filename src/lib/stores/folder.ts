@@ -1,6 +1,7 @@
 import { writable } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
 import { tabsStore } from './tabs';
+import { readFile, saveOptionsFor } from '$lib/services/file';
 
 export interface FileNode {
   name: string;
@@ -83,9 +84,9 @@ function createFolderStore() {
 
     async openFile(path: string) {
       try {
-        const content = await invoke<string>('read_file', { path });
+        const text = await readFile(path);
         const name = await invoke<string | null>('get_file_name', { path });
-        tabsStore.openFile(content, path, name);
+        tabsStore.openFile(text.content, path, name, saveOptionsFor(text));
       } catch (e) {
         console.error("Failed to open file:", e);
       }
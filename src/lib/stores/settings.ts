@@ -26,6 +26,8 @@ export const lightThemes = [
 export interface AppSettings {
   // Theme settings
   isDarkMode: boolean;
+  // Follow the OS appearance; choosing light or dark turns this off
+  followSystemTheme: boolean;
   darkTheme: 'json-studio-dark' | 'one-dark' | 'github-dark' | 'tokyo-night' | 'dracula' | 'nord';
   lightTheme: 'json-studio-light' | 'vs' | 'github-light' | 'solarized-light' | 'catppuccin-latte' | 'rose-ivy';
   
@@ -39,11 +41,17 @@ export interface AppSettings {
   showTreeView: boolean;
   showFolderView: boolean;
   autoSave: boolean;
+  // Larger files open in preview mode instead; read by the backend
+  maxEditorFileBytes: number;
+
+  // Window settings, read by the backend at startup
+  restoreWindowGeometry: boolean;
 }
 
 // Default settings
 const defaultSettings: AppSettings = {
   isDarkMode: false,
+  followSystemTheme: false,
   darkTheme: 'json-studio-dark',
   lightTheme: 'json-studio-light',
   language: 'en',
@@ -53,6 +61,8 @@ const defaultSettings: AppSettings = {
   showTreeView: true,
   showFolderView: false,
   autoSave: false,
+  maxEditorFileBytes: 256 * 1024 * 1024,
+  restoreWindowGeometry: true,
 };
 
 function getSystemLanguage(): Locale {
@@ -101,10 +111,32 @@ function saveSettings(settings: AppSettings) {
   }
 }
 
+// The backend settings file is the shared copy: every window and the next
+// launch read it. localStorage only keeps this window's first paint fast.
+function knownSettings(values: Record<string, unknown>): Partial<AppSettings> {
+  const known: Record<string, unknown> = {};
+  for (const key of Object.keys(defaultSettings)) {
+    if (key in values) known[key] = values[key];
+  }
+  return known as Partial<AppSettings>;
+}
+
+async function saveBackendSettings(partial: Partial<AppSettings>) {
+  try {
+    const { invoke } = await import('@tauri-apps/api/core');
+    for (const [key, value] of Object.entries(partial)) {
+      await invoke('set_setting', { key, value });
+    }
+  } catch (error) {
+    console.error('Failed to save settings:', error);
+  }
+}
+
 async function syncAppMenuLanguage(language: Locale) {
   try {
     const { invoke } = await import('@tauri-apps/api/core');
     await invoke('set_app_menu_language', { language });
+    await invoke('set_locale', { locale: language });
   } catch (error) {
     console.error('Failed to update app menu language:', error);
   }
@@ -113,16 +145,50 @@ async function syncAppMenuLanguage(language: Locale) {
 // Create settings store
 function createSettingsStore() {
   const { subscribe, set, update } = writable<AppSettings>(defaultSettings);
+  let current = defaultSettings;
+  subscribe(settings => { current = settings; });
+
+  // Take the backend's settings, e.g. after another window changed them
+  function applyBackendSettings(values: Record<string, unknown>) {
+    const settings = { ...getDefaultSettings(), ...knownSettings(values) };
+    const languageChanged = settings.language !== current.language;
+    set(settings);
+    saveSettings(settings);
+    if (languageChanged) {
+      locale.set(settings.language);
+      void syncAppMenuLanguage(settings.language);
+    }
+  }
+
+  async function syncWithBackend(local: AppSettings) {
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      const { listen } = await import('@tauri-apps/api/event');
+      const stored = await invoke<Record<string, unknown>>('get_settings');
+      if (Object.keys(knownSettings(stored)).length === 0) {
+        // First launch with the settings file: move this window's settings over
+        await saveBackendSettings(local);
+      } else {
+        applyBackendSettings(stored);
+      }
+      await listen<Record<string, unknown>>('settings-changed', (event) => {
+        applyBackendSettings(event.payload);
+      });
+    } catch (error) {
+      console.error('Failed to load settings:', error);
+    }
+  }
   
   return {
     subscribe,
     
-    // Initialize (load from localStorage)
+    // Initialize from localStorage, then from the backend settings file
     init() {
       const settings = loadSettings();
       set(settings);
       locale.set(settings.language);
       void syncAppMenuLanguage(settings.language);
+      void syncWithBackend(settings);
     },
     
     // Update single setting
@@ -132,6 +198,7 @@ function createSettingsStore() {
         saveSettings(newSettings);
         return newSettings;
       });
+      void saveBackendSettings({ [key]: value } as Partial<AppSettings>);
       
       if (key === 'language') {
         locale.set(value as Locale);
@@ -146,6 +213,7 @@ function createSettingsStore() {
         saveSettings(newSettings);
         return newSettings;
       });
+      void saveBackendSettings(partial);
     },
     
     // Reset to default settings
@@ -155,6 +223,14 @@ function createSettingsStore() {
       locale.set(settings.language);
       void syncAppMenuLanguage(settings.language);
       saveSettings(settings);
+      void (async () => {
+        try {
+          const { invoke } = await import('@tauri-apps/api/core');
+          await invoke('reset_settings');
+        } catch (error) {
+          console.error('Failed to reset settings:', error);
+        }
+      })();
     },
   };
 }
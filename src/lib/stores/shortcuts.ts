@@ -13,8 +13,17 @@ export interface ShortcutsSettings {
   // Global shortcuts (registered via Tauri backend)
   showApp: ShortcutConfig;
   formatClipboard: ShortcutConfig;
+  formatClipboardInPlace: ShortcutConfig;
+  minifyClipboard: ShortcutConfig;
+  validateClipboard: ShortcutConfig;
+  escapeClipboard: ShortcutConfig;
+  unescapeClipboard: ShortcutConfig;
+  quickPeek: ShortcutConfig;
+  captureClipboard: ShortcutConfig;
   // Editor shortcuts (handled via frontend keydown)
   newFile: ShortcutConfig;
+  newWindow: ShortcutConfig;
+  toggleMiniMode: ShortcutConfig;
   openFile: ShortcutConfig;
   saveFile: ShortcutConfig;
   format: ShortcutConfig;
@@ -47,6 +56,62 @@ const defaultShortcuts: ShortcutsSettings = {
     currentKey: 'CommandOrControl+Shift+V',
     isGlobal: true,
   },
+  formatClipboardInPlace: {
+    id: 'format_clipboard_in_place',
+    name: 'Format Clipboard In Place',
+    description: 'Format JSON in clipboard without showing the window',
+    defaultKey: '',
+    currentKey: '',
+    isGlobal: true,
+  },
+  minifyClipboard: {
+    id: 'minify_clipboard',
+    name: 'Minify Clipboard',
+    description: 'Minify JSON in clipboard in place',
+    defaultKey: '',
+    currentKey: '',
+    isGlobal: true,
+  },
+  validateClipboard: {
+    id: 'validate_clipboard',
+    name: 'Validate Clipboard',
+    description: 'Validate JSON in clipboard and show the result',
+    defaultKey: '',
+    currentKey: '',
+    isGlobal: true,
+  },
+  escapeClipboard: {
+    id: 'escape_clipboard',
+    name: 'Escape Clipboard',
+    description: 'Escape clipboard text as a JSON string',
+    defaultKey: '',
+    currentKey: '',
+    isGlobal: true,
+  },
+  unescapeClipboard: {
+    id: 'unescape_clipboard',
+    name: 'Unescape Clipboard',
+    description: 'Unescape a JSON string in clipboard',
+    defaultKey: '',
+    currentKey: '',
+    isGlobal: true,
+  },
+  quickPeek: {
+    id: 'quick_peek',
+    name: 'Quick Peek',
+    description: 'Peek at clipboard JSON in a popup near the cursor',
+    defaultKey: '',
+    currentKey: '',
+    isGlobal: true,
+  },
+  captureClipboard: {
+    id: 'capture_clipboard_to_scratch',
+    name: 'Capture Clipboard',
+    description: "Append clipboard JSON to today's scratch file",
+    defaultKey: '',
+    currentKey: '',
+    isGlobal: true,
+  },
   newFile: {
     id: 'new_file',
     name: 'New File',
@@ -54,6 +119,20 @@ const defaultShortcuts: ShortcutsSettings = {
     defaultKey: 'CommandOrControl+N',
     currentKey: 'CommandOrControl+N',
   },
+  newWindow: {
+    id: 'new_window',
+    name: 'New Window',
+    description: 'Open another editor window',
+    defaultKey: 'CommandOrControl+Shift+N',
+    currentKey: 'CommandOrControl+Shift+N',
+  },
+  toggleMiniMode: {
+    id: 'toggle_mini_mode',
+    name: 'Mini Mode',
+    description: 'Shrink the window to a compact strip on top of other windows',
+    defaultKey: 'CommandOrControl+Alt+M',
+    currentKey: 'CommandOrControl+Alt+M',
+  },
   openFile: {
     id: 'open_file',
     name: 'Open File',
@@ -164,6 +243,52 @@ function invokeGlobalShortcutUpdate(id: string, key: string): Promise<void> {
   return operation;
 }
 
+export interface GlobalShortcutInfo {
+  id: string;
+  /** Empty when the shortcut is disabled */
+  accelerator: string;
+  enabled: boolean;
+  description: string;
+}
+
+export interface ShortcutValidation {
+  status: 'ok' | 'parse_error' | 'conflict_internal' | 'conflict_system';
+  message: string | null;
+  conflicting_id: string | null;
+}
+
+/**
+ * Global shortcuts as currently registered by the backend
+ */
+export async function listGlobalShortcuts(): Promise<GlobalShortcutInfo[]> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return await invoke<GlobalShortcutInfo[]>('list_shortcuts');
+}
+
+/**
+ * Check a candidate accelerator for the global shortcut `id` before saving it
+ */
+export async function validateGlobalShortcut(key: string, id?: string): Promise<ShortcutValidation> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return await invoke<ShortcutValidation>('validate_shortcut', { key, id });
+}
+
+/**
+ * Whether global shortcuts are registered at all; the keymap is kept either way
+ */
+export async function getGlobalShortcutsEnabled(): Promise<boolean> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return await invoke<boolean>('shortcuts_enabled');
+}
+
+/**
+ * Turn all global shortcuts off or back on; remembered across restarts
+ */
+export async function setGlobalShortcutsEnabled(enabled: boolean): Promise<void> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('set_shortcuts_enabled', { enabled });
+}
+
 function createShortcutsStore() {
   const { subscribe, set, update } = writable<ShortcutsSettings>(getDefaultShortcuts());
 
@@ -1,5 +1,6 @@
 import { writable, derived } from 'svelte/store';
 import type { JsonStats } from '$lib/services/json';
+import type { SaveOptions } from '$lib/services/file';
 import { fileWatcherService } from '$lib/services/fileWatcher';
 import { FIRST_UNTITLED_NAME, getNextUntitledName } from './untitledTabs.js';
 import { openFileInTabs } from './tabOpen.js';
@@ -11,6 +12,7 @@ import {
   removeDocumentContent,
   setDocumentContent,
 } from './documentStore';
+import { isMainWindow } from '$lib/services/windowContext';
 
 export interface Tab {
   id: string;                    // Unique identifier
@@ -20,6 +22,7 @@ export interface Tab {
   stats: JsonStats;              // JSON statistics
   isPinned: boolean;             // Pinned tab flag
   contentVersion: number;        // Incremented when document content changes
+  saveOptions?: SaveOptions;     // Encoding and line endings of the file on disk
 }
 
 export interface TabsState {
@@ -28,6 +31,7 @@ export interface TabsState {
 }
 
 const STORAGE_KEY = 'jsonstudio_tabs_state';
+const PERSIST_TABS = isMainWindow();
 const PERSIST_DEBOUNCE_MS = 500;
 let persistTimer: ReturnType<typeof setTimeout> | null = null;
 let pendingPersistState: TabsState | null = null;
@@ -54,9 +58,10 @@ export function createNewTab(
   content: string = '',
   filePath: string | null = null,
   fileName: string | null = FIRST_UNTITLED_NAME,
-  isPinned: boolean = false
+  isPinned: boolean = false,
+  saveOptions?: SaveOptions
 ): Tab {
-  const tab = {
+  const tab: Tab = {
     id: generateId(),
     filePath,
     fileName,
@@ -64,6 +69,7 @@ export function createNewTab(
     stats: createEmptyStats(),
     isPinned,
     contentVersion: content ? 1 : 0,
+    saveOptions,
   };
   setDocumentContent(tab.id, content);
   return tab;
@@ -72,7 +78,7 @@ export function createNewTab(
 // Load state from localStorage
 function loadState(): TabsState {
   try {
-    const stored = localStorage.getItem(STORAGE_KEY);
+    const stored = PERSIST_TABS ? localStorage.getItem(STORAGE_KEY) : null;
     if (stored) {
       const parsed: TabsState = JSON.parse(stored);
       const sanitizedTabs: Tab[] = parsed.tabs.reduce<Tab[]>((result, tab) => {
@@ -92,6 +98,7 @@ function loadState(): TabsState {
           isModified: false,
           isPinned: tab.isPinned ?? false,
           contentVersion: tab.contentVersion ?? (legacyContent ? 1 : 0),
+          saveOptions: tab.saveOptions,
         });
         return result;
       }, []);
@@ -129,6 +136,7 @@ function saveState(state: TabsState) {
     persistTimer = null;
   }
   pendingPersistState = null;
+  if (!PERSIST_TABS) return;
   try {
     const toSave: TabsState = {
       tabs: state.tabs.map(tab => ({ ...tab })),
@@ -182,13 +190,17 @@ function createTabsStore() {
     },
     
     // Open file: reuse empty tab if possible, otherwise create new tab
-    openFile: (content: string, filePath: string, fileName: string | null) => {
+    openFile: (content: string, filePath: string, fileName: string | null, saveOptions?: SaveOptions) => {
       update(state => {
-        const existingState = openFileInTabs(state, filePath, fileName);
+        let existingState = openFileInTabs(state, filePath, fileName);
         if (existingState !== state) {
           const existingTab = existingState.tabs.find(tab => tab.filePath === filePath);
           if (existingTab && !existingTab.isModified) {
             setDocumentContent(existingTab.id, content);
+            existingState = {
+              ...existingState,
+              tabs: existingState.tabs.map(tab => tab === existingTab ? { ...tab, saveOptions } : tab),
+            };
           }
           saveState(existingState);
           return existingState;
@@ -203,7 +215,7 @@ function createTabsStore() {
             ...state,
             tabs: state.tabs.map(tab =>
               tab.id === currentTab.id
-                ? { ...tab, filePath, fileName, isModified: false, contentVersion: tab.contentVersion + 1 }
+                ? { ...tab, filePath, fileName, isModified: false, contentVersion: tab.contentVersion + 1, saveOptions }
                 : tab
             ),
           };
@@ -212,7 +224,7 @@ function createTabsStore() {
         }
         
         // Create new tab
-        const newTab = createNewTab(content, filePath, fileName);
+        const newTab = createNewTab(content, filePath, fileName, false, saveOptions);
         const newState = {
           tabs: [...state.tabs, newTab],
           activeTabId: newTab.id,
@@ -309,6 +321,22 @@ function createTabsStore() {
       });
     },
 
+    // Update how the tab's file is encoded on disk, e.g. after an external reload
+    updateTabSaveOptions: (tabId: string, saveOptions: SaveOptions) => {
+      update(state => {
+        const newState = {
+          ...state,
+          tabs: state.tabs.map(tab =>
+            tab.id === tabId
+              ? { ...tab, saveOptions }
+              : tab
+          ),
+        };
+        saveState(newState);
+        return newState;
+      });
+    },
+
     renameTab: (tabId: string, filePath: string | null, fileName: string) => {
       update(state => {
         const newState = {
@@ -34,7 +34,7 @@ test('export image service exposes PNG base64 to byte conversion', async () => {
 
 test('export image no longer registers image clipboard backend code', async () => {
   const commandName = ['copy', 'image', 'to', 'clipboard'].join('_');
-  const clipboardModule = ['pub mod', 'clipboard'].join(' ');
+  const clipboardModule = ['pub mod', 'clipboard;'].join(' ');
   const writeImagePermission = ['clipboard-manager', 'allow-write-image'].join(':');
   const [libSource, commandsSource, capabilitySource] = await Promise.all([
     readFile(new URL('../src-tauri/src/lib.rs', import.meta.url), 'utf8'),
@@ -70,6 +70,20 @@ test('toolbar creates new tabs directly', async () => {
   assert.match(handlerBody, /New tab created/);
 });
 
+test('toolbar saves files back with the encoding and line endings they were opened with', async () => {
+  const source = await readFile(
+    new URL('../src/lib/components/editor/JsonEditorToolbar.svelte', import.meta.url),
+    'utf8',
+  );
+  const openBody = source.match(/async function handleOpenFile\(\) \{[\s\S]*?\n  \}/)?.[0] || '';
+  const saveBody = source.match(/async function handleSaveFile\([\s\S]*?\n  \}/)?.[0] || '';
+  const saveAsBody = source.match(/async function handleSaveAsFile\(\) \{[\s\S]*?\n  \}/)?.[0] || '';
+
+  assert.match(openBody, /tabsStore\.openFile\([^)]*saveOptionsFor\(text\)\)/);
+  assert.match(saveBody, /writeFile\(activeTab\.filePath, currentContent, activeTab\.saveOptions\)/);
+  assert.match(saveAsBody, /saveFileDialog\([\s\S]*?activeTab\.saveOptions,\s*\)/);
+});
+
 test('toolbar exposes a save button matching the file operation buttons', async () => {
   const source = await readFile(
     new URL('../src/lib/components/editor/JsonEditorToolbar.svelte', import.meta.url),
@@ -13,10 +13,10 @@ test('global shortcut handlers only run for key press events', async () => {
     shortcutSource.match(/event\.state != ShortcutState::Pressed/g) || []
   ).length;
 
-  assert.equal(handlerCount, 2);
+  assert.equal(handlerCount, 1);
   assert.equal(pressGuardCount, handlerCount);
   assert.doesNotMatch(libSource, /\.on_shortcut\(/);
-  assert.match(libSource, /register_global_shortcut/);
+  assert.match(libSource, /register_default_shortcuts/);
 });
 
 test('format clipboard shortcut delegates JSON normalization to the frontend worker', async () => {
@@ -25,10 +25,12 @@ test('format clipboard shortcut delegates JSON normalization to the frontend wor
     'utf8',
   );
   const handler = source.match(
-    /pub async fn format_clipboard_and_show[\s\S]*?\n}\n\nfn ensure_window_in_front/,
+    /pub async fn format_clipboard_and_show[\s\S]*?\n}\n/,
   )?.[0] || '';
 
-  assert.match(handler, /\.emit\("clipboard-content", clipboard_text\)/);
+  assert.match(handler, /emit_when_ready\([\s\S]*?"clipboard-content",\s*clipboard_text\.clone\(\)/);
+  // History only keeps JSON, checked off the handler once the window is shown.
+  assert.match(handler, /emit_when_ready[\s\S]*?record_clipboard_if_json\(&app, clipboard_text\)/);
   assert.doesNotMatch(handler, /serde_json::from_str/);
   assert.doesNotMatch(handler, /serde_json::to_string_pretty/);
   assert.doesNotMatch(handler, /clipboard-formatted|clipboard-pasted-raw/);
@@ -40,13 +42,17 @@ test('shortcut updates unregister the current binding and restore it on failure'
     'utf8',
   );
 
-  assert.match(source, /let mut keys = registry[\s\S]*?\.keys[\s\S]*?\.lock\(\)/);
-  assert.match(source, /let old_key = keys[\s\S]*?\.get\(&id\)/);
-  assert.match(source, /if shortcuts\.is_registered\(old_key\.as_str\(\)\)/);
-  assert.match(source, /shortcuts[\s\S]*?\.unregister\(old_key\.as_str\(\)\)/);
-  assert.match(source, /register_global_shortcut\(&app, &id, &old_key\)/);
-  assert.match(source, /failed to restore previous shortcut/);
-  assert.match(source, /keys\.insert\(id, key\)/);
+  assert.match(source, /let mut bindings = lock_bindings\(&registry\)\?/);
+  assert.match(source, /let change = plan_change\(&bindings, &id,/);
+  assert.match(source, /if let Some\(old\) = change\.unregister/);
+  assert.match(source, /if shortcuts\.is_registered\(old\)/);
+  assert.match(source, /shortcuts[\s\S]*?\.unregister\(old\)/);
+  assert.match(source, /register_shortcut\(&app, &id, old\)/);
+  assert.match(source, /ErrorCode::RestoreShortcut/);
+  assert.match(source, /bindings\.insert\(id, binding\)/);
+
+  const errors = await readFile(new URL('../src-tauri/src/error.rs', import.meta.url), 'utf8');
+  assert.match(errors, /failed to restore previous shortcut/);
 });
 
 test('synchronizing an unchanged registered shortcut is a no-op', async () => {
@@ -55,8 +61,8 @@ test('synchronizing an unchanged registered shortcut is a no-op', async () => {
     'utf8',
   );
 
-  assert.match(source, /if old_key == key && shortcuts\.is_registered\(key\.as_str\(\)\)/);
-  assert.match(source, /if old_key == key[\s\S]*?return Ok\(\(\)\)/);
+  assert.match(source, /unregister: current\.filter\(\|_\| current != shortcut\)/);
+  assert.match(source, /change\.register\.filter\(\|new\| !shortcuts\.is_registered\(\*new\)\)/);
 });
 
 test('saved global shortcuts are synchronized when the frontend starts', async () => {